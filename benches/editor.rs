@@ -0,0 +1,100 @@
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::bench::{parse_keys, sample_source};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::search::SearchOptions;
+
+const LINES: usize = 20_000;
+const WIDTH: u16 = 200;
+const HEIGHT: u16 = 60;
+
+fn large_editor() -> Editor {
+    let mut editor = Editor::new("rust", &sample_source(LINES), vec![]).unwrap();
+    let middle = editor.borrow_code().line_to_char(LINES / 2);
+    editor.set_cursor(middle);
+    editor
+}
+
+fn bench_typing(c: &mut Criterion) {
+    let keys = parse_keys("let typed = value_1(&[1, 2, 3]);<Enter>");
+    c.bench_function("editor/typing", |b| {
+        b.iter_batched(
+            large_editor,
+            |mut editor| {
+                editor.feed_keys(&keys, WIDTH, HEIGHT).unwrap();
+                editor.render_to_buffer(WIDTH, HEIGHT)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_paste(c: &mut Criterion) {
+    let block = sample_source(500);
+    c.bench_function("editor/paste", |b| {
+        b.iter_batched(
+            large_editor,
+            |mut editor| {
+                editor.apply(InsertText {
+                    text: block.clone(),
+                });
+                editor.render_to_buffer(WIDTH, HEIGHT)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn bench_scrolling(c: &mut Criterion) {
+    let mut editor = large_editor();
+    c.bench_function("editor/scrolling", |b| {
+        b.iter(|| {
+            editor.set_offset_y(0);
+            for _ in 0..100 {
+                editor.scroll_down(HEIGHT as usize);
+                editor.render_to_buffer(WIDTH, HEIGHT);
+            }
+        })
+    });
+}
+
+fn bench_highlighting(c: &mut Criterion) {
+    let mut editor = large_editor();
+    editor.set_offset_y(LINES / 2);
+    c.bench_function("editor/highlighting", |b| {
+        b.iter(|| {
+            editor.reset_highlight_cache();
+            editor.render_to_buffer(WIDTH, HEIGHT)
+        })
+    });
+}
+
+fn bench_search(c: &mut Criterion) {
+    let mut editor = large_editor();
+    let options = SearchOptions {
+        smart_case: true,
+        whole_word: false,
+    };
+    c.bench_function("editor/search", |b| {
+        b.iter(|| editor.search("total", options))
+    });
+    editor.set_selection(None);
+    c.bench_function("editor/search_while_typing", |b| {
+        b.iter(|| {
+            editor.apply(InsertText {
+                text: "total".to_string(),
+            });
+            editor.search_state()
+        })
+    });
+}
+
+criterion_group!(
+    benches,
+    bench_typing,
+    bench_paste,
+    bench_scrolling,
+    bench_highlighting,
+    bench_search
+);
+criterion_main!(benches);
@@ -1,4 +1,5 @@
 use crossterm::{
+    cursor::SetCursorStyle,
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
     },
@@ -34,19 +35,17 @@ fn main() -> anyhow::Result<()> {
     let theme = vesper();
 
     let mut editor = Editor::new(&language, &content, theme)?;
-    let mut editor_area = ratatui::layout::Rect::default();
 
     loop {
         terminal.draw(|f| {
-            let area = f.area();
-            editor_area = area;
-            f.render_widget(&editor, area);
+            f.render_widget(&editor, f.area());
 
-            let cursor = editor.get_visible_cursor(&area);
+            let cursor = editor.visible_cursor();
             if let Some((x, y)) = cursor {
                 f.set_cursor_position(Position::new(x, y));
             }
         })?;
+        execute!(stdout(), SetCursorStyle::from(editor.cursor_style()))?;
 
         if event::poll(std::time::Duration::from_millis(100))? {
             match event::read()? {
@@ -57,11 +56,11 @@ fn main() -> anyhow::Result<()> {
                         let content = editor.get_content();
                         save_to_file(&content, filename)?;
                     } else {
-                        editor.input(key, &editor_area)?;
+                        editor.handle_key(key)?;
                     }
                 }
                 Event::Mouse(mouse) => {
-                    editor.mouse(mouse, &editor_area)?;
+                    editor.handle_mouse(mouse)?;
                 }
                 Event::Resize(_, _) => {}
                 _ => {}
@@ -72,6 +71,7 @@ fn main() -> anyhow::Result<()> {
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
+        SetCursorStyle::DefaultUserShape,
         LeaveAlternateScreen,
         DisableMouseCapture
     )?;
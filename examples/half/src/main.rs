@@ -26,8 +26,6 @@ fn main() -> anyhow::Result<()> {
 
     let mut editor = Editor::new(&language, &content, theme)?;
 
-    let mut editor_area = ratatui::layout::Rect::default();
-
     let layout = Layout::default()
         .direction(Direction::Vertical)
         .constraints([Constraint::Percentage(50), Constraint::Percentage(50)].as_ref());
@@ -37,11 +35,10 @@ fn main() -> anyhow::Result<()> {
             let block = Block::default().title(" Editor ").borders(Borders::ALL);
             let chunks = layout.split(f.area());
             let inner = block.inner(chunks[0]);
-            editor_area = inner;
             f.render_widget(block, chunks[0]);
-            f.render_widget(&editor, editor_area);
+            f.render_widget(&editor, inner);
 
-            let cursor = editor.get_visible_cursor(&editor_area);
+            let cursor = editor.visible_cursor();
             if let Some((x, y)) = cursor {
                 f.set_cursor_position(Position::new(x, y));
             }
@@ -53,11 +50,11 @@ fn main() -> anyhow::Result<()> {
                     if key.code == KeyCode::Esc {
                         break;
                     } else {
-                        editor.input(key, &editor_area)?;
+                        editor.handle_key(key)?;
                     }
                 }
                 Event::Mouse(mouse) => {
-                    editor.mouse(mouse, &editor_area)?;
+                    editor.handle_mouse(mouse)?;
                 }
                 Event::Resize(_, _) => {}
                 _ => {}
@@ -29,6 +29,7 @@ fn main() -> anyhow::Result<()> {
 
     let mut editor1 = Editor::new(&language, &content1, theme.clone())?;
     let mut editor2 = Editor::new(&language, &content2, theme)?;
+    editor2.set_focused(false);
 
     let mut editor1_area = ratatui::layout::Rect::default();
     let mut editor2_area = ratatui::layout::Rect::default();
@@ -53,10 +54,10 @@ fn main() -> anyhow::Result<()> {
             f.render_widget(&editor1, editor1_area);
             f.render_widget(&editor2, editor2_area);
 
-            let cursor = match active_editor {
-                0 => editor1.get_visible_cursor(&editor1_area),
-                _ => editor2.get_visible_cursor(&editor2_area),
-            };
+            // Only the focused editor reports a cursor
+            let cursor = editor1
+                .get_visible_cursor(&editor1_area)
+                .or_else(|| editor2.get_visible_cursor(&editor2_area));
 
             if let Some((x, y)) = cursor {
                 f.set_cursor_position(Position::new(x, y));
@@ -70,12 +71,12 @@ fn main() -> anyhow::Result<()> {
                         break;
                     } else if key.code == KeyCode::Tab {
                         active_editor = (active_editor + 1) % 2;
+                        editor1.set_focused(active_editor == 0);
+                        editor2.set_focused(active_editor == 1);
                     } else {
-                        match active_editor {
-                            0 => editor1.input(key, &editor1_area)?,
-                            1 => editor2.input(key, &editor2_area)?,
-                            _ => {}
-                        }
+                        // Unfocused editors ignore keys
+                        editor1.input(key, &editor1_area)?;
+                        editor2.input(key, &editor2_area)?;
                     }
                 }
                 Event::Mouse(mouse) => {
@@ -83,6 +84,8 @@ fn main() -> anyhow::Result<()> {
                         detect_active_editor(&mouse, editor1_area, editor2_area)
                     {
                         active_editor = new_active;
+                        editor1.set_focused(active_editor == 0);
+                        editor2.set_focused(active_editor == 1);
                     }
 
                     match active_editor {
@@ -0,0 +1,10 @@
+; Names shown for the declarations around the cursor
+
+(function_declaration
+  name: (identifier) @name) @breadcrumb
+
+(method_declaration
+  name: (field_identifier) @name) @breadcrumb
+
+(type_spec
+  name: (type_identifier) @name) @breadcrumb
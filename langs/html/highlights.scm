@@ -11,11 +11,3 @@
   "</"
   "/>"
 ] @punctuation.bracket
-
-(script_element
-  (raw_text) @injection.content.javascript)
- (#set! injection.language "javascript")
-
-(style_element
-  (raw_text) @injection.content.css)
- (#set! injection.language "css")
\ No newline at end of file
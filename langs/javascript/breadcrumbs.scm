@@ -0,0 +1,17 @@
+; Names shown for the declarations around the cursor
+
+(class_declaration
+  name: (_) @name) @breadcrumb
+
+(function_declaration
+  name: (identifier) @name) @breadcrumb
+
+(generator_function_declaration
+  name: (identifier) @name) @breadcrumb
+
+(method_definition
+  name: (_) @name) @breadcrumb
+
+(variable_declarator
+  name: (identifier) @name
+  value: [(arrow_function) (function_expression)]) @breadcrumb
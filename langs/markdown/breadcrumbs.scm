@@ -0,0 +1,7 @@
+; Headings of the sections around the cursor
+
+(section
+  [
+    (atx_heading heading_content: (_) @name)
+    (setext_heading heading_content: (_) @name)
+  ]) @breadcrumb
@@ -0,0 +1,14 @@
+((inline) @injection.content
+ (#set! injection.language "markdown-inline"))
+
+((html_block) @injection.content
+ (#set! injection.language "html"))
+
+((minus_metadata) @injection.content (#set! injection.language "yaml"))
+
+((plus_metadata) @injection.content (#set! injection.language "toml"))
+
+(fenced_code_block
+  (info_string
+    (language) @injection.language)
+  (code_fence_content) @injection.content)
@@ -0,0 +1,7 @@
+; Names shown for the declarations around the cursor
+
+(class_definition
+  name: (identifier) @name) @breadcrumb
+
+(function_definition
+  name: (identifier) @name) @breadcrumb
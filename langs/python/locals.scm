@@ -0,0 +1,25 @@
+;; Scopes
+
+[
+  (module)
+  (function_definition)
+  (lambda)
+] @local.scope
+
+;; Definitions
+
+; Parameters
+(parameters (identifier) @local.definition)
+(parameters (typed_parameter (identifier) @local.definition))
+(parameters (default_parameter name: (identifier) @local.definition))
+(parameters (typed_default_parameter name: (identifier) @local.definition))
+(parameters (list_splat_pattern (identifier) @local.definition))
+(parameters (dictionary_splat_pattern (identifier) @local.definition))
+(lambda_parameters (identifier) @local.definition)
+
+; Assignments
+(assignment left: (identifier) @local.definition)
+
+;; References
+
+(identifier) @local.reference
@@ -0,0 +1,34 @@
+; Names shown for the declarations around the cursor. The name is the text
+; from the first @name capture to the last one.
+
+(mod_item
+  name: (identifier) @name) @breadcrumb
+
+(impl_item
+  trait: (_) @name
+  type: (_) @name) @breadcrumb
+
+(impl_item
+  !trait
+  type: (_) @name) @breadcrumb
+
+(trait_item
+  name: (type_identifier) @name) @breadcrumb
+
+(struct_item
+  name: (type_identifier) @name) @breadcrumb
+
+(enum_item
+  name: (type_identifier) @name) @breadcrumb
+
+(union_item
+  name: (type_identifier) @name) @breadcrumb
+
+(function_item
+  name: (identifier) @name) @breadcrumb
+
+(function_signature_item
+  name: (identifier) @name) @breadcrumb
+
+(macro_definition
+  name: (identifier) @name) @breadcrumb
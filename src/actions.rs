@@ -1,9 +1,21 @@
-use crate::code::Operation;
+use crate::code::{NodeEdit, Operation};
 use crate::editor::Editor;
+use crate::events::EditorEvent;
+use crate::list::{ListItem, Marker};
+use crate::number::NumberLiteral;
 use crate::selection::Selection;
+use crate::types::{LineEnding, PasteMode};
+use crate::width::str_width;
+use regex::Regex;
 
 pub trait Action {
     fn apply(&mut self, editor: &mut Editor);
+
+    /// Whether the action works with secondary selections. For other
+    /// actions, [`Editor::apply`] drops them before applying.
+    fn supports_multiple_selections(&self) -> bool {
+        false
+    }
 }
 
 /// Moves the cursor one character to the right.
@@ -32,8 +44,8 @@ impl Action for MoveRight {
             }
         }
 
-        if cursor < editor.code_ref().len() {
-            let code = editor.code_ref();
+        if cursor < editor.borrow_code().len() {
+            let code = editor.borrow_code();
             let mut new_cursor = code.next_grapheme_boundary(cursor);
             let new_line = code.char_to_line(new_cursor);
             if !editor.line_visible(new_line) {
@@ -41,6 +53,7 @@ impl Action for MoveRight {
                     new_cursor = code.line_to_char(next_line);
                 }
             }
+            drop(code);
             if self.shift {
                 editor.extend_selection(new_cursor);
             } else {
@@ -79,7 +92,7 @@ impl Action for MoveLeft {
         }
 
         if cursor > 0 {
-            let code = editor.code_ref();
+            let code = editor.borrow_code();
             let mut new_cursor = code.prev_grapheme_boundary(cursor);
             let new_line = code.char_to_line(new_cursor);
             if !editor.line_visible(new_line) {
@@ -88,6 +101,7 @@ impl Action for MoveLeft {
                         code.line_to_char(prev_line) + code.line_len(prev_line).saturating_sub(1);
                 }
             }
+            drop(code);
             if self.shift {
                 editor.extend_selection(new_cursor);
             } else {
@@ -99,6 +113,120 @@ impl Action for MoveLeft {
     }
 }
 
+/// Moves the cursor to the end of the next identifier part, see
+/// [`crate::code::Code::next_sub_word_boundary`].
+///
+/// If `shift` is true, the selection is extended to the new cursor position,
+/// otherwise it is cleared.
+pub struct MoveSubWordRight {
+    pub shift: bool,
+}
+
+impl Action for MoveSubWordRight {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let new_cursor = editor.borrow_code().next_sub_word_boundary(cursor);
+        move_cursor_to(editor, new_cursor, self.shift);
+    }
+}
+
+/// Moves the cursor to the start of the previous identifier part, see
+/// [`crate::code::Code::prev_sub_word_boundary`].
+///
+/// If `shift` is true, the selection is extended to the new cursor position,
+/// otherwise it is cleared.
+pub struct MoveSubWordLeft {
+    pub shift: bool,
+}
+
+impl Action for MoveSubWordLeft {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let new_cursor = editor.borrow_code().prev_sub_word_boundary(cursor);
+        move_cursor_to(editor, new_cursor, self.shift);
+    }
+}
+
+/// Moves the cursor down to the blank line after the paragraph, see
+/// [`crate::code::Code::next_paragraph_boundary`].
+///
+/// If `shift` is true, the selection is extended to the new cursor position,
+/// otherwise it is cleared.
+pub struct MoveParagraphDown {
+    pub shift: bool,
+}
+
+impl Action for MoveParagraphDown {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let new_cursor = editor.borrow_code().next_paragraph_boundary(cursor);
+        move_cursor_to(editor, new_cursor, self.shift);
+    }
+}
+
+/// Moves the cursor up to the blank line before the paragraph, see
+/// [`crate::code::Code::prev_paragraph_boundary`].
+///
+/// If `shift` is true, the selection is extended to the new cursor position,
+/// otherwise it is cleared.
+pub struct MoveParagraphUp {
+    pub shift: bool,
+}
+
+impl Action for MoveParagraphUp {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let new_cursor = editor.borrow_code().prev_paragraph_boundary(cursor);
+        move_cursor_to(editor, new_cursor, self.shift);
+    }
+}
+
+/// Moves the cursor to the start of the enclosing block, see
+/// [`crate::code::Code::enclosing_blocks`]. At the start already, it moves
+/// to the start of the block around that one.
+///
+/// If `shift` is true, the selection is extended to the new cursor position,
+/// otherwise it is cleared.
+pub struct MoveBlockStart {
+    pub shift: bool,
+}
+
+impl Action for MoveBlockStart {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let blocks = editor.borrow_code().enclosing_blocks(cursor);
+        if let Some(&(start, _)) = blocks.iter().find(|&&(start, _)| start < cursor) {
+            move_cursor_to(editor, start, self.shift);
+        }
+    }
+}
+
+/// Moves the cursor to the end of the enclosing block, or of the block
+/// around it when at the end already, see [`MoveBlockStart`].
+pub struct MoveBlockEnd {
+    pub shift: bool,
+}
+
+impl Action for MoveBlockEnd {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let blocks = editor.borrow_code().enclosing_blocks(cursor);
+        if let Some(&(_, end)) = blocks.iter().find(|&&(_, end)| end > cursor) {
+            move_cursor_to(editor, end, self.shift);
+        }
+    }
+}
+
+fn move_cursor_to(editor: &mut Editor, new_cursor: usize, shift: bool) {
+    if shift {
+        editor.extend_selection(new_cursor);
+    } else {
+        editor.clear_selection();
+    }
+    editor.set_cursor(new_cursor);
+    editor.clamp_cursor_to_focus_rows();
+}
+
 /// Moves the cursor one line up.
 ///
 /// If the previous line is shorter, the cursor is placed at the end of that line.
@@ -111,7 +239,7 @@ pub struct MoveUp {
 impl Action for MoveUp {
     fn apply(&mut self, editor: &mut Editor) {
         let cursor = editor.get_cursor();
-        let code = editor.code_ref();
+        let code = editor.borrow_code();
         let (row, col) = code.point(cursor);
 
         let Some(prev_row) = editor.prev_line(row) else {
@@ -125,6 +253,7 @@ impl Action for MoveUp {
             .visual_to_char_col(prev_row, current_visual_col)
             .min(prev_len);
         let new_cursor = prev_start + new_col;
+        drop(code);
 
         // Update selection or clear it
         if self.shift {
@@ -152,7 +281,7 @@ pub struct MoveDown {
 impl Action for MoveDown {
     fn apply(&mut self, editor: &mut Editor) {
         let cursor = editor.get_cursor();
-        let code = editor.code_ref();
+        let code = editor.borrow_code();
         let (row, col) = code.point(cursor);
         let Some(next_row) = editor.next_line(row) else {
             return;
@@ -165,6 +294,7 @@ impl Action for MoveDown {
             .visual_to_char_col(next_row, current_visual_col)
             .min(next_len);
         let new_cursor = next_start + new_col;
+        drop(code);
 
         // Update selection or clear it
         if self.shift {
@@ -179,6 +309,53 @@ impl Action for MoveDown {
     }
 }
 
+/// Scrolls the view up by `lines` without moving the cursor, like Vim's
+/// `CTRL-Y`. With `keep_cursor`, a cursor scrolled out of view moves to the
+/// nearest visible line. See [`Editor::scroll_view`].
+pub struct ScrollViewUp {
+    pub lines: usize,
+    pub keep_cursor: bool,
+}
+
+impl Action for ScrollViewUp {
+    fn apply(&mut self, editor: &mut Editor) {
+        let lines = isize::try_from(self.lines).unwrap_or(isize::MAX);
+        editor.scroll_view(-lines, self.keep_cursor);
+    }
+}
+
+/// Scrolls the view down by `lines` without moving the cursor, like Vim's
+/// `CTRL-E`.
+pub struct ScrollViewDown {
+    pub lines: usize,
+    pub keep_cursor: bool,
+}
+
+impl Action for ScrollViewDown {
+    fn apply(&mut self, editor: &mut Editor) {
+        let lines = isize::try_from(self.lines).unwrap_or(isize::MAX);
+        editor.scroll_view(lines, self.keep_cursor);
+    }
+}
+
+/// Scrolls half a page up and moves the cursor along, like Vim's `CTRL-U`.
+pub struct ScrollHalfPageUp;
+
+impl Action for ScrollHalfPageUp {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.scroll_half_page(false);
+    }
+}
+
+/// Scrolls half a page down and moves the cursor along, like Vim's `CTRL-D`.
+pub struct ScrollHalfPageDown;
+
+impl Action for ScrollHalfPageDown {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.scroll_half_page(true);
+    }
+}
+
 /// Inserts arbitrary text at the cursor, replacing the selection if any.
 pub struct InsertText {
     pub text: String,
@@ -186,12 +363,22 @@ pub struct InsertText {
 
 impl Action for InsertText {
     fn apply(&mut self, editor: &mut Editor) {
+        if self.text == " " && editor.expand_abbreviation() {
+            return;
+        }
+        // the buffer always uses `\n`, see `Editor::line_ending`
+        let text = LineEnding::normalize(&self.text).into_owned();
+        if editor.has_secondary_selections() {
+            editor.edit_selections(|_, sel| (sel.start, sel.end, text.clone()));
+            return;
+        }
+
         // 1. Extract current cursor and selection
         let mut cursor = editor.get_cursor();
         let mut selection = editor.get_selection();
 
         // 2. Work with code
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
         code.tx();
         code.set_state_before(cursor, selection);
 
@@ -206,17 +393,51 @@ impl Action for InsertText {
         selection = None;
 
         // 4. Insert the text at the cursor
-        code.insert(cursor, &self.text);
-        cursor += self.text.chars().count();
+        code.insert(cursor, &text);
+        cursor += text.chars().count();
 
         // 5. Update editor state
         code.set_state_after(cursor, selection);
         code.commit();
+        drop(code);
 
         editor.set_cursor(cursor);
         editor.set_selection(selection);
         editor.reset_highlight_cache();
     }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
+}
+
+/// Types text in overwrite mode: it replaces as many characters after the
+/// cursor as it has, without reaching past the end of the line. The
+/// replacement is one undo step. A selection is replaced as by
+/// [`InsertText`], and text with line breaks is inserted.
+pub struct OverwriteText {
+    pub text: String,
+}
+
+impl Action for OverwriteText {
+    fn apply(&mut self, editor: &mut Editor) {
+        let len = self.text.chars().count();
+        let single_line = !self.text.contains('\n');
+        editor.edit_selections(|code, sel| {
+            if !sel.is_empty() || !single_line {
+                let (start, end) = sel.sorted();
+                return (start, end, self.text.clone());
+            }
+            let line = code.char_to_line(sel.start);
+            let line_end = code.line_to_char(line) + code.line_len(line);
+            let end = (sel.start + len).min(line_end);
+            (sel.start, end, self.text.clone())
+        });
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
 }
 
 /// Inserts a newline at the cursor with automatic indentation.
@@ -227,9 +448,22 @@ pub struct InsertNewline;
 
 impl Action for InsertNewline {
     fn apply(&mut self, editor: &mut Editor) {
+        if editor.list_continuation() && continue_list(editor) {
+            return;
+        }
+        // Each cursor gets the indentation of its own line
+        if editor.has_secondary_selections() {
+            editor.edit_selections(|code, sel| {
+                let (row, col) = code.point(sel.start);
+                let indent = code.indent().repeat(code.indentation_level(row, col));
+                (sel.start, sel.end, format!("\n{indent}"))
+            });
+            return;
+        }
+
         // 1. Get current cursor position
         let cursor = editor.get_cursor();
-        let code = editor.code_mut();
+        let code = editor.borrow_code_mut();
         let (row, col) = code.point(cursor);
 
         // 2. Compute indentation for the new line
@@ -243,8 +477,13 @@ impl Action for InsertNewline {
         let mut insert_action = InsertText {
             text: text_to_insert,
         };
+        drop(code);
         insert_action.apply(editor);
     }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
 }
 
 /// Deletes the selected text or the character before the cursor.
@@ -256,12 +495,20 @@ pub struct Delete;
 
 impl Action for Delete {
     fn apply(&mut self, editor: &mut Editor) {
+        if editor.has_secondary_selections() {
+            editor.edit_selections(|code, sel| match sel.is_empty() {
+                true => (code.prev_grapheme_boundary(sel.start), sel.end, String::new()),
+                false => (sel.start, sel.end, String::new()),
+            });
+            return;
+        }
+
         // 1. Extract current cursor and selection
         let mut cursor = editor.get_cursor();
         let mut selection = editor.get_selection();
 
         // 2. Work with code
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
         code.tx();
         code.set_state_before(cursor, selection);
 
@@ -290,11 +537,16 @@ impl Action for Delete {
         // 3. Commit changes and update editor
         code.set_state_after(cursor, selection);
         code.commit();
+        drop(code);
 
         editor.set_cursor(cursor);
         editor.set_selection(selection);
         editor.reset_highlight_cache();
     }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
 }
 
 pub struct ToggleComment;
@@ -314,12 +566,15 @@ impl Action for ToggleComment {
         let selection_anchor = editor.selection_anchor();
 
         // 2. Work with code
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
+        let comment_text = code.comment();
+        if comment_text.is_empty() {
+            return;
+        }
 
         code.tx();
         code.set_state_before(cursor, selection);
 
-        let comment_text = code.comment();
         let comment_len = comment_text.chars().count();
 
         // 3. Determine lines to modify
@@ -401,6 +656,7 @@ impl Action for ToggleComment {
         // 7. Commit changes
         code.set_state_after(cursor, selection);
         code.commit();
+        drop(code);
 
         // 8. Return changed values to the editor
         editor.set_cursor(cursor);
@@ -418,13 +674,17 @@ pub struct Indent;
 
 impl Action for Indent {
     fn apply(&mut self, editor: &mut Editor) {
+        if editor.expand_abbreviation() {
+            return;
+        }
+
         // 1. Extract current cursor and selection
         let mut cursor = editor.get_cursor();
         let mut selection = editor.get_selection();
         let selection_anchor = editor.selection_anchor();
 
         // 2. Work with code
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
         code.tx();
         code.set_state_before(cursor, selection);
 
@@ -475,6 +735,7 @@ impl Action for Indent {
         // 6. Commit changes
         code.set_state_after(cursor, selection);
         code.commit();
+        drop(code);
 
         editor.set_cursor(cursor);
         editor.set_selection(selection);
@@ -497,7 +758,7 @@ impl Action for UnIndent {
         let selection_anchor = editor.selection_anchor();
 
         // 2. Work with code
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
         code.tx();
         code.set_state_before(cursor, selection);
 
@@ -554,6 +815,7 @@ impl Action for UnIndent {
         // 6. Commit changes
         code.set_state_after(cursor, selection);
         code.commit();
+        drop(code);
 
         editor.set_cursor(cursor);
         editor.set_selection(selection);
@@ -561,6 +823,149 @@ impl Action for UnIndent {
     }
 }
 
+/// Continues the list item at the cursor line with a new item, or ends the
+/// list if the item is empty. Returns false if the line is not a list item.
+fn continue_list(editor: &mut Editor) -> bool {
+    if editor.has_secondary_selections() || editor.get_selection().is_some_and(|s| s.is_active()) {
+        return false;
+    }
+    let cursor = editor.get_cursor();
+    let code = editor.borrow_code();
+    let line_start = code.line_to_char(code.char_to_line(cursor));
+    let before_cursor = code.slice(line_start, cursor);
+    let Some(item) = ListItem::parse(&before_cursor) else {
+        return false;
+    };
+    if before_cursor.len() < item.prefix_len {
+        return false;
+    }
+
+    let line = code.char_to_line(cursor);
+    let line_end = line_start + code.line_len(line);
+    let empty_item = before_cursor[item.prefix_len..].trim().is_empty()
+        && code.slice(cursor, line_end).trim().is_empty();
+    drop(code);
+
+    let mut code = editor.borrow_code_mut();
+    code.tx();
+    code.set_state_before(cursor, None);
+    let cursor = if empty_item {
+        // An empty item ends the list
+        code.remove(line_start, line_end);
+        line_start
+    } else {
+        let text = format!("\n{}", item.next_prefix());
+        code.insert(cursor, &text);
+        cursor + text.chars().count()
+    };
+    code.set_state_after(cursor, None);
+    code.commit();
+    drop(code);
+
+    editor.set_cursor(cursor);
+    editor.reset_highlight_cache();
+    true
+}
+
+/// Renumbers the ordered list around the cursor from its first number.
+/// Nested items and lists with other markers are left alone.
+pub struct RenumberList;
+
+impl Action for RenumberList {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let code = editor.borrow_code();
+        let line_text = |line: usize| {
+            code.line(line)
+                .to_string()
+                .trim_end_matches('\n')
+                .to_string()
+        };
+        let row = code.char_to_line(cursor);
+        let Some(ListItem {
+            indent,
+            marker: Marker::Number(_, delimiter),
+            ..
+        }) = ListItem::parse(&line_text(row))
+        else {
+            return;
+        };
+
+        // Items of this list, and lines nested deeper, belong to the block
+        let belongs = |line: usize| {
+            let text = line_text(line);
+            match ListItem::parse(&text) {
+                Some(item) if item.indent == indent => {
+                    matches!(item.marker, Marker::Number(_, d) if d == delimiter)
+                }
+                _ => {
+                    !text.trim().is_empty()
+                        && text.starts_with(&indent)
+                        && text.len() > indent.len()
+                        && text[indent.len()..].starts_with([' ', '\t'])
+                }
+            }
+        };
+        let mut first = row;
+        while first > 0 && belongs(first - 1) {
+            first -= 1;
+        }
+        let mut last = row;
+        while last + 1 < code.len_lines() && belongs(last + 1) {
+            last += 1;
+        }
+
+        let mut edits = Vec::new();
+        let mut number = None;
+        for line in first..=last {
+            let text = line_text(line);
+            let Some(ListItem {
+                indent: item_indent,
+                marker: Marker::Number(n, _),
+                ..
+            }) = ListItem::parse(&text)
+            else {
+                continue;
+            };
+            if item_indent != indent {
+                continue;
+            }
+            let expected = *number.get_or_insert(n);
+            number = Some(expected + 1);
+            if n != expected {
+                let start = code.line_to_char(line) + indent.chars().count();
+                let digits = n.to_string().len();
+                edits.push((start, start + digits, expected.to_string()));
+            }
+        }
+        if edits.is_empty() {
+            return;
+        }
+        drop(code);
+
+        let selection = editor.get_selection();
+        let mut code = editor.borrow_code_mut();
+        code.tx();
+        code.set_state_before(cursor, selection);
+        let mut cursor = cursor;
+        for (start, end, text) in edits.iter().rev() {
+            code.remove(*start, *end);
+            code.insert(*start, text);
+            if cursor > *start {
+                cursor = (cursor + text.len())
+                    .saturating_sub(end - start)
+                    .max(*start);
+            }
+        }
+        code.set_state_after(cursor, selection);
+        code.commit();
+        drop(code);
+
+        editor.set_cursor(cursor);
+        editor.reset_highlight_cache();
+    }
+}
+
 /// Selects the entire text in the editor.
 pub struct SelectAll;
 
@@ -568,9 +973,10 @@ impl Action for SelectAll {
     fn apply(&mut self, editor: &mut Editor) {
         // Set selection from start to end of the document
         let from = 0;
-        let code = editor.code_mut();
+        let code = editor.borrow_code_mut();
         let to = code.len_chars();
         let sel = Selection::new(from, to);
+        drop(code);
         editor.set_selection(Some(sel));
     }
 }
@@ -587,7 +993,7 @@ impl Action for Duplicate {
         // 1. Extract current cursor and selection
         let mut cursor = editor.get_cursor();
         let mut selection = editor.get_selection();
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
 
         code.tx();
         code.set_state_before(cursor, selection);
@@ -621,6 +1027,7 @@ impl Action for Duplicate {
 
         code.set_state_after(cursor, selection);
         code.commit();
+        drop(code);
 
         // Update editor state
         editor.set_cursor(cursor);
@@ -637,7 +1044,7 @@ impl Action for DeleteLine {
         // 1. Extract current cursor and selection
         let mut cursor = editor.get_cursor();
         let mut selection = editor.get_selection();
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
 
         // 2. Compute line boundaries
         let (start, end) = code.line_boundaries(cursor);
@@ -657,6 +1064,7 @@ impl Action for DeleteLine {
         // 4. Update editor state
         cursor = start;
         selection = None;
+        drop(code);
         editor.set_cursor(cursor);
         editor.set_selection(selection);
         editor.reset_highlight_cache();
@@ -674,15 +1082,20 @@ impl Action for Cut {
 
         let sel = match &selection {
             Some(sel) if !sel.is_empty() => sel.clone(),
+            _ if editor.line_wise_copy() => {
+                cut_line(editor);
+                return;
+            }
             _ => return, // nothing to cut
         };
 
         // 2. Copy to clipboard first, before borrowing code mutably
-        let text = editor.code_ref().slice(sel.start, sel.end);
+        let text = editor.borrow_code().slice(sel.start, sel.end);
         let _ = editor.set_clipboard(&text);
+        editor.line_wise_clipboard = None;
 
         // 3. Now borrow code mutably
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
         code.tx();
         code.set_state_before(cursor, selection);
         code.remove(sel.start, sel.end);
@@ -692,6 +1105,7 @@ impl Action for Cut {
         // 4. Update editor state
         cursor = sel.start;
         selection = None;
+        drop(code);
         editor.set_cursor(cursor);
         editor.set_selection(selection);
         editor.reset_highlight_cache();
@@ -708,68 +1122,857 @@ impl Action for Copy {
         // 1. Extract current selection
         let selection = editor.get_selection();
 
-        // 2. Return early if no selection
-        let Some(sel) = selection else { return };
-        if sel.is_empty() {
-            return;
-        }
+        // 2. Without a selection, copy the whole line if enabled
+        let sel = match selection {
+            Some(sel) if !sel.is_empty() => sel,
+            _ if editor.line_wise_copy() => {
+                let (_, _, text) = cursor_line(editor);
+                let _ = editor.set_clipboard(&text);
+                editor.line_wise_clipboard = Some(text);
+                return;
+            }
+            _ => return,
+        };
 
         // 3. Get text and copy to clipboard
-        let text = editor.code_ref().slice(sel.start, sel.end);
+        let text = editor.borrow_code().slice(sel.start, sel.end);
         let _ = editor.set_clipboard(&text);
+        editor.line_wise_clipboard = None;
     }
 }
 
+/// Range of the cursor line including its line break, and its text with a
+/// line break added if it is the last line.
+fn cursor_line(editor: &Editor) -> (usize, usize, String) {
+    let code = editor.borrow_code();
+    let line = code.char_to_line(editor.get_cursor());
+    let start = code.line_to_char(line);
+    let end = start + code.line_len(line);
+    let end = if end < code.len_chars() { end + 1 } else { end };
+    let mut text = code.slice(start, end);
+    if !text.ends_with('\n') {
+        text.push('\n');
+    }
+    (start, end, text)
+}
+
+/// Cuts the cursor line into a line-wise clipboard.
+fn cut_line(editor: &mut Editor) {
+    let cursor = editor.get_cursor();
+    let (start, end, text) = cursor_line(editor);
+    let _ = editor.set_clipboard(&text);
+    editor.line_wise_clipboard = Some(text);
+
+    let mut code = editor.borrow_code_mut();
+    // The last line takes the line break before it
+    let start = if end == code.len_chars() && start > 0 {
+        start - 1
+    } else {
+        start
+    };
+    code.tx();
+    code.set_state_before(cursor, None);
+    code.remove(start, end);
+    let cursor = code.line_to_char(code.char_to_line(start));
+    code.set_state_after(cursor, None);
+    code.commit();
+    drop(code);
+
+    editor.set_cursor(cursor);
+    editor.set_selection(None);
+    editor.reset_highlight_cache();
+}
+
 /// Pastes text from the clipboard at the current cursor position.
 ///
 /// If a selection exists, it will be replaced by the pasted text.
-/// The pasted text is adjusted using language-specific indentation rules.
+/// In [`PasteMode::Smart`] the pasted text is adjusted using language-specific
+/// indentation rules; in [`PasteMode::Literal`] it is inserted unchanged.
 pub struct Paste;
 
 impl Action for Paste {
     fn apply(&mut self, editor: &mut Editor) {
-        // 1. Get clipboard contents
-        let Ok(text) = editor.get_clipboard() else {
+        let literal = editor.paste_mode() == PasteMode::Literal;
+        paste(editor, literal);
+    }
+}
+
+/// Pastes text from the clipboard exactly as it is, without re-indenting,
+/// regardless of the editor's paste mode.
+pub struct PasteLiteral;
+
+impl Action for PasteLiteral {
+    fn apply(&mut self, editor: &mut Editor) {
+        paste(editor, true);
+    }
+}
+
+/// Pastes the primary selection unchanged at the cursor, as middle click
+/// does, see [`Editor::set_primary_selection_enabled`].
+pub struct PastePrimary;
+
+impl Action for PastePrimary {
+    fn apply(&mut self, editor: &mut Editor) {
+        if let Ok(text) = editor.get_primary_selection() {
+            paste_text(editor, &text, true);
+        }
+    }
+}
+
+/// Moves the selected text to `to`, or copies it there if `copy` is set,
+/// and selects it at its new place. Moving it into itself does nothing.
+pub struct MoveSelection {
+    pub to: usize,
+    pub copy: bool,
+}
+
+impl Action for MoveSelection {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let Some(selection) = editor.get_selection().filter(|s| !s.is_empty()) else {
             return;
         };
-        if text.is_empty() {
+        let (start, end) = selection.sorted();
+        let mut code = editor.borrow_code_mut();
+        let to = self.to.min(code.len_chars());
+        if !self.copy && (start..=end).contains(&to) {
             return;
         }
+        let text = code.slice(start, end);
+        let len = end - start;
 
-        // 2. Extract current cursor and selection
-        let mut cursor = editor.get_cursor();
-        let mut selection = editor.get_selection();
-        let code = editor.code_mut();
+        code.tx();
+        code.set_state_before(cursor, Some(selection));
+        let at = if self.copy || to < start {
+            to
+        } else {
+            to - len
+        };
+        if !self.copy {
+            code.remove(start, end);
+        }
+        code.insert(at, &text);
+        let selection = Some(Selection::new(at, at + len));
+        code.set_state_after(at + len, selection);
+        code.commit();
+        drop(code);
+
+        editor.set_cursor(at + len);
+        editor.set_selection(selection);
+        editor.reset_highlight_cache();
+    }
+}
+
+/// Swaps the selected syntax node, or the one at the cursor, with the next
+/// (or previous, if `forward` is false) named node at the same level, e.g.
+/// two arguments or two functions, and selects it at its new place.
+pub struct SwapNode {
+    pub forward: bool,
+}
 
-        // 3. Prepare transaction
+impl Action for SwapNode {
+    fn apply(&mut self, editor: &mut Editor) {
+        let (start, end) = selection_or_cursor(editor);
+        let edit = editor.borrow_code().swap_node(start, end, self.forward);
+        apply_node_edit(editor, edit);
+    }
+}
+
+/// Replaces the node around the selected syntax node, or the one at the
+/// cursor, with that node, e.g. `f(g(x))` becomes `f(x)`.
+pub struct RaiseNode;
+
+impl Action for RaiseNode {
+    fn apply(&mut self, editor: &mut Editor) {
+        let (start, end) = selection_or_cursor(editor);
+        let edit = editor.borrow_code().raise_node(start, end);
+        apply_node_edit(editor, edit);
+    }
+}
+
+/// Pulls the node after the brackets around the cursor into them (or the
+/// one before, if `forward` is false), e.g. `(a b) c` becomes `(a b c)`.
+pub struct Slurp {
+    pub forward: bool,
+}
+
+impl Action for Slurp {
+    fn apply(&mut self, editor: &mut Editor) {
+        let edit = editor.borrow_code().slurp(editor.get_cursor(), self.forward);
+        apply_node_edit(editor, edit);
+    }
+}
+
+/// Pushes the last node in the brackets around the cursor out of them (or
+/// the first one, if `forward` is false), e.g. `(a b c)` becomes `(a b) c`.
+pub struct Barf {
+    pub forward: bool,
+}
+
+impl Action for Barf {
+    fn apply(&mut self, editor: &mut Editor) {
+        let edit = editor.borrow_code().barf(editor.get_cursor(), self.forward);
+        apply_node_edit(editor, edit);
+    }
+}
+
+fn selection_or_cursor(editor: &mut Editor) -> (usize, usize) {
+    match editor.get_selection().filter(|s| !s.is_empty()) {
+        Some(selection) => selection.sorted(),
+        None => (editor.get_cursor(), editor.get_cursor()),
+    }
+}
+
+/// Applies a structural edit as a single undo step.
+fn apply_node_edit(editor: &mut Editor, edit: Option<NodeEdit>) {
+    let Some(edit) = edit else {
+        return;
+    };
+    let cursor = editor.get_cursor();
+    let selection = editor.get_selection();
+    let new_selection = edit
+        .selection
+        .map(|(start, end)| Selection::new(start, end));
+
+    let mut code = editor.borrow_code_mut();
+    code.tx();
+    code.set_state_before(cursor, selection);
+    code.remove(edit.start, edit.end);
+    code.insert(edit.start, &edit.text);
+    code.set_state_after(edit.cursor, new_selection);
+    code.commit();
+    drop(code);
+
+    editor.set_cursor(edit.cursor);
+    editor.set_selection(new_selection);
+    editor.reset_highlight_cache();
+}
+
+/// Selects the word at the cursor, or adds the next occurrence of the
+/// selected text as another selection.
+pub struct SelectNextOccurrence;
+
+impl Action for SelectNextOccurrence {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.select_next_occurrence();
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
+}
+
+/// Selects every occurrence of the selected text, or of the word at the cursor.
+pub struct SelectAllOccurrences;
+
+impl Action for SelectAllOccurrences {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.select_all_occurrences();
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
+}
+
+/// Rewraps the selected lines, or the comment block or paragraph around the
+/// cursor, so no line is wider than `width` columns.
+///
+/// The indentation and comment prefix (`// `, `# `, ...) of the first line is
+/// repeated on every line, and lines that are empty after the prefix are kept
+/// as paragraph breaks.
+pub struct Reflow {
+    pub width: usize,
+}
+
+impl Action for Reflow {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let selection = editor.get_selection().filter(|s| s.is_active());
+        let code = editor.borrow_code();
+        let comment = code.comment();
+        let line_text = |line: usize| {
+            code.line(line)
+                .to_string()
+                .trim_end_matches('\n')
+                .to_string()
+        };
+
+        // 1. Find the lines to reflow
+        let (first, last) = match selection {
+            Some(sel) => {
+                let first = code.char_to_line(sel.start);
+                let mut last = code.char_to_line(sel.end);
+                if last > first && code.line_to_char(last) == sel.end {
+                    last -= 1;
+                }
+                (first, last)
+            }
+            None => {
+                let line = code.char_to_line(cursor);
+                let prefix = reflow_prefix(&line_text(line), &comment);
+                let same_block = |l: usize| {
+                    let text = line_text(l);
+                    !text.trim().is_empty() && reflow_prefix(&text, &comment) == prefix
+                };
+                if !same_block(line) {
+                    return;
+                }
+                let mut first = line;
+                while first > 0 && same_block(first - 1) {
+                    first -= 1;
+                }
+                let mut last = line;
+                while last + 1 < code.len_lines() && same_block(last + 1) {
+                    last += 1;
+                }
+                (first, last)
+            }
+        };
+
+        // 2. Wrap the words of each paragraph after the shared prefix
+        let prefix = reflow_prefix(&line_text(first), &comment);
+        let available = self.width.saturating_sub(str_width(&prefix)).max(1);
+        let mut lines: Vec<String> = Vec::new();
+        let mut current = String::new();
+        for line in first..=last {
+            let text = line_text(line);
+            let body = text.strip_prefix(prefix.as_str()).unwrap_or(text.trim_start());
+            let body = body.strip_prefix(prefix.trim_start()).unwrap_or(body);
+            if body.trim().is_empty() {
+                if !current.is_empty() {
+                    lines.push(std::mem::take(&mut current));
+                }
+                lines.push(String::new());
+                continue;
+            }
+            for word in body.split_whitespace() {
+                if !current.is_empty() && str_width(&current) + 1 + str_width(word) > available {
+                    lines.push(std::mem::take(&mut current));
+                }
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(word);
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        let text = lines
+            .iter()
+            .map(|line| match line.is_empty() {
+                true => prefix.trim_end().to_string(),
+                false => format!("{prefix}{line}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // 3. Replace the lines in one transaction
+        let start = code.line_to_char(first);
+        let end = code.line_to_char(last) + code.line_len(last);
+        if code.slice(start, end) == text {
+            return;
+        }
+        drop(code);
+
+        let mut code = editor.borrow_code_mut();
         code.tx();
         code.set_state_before(cursor, selection);
+        code.remove(start, end);
+        code.insert(start, &text);
+        let cursor = start + text.chars().count();
+        code.set_state_after(cursor, None);
+        code.commit();
+        drop(code);
 
-        // 4. Remove selection if present
-        if let Some(sel) = &selection {
-            if !sel.is_empty() {
-                let (start, end) = sel.sorted();
-                code.remove(start, end);
-                cursor = start;
-                selection = None;
+        editor.set_cursor(cursor);
+        editor.set_selection(None);
+        editor.reset_highlight_cache();
+    }
+}
+
+/// Indentation plus comment marker and the space after it, e.g. `    /// `.
+fn reflow_prefix(line: &str, comment: &str) -> String {
+    let indent_len = line.len() - line.trim_start().len();
+    let rest = &line[indent_len..];
+    let Some(after) = rest.strip_prefix(comment) else {
+        return line[..indent_len].to_string();
+    };
+    // doc comments and repeated markers: `///`, `//!`, `##`
+    let last = comment.chars().last().unwrap_or(' ');
+    let extra = after.len() - after.trim_start_matches([last, '!']).len();
+    let marker_end = indent_len + comment.len() + extra;
+    let space = line[marker_end..].starts_with(' ') as usize;
+    line[..marker_end + space].to_string()
+}
+
+/// Pads the selected lines with spaces so the first match of the regex
+/// `pattern` starts in the same column on each of them, e.g. `=` to align
+/// assignments. If the pattern has a capture group, the start of the group is
+/// aligned instead, so `:\s*(\S)` lines up YAML values. Without a selection,
+/// the run of lines around the cursor with a match is aligned.
+pub struct Align {
+    pub pattern: String,
+}
+
+impl Action for Align {
+    fn apply(&mut self, editor: &mut Editor) {
+        let Ok(regex) = Regex::new(&self.pattern) else {
+            return;
+        };
+        let cursor = editor.get_cursor();
+        let selection = editor.get_selection().filter(|s| s.is_active());
+        let code = editor.borrow_code();
+        let line_text = |line: usize| {
+            code.line(line)
+                .to_string()
+                .trim_end_matches('\n')
+                .to_string()
+        };
+        // byte offset in the line text to align
+        let align_at = |text: &str| {
+            let captures = regex.captures(text)?;
+            captures.get(1).or(captures.get(0)).map(|m| m.start())
+        };
+
+        // 1. Find the lines to align
+        let (first, last) = match selection {
+            Some(sel) => {
+                let first = code.char_to_line(sel.start);
+                let mut last = code.char_to_line(sel.end);
+                if last > first && code.line_to_char(last) == sel.end {
+                    last -= 1;
+                }
+                (first, last)
             }
+            None => {
+                let line = code.char_to_line(cursor);
+                let matches = |l: usize| align_at(&line_text(l)).is_some();
+                if !matches(line) {
+                    return;
+                }
+                let mut first = line;
+                while first > 0 && matches(first - 1) {
+                    first -= 1;
+                }
+                let mut last = line;
+                while last + 1 < code.len_lines() && matches(last + 1) {
+                    last += 1;
+                }
+                (first, last)
+            }
+        };
+
+        // 2. Pad every match to the rightmost one
+        let points: Vec<(usize, usize)> = (first..=last)
+            .filter_map(|line| {
+                let text = line_text(line);
+                let before = &text[..align_at(&text)?];
+                let at = code.line_to_char(line) + before.chars().count();
+                Some((at, str_width(before)))
+            })
+            .collect();
+        let column = points.iter().map(|&(_, width)| width).max().unwrap_or(0);
+        let pads: Vec<(usize, usize)> = points
+            .into_iter()
+            .filter(|&(_, width)| width < column)
+            .map(|(at, width)| (at, column - width))
+            .collect();
+        if pads.is_empty() {
+            return;
         }
+        drop(code);
+        let shift = |pos: usize| {
+            let padding: usize = pads
+                .iter()
+                .filter(|&&(at, _)| at < pos)
+                .map(|&(_, n)| n)
+                .sum();
+            pos + padding
+        };
 
-        // 5. Perform paste with smart indentation
-        let inserted = code.smart_paste(cursor, &text);
-        cursor += inserted;
+        // 3. Insert the padding in one transaction, last line first
+        let mut code = editor.borrow_code_mut();
+        code.tx();
+        code.set_state_before(cursor, selection);
+        for &(at, n) in pads.iter().rev() {
+            code.insert(at, &" ".repeat(n));
+        }
+        let new_cursor = shift(cursor);
+        let new_selection = selection.map(|sel| Selection::new(shift(sel.start), shift(sel.end)));
+        code.set_state_after(new_cursor, new_selection);
+        code.commit();
+        drop(code);
 
-        // 6. Finalize transaction
-        code.set_state_after(cursor, selection);
+        editor.set_cursor(new_cursor);
+        editor.set_selection(new_selection);
+        editor.reset_highlight_cache();
+    }
+}
+
+/// Adds `count` times the number step ([`Editor::set_number_step`]) to the
+/// number at or after the cursor on its line, like Vim's `CTRL-A`. Decimal,
+/// float, hex, binary and octal literals keep their prefix and width.
+pub struct IncrementNumber {
+    pub count: usize,
+}
+
+impl Action for IncrementNumber {
+    fn apply(&mut self, editor: &mut Editor) {
+        add_to_number(editor, self.count, false);
+    }
+}
+
+/// Subtracts `count` times the number step from the number at or after the
+/// cursor on its line, like Vim's `CTRL-X`.
+pub struct DecrementNumber {
+    pub count: usize,
+}
+
+impl Action for DecrementNumber {
+    fn apply(&mut self, editor: &mut Editor) {
+        add_to_number(editor, self.count, true);
+    }
+}
+
+/// Adds `count` steps to the number at the cursor and puts the cursor on its
+/// last character. A count of 0 counts as 1.
+fn add_to_number(editor: &mut Editor, count: usize, subtract: bool) {
+    let Some(delta) = i64::try_from(count.max(1))
+        .ok()
+        .and_then(|count| count.checked_mul(editor.number_step()))
+        .and_then(|delta| {
+            if subtract {
+                delta.checked_neg()
+            } else {
+                Some(delta)
+            }
+        })
+    else {
+        return;
+    };
+    let cursor = editor.get_cursor();
+    let selection = editor.get_selection();
+    let code = editor.borrow_code();
+    let line = code.char_to_line(cursor);
+    let line_start = code.line_to_char(line);
+    let text = code.line(line).to_string();
+    let col = text
+        .char_indices()
+        .nth(cursor - line_start)
+        .map_or(text.len(), |(i, _)| i);
+    let Some(number) = NumberLiteral::find(&text, col) else {
+        return;
+    };
+    let Some(replacement) = number.add(delta) else {
+        return;
+    };
+    let start = line_start + text[..number.start].chars().count();
+    let end = line_start + text[..number.end].chars().count();
+    drop(code);
+
+    let mut code = editor.borrow_code_mut();
+    code.tx();
+    code.set_state_before(cursor, selection);
+    code.remove(start, end);
+    code.insert(start, &replacement);
+    let cursor = start + replacement.chars().count() - 1;
+    code.set_state_after(cursor, None);
+    code.commit();
+    drop(code);
+
+    editor.set_cursor(cursor);
+    editor.set_selection(None);
+    editor.reset_highlight_cache();
+}
+
+/// Replaces the value at the cursor with the next one (or the previous one,
+/// if `forward` is false) of its cycle in [`LanguageConfig::cycles`], e.g.
+/// `true` with `false` or `Some` with `None`. The longest value around the
+/// cursor wins, so `===` is not taken for `==`, and words only match whole.
+///
+/// [`LanguageConfig::cycles`]: crate::language::LanguageConfig::cycles
+pub struct CycleValue {
+    pub forward: bool,
+}
+
+impl Action for CycleValue {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let selection = editor.get_selection();
+        let code = editor.borrow_code();
+        let line = code.char_to_line(cursor);
+        let line_start = code.line_to_char(line);
+        let chars: Vec<char> = code.line(line).chars().collect();
+        let col = cursor - line_start;
+        let is_word = |i: usize| chars.get(i).is_some_and(|&c| code.is_word_char(c));
+
+        // (start column, length, replacement) of the longest value at the cursor
+        let mut found: Option<(usize, usize, String)> = None;
+        for cycle in &code.language_config().cycles {
+            for (i, value) in cycle.iter().enumerate() {
+                let value: Vec<char> = value.chars().collect();
+                let len = value.len();
+                if len == 0 || found.as_ref().is_some_and(|&(_, l, _)| l >= len) {
+                    continue;
+                }
+                let at = (col.saturating_sub(len)..=col).find(|&start| {
+                    chars.get(start..start + len) == Some(&value[..])
+                        && !(code.is_word_char(value[0]) && start > 0 && is_word(start - 1))
+                        && !(code.is_word_char(value[len - 1]) && is_word(start + len))
+                });
+                if let Some(start) = at {
+                    let next = match self.forward {
+                        true => (i + 1) % cycle.len(),
+                        false => (i + cycle.len() - 1) % cycle.len(),
+                    };
+                    found = Some((start, len, cycle[next].clone()));
+                }
+            }
+        }
+        let Some((start, len, replacement)) = found else {
+            return;
+        };
+        drop(code);
+
+        let start = line_start + start;
+        let end = start + len;
+        let new_end = start + replacement.chars().count();
+        let mut code = editor.borrow_code_mut();
+        code.tx();
+        code.set_state_before(cursor, selection);
+        code.remove(start, end);
+        code.insert(start, &replacement);
+        let cursor = if cursor == end { new_end } else { start };
+        code.set_state_after(cursor, None);
         code.commit();
+        drop(code);
 
-        // 7. Update editor state
         editor.set_cursor(cursor);
-        editor.set_selection(selection);
+        editor.set_selection(None);
         editor.reset_highlight_cache();
     }
 }
 
+/// Moves to the next tabstop of the snippet being filled in.
+pub struct NextTabstop;
+
+impl Action for NextTabstop {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.next_tabstop();
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
+}
+
+/// Moves to the previous tabstop of the snippet being filled in.
+pub struct PrevTabstop;
+
+impl Action for PrevTabstop {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.prev_tabstop();
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
+}
+
+/// Replaces each selection, or the word at a cursor without one, with what
+/// `transform` returns for its text, in one undo step. Hosts build commands
+/// like base64 or URL encoding on it, see [`Editor::transform_selection`].
+pub struct TransformSelection<F: FnMut(&str) -> String> {
+    pub transform: F,
+}
+
+impl<F: FnMut(&str) -> String> Action for TransformSelection<F> {
+    fn apply(&mut self, editor: &mut Editor) {
+        let selections = editor.selections();
+        let code = editor.borrow_code();
+        let mut replacements = Vec::new();
+        for selection in selections {
+            let (start, end) = if selection.is_empty() {
+                code.word_boundaries(selection.start)
+            } else {
+                selection.sorted()
+            };
+            if start < end {
+                let text = (self.transform)(&code.slice(start, end));
+                replacements.push((selection, (start, end, text)));
+            }
+        }
+        drop(code);
+        if replacements.is_empty() {
+            return;
+        }
+        editor.edit_selections(|_, selection| {
+            replacements
+                .iter()
+                .find(|(s, _)| *s == selection)
+                .map(|(_, replacement)| replacement.clone())
+                .unwrap_or((selection.start, selection.start, String::new()))
+        });
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
+}
+
+/// Renames the identifier under the cursor everywhere in the buffer.
+///
+/// Occurrences are found with the syntax tree, see
+/// [`Code::token_occurrences`](crate::code::Code::token_occurrences). With a
+/// `replacement` all of them are replaced in one undo step, otherwise they are
+/// selected so typing renames them together.
+pub struct RenameInBuffer {
+    pub replacement: Option<String>,
+}
+
+impl Action for RenameInBuffer {
+    fn apply(&mut self, editor: &mut Editor) {
+        let cursor = editor.get_cursor();
+        let ranges = editor.borrow_code().token_occurrences(cursor);
+        let Some(&(start, end)) = ranges
+            .iter()
+            .find(|&&(start, end)| cursor >= start && cursor <= end)
+        else {
+            return;
+        };
+
+        let Some(replacement) = &self.replacement else {
+            editor.set_selection(Some(Selection::new(start, end)));
+            editor.set_cursor(end);
+            let others = ranges
+                .iter()
+                .filter(|&&range| range != (start, end))
+                .map(|&(s, e)| Selection::new(s, e))
+                .collect();
+            editor.set_secondary_selections(others);
+            return;
+        };
+
+        let selection = editor.get_selection();
+        let mut code = editor.borrow_code_mut();
+        code.tx();
+        code.set_state_before(cursor, selection);
+        for &(s, e) in ranges.iter().rev() {
+            code.remove(s, e);
+            code.insert(s, replacement);
+        }
+        // keep the cursor at the same place within the renamed token
+        let len = replacement.chars().count();
+        let before = ranges.iter().filter(|&&(s, _)| s < start).count();
+        let new_start = start + before * len - before * (end - start);
+        let cursor = new_start + (cursor - start).min(len);
+        code.set_state_after(cursor, None);
+        code.commit();
+        drop(code);
+
+        editor.set_cursor(cursor);
+        editor.set_selection(None);
+        editor.reset_highlight_cache();
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        true
+    }
+}
+
+/// Restores the most recently dropped selection, like vim's `gv`.
+pub struct ReselectLast;
+
+impl Action for ReselectLast {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.reselect_last();
+    }
+}
+
+/// Replays the last run of editing commands executed through
+/// [`Editor::execute`] at the current cursor, like vim's `.`.
+pub struct RepeatLastEdit;
+
+impl Action for RepeatLastEdit {
+    fn apply(&mut self, editor: &mut Editor) {
+        editor.repeat_last_edit();
+    }
+}
+
+fn paste(editor: &mut Editor, literal: bool) {
+    // 1. Get clipboard contents
+    let Ok(text) = editor.get_clipboard() else {
+        return;
+    };
+    paste_text(editor, &text, literal);
+}
+
+fn paste_text(editor: &mut Editor, text: &str, literal: bool) {
+    let text = LineEnding::normalize(text);
+    if text.is_empty() {
+        return;
+    }
+
+    // Whole lines copied without a selection go above the cursor line
+    let has_selection = editor.get_selection().is_some_and(|s| !s.is_empty());
+    if !has_selection && editor.line_wise_clipboard.as_deref() == Some(&*text) {
+        let cursor = editor.get_cursor();
+        let mut code = editor.borrow_code_mut();
+        let line_start = code.line_to_char(code.char_to_line(cursor));
+        code.tx();
+        code.set_state_before(cursor, None);
+        code.insert(line_start, &text);
+        let cursor = cursor + text.chars().count();
+        code.set_state_after(cursor, None);
+        code.commit();
+        drop(code);
+        editor.set_cursor(cursor);
+        editor.set_selection(None);
+        editor.reset_highlight_cache();
+        return;
+    }
+
+    // 2. Extract current cursor and selection
+    let mut cursor = editor.get_cursor();
+    let mut selection = editor.get_selection();
+    let mut code = editor.borrow_code_mut();
+
+    // 3. Prepare transaction
+    code.tx();
+    code.set_state_before(cursor, selection);
+
+    // 4. Remove selection if present
+    if let Some(sel) = &selection {
+        if !sel.is_empty() {
+            let (start, end) = sel.sorted();
+            code.remove(start, end);
+            cursor = start;
+            selection = None;
+        }
+    }
+
+    // 5. Perform paste, with smart indentation unless literal
+    let inserted = if literal {
+        code.insert(cursor, &text);
+        text.chars().count()
+    } else {
+        code.smart_paste(cursor, &text)
+    };
+    cursor += inserted;
+
+    // 6. Finalize transaction
+    code.set_state_after(cursor, selection);
+    code.commit();
+    drop(code);
+
+    // 7. Update editor state
+    editor.set_cursor(cursor);
+    editor.set_selection(selection);
+    editor.reset_highlight_cache();
+}
+
 /// Undoes the last edit in the code buffer.
 ///
 /// Restores both the cursor position and selection state
@@ -779,14 +1982,16 @@ pub struct Undo;
 impl Action for Undo {
     fn apply(&mut self, editor: &mut Editor) {
         // 1. Get mutable access to code
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
 
         // 2. Try to undo
         let edits = code.undo();
+        drop(code);
         editor.reset_highlight_cache();
 
         // 3. If nothing to undo, return
         let Some(batch) = edits else { return };
+        editor.push_event(EditorEvent::Undo);
 
         // 4. Restore cursor and selection from saved state if possible
         if let Some(before) = batch.state_before {
@@ -818,14 +2023,16 @@ pub struct Redo;
 impl Action for Redo {
     fn apply(&mut self, editor: &mut Editor) {
         // 1. Get mutable access to code
-        let code = editor.code_mut();
+        let mut code = editor.borrow_code_mut();
 
         // 2. Try to redo
         let edits = code.redo();
+        drop(code);
         editor.reset_highlight_cache();
 
         // 3. If nothing to redo, return
         let Some(batch) = edits else { return };
+        editor.push_event(EditorEvent::Redo);
 
         // 4. Restore cursor and selection from saved state if possible
         if let Some(after) = batch.state_after {
@@ -0,0 +1,89 @@
+use std::cell::Cell;
+use std::rc::{Rc, Weak};
+
+/// Which way an anchor moves when text is inserted exactly at its position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnchorBias {
+    /// Stay before text inserted at the anchor position.
+    Left,
+    /// Move after text inserted at the anchor position.
+    Right,
+}
+
+#[derive(Debug)]
+struct AnchorState {
+    offset: Cell<usize>,
+    bias: AnchorBias,
+}
+
+/// A character position that follows edits made to the document it was created for.
+///
+/// Anchors are cheap handles; clones share the same position. The document stops
+/// tracking an anchor once all of its handles are dropped.
+#[derive(Debug, Clone)]
+pub struct Anchor {
+    state: Rc<AnchorState>,
+}
+
+impl Anchor {
+    /// Current character offset of the anchor.
+    pub fn position(&self) -> usize {
+        self.state.offset.get()
+    }
+
+    pub fn bias(&self) -> AnchorBias {
+        self.state.bias
+    }
+}
+
+/// Weak registry of anchors updated by every buffer edit.
+#[derive(Debug, Default)]
+pub(crate) struct AnchorSet {
+    anchors: Vec<Weak<AnchorState>>,
+}
+
+impl AnchorSet {
+    pub(crate) fn create(&mut self, offset: usize, bias: AnchorBias) -> Anchor {
+        let state = Rc::new(AnchorState {
+            offset: Cell::new(offset),
+            bias,
+        });
+        self.anchors.push(Rc::downgrade(&state));
+        Anchor { state }
+    }
+
+    /// Shifts anchors after `len` characters were inserted at `at`.
+    pub(crate) fn on_insert(&mut self, at: usize, len: usize) {
+        self.update(|offset, bias| {
+            if offset > at || (offset == at && bias == AnchorBias::Right) {
+                offset + len
+            } else {
+                offset
+            }
+        });
+    }
+
+    /// Shifts anchors after the characters in `from..to` were removed.
+    /// Anchors inside the removed range collapse to `from`.
+    pub(crate) fn on_remove(&mut self, from: usize, to: usize) {
+        self.update(|offset, _| {
+            if offset >= to {
+                offset - (to - from)
+            } else if offset > from {
+                from
+            } else {
+                offset
+            }
+        });
+    }
+
+    fn update(&mut self, f: impl Fn(usize, AnchorBias) -> usize) {
+        self.anchors.retain(|anchor| {
+            let Some(state) = anchor.upgrade() else {
+                return false;
+            };
+            state.offset.set(f(state.offset.get(), state.bias));
+            true
+        });
+    }
+}
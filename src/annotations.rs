@@ -0,0 +1,32 @@
+use crate::editor::Editor;
+
+impl Editor {
+    /// Replaces the line annotations, e.g. git blame as
+    /// `(line, "jdoe 3 days ago")`. They are drawn dimmed and right-aligned
+    /// after the end of their line and cut to the space the text leaves.
+    pub fn set_line_annotations<S: Into<String>>(
+        &mut self,
+        annotations: impl IntoIterator<Item = (usize, S)>,
+    ) {
+        self.line_annotations = annotations
+            .into_iter()
+            .map(|(line, text)| (line, text.into()))
+            .collect();
+    }
+
+    pub fn set_line_annotation(&mut self, line: usize, text: impl Into<String>) {
+        self.line_annotations.insert(line, text.into());
+    }
+
+    pub fn clear_line_annotation(&mut self, line: usize) {
+        self.line_annotations.remove(&line);
+    }
+
+    pub fn clear_line_annotations(&mut self) {
+        self.line_annotations.clear();
+    }
+
+    pub fn line_annotation(&self, line: usize) -> Option<&str> {
+        self.line_annotations.get(&line).map(String::as_str)
+    }
+}
@@ -0,0 +1,67 @@
+use ratatui_core::layout::Rect;
+use std::time::{Duration, Instant};
+
+/// Lines scrolled per second for each row the pointer is past the viewport edge.
+const LINES_PER_SECOND_PER_ROW: f32 = 15.0;
+
+/// Timer-driven scrolling while a selection is dragged past the top or bottom edge.
+///
+/// The speed grows with the distance between the pointer and the edge. Progress is
+/// made on every `advance` call, so scrolling continues while the mouse is held
+/// still as long as the host keeps calling [`Editor::tick`](crate::editor::Editor::tick).
+#[derive(Debug, Clone, Copy)]
+pub struct AutoScroll {
+    /// Scroll velocity in lines per second, negative when scrolling up
+    pub velocity: f32,
+    /// Last pointer position and the editor area it was reported for
+    pub mouse: (u16, u16),
+    pub area: Rect,
+    last: Instant,
+    pending: f32,
+}
+
+impl AutoScroll {
+    /// Returns the auto-scroll for a pointer at `mouse_y`, or `None` if the pointer
+    /// is inside the viewport away from its first and last rows.
+    pub fn for_pointer(mouse_x: u16, mouse_y: u16, area: Rect, now: Instant) -> Option<Self> {
+        let top = area.top();
+        let bottom = area.bottom().saturating_sub(1);
+        let rows = if mouse_y <= top {
+            -((top - mouse_y) as f32 + 1.0)
+        } else if mouse_y >= bottom {
+            (mouse_y - bottom) as f32 + 1.0
+        } else {
+            return None;
+        };
+        Some(Self {
+            velocity: rows * LINES_PER_SECOND_PER_ROW,
+            mouse: (mouse_x, mouse_y),
+            area,
+            last: now,
+            pending: 0.0,
+        })
+    }
+
+    /// Takes the speed and pointer of `other` while keeping accumulated progress.
+    pub fn retarget(&mut self, other: AutoScroll) {
+        self.velocity = other.velocity;
+        self.mouse = other.mouse;
+        self.area = other.area;
+    }
+
+    /// Returns the signed number of whole lines to scroll since the last call.
+    pub fn advance(&mut self, now: Instant) -> isize {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f32();
+        self.last = now.max(self.last);
+        self.pending += elapsed * self.velocity;
+        let lines = self.pending.trunc();
+        self.pending -= lines;
+        lines as isize
+    }
+
+    /// When the next whole line is due.
+    pub fn deadline(&self) -> Instant {
+        let remaining = (1.0 - self.pending.abs()).max(0.0) / self.velocity.abs();
+        self.last + Duration::from_secs_f32(remaining)
+    }
+}
@@ -0,0 +1,63 @@
+use crate::editor::Editor;
+use anyhow::Result;
+use crossterm::event::KeyEvent;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+pub use crate::testing::parse_keys;
+
+/// Deterministic headless entry points for benchmarks and performance tests.
+impl Editor {
+    /// Renders the editor into a fresh `width` x `height` buffer, as one
+    /// terminal frame would be drawn.
+    pub fn render_to_buffer(&self, width: u16, height: u16) -> Buffer {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        self.render(area, &mut buf);
+        buf
+    }
+
+    /// Feeds `keys` to [`Editor::input`] one by one, as typed into an area
+    /// of `width` x `height`.
+    pub fn feed_keys(&mut self, keys: &[KeyEvent], width: u16, height: u16) -> Result<()> {
+        let area = Rect::new(0, 0, width, height);
+        for key in keys {
+            self.input(*key, &area)?;
+        }
+        Ok(())
+    }
+}
+
+/// A deterministic Rust source of `lines` lines, for benchmarking large files.
+pub fn sample_source(lines: usize) -> String {
+    let mut source = String::new();
+    let mut line = 0;
+    while line < lines {
+        let n = line / 6;
+        let block = [
+            format!("/// Computes value number {n}."),
+            format!("pub fn value_{n}(input: &[u32]) -> Option<u32> {{"),
+            format!("    let total: u32 = input.iter().map(|x| x * {n}).sum();"),
+            format!("    let label = \"value {n}\"; // {n} of {lines}"),
+            "    if label.is_empty() { None } else { Some(total) }".to_string(),
+            "}".to_string(),
+        ];
+        for text in block.iter().take(lines - line) {
+            source.push_str(text);
+            source.push('\n');
+        }
+        line += block.len();
+    }
+    source
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_source_has_the_requested_lines() {
+        assert_eq!(sample_source(8).lines().count(), 8);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::code::{Code, grapheme_width};
+use ropey::RopeSlice;
+use unicode_bidi::{BidiClass, BidiInfo, Level, bidi_class};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A grapheme of a line and where it sits in the line's text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BidiCell {
+    /// Byte offset within the line
+    pub(crate) byte: usize,
+    pub(crate) bytes: usize,
+    /// Char column within the line
+    pub(crate) char_col: usize,
+    pub(crate) chars: usize,
+    pub(crate) width: usize,
+}
+
+/// Display order of a line mixing left-to-right and right-to-left text.
+///
+/// Terminals draw cells left to right, so right-to-left runs are reordered
+/// here following UAX #9, with left-to-right as the paragraph direction as
+/// code is mostly written that way. Rendering, the terminal cursor and mouse
+/// hits all go through the same cells so they agree on where a char is shown.
+#[derive(Debug, Clone)]
+pub(crate) struct BidiLine {
+    pub(crate) text: String,
+    /// Graphemes in display order, left to right
+    pub(crate) cells: Vec<BidiCell>,
+}
+
+impl BidiLine {
+    /// Lays out line `line_idx` of `code`, or returns `None` if it has no
+    /// right-to-left characters and is displayed in logical order.
+    pub(crate) fn for_line(code: &Code, line_idx: usize) -> Option<Self> {
+        let start = code.line_to_char(line_idx);
+        let line = code.char_slice(start, start + code.line_len(line_idx));
+        if !line.chars().any(is_rtl) {
+            return None;
+        }
+        Self::new(&line.to_string())
+    }
+
+    pub(crate) fn new(text: &str) -> Option<Self> {
+        if !text.chars().any(is_rtl) {
+            return None;
+        }
+
+        let mut logical = Vec::new();
+        let mut char_col = 0;
+        for (byte, g) in text.grapheme_indices(true) {
+            let chars = g.chars().count();
+            logical.push(BidiCell {
+                byte,
+                bytes: g.len(),
+                char_col,
+                chars,
+                width: grapheme_width(RopeSlice::from(g)),
+            });
+            char_col += chars;
+        }
+
+        let info = BidiInfo::new(text, Some(Level::ltr()));
+        let mut cells = Vec::with_capacity(logical.len());
+        for paragraph in &info.paragraphs {
+            let (levels, runs) = info.visual_runs(paragraph, paragraph.range.clone());
+            for run in runs {
+                let from = logical.partition_point(|cell: &BidiCell| cell.byte < run.start);
+                let to = logical.partition_point(|cell: &BidiCell| cell.byte < run.end);
+                let run_cells = &logical[from..to];
+                if levels[run.start].is_rtl() {
+                    cells.extend(run_cells.iter().rev());
+                } else {
+                    cells.extend(run_cells);
+                }
+            }
+        }
+
+        Some(Self {
+            text: text.to_string(),
+            cells,
+        })
+    }
+
+    /// Number of leading cells hidden by a horizontal scroll of `offset_x` chars.
+    pub(crate) fn skip(&self, offset_x: usize) -> usize {
+        let mut chars = 0;
+        self.cells
+            .iter()
+            .take_while(|cell| {
+                let hidden = chars < offset_x;
+                chars += cell.chars;
+                hidden
+            })
+            .count()
+    }
+
+    /// Columns taken by the cells hidden by a horizontal scroll of `offset_x` chars.
+    pub(crate) fn skipped_width(&self, offset_x: usize) -> usize {
+        self.cells[..self.skip(offset_x)]
+            .iter()
+            .map(|cell| cell.width)
+            .sum()
+    }
+
+    /// Display column of the char at `char_col`, counted from the line start.
+    /// Positions past the last char are shown after the whole line.
+    pub(crate) fn visual_col(&self, char_col: usize) -> usize {
+        let mut x = 0;
+        for cell in &self.cells {
+            if (cell.char_col..cell.char_col + cell.chars).contains(&char_col) {
+                return x;
+            }
+            x += cell.width;
+        }
+        x
+    }
+
+    /// Char column shown at display column `x`, or the line end past the text.
+    pub(crate) fn char_col_at(&self, x: usize) -> usize {
+        let mut left = 0;
+        for cell in &self.cells {
+            if left + cell.width > x {
+                return cell.char_col;
+            }
+            left += cell.width;
+        }
+        self.cells.iter().map(|cell| cell.chars).sum()
+    }
+}
+
+fn is_rtl(c: char) -> bool {
+    matches!(
+        bidi_class(c),
+        BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reorders_right_to_left_runs() {
+        assert!(BidiLine::new("let x = 1;").is_none());
+
+        // "abc " followed by hebrew alef, bet, gimel
+        let line = BidiLine::new("abc \u{5d0}\u{5d1}\u{5d2}").unwrap();
+        let shown: String = line
+            .cells
+            .iter()
+            .map(|cell| &line.text[cell.byte..cell.byte + cell.bytes])
+            .collect();
+        assert_eq!(shown, "abc \u{5d2}\u{5d1}\u{5d0}");
+
+        // alef is logically first in the run but drawn last
+        assert_eq!(line.visual_col(4), 6);
+        assert_eq!(line.visual_col(6), 4);
+        assert_eq!(line.visual_col(7), 7);
+        assert_eq!(line.char_col_at(4), 6);
+        assert_eq!(line.char_col_at(10), 7);
+        assert_eq!(line.skip(2), 2);
+    }
+}
@@ -0,0 +1,103 @@
+use crate::anchor::{Anchor, AnchorBias};
+use crate::editor::Editor;
+use ratatui_core::style::{Color, Style};
+use std::collections::BTreeSet;
+
+/// Id reported in [`crate::events::EditorEvent::GutterClicked`] for clicks
+/// on the breakpoint column, namespaced so it does not collide with the ids
+/// of host columns.
+pub const BREAKPOINT_COLUMN: &str = "ratatui-code-editor.breakpoints";
+
+/// Width of the breakpoint column: a breakpoint dot and an execution arrow.
+pub(crate) const BREAKPOINT_GUTTER_WIDTH: usize = 2;
+
+impl Editor {
+    /// Replaces the breakpoints and shows the breakpoint column. Breakpoints
+    /// stay on their lines as text is inserted or removed above them.
+    pub fn set_breakpoints(&mut self, lines: impl IntoIterator<Item = usize>) {
+        self.breakpoints = lines
+            .into_iter()
+            .map(|line| self.line_anchor(line))
+            .collect();
+        self.breakpoint_gutter = true;
+    }
+
+    /// Lines with a breakpoint, in order.
+    pub fn breakpoints(&self) -> Vec<usize> {
+        self.breakpoint_lines().into_iter().collect()
+    }
+
+    /// Adds or removes the breakpoint on `line`. Returns whether the line
+    /// has a breakpoint afterwards.
+    pub fn toggle_breakpoint(&mut self, line: usize) -> bool {
+        let before = self.breakpoints.len();
+        let code = self.code.borrow();
+        self.breakpoints
+            .retain(|anchor| code.char_to_line(anchor.position()) != line);
+        drop(code);
+        self.breakpoint_gutter = true;
+        if self.breakpoints.len() < before {
+            return false;
+        }
+        let anchor = self.line_anchor(line);
+        self.breakpoints.push(anchor);
+        true
+    }
+
+    /// Marks the line a debugger is stopped at with an arrow and a full-line
+    /// background, or clears it. The mark follows edits like breakpoints.
+    pub fn set_execution_line(&mut self, line: Option<usize>) {
+        self.execution_line = line.map(|line| self.line_anchor(line));
+        if line.is_some() {
+            self.breakpoint_gutter = true;
+        }
+    }
+
+    pub fn execution_line(&self) -> Option<usize> {
+        let anchor = self.execution_line.as_ref()?;
+        Some(self.code.borrow().char_to_line(anchor.position()))
+    }
+
+    /// Shows or hides the breakpoint column. It is shown as soon as
+    /// breakpoints or an execution line are set.
+    pub fn set_breakpoint_gutter_enabled(&mut self, enabled: bool) {
+        self.breakpoint_gutter = enabled;
+    }
+
+    pub(crate) fn breakpoint_gutter_width(&self) -> usize {
+        if self.breakpoint_gutter {
+            BREAKPOINT_GUTTER_WIDTH
+        } else {
+            0
+        }
+    }
+
+    pub(crate) fn breakpoint_lines(&self) -> BTreeSet<usize> {
+        let code = self.code.borrow();
+        self.breakpoints
+            .iter()
+            .map(|anchor| code.char_to_line(anchor.position()))
+            .collect()
+    }
+
+    pub(crate) fn breakpoint_style(&self) -> Style {
+        Style::default()
+            .fg(Color::Red)
+            .patch(self.theme_style("breakpoint"))
+    }
+
+    /// Arrow style and full-line background of the execution line.
+    pub(crate) fn execution_line_styles(&self) -> (Style, Color) {
+        let style = self.theme_style("execution_line");
+        let arrow = Style::default().fg(Color::Yellow).patch(style);
+        (arrow, style.bg.unwrap_or(Color::Rgb(74, 64, 20)))
+    }
+
+    /// Anchor at the start of `line`, moving down with text inserted there.
+    fn line_anchor(&mut self, line: usize) -> Anchor {
+        let mut code = self.code.borrow_mut();
+        let line = line.min(code.len_lines().saturating_sub(1));
+        let start = code.line_to_char(line);
+        code.create_anchor(start, AnchorBias::Right)
+    }
+}
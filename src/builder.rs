@@ -0,0 +1,179 @@
+use crate::editor::Editor;
+use crate::language::LanguageConfig;
+use crate::types::{CodeFoldingOptions, ScrollFollow};
+use anyhow::Result;
+use std::collections::HashMap;
+
+impl Editor {
+    /// Starts an [`EditorBuilder`], an alternative to [`Editor::new`] that
+    /// keeps working as options are added.
+    ///
+    /// ```
+    /// use ratatui_code_editor::prelude::*;
+    ///
+    /// let editor = Editor::builder()
+    ///     .language("rust")
+    ///     .content("fn main() {}")
+    ///     .theme(vesper())
+    ///     .scrollbar(true)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(editor.get_content(), "fn main() {}");
+    /// ```
+    pub fn builder() -> EditorBuilder {
+        EditorBuilder::default()
+    }
+}
+
+/// Configures an [`Editor`] before it is created, see [`Editor::builder`].
+///
+/// Options that are not set keep the defaults of [`Editor::new`].
+#[derive(Debug, Clone, Default)]
+pub struct EditorBuilder {
+    language: Option<String>,
+    content: String,
+    theme: Vec<(String, String)>,
+    custom_highlights: Option<HashMap<String, String>>,
+    language_config: Option<LanguageConfig>,
+    line_numbers: Option<bool>,
+    line_number_min_digits: Option<usize>,
+    left_padding: Option<usize>,
+    scrollbar: Option<bool>,
+    code_folding: Option<CodeFoldingOptions>,
+    word_highlight: Option<bool>,
+    scrolloff: Option<usize>,
+    scroll_follow: Option<ScrollFollow>,
+    placeholder: Option<String>,
+}
+
+impl EditorBuilder {
+    /// Language name as in [`Editor::new`], plain text by default.
+    pub fn language(mut self, lang: impl Into<String>) -> Self {
+        self.language = Some(lang.into());
+        self
+    }
+
+    pub fn content(mut self, text: impl Into<String>) -> Self {
+        self.content = text.into();
+        self
+    }
+
+    /// Capture to color mappings such as [`crate::theme::vesper`].
+    pub fn theme(mut self, theme: Vec<(&str, &str)>) -> Self {
+        self.theme = theme
+            .into_iter()
+            .map(|(name, color)| (name.to_string(), color.to_string()))
+            .collect();
+        self
+    }
+
+    /// Highlight queries used instead of the built-in ones, keyed by language.
+    pub fn custom_highlights(mut self, highlights: HashMap<String, String>) -> Self {
+        self.custom_highlights = Some(highlights);
+        self
+    }
+
+    pub fn language_config(mut self, config: LanguageConfig) -> Self {
+        self.language_config = Some(config);
+        self
+    }
+
+    pub fn line_numbers(mut self, show: bool) -> Self {
+        self.line_numbers = Some(show);
+        self
+    }
+
+    /// See [`Editor::set_line_number_min_digits`].
+    pub fn line_number_min_digits(mut self, digits: usize) -> Self {
+        self.line_number_min_digits = Some(digits);
+        self
+    }
+
+    /// Blank columns between the line numbers and the text.
+    pub fn left_padding(mut self, columns: usize) -> Self {
+        self.left_padding = Some(columns);
+        self
+    }
+
+    pub fn scrollbar(mut self, show: bool) -> Self {
+        self.scrollbar = Some(show);
+        self
+    }
+
+    /// Turns code folding on with the default indicators, or off.
+    pub fn code_folding(mut self, enabled: bool) -> Self {
+        let options = self.code_folding.take().unwrap_or_default();
+        self.code_folding = Some(CodeFoldingOptions { enabled, ..options });
+        self
+    }
+
+    pub fn code_folding_options(mut self, options: CodeFoldingOptions) -> Self {
+        self.code_folding = Some(options);
+        self
+    }
+
+    pub fn word_highlight(mut self, enabled: bool) -> Self {
+        self.word_highlight = Some(enabled);
+        self
+    }
+
+    pub fn scrolloff(mut self, lines: usize) -> Self {
+        self.scrolloff = Some(lines);
+        self
+    }
+
+    pub fn scroll_follow(mut self, follow: ScrollFollow) -> Self {
+        self.scroll_follow = Some(follow);
+        self
+    }
+
+    /// Text shown while the editor is empty.
+    pub fn placeholder(mut self, text: impl Into<String>) -> Self {
+        self.placeholder = Some(text.into());
+        self
+    }
+
+    /// Creates the editor. Fails like [`Editor::new_with_highlights`].
+    pub fn build(self) -> Result<Editor> {
+        let lang = self.language.as_deref().unwrap_or("text");
+        let theme = self
+            .theme
+            .iter()
+            .map(|(name, color)| (name.as_str(), color.as_str()))
+            .collect();
+        let mut editor =
+            Editor::new_with_highlights(lang, &self.content, theme, self.custom_highlights)?;
+
+        if let Some(config) = self.language_config {
+            editor.set_language_config(config);
+        }
+        if let Some(show) = self.line_numbers {
+            editor.show_line_numbers(show);
+        }
+        if let Some(digits) = self.line_number_min_digits {
+            editor.set_line_number_min_digits(digits);
+        }
+        if let Some(columns) = self.left_padding {
+            editor.set_left_code_padding(columns);
+        }
+        if let Some(show) = self.scrollbar {
+            editor.show_scrollbar(show);
+        }
+        if let Some(options) = self.code_folding {
+            editor.set_code_folding_options(options);
+        }
+        if let Some(enabled) = self.word_highlight {
+            editor.set_word_highlight_enabled(enabled);
+        }
+        if let Some(lines) = self.scrolloff {
+            editor.set_scrolloff(lines);
+        }
+        if let Some(follow) = self.scroll_follow {
+            editor.set_scroll_follow(follow);
+        }
+        if let Some(text) = self.placeholder {
+            editor.set_placeholder(&text);
+        }
+        Ok(editor)
+    }
+}
@@ -5,13 +5,19 @@ pub enum ClickKind {
     Single,
     Double,
     Triple,
+    Quadruple,
 }
 
 #[derive(Debug, Clone, Copy)]
 pub struct ClickTracker {
     pub last: Option<(Instant, usize)>,
+    /// Click before `last`
     pub prev: Option<(Instant, usize)>,
+    /// Clicks in the current chain
+    pub count: usize,
     pub max_dt: Duration,
+    /// Longest click chain; the click after it starts a new chain
+    pub max_chain: usize,
 }
 
 impl ClickTracker {
@@ -19,36 +25,72 @@ impl ClickTracker {
         Self {
             last: None,
             prev: None,
+            count: 0,
             max_dt,
+            max_chain: 4,
         }
     }
 
     pub fn register(&mut self, cursor: usize) -> ClickKind {
-        let now = Instant::now();
-        let dbl = self
-            .last
-            .map(|(t, p)| p == cursor && now.duration_since(t) < self.max_dt)
-            .unwrap_or(false);
-        let tpl = self
-            .last
-            .zip(self.prev)
-            .map(|((t1, p1), (t0, p0))| {
-                p0 == cursor
-                    && p1 == cursor
-                    && now.duration_since(t0) < self.max_dt
-                    && t1.duration_since(t0) < self.max_dt
-            })
-            .unwrap_or(false);
+        self.register_at(cursor, Instant::now())
+    }
 
+    /// Registers a click at `cursor` made at `now`. A click continues the
+    /// chain when it hits the same position within `max_dt` of the previous one.
+    pub fn register_at(&mut self, cursor: usize, now: Instant) -> ClickKind {
+        let chained = self
+            .last
+            .is_some_and(|(t, p)| p == cursor && now.saturating_duration_since(t) < self.max_dt);
+        self.count = if chained && self.count < self.max_chain.max(1) {
+            self.count + 1
+        } else {
+            1
+        };
         self.prev = self.last;
         self.last = Some((now, cursor));
 
-        if tpl {
-            ClickKind::Triple
-        } else if dbl {
-            ClickKind::Double
-        } else {
-            ClickKind::Single
+        match self.count {
+            1 => ClickKind::Single,
+            2 => ClickKind::Double,
+            3 => ClickKind::Triple,
+            _ => ClickKind::Quadruple,
         }
     }
+
+    /// Forgets the chain, e.g. once `max_dt` passed since the last click.
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.prev = None;
+        self.count = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chains_wrap_after_the_longest_one() {
+        let mut clicks = ClickTracker::new(Duration::from_millis(300));
+        clicks.max_chain = 3;
+        let start = Instant::now();
+        let kinds: Vec<_> = (0..5)
+            .map(|i| clicks.register_at(7, start + Duration::from_millis(200 * i)))
+            .collect();
+        let expected = [
+            ClickKind::Single,
+            ClickKind::Double,
+            ClickKind::Triple,
+            ClickKind::Single,
+            ClickKind::Double,
+        ];
+        assert_eq!(kinds, expected);
+        let at = |i: u64| Some((start + Duration::from_millis(200 * i), 7));
+        assert_eq!((clicks.prev, clicks.last), (at(3), at(4)));
+
+        // too slow or elsewhere starts over
+        let later = start + Duration::from_secs(2);
+        assert_eq!(clicks.register_at(7, later), ClickKind::Single);
+        assert_eq!(clicks.register_at(8, later), ClickKind::Single);
+    }
 }
@@ -1,17 +1,31 @@
+use crate::anchor::{Anchor, AnchorBias, AnchorSet};
 use crate::history::History;
+use crate::language::{self, LanguageConfig};
+use crate::layout::{LineMetrics, LineMetricsCache};
+use crate::predicates::satisfies_predicates;
 use crate::selection::Selection;
-use crate::utils::{calculate_end_position, comment as lang_comment, count_indent_units, indent};
+use crate::theme::resolve_capture;
+use crate::types::{ChangeEvent, ChangeListener, ChangeOrigin, ChangeSync, Symbol, TextEdit};
+use crate::utils::{calculate_end_position, count_indent_units, is_word_char, lang_from_extension};
+use crate::width::grapheme_str_width;
 use anyhow::{Result, anyhow};
 use ropey::{Rope, RopeSlice};
 use rust_embed::RustEmbed;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
 use std::rc::Rc;
 use streaming_iterator::StreamingIterator;
 use tree_sitter::{InputEdit, Point, QueryCursor};
 use tree_sitter::{Language, Node, Parser, Query, Tree};
 use unicode_segmentation::{GraphemeCursor, GraphemeIncomplete};
-use unicode_width::UnicodeWidthStr;
+
+/// Content size in bytes above which [`Code::from_reader`] defers parsing.
+pub const LARGE_FILE_THRESHOLD: usize = 16 * 1024 * 1024;
+
+/// Number of recent edits kept for [`Code::map_offset_since`].
+const EDIT_LOG_CAPACITY: usize = 1024;
 
 #[derive(RustEmbed)]
 #[folder = ""]
@@ -19,12 +33,14 @@ use unicode_width::UnicodeWidthStr;
 struct LangAssets;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Operation {
     Insert,
     Remove,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Edit {
     pub start: usize,
     pub text: String,
@@ -32,6 +48,7 @@ pub struct Edit {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EditBatch {
     pub edits: Vec<Edit>,
     pub state_before: Option<EditState>,
@@ -49,11 +66,84 @@ impl EditBatch {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EditState {
     pub offset: usize,
     pub selection: Option<Selection>,
 }
 
+/// A set of programmatic edits applied together as a single undo step.
+///
+/// All offsets are character indices into the document as it was when the
+/// transaction started, so hosts do not need to adjust positions for edits
+/// they queued earlier. Edits must not overlap; insertions at the same offset
+/// are applied in the order they were queued.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EditTransaction {
+    edits: Vec<(usize, usize, String)>,
+}
+
+impl EditTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts `text` at `offset`.
+    pub fn insert(&mut self, offset: usize, text: &str) {
+        self.replace(offset..offset, text);
+    }
+
+    /// Removes the characters in `range`.
+    pub fn remove(&mut self, range: std::ops::Range<usize>) {
+        self.replace(range, "");
+    }
+
+    /// Replaces the characters in `range` with `text`.
+    pub fn replace(&mut self, range: std::ops::Range<usize>, text: &str) {
+        let start = range.start.min(range.end);
+        let end = range.start.max(range.end);
+        self.edits.push((start, end, text.to_string()));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Returns the queued edits sorted by start offset, or an error if any overlap.
+    pub(crate) fn sorted_edits(&self, len: usize) -> Result<Vec<(usize, usize, String)>> {
+        let mut edits = self.edits.clone();
+        edits.sort_by_key(|(start, end, _)| (*start, *end));
+
+        let mut prev_end = 0;
+        for (start, end, _) in &edits {
+            if *end > len {
+                return Err(anyhow!("edit {}..{} is out of bounds ({})", start, end, len));
+            }
+            if *start < prev_end {
+                return Err(anyhow!("edit {}..{} overlaps a previous edit", start, end));
+            }
+            prev_end = *end;
+        }
+        Ok(edits)
+    }
+
+    /// Maps an offset in the original document to the document after all edits.
+    ///
+    /// Offsets inside a replaced range move to the end of the replacement text.
+    pub fn map_offset(&self, offset: usize) -> usize {
+        let mut mapped = offset as isize;
+        for (start, end, text) in &self.edits {
+            let inserted = text.chars().count() as isize;
+            if *end <= offset {
+                mapped += inserted - (*end - *start) as isize;
+            } else if *start < offset {
+                mapped += inserted - (offset - *start) as isize;
+            }
+        }
+        mapped.max(0) as usize
+    }
+}
+
 /// A multi-line source range that can be collapsed by the editor.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct FoldRange {
@@ -61,21 +151,55 @@ pub struct FoldRange {
     pub end_line: usize,
 }
 
+/// A tree-aware edit: `start..end` (chars) is replaced by `text`, after
+/// which the cursor and selection are the given ones.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NodeEdit {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub cursor: usize,
+    pub selection: Option<(usize, usize)>,
+}
+
 pub struct Code {
     pub(crate) content: ropey::Rope,
     lang: String,
+    language_config: LanguageConfig,
     tree: Option<Tree>,
     parser: Option<Parser>,
     query: Option<Query>,
     fold_query: Option<Query>,
+    /// Scopes, definitions and references from `locals.scm`
+    locals_query: Option<Query>,
+    /// Declarations and their names from `breadcrumbs.scm`
+    breadcrumbs_query: Option<Query>,
     fold_ranges: Vec<FoldRange>,
     applying_history: bool,
     history: History,
     current_batch: EditBatch,
-    injection_parsers: Option<HashMap<String, Rc<RefCell<Parser>>>>,
-    injection_queries: Option<HashMap<String, Query>>,
+    /// Where other languages are embedded, from `injections.scm`
+    injections_query: Option<Query>,
+    /// Injected languages loaded so far, `None` for ones without a grammar
+    injection_layers: RefCell<HashMap<String, Option<Rc<InjectionLayer>>>>,
     change_callback: Option<Box<dyn Fn(Vec<(usize, usize, usize, usize, String)>)>>,
+    change_listener: Option<ChangeListener>,
+    pending_changes: Vec<TextEdit>,
+    change_origin: ChangeOrigin,
+    change_sync: ChangeSync,
+    /// End of the document before the pending changes, for full sync
+    pending_old_end: Option<(usize, usize)>,
+    version: u64,
+    /// Recent edits as (version, from, removed chars, inserted chars), so
+    /// editors sharing the document can map their positions
+    edit_log: VecDeque<(u64, usize, usize, usize)>,
+    /// Newest version with edits dropped from the log
+    edit_log_dropped: u64,
     custom_highlights: Option<HashMap<String, String>>,
+    anchors: AnchorSet,
+    /// Lengths and widths of lines, dropped for the lines an edit touches
+    line_metrics: LineMetricsCache,
+    modified: bool,
 }
 
 impl Code {
@@ -85,46 +209,98 @@ impl Code {
         lang: &str,
         custom_highlights: Option<HashMap<String, String>>,
     ) -> Result<Self> {
-        let mut code = Self {
-            content: Rope::from_str(text),
+        let mut code = Self::plain(Rope::from_str(text), lang, custom_highlights);
+        code.enable_syntax()?;
+        Ok(code)
+    }
+
+    /// Create a `Code` instance by streaming text from `reader` into the rope.
+    ///
+    /// Tree-sitter parsing is skipped for content larger than
+    /// [`LARGE_FILE_THRESHOLD`] bytes, so huge files open without a full parse.
+    /// Call [`Code::enable_syntax`] to parse and highlight them later.
+    pub fn from_reader<R: std::io::Read>(
+        reader: R,
+        lang: &str,
+        custom_highlights: Option<HashMap<String, String>>,
+    ) -> Result<Self> {
+        let content = Rope::from_reader(reader)?;
+        let mut code = Self::plain(content, lang, custom_highlights);
+        if code.content.len_bytes() <= LARGE_FILE_THRESHOLD {
+            code.enable_syntax()?;
+        }
+        Ok(code)
+    }
+
+    fn plain(
+        content: Rope,
+        lang: &str,
+        custom_highlights: Option<HashMap<String, String>>,
+    ) -> Self {
+        Self {
+            content,
             lang: lang.to_string(),
+            language_config: language::config(lang),
             tree: None,
             parser: None,
             query: None,
             fold_query: None,
+            locals_query: None,
+            breadcrumbs_query: None,
             fold_ranges: Vec::new(),
             applying_history: true,
             history: History::new(1000),
             current_batch: EditBatch::new(),
-            injection_parsers: None,
-            injection_queries: None,
+            injections_query: None,
+            injection_layers: RefCell::new(HashMap::new()),
             change_callback: None,
+            change_listener: None,
+            pending_changes: Vec::new(),
+            change_origin: ChangeOrigin::Edit,
+            change_sync: ChangeSync::Incremental,
+            pending_old_end: None,
+            version: 0,
+            edit_log: VecDeque::new(),
+            edit_log_dropped: 0,
             custom_highlights,
-        };
-
-        if let Some(language) = Self::get_language(lang) {
-            let highlights = code.get_highlights(lang)?;
-            let mut parser = Parser::new();
-            parser.set_language(&language)?;
-            let tree = parser.parse(text, None);
-            let query = Query::new(&language, &highlights)?;
-            let fold_query = code
-                .get_folds(lang)
-                .and_then(|source| Query::new(&language, &source).ok());
-            let (iparsers, iqueries) = code.init_injections(&query)?;
-            code.tree = tree;
-            code.parser = Some(parser);
-            code.query = Some(query);
-            code.fold_query = fold_query;
-            code.update_fold_ranges();
-            code.injection_parsers = Some(iparsers);
-            code.injection_queries = Some(iqueries);
+            anchors: AnchorSet::default(),
+            line_metrics: LineMetricsCache::default(),
+            modified: false,
         }
-
-        Ok(code)
     }
 
-    fn get_language(lang: &str) -> Option<Language> {
+    /// Sets up the Tree-sitter parser and queries for the language and parses
+    /// the whole buffer. Does nothing if syntax is already enabled or the
+    /// language is not supported.
+    pub fn enable_syntax(&mut self) -> Result<()> {
+        if self.parser.is_some() {
+            return Ok(());
+        }
+        let Some(language) = Self::get_language(&self.lang) else {
+            return Ok(());
+        };
+        let highlights = self.get_highlights(&self.lang)?;
+        let mut parser = Parser::new();
+        parser.set_language(&language)?;
+        let query = Query::new(&language, &highlights)
+            .map_err(|e| anyhow!("invalid highlights query for {}: {}", self.lang, e))?;
+        let fold_query = self
+            .get_folds(&self.lang)
+            .and_then(|source| Query::new(&language, &source).ok());
+        let locals_query = self.load_query(&language, "locals")?;
+        let breadcrumbs_query = self.load_query(&language, "breadcrumbs")?;
+        let injections_query = self.load_query(&language, "injections")?;
+        self.parser = Some(parser);
+        self.query = Some(query);
+        self.fold_query = fold_query;
+        self.locals_query = locals_query;
+        self.breadcrumbs_query = breadcrumbs_query;
+        self.injections_query = injections_query;
+        self.reparse();
+        Ok(())
+    }
+
+    pub(crate) fn get_language(lang: &str) -> Option<Language> {
         match lang {
             "rust" => Some(tree_sitter_rust::LANGUAGE.into()),
             "javascript" => Some(tree_sitter_javascript::LANGUAGE.into()),
@@ -169,33 +345,64 @@ impl Code {
             .map(str::to_owned)
     }
 
-    fn init_injections(
-        &self,
-        query: &Query,
-    ) -> anyhow::Result<(HashMap<String, Rc<RefCell<Parser>>>, HashMap<String, Query>)> {
-        let mut injection_parsers = HashMap::new();
-        let mut injection_queries = HashMap::new();
+    /// Source of the `kind` query (e.g. `locals`) from the custom
+    /// highlights under `"<lang>.<kind>"`, or the embedded `<kind>.scm`.
+    fn get_query(&self, lang: &str, kind: &str) -> Option<String> {
+        let key = format!("{lang}.{kind}");
+        let custom = self.custom_highlights.as_ref();
+        if let Some(source) = custom.and_then(|highlights| highlights.get(&key)) {
+            return Some(source.clone());
+        }
+        let path = format!("langs/{lang}/{kind}.scm");
+        let source = LangAssets::get(&path)?;
+        std::str::from_utf8(source.data.as_ref())
+            .ok()
+            .map(str::to_owned)
+    }
 
-        for name in query.capture_names() {
-            if let Some(lang) = name.strip_prefix("injection.content.") {
-                if injection_parsers.contains_key(lang) {
-                    continue;
-                }
-                if let Some(language) = Self::get_language(lang) {
-                    let mut parser = Parser::new();
-                    parser.set_language(&language)?;
-                    let highlights = self.get_highlights(lang)?;
-                    let inj_query = Query::new(&language, &highlights)?;
-
-                    injection_parsers.insert(lang.to_string(), Rc::new(RefCell::new(parser)));
-                    injection_queries.insert(lang.to_string(), inj_query);
-                } else {
-                    eprintln!("Unknown injection language: {}", lang);
-                }
-            }
+    /// Compiles the `kind` query of the buffer's language. A custom query
+    /// that does not compile is an error; a broken embedded one is skipped.
+    fn load_query(&self, language: &Language, kind: &str) -> Result<Option<Query>> {
+        let Some(source) = self.get_query(&self.lang, kind) else {
+            return Ok(None);
+        };
+        let key = format!("{}.{kind}", self.lang);
+        let custom = self.custom_highlights.as_ref();
+        match Query::new(language, &source) {
+            Ok(query) => Ok(Some(query)),
+            Err(e) if custom.is_some_and(|highlights| highlights.contains_key(&key)) => Err(
+                anyhow!("invalid custom {} query for {}: {}", kind, self.lang, e),
+            ),
+            Err(_) => Ok(None),
         }
+    }
 
-        Ok((injection_parsers, injection_queries))
+    /// Parser and queries of the injected language `lang`, loaded on first use.
+    fn injection_layer(&self, lang: &str) -> Option<Rc<InjectionLayer>> {
+        if let Some(layer) = self.injection_layers.borrow().get(lang) {
+            return layer.clone();
+        }
+        let layer = self.load_injection_layer(lang).map(Rc::new);
+        self.injection_layers
+            .borrow_mut()
+            .insert(lang.to_string(), layer.clone());
+        layer
+    }
+
+    fn load_injection_layer(&self, lang: &str) -> Option<InjectionLayer> {
+        let language = Self::get_language(lang)?;
+        let mut parser = Parser::new();
+        parser.set_language(&language).ok()?;
+        let highlights = self.get_highlights(lang).ok()?;
+        let highlights = Query::new(&language, &highlights).ok()?;
+        let injections = self
+            .get_query(lang, "injections")
+            .and_then(|source| Query::new(&language, &source).ok());
+        Some(InjectionLayer {
+            parser: RefCell::new(parser),
+            highlights,
+            injections,
+        })
     }
 
     pub fn point(&self, offset: usize) -> (usize, usize) {
@@ -218,6 +425,9 @@ impl Code {
         );
         let mut ranges = Vec::new();
         while let Some(m) = matches.next() {
+            if !satisfies_predicates(query, m, self.content.slice(..)) {
+                continue;
+            }
             for capture in m.captures {
                 let start_line = capture.node.start_position().row;
                 let end_line = capture.node.end_position().row;
@@ -255,6 +465,11 @@ impl Code {
             .max_by_key(|range| range.end_line)
     }
 
+    /// Creates an anchor at `offset` that follows subsequent edits.
+    pub fn create_anchor(&mut self, offset: usize, bias: AnchorBias) -> Anchor {
+        self.anchors.create(offset.min(self.len_chars()), bias)
+    }
+
     pub fn offset(&self, row: usize, col: usize) -> usize {
         let line_start = self.content.line_to_char(row);
         line_start + col
@@ -292,13 +507,17 @@ impl Code {
     }
 
     pub fn line_len(&self, idx: usize) -> usize {
-        let line = self.content.line(idx);
-        let len = line.len_chars();
-        if idx == self.content.len_lines() - 1 {
-            len
-        } else {
-            len.saturating_sub(1)
-        }
+        self.line_metrics(idx).len
+    }
+
+    /// Display width of line `idx` in cells, without its line break.
+    pub fn line_width(&self, idx: usize) -> usize {
+        self.line_metrics(idx).width
+    }
+
+    /// Length and width of line `idx`, cached until an edit touches the line.
+    pub fn line_metrics(&self, idx: usize) -> LineMetrics {
+        self.line_metrics.get(&self.content, idx)
     }
 
     pub fn line(&self, line_idx: usize) -> RopeSlice<'_> {
@@ -444,8 +663,72 @@ impl Code {
         prev
     }
 
+    /// Returns true if the content changed since creation or the last `mark_saved`.
+    pub fn is_modified(&self) -> bool {
+        self.modified
+    }
+
+    /// Marks the content as saved. Undoing or redoing back to it makes the
+    /// content unmodified again.
+    pub fn mark_saved(&mut self) {
+        self.modified = false;
+        self.history.mark_saved();
+    }
+
+    /// Undo history as the stored batches and the undo position.
+    pub(crate) fn history_batches(&self) -> (Vec<EditBatch>, usize) {
+        self.history.batches()
+    }
+
+    /// Replaces the undo history. The batches must describe edits leading to
+    /// the current content, as returned by [`Code::history_batches`].
+    pub(crate) fn restore_history(&mut self, batches: Vec<EditBatch>, index: usize) {
+        self.history.restore(batches, index);
+    }
+
+    pub(crate) fn set_modified(&mut self, modified: bool) {
+        if modified {
+            self.modified = true;
+        } else {
+            self.mark_saved();
+        }
+    }
+
     pub fn tx(&mut self) {
         self.current_batch = EditBatch::new();
+        self.change_origin = ChangeOrigin::Edit;
+    }
+
+    /// Sets where the edits of the current transaction come from, as reported
+    /// to change listeners. [`Code::tx`] resets it to [`ChangeOrigin::Edit`].
+    pub fn set_change_origin(&mut self, origin: ChangeOrigin) {
+        self.change_origin = origin;
+    }
+
+    /// Document version, incremented once per committed transaction, undo or redo.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Checks internal consistency in debug builds: the undo history position
+    /// is valid and the syntax tree covers exactly the text.
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if an invariant does not hold.
+    pub fn debug_assert_invariants(&self) {
+        debug_assert!(
+            self.history.is_consistent(),
+            "undo history index out of range"
+        );
+        if let Some(tree) = &self.tree {
+            debug_assert!(
+                tree.root_node().end_byte() == self.content.len_bytes(),
+                "syntax tree ends at byte {}, the text at {}",
+                tree.root_node().end_byte(),
+                self.content.len_bytes()
+            );
+        }
     }
 
     pub fn set_state_before(&mut self, offset: usize, selection: Option<Selection>) {
@@ -458,7 +741,7 @@ impl Code {
 
     pub fn commit(&mut self) {
         if !self.current_batch.edits.is_empty() {
-            self.notify_changes(&self.current_batch.edits);
+            self.notify_changes(self.change_origin);
             self.history.push(self.current_batch.clone());
             self.current_batch = EditBatch::new();
         }
@@ -467,8 +750,16 @@ impl Code {
     pub fn insert(&mut self, from: usize, text: &str) {
         let byte_idx = self.content.char_to_byte(from);
         let byte_len: usize = text.chars().map(|ch| ch.len_utf8()).sum();
+        let start_position = self.byte_point(byte_idx);
+        self.record_change(from, from, text);
+        let first_line = self.content.char_to_line(from);
 
         self.content.insert(from, text);
+        let last_line = self.content.char_to_line(from + text.chars().count());
+        self.line_metrics.on_edit(first_line, first_line, last_line);
+        self.anchors.on_insert(from, text.chars().count());
+        self.log_edit(from, 0, text.chars().count());
+        self.modified = true;
 
         if self.applying_history {
             self.current_batch.edits.push(Edit {
@@ -483,9 +774,9 @@ impl Code {
                 start_byte: byte_idx,
                 old_end_byte: byte_idx,
                 new_end_byte: byte_idx + byte_len,
-                start_position: Point { row: 0, column: 0 },
-                old_end_position: Point { row: 0, column: 0 },
-                new_end_position: Point { row: 0, column: 0 },
+                start_position,
+                old_end_position: start_position,
+                new_end_position: self.byte_point(byte_idx + byte_len),
             });
         }
     }
@@ -493,9 +784,18 @@ impl Code {
     pub fn remove(&mut self, from: usize, to: usize) {
         let from_byte = self.content.char_to_byte(from);
         let to_byte = self.content.char_to_byte(to);
+        let start_position = self.byte_point(from_byte);
+        let old_end_position = self.byte_point(to_byte);
         let removed_text = self.content.slice(from..to).to_string();
+        self.record_change(from, to, "");
+        let first_line = self.content.char_to_line(from);
+        let last_line = self.content.char_to_line(to);
 
         self.content.remove(from..to);
+        self.line_metrics.on_edit(first_line, last_line, first_line);
+        self.anchors.on_remove(from, to);
+        self.log_edit(from, to - from, 0);
+        self.modified = true;
 
         if self.applying_history {
             self.current_batch.edits.push(Edit {
@@ -510,13 +810,86 @@ impl Code {
                 start_byte: from_byte,
                 old_end_byte: to_byte,
                 new_end_byte: from_byte,
-                start_position: Point { row: 0, column: 0 },
-                old_end_position: Point { row: 0, column: 0 },
-                new_end_position: Point { row: 0, column: 0 },
+                start_position,
+                old_end_position,
+                new_end_position: start_position,
             });
         }
     }
 
+    fn log_edit(&mut self, from: usize, removed: usize, inserted: usize) {
+        if self.edit_log.len() == EDIT_LOG_CAPACITY
+            && let Some((dropped, ..)) = self.edit_log.pop_front()
+        {
+            self.edit_log_dropped = dropped;
+        }
+        // edits belong to the version their transaction commits as
+        self.edit_log
+            .push_back((self.version + 1, from, removed, inserted));
+    }
+
+    /// Maps `offset` through the edits made after `version`, like an anchor
+    /// with left bias. Returns `None` if those edits are no longer logged.
+    pub(crate) fn map_offset_since(&self, version: u64, offset: usize) -> Option<usize> {
+        if version < self.edit_log_dropped {
+            return None;
+        }
+        let edits = self.edit_log.iter().filter(|&&(v, ..)| v > version);
+        Some(edits.fold(offset, |offset, &(_, from, removed, inserted)| {
+            let offset = if offset >= from + removed {
+                offset - removed
+            } else {
+                offset.min(from)
+            };
+            if offset > from {
+                offset + inserted
+            } else {
+                offset
+            }
+        }))
+    }
+
+    /// Edits made after `version` as (from, removed chars, inserted chars), each
+    /// in the coordinates left by the previous one. Returns `None` if those
+    /// edits are no longer logged.
+    pub(crate) fn edits_since(&self, version: u64) -> Option<Vec<(usize, usize, usize)>> {
+        if version < self.edit_log_dropped {
+            return None;
+        }
+        Some(
+            self.edit_log
+                .iter()
+                .filter(|&&(v, ..)| v > version)
+                .map(|&(_, from, removed, inserted)| (from, removed, inserted))
+                .collect(),
+        )
+    }
+
+    /// Converts a byte offset into a Tree-sitter point (row, byte column).
+    ///
+    /// Tree-sitter only breaks rows at `\n`, while ropey also counts `\r`,
+    /// U+0085 and U+2028, so the rows are counted here instead.
+    fn byte_point(&self, byte_idx: usize) -> Point {
+        let mut row = 0;
+        let mut line_start = 0;
+        let mut chunk_start = 0;
+        for chunk in self.content.chunks() {
+            if chunk_start >= byte_idx {
+                break;
+            }
+            let bytes = &chunk.as_bytes()[..chunk.len().min(byte_idx - chunk_start)];
+            for (i, _) in bytes.iter().enumerate().filter(|(_, b)| **b == b'\n') {
+                row += 1;
+                line_start = chunk_start + i + 1;
+            }
+            chunk_start += chunk.len();
+        }
+        Point {
+            row,
+            column: byte_idx - line_start,
+        }
+    }
+
     fn edit_tree(&mut self, edit: InputEdit) {
         if let Some(tree) = self.tree.as_mut() {
             tree.edit(&edit);
@@ -543,12 +916,63 @@ impl Code {
         }
     }
 
+    /// Syntax scopes at char `offset`, outermost first: the kinds of the named
+    /// nodes from the root down to the one at `offset`, each followed by the
+    /// highlight captures on it as `@name`, the one drawn on top last, e.g.
+    /// `["source_file", "let_declaration", "string_literal", "@string"]`.
+    /// Empty without a syntax tree. Injected languages are not included.
+    pub fn scope_at(&self, offset: usize) -> Vec<String> {
+        let Some(tree) = &self.tree else {
+            return Vec::new();
+        };
+        let byte = self
+            .content
+            .char_to_byte(offset.min(self.content.len_chars()));
+        let Some(node) = tree.root_node().named_descendant_for_byte_range(byte, byte) else {
+            return Vec::new();
+        };
+        let mut nodes: Vec<Node> = std::iter::successors(Some(node), Node::parent).collect();
+        nodes.reverse();
+
+        let mut captures = Vec::new();
+        if let Some(query) = &self.query {
+            let text = self.content.slice(..);
+            let mut cursor = QueryCursor::new();
+            cursor.set_byte_range(byte..byte + 1);
+            let mut matches = cursor.matches(query, tree.root_node(), RopeProvider(text));
+            let names = query.capture_names();
+            while let Some(m) = matches.next() {
+                if !satisfies_predicates(query, m, text) {
+                    continue;
+                }
+                for (order, capture) in m.captures.iter().enumerate() {
+                    let name = names[capture.index as usize];
+                    captures.push((capture.node.id(), m.pattern_index, order, name));
+                }
+            }
+        }
+        captures.sort_by_key(|&(_, pattern, order, _)| (pattern, order));
+
+        let mut scopes = Vec::new();
+        for node in nodes {
+            scopes.push(node.kind().to_string());
+            for &(_, _, _, name) in captures.iter().filter(|capture| capture.0 == node.id()) {
+                scopes.push(format!("@{name}"));
+            }
+        }
+        scopes
+    }
+
     pub fn is_highlight(&self) -> bool {
         self.query.is_some()
     }
 
-    /// Highlights the interval between `start` and `end` char indices.
-    /// Returns a list of (start byte, end byte, token_name) for highlighting.
+    /// Highlights the interval between `start` and `end` byte indices.
+    /// Returns non-overlapping (start byte, end byte, token_name) runs clipped
+    /// to the interval, where overlapping captures are resolved the way
+    /// Neovim does: a higher `(#set! "priority" N)` wins, then the innermost
+    /// node, then the pattern that comes later in the query. A capture named
+    /// `@none` clears the highlights below it.
     pub fn highlight_interval<T: Copy>(
         &self,
         start: usize,
@@ -569,104 +993,298 @@ impl Code {
         let text = self.content.slice(..);
         let root_node = tree.root_node();
 
-        let mut results = Self::highlight(
+        let mut captures = self.highlight(
             text,
-            start,
-            end,
+            start..end,
             query,
+            self.injections_query.as_ref(),
             root_node,
             theme,
-            self.injection_parsers.as_ref(),
-            self.injection_queries.as_ref(),
         );
+        captures.extend(self.local_reference_captures(start, end, theme));
+
+        resolve_highlights(captures, start, end)
+    }
+
+    /// Captures that highlight the local references in `start..end` bytes
+    /// like the definitions they resolve to, e.g. a parameter wherever it is
+    /// used in its function.
+    fn local_reference_captures<T: Copy>(
+        &self,
+        start: usize,
+        end: usize,
+        theme: &HashMap<String, T>,
+    ) -> Vec<HighlightCapture<T>> {
+        let (Some(query), Some(tree)) = (&self.query, &self.tree) else {
+            return Vec::new();
+        };
+        let text = self.content.slice(..);
+        let mut definitions: HashMap<Range<usize>, Option<T>> = HashMap::new();
+        let mut results = Vec::new();
+        for (reference, definition) in self.local_references(start, end) {
+            let value = *definitions.entry(definition.clone()).or_insert_with(|| {
+                let captures = self.highlight(
+                    text,
+                    definition.clone(),
+                    query,
+                    None,
+                    tree.root_node(),
+                    theme,
+                );
+                captures
+                    .into_iter()
+                    .filter(|capture| (capture.start..capture.end) == definition)
+                    .max_by_key(|capture| capture.precedence)
+                    .and_then(|capture| capture.value)
+            });
+            let Some(value) = value else {
+                continue;
+            };
+            results.push(HighlightCapture {
+                start: reference.start,
+                end: reference.end,
+                value: Some(value),
+                precedence: (
+                    0,
+                    DEFAULT_HIGHLIGHT_PRIORITY,
+                    Reverse(reference.len()),
+                    usize::MAX,
+                    0,
+                ),
+            });
+        }
+        results
+    }
+
+    /// Pairs the `@local.reference` captures in `start..end` bytes with the
+    /// `@local.definition` each resolves to: the latest one before it with
+    /// the same name in the innermost `@local.scope` around it that has one.
+    fn local_references(&self, start: usize, end: usize) -> Vec<(Range<usize>, Range<usize>)> {
+        let (Some(query), Some(tree)) = (&self.locals_query, &self.tree) else {
+            return Vec::new();
+        };
+        let root = tree.root_node();
+        // Definitions a reference can see start at the latest in the top
+        // level node around it
+        let from = root
+            .first_child_for_byte(start)
+            .map_or(start, |node| node.start_byte().min(start));
 
-        results.sort_by(|a, b| {
-            let len_a = a.1 - a.0;
-            let len_b = b.1 - b.0;
-            match len_b.cmp(&len_a) {
-                std::cmp::Ordering::Equal => b.2.cmp(&a.2),
-                other => other,
+        let text = self.content.slice(..);
+        let mut cursor = QueryCursor::new();
+        cursor.set_byte_range(from..end);
+        let mut captures = cursor.captures(query, root, RopeProvider(text));
+        let names = query.capture_names();
+
+        let mut scopes: Vec<Range<usize>> = Vec::new();
+        let mut definitions: HashMap<String, Vec<Range<usize>>> = HashMap::new();
+        let mut references: Vec<Range<usize>> = Vec::new();
+        while let Some((m, idx)) = captures.next() {
+            if !satisfies_predicates(query, m, text) {
+                continue;
             }
-        });
+            let capture = m.captures[*idx];
+            let range = capture.node.byte_range();
+            match names[capture.index as usize] {
+                "local.scope" => scopes.push(range),
+                "local.reference" => references.push(range),
+                name if name.starts_with("local.definition") => {
+                    let name = text.byte_slice(range.clone()).to_string();
+                    let ranges = definitions.entry(name).or_default();
+                    if !ranges.contains(&range) {
+                        ranges.push(range);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Innermost scope around a range, where `None` is the whole document
+        let scope_of = |range: &Range<usize>| {
+            scopes
+                .iter()
+                .filter(|scope| scope.start <= range.start && range.end <= scope.end)
+                .min_by_key(|scope| scope.len())
+        };
 
+        let mut results = Vec::new();
+        for reference in references {
+            if reference.end <= start || reference.start >= end {
+                continue;
+            }
+            let name = text.byte_slice(reference.clone()).to_string();
+            let Some(candidates) = definitions.get(&name) else {
+                continue;
+            };
+            if candidates.contains(&reference) {
+                continue;
+            }
+            let definition = candidates
+                .iter()
+                .filter(|definition| definition.start <= reference.start)
+                .filter_map(|definition| {
+                    let scope = scope_of(definition);
+                    let visible = scope.is_none_or(|scope| {
+                        scope.start <= reference.start && reference.end <= scope.end
+                    });
+                    visible.then(|| (scope.map_or(usize::MAX, |scope| scope.len()), definition))
+                })
+                .min_by_key(|(scope_len, definition)| (*scope_len, Reverse(definition.start)));
+            if let Some((_, definition)) = definition {
+                results.push((reference, definition.clone()));
+            }
+        }
         results
-            .into_iter()
-            .map(|(start, end, _, value)| (start, end, value))
-            .collect()
     }
 
+    /// Highlights lines `start_line..end_line` with a single query pass.
+    /// Returns one highlight list per line, ordered the same way as `highlight_interval`.
+    pub fn highlight_lines<T: Copy>(
+        &self,
+        start_line: usize,
+        end_line: usize,
+        theme: &HashMap<String, T>,
+    ) -> Vec<Vec<(usize, usize, T)>> {
+        let (start, end) = self.lines_byte_range(start_line, end_line);
+        let highlights = self.highlight_interval(start, end, theme);
+        self.group_highlights_by_line(&highlights, start_line, end_line)
+    }
+
+    /// Returns the byte range covering lines `start_line..end_line`.
+    pub fn lines_byte_range(&self, start_line: usize, end_line: usize) -> (usize, usize) {
+        let len_lines = self.content.len_lines();
+        let start = self.content.line_to_byte(start_line.min(len_lines));
+        let end = self.content.line_to_byte(end_line.min(len_lines));
+        (start, end.max(start))
+    }
+
+    /// Distributes highlights over the lines `start_line..end_line` they intersect.
+    /// Highlights spanning several lines are repeated in every line they cover.
+    pub fn group_highlights_by_line<T: Copy>(
+        &self,
+        highlights: &[(usize, usize, T)],
+        start_line: usize,
+        end_line: usize,
+    ) -> Vec<Vec<(usize, usize, T)>> {
+        let mut lines = vec![Vec::new(); end_line.saturating_sub(start_line)];
+        if lines.is_empty() {
+            return lines;
+        }
+
+        let len_bytes = self.content.len_bytes();
+        for &(start, end, value) in highlights {
+            let first = self.content.byte_to_line(start.min(len_bytes)).max(start_line);
+            let last_byte = end.saturating_sub(1).max(start).min(len_bytes);
+            let last = self.content.byte_to_line(last_byte).min(end_line - 1);
+            for line in first..=last {
+                lines[line - start_line].push((start, end, value));
+            }
+        }
+        lines
+    }
+
+    /// Captures of `query` in `range` of `text`, along with the captures of
+    /// the languages `injections` embeds there.
     fn highlight<T: Copy>(
+        &self,
         text: RopeSlice<'_>,
-        start_byte: usize,
-        end_byte: usize,
+        range: Range<usize>,
         query: &Query,
+        injections: Option<&Query>,
         root_node: Node,
         theme: &HashMap<String, T>,
-        injection_parsers: Option<&HashMap<String, Rc<RefCell<Parser>>>>,
-        injection_queries: Option<&HashMap<String, Query>>,
-    ) -> Vec<(usize, usize, usize, T)> {
+    ) -> Vec<HighlightCapture<T>> {
         let mut cursor = QueryCursor::new();
-        cursor.set_byte_range(start_byte..end_byte);
+        cursor.set_byte_range(range.clone());
 
         let mut matches = cursor.matches(query, root_node, RopeProvider(text));
 
         let mut results = Vec::new();
-        let capture_names = query.capture_names();
+        // Theme value of each capture, `Some(None)` for `@none` and `None`
+        // for captures the theme has nothing for
+        let values: Vec<Option<Option<T>>> = query
+            .capture_names()
+            .iter()
+            .map(|&name| match name {
+                "none" => Some(None),
+                name => resolve_capture(theme, name).map(|(_, value)| Some(*value)),
+            })
+            .collect();
 
         while let Some(m) = matches.next() {
-            for capture in m.captures {
-                let name = capture_names[capture.index as usize];
-                if let Some(value) = theme.get(name) {
-                    results.push((
-                        capture.node.start_byte(),
-                        capture.node.end_byte(),
-                        capture.index as usize,
-                        *value,
-                    ));
-                } else if let Some(lang) = name.strip_prefix("injection.content.") {
-                    let Some(injection_parsers) = injection_parsers else {
-                        continue;
-                    };
-                    let Some(injection_queries) = injection_queries else {
-                        continue;
-                    };
-                    let Some(parser) = injection_parsers.get(lang) else {
-                        continue;
-                    };
-                    let Some(injection_query) = injection_queries.get(lang) else {
-                        continue;
-                    };
-
-                    let start = capture.node.start_byte();
-                    let end = capture.node.end_byte();
-                    let slice = text.byte_slice(start..end);
-
-                    let mut parser = parser.borrow_mut();
-                    let Some(inj_tree) = parser.parse(slice.to_string(), None) else {
-                        continue;
-                    };
-
-                    let injection_results = Self::highlight(
-                        slice,
-                        0,
-                        end - start,
-                        injection_query,
-                        inj_tree.root_node(),
-                        theme,
-                        injection_parsers.into(),
-                        injection_queries.into(),
-                    );
+            if !satisfies_predicates(query, m, text) {
+                continue;
+            }
+            let priority = pattern_priority(query, m.pattern_index);
+            for (order, capture) in m.captures.iter().enumerate() {
+                let Some(value) = values[capture.index as usize] else {
+                    continue;
+                };
+                let start = capture.node.start_byte();
+                let end = capture.node.end_byte();
+                results.push(HighlightCapture {
+                    start,
+                    end,
+                    value,
+                    precedence: (0, priority, Reverse(end - start), m.pattern_index, order),
+                });
+            }
+        }
 
-                    for (s, e, i, v) in injection_results {
-                        results.push((s + start, e + start, i, v));
-                    }
-                }
+        if let Some(injections) = injections {
+            for (lang, content) in injection_ranges(text, range.clone(), injections, root_node) {
+                results.extend(self.highlight_injection(
+                    text,
+                    content,
+                    range.clone(),
+                    &lang,
+                    theme,
+                ));
             }
         }
 
         results
     }
 
+    /// Highlights the `content` bytes of `text` as language `lang`, with the
+    /// captures in `text` bytes. Only captures in `range` are queried.
+    fn highlight_injection<T: Copy>(
+        &self,
+        text: RopeSlice<'_>,
+        content: Range<usize>,
+        range: Range<usize>,
+        lang: &str,
+        theme: &HashMap<String, T>,
+    ) -> Vec<HighlightCapture<T>> {
+        let Some(layer) = self.injection_layer(lang) else {
+            return Vec::new();
+        };
+
+        let slice = text.byte_slice(content.clone());
+        let tree = layer.parser.borrow_mut().parse(slice.to_string(), None);
+        let Some(tree) = tree else {
+            return Vec::new();
+        };
+
+        let from = range.start.clamp(content.start, content.end) - content.start;
+        let to = range.end.clamp(content.start, content.end) - content.start;
+        let mut results = self.highlight(
+            slice,
+            from..to,
+            &layer.highlights,
+            layer.injections.as_ref(),
+            tree.root_node(),
+            theme,
+        );
+        // Injected captures are drawn over the ones of the host language
+        for capture in &mut results {
+            capture.start += content.start;
+            capture.end += content.start;
+            capture.precedence.0 += 1;
+        }
+        results
+    }
+
     pub fn undo(&mut self) -> Option<EditBatch> {
         let batch = self.history.undo()?;
         self.applying_history = false;
@@ -683,6 +1301,8 @@ impl Code {
         }
 
         self.applying_history = true;
+        self.modified = !self.history.is_saved();
+        self.notify_changes(ChangeOrigin::Undo);
         Some(batch)
     }
 
@@ -702,21 +1322,27 @@ impl Code {
         }
 
         self.applying_history = true;
+        self.modified = !self.history.is_saved();
+        self.notify_changes(ChangeOrigin::Redo);
         Some(batch)
     }
 
+    /// Whether `c` is part of a word: a letter, digit, `_` or one of the
+    /// language's [`LanguageConfig::word_chars`].
+    pub fn is_word_char(&self, c: char) -> bool {
+        is_word_char(c) || self.language_config.word_chars.contains(&c)
+    }
+
     pub fn word_boundaries(&self, pos: usize) -> (usize, usize) {
         let len = self.content.len_chars();
         if pos >= len {
             return (pos, pos);
         }
 
-        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
-
         let mut start = pos;
         while start > 0 {
             let c = self.content.char(start - 1);
-            if !is_word_char(c) {
+            if !self.is_word_char(c) {
                 break;
             }
             start -= 1;
@@ -725,7 +1351,7 @@ impl Code {
         let mut end = pos;
         while end < len {
             let c = self.content.char(end);
-            if !is_word_char(c) {
+            if !self.is_word_char(c) {
                 break;
             }
             end += 1;
@@ -734,50 +1360,782 @@ impl Code {
         (start, end)
     }
 
-    pub fn line_boundaries(&self, pos: usize) -> (usize, usize) {
-        let total_chars = self.content.len_chars();
-        if pos >= total_chars {
-            return (pos, pos);
+    /// End of the identifier part or punctuation run at `pos`, or of the next
+    /// one if `pos` is on a separator or the end of a part. Identifiers are
+    /// split at underscores and case changes, so `parseHTTPRequest` and
+    /// `parse_http_request` both have three parts.
+    pub fn next_sub_word_boundary(&self, pos: usize) -> usize {
+        let len = self.content.len_chars();
+        let mut i = pos.min(len);
+        while i < len && self.is_sub_word_separator(self.content.char(i)) {
+            i += 1;
         }
-
-        let line = self.content.char_to_line(pos);
-        let start = self.content.line_to_char(line);
-        let end = start + self.content.line(line).len_chars();
-
-        (start, end)
+        if i == len {
+            return len;
+        }
+        i += 1;
+        while i < len
+            && !self.is_sub_word_separator(self.content.char(i))
+            && !self.is_sub_word_start(i)
+        {
+            i += 1;
+        }
+        i
     }
 
-    pub fn indent(&self) -> String {
-        indent(&self.lang)
+    /// Start of the identifier part or punctuation run at `pos`, or of the
+    /// previous one, see [`Code::next_sub_word_boundary`].
+    pub fn prev_sub_word_boundary(&self, pos: usize) -> usize {
+        let mut i = pos.min(self.content.len_chars());
+        while i > 0 && self.is_sub_word_separator(self.content.char(i - 1)) {
+            i -= 1;
+        }
+        if i == 0 {
+            return 0;
+        }
+        i -= 1;
+        while i > 0
+            && !self.is_sub_word_separator(self.content.char(i - 1))
+            && !self.is_sub_word_start(i)
+        {
+            i -= 1;
+        }
+        i
     }
 
-    pub fn comment(&self) -> String {
-        lang_comment(&self.lang).to_string()
+    /// The identifier part at `pos`, or the whole word on a separator.
+    pub fn sub_word_boundaries(&self, pos: usize) -> (usize, usize) {
+        if pos >= self.content.len_chars() || self.is_sub_word_separator(self.content.char(pos)) {
+            return self.word_boundaries(pos);
+        }
+        let end = self.next_sub_word_boundary(pos);
+        (self.prev_sub_word_boundary(end), end)
     }
 
-    pub fn indentation_level(&self, line: usize, col: usize) -> usize {
-        if self.lang == "unknown" || self.lang.is_empty() {
-            return 0;
-        }
-        let line_str = self.line(line);
-        count_indent_units(line_str, &self.indent(), Some(col))
+    /// Whitespace and word characters other than letters and digits, like
+    /// `_`, separate sub-words.
+    fn is_sub_word_separator(&self, c: char) -> bool {
+        c.is_whitespace() || self.is_word_char(c) && !c.is_alphanumeric()
     }
 
-    pub fn is_only_indentation_before(&self, r: usize, c: usize) -> bool {
-        if self.lang == "unknown" || self.lang.is_empty() {
-            return false;
+    /// Whether a new sub-word starts at `i`, which follows a character of
+    /// the same run: at a switch between punctuation and letters, a lower
+    /// to upper case change, or the last capital of an acronym followed by
+    /// a lowercase letter (`HTTP|Request`).
+    fn is_sub_word_start(&self, i: usize) -> bool {
+        let prev = self.content.char(i - 1);
+        let c = self.content.char(i);
+        if prev.is_alphanumeric() != c.is_alphanumeric() {
+            return true;
         }
-        if r >= self.len_lines() || c == 0 {
+        if !c.is_uppercase() {
             return false;
         }
+        let next_is_lower = i + 1 < self.content.len_chars()
+            && self.content.char(i + 1).is_lowercase();
+        !prev.is_uppercase() || next_is_lower
+    }
+
+    /// Character ranges of the outermost comment and string nodes of the syntax
+    /// tree, in document order. Empty without a syntax tree.
+    pub fn comment_and_string_ranges(&self) -> Vec<(usize, usize)> {
+        let Some(tree) = &self.tree else {
+            return vec![];
+        };
+        let mut ranges = Vec::new();
+        let mut cursor = tree.walk();
+        'walk: loop {
+            let node = cursor.node();
+            let kind = node.kind();
+            if kind.contains("comment") || kind.contains("string") {
+                let range = node.byte_range();
+                ranges.push((
+                    self.content.byte_to_char(range.start),
+                    self.content.byte_to_char(range.end),
+                ));
+            } else if cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    break 'walk;
+                }
+            }
+        }
+        ranges
+    }
+
+    /// Whether char `offset` is inside a comment, e.g. to skip auto-pairing
+    /// there. The end of a line comment counts as inside, since typing there
+    /// extends the comment. False without a syntax tree.
+    pub fn is_in_comment(&self, offset: usize) -> bool {
+        self.literal_at(offset)
+            .is_some_and(|node| node.kind().contains("comment"))
+    }
+
+    /// Whether char `offset` is between the quotes of a string, outside any
+    /// interpolation such as `${...}` or `{...}` in an f-string. False without
+    /// a syntax tree.
+    pub fn is_in_string(&self, offset: usize) -> bool {
+        self.literal_at(offset)
+            .is_some_and(|node| !node.kind().contains("comment"))
+    }
+
+    /// Innermost comment or string node `offset` is inside of, by the same
+    /// node kinds as [`Code::comment_and_string_ranges`].
+    fn literal_at(&self, offset: usize) -> Option<Node<'_>> {
+        let tree = self.tree.as_ref()?;
+        let byte = self
+            .content
+            .char_to_byte(offset.min(self.content.len_chars()));
+        // Also finds the node ending at `offset`, e.g. a line comment before
+        // the line break
+        let node = tree
+            .root_node()
+            .descendant_for_byte_range(byte.saturating_sub(1), byte)?;
+        for node in std::iter::successors(Some(node), Node::parent) {
+            let kind = node.kind();
+            let range = node.byte_range();
+            let inside = range.start < byte && byte < range.end;
+            if kind.contains("interpolation") || kind.contains("substitution") {
+                if inside {
+                    return None;
+                }
+                continue;
+            }
+            let is_comment = kind.contains("comment");
+            if !is_comment && !kind.contains("string") {
+                continue;
+            }
+            let open_end = is_comment && byte == range.end && {
+                let text = self.content.byte_slice(range.clone()).to_string();
+                !text.ends_with("*/") && !text.ends_with("-->")
+            };
+            if inside || (range.start < byte && open_end) {
+                return Some(node);
+            }
+        }
+        None
+    }
+
+    /// Character ranges of all tokens equal to the identifier at `pos`.
+    ///
+    /// With a syntax tree, tokens must be leaf nodes of the same kind and text,
+    /// so matches inside strings, comments or longer names are skipped. Without
+    /// one, whole-word text matches are returned.
+    pub fn token_occurrences(&self, pos: usize) -> Vec<(usize, usize)> {
+        let (start, end) = self.word_boundaries(pos);
+        let (start, end) = if start == end && pos > 0 {
+            self.word_boundaries(pos - 1)
+        } else {
+            (start, end)
+        };
+        if start == end {
+            return vec![];
+        }
+        let name = self.content.slice(start..end).to_string();
+
+        let Some(tree) = &self.tree else {
+            let content = self.content.to_string();
+            return content
+                .match_indices(&name)
+                .map(|(byte, _)| self.content.byte_to_char(byte))
+                .filter(|&at| self.word_boundaries(at) == (at, at + (end - start)))
+                .map(|at| (at, at + (end - start)))
+                .collect();
+        };
+
+        let start_byte = self.content.char_to_byte(start);
+        let end_byte = self.content.char_to_byte(end);
+        let Some(target) = tree
+            .root_node()
+            .descendant_for_byte_range(start_byte, end_byte)
+            .filter(|node| node.child_count() == 0 && node.byte_range() == (start_byte..end_byte))
+        else {
+            return vec![];
+        };
+
+        let mut ranges = Vec::new();
+        let mut cursor = tree.walk();
+        'walk: loop {
+            let node = cursor.node();
+            if node.child_count() == 0 {
+                if node.kind_id() == target.kind_id()
+                    && node.byte_range().len() == name.len()
+                    && self.content.byte_slice(node.byte_range()) == name.as_str()
+                {
+                    let range = node.byte_range();
+                    ranges.push((
+                        self.content.byte_to_char(range.start),
+                        self.content.byte_to_char(range.end),
+                    ));
+                }
+            } else if cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    break 'walk;
+                }
+            }
+        }
+        ranges
+    }
+
+    /// Named declarations (functions, types, modules, headings) in document order.
+    /// Empty without a syntax tree.
+    pub fn symbols(&self) -> Vec<Symbol> {
+        let Some(tree) = &self.tree else {
+            return vec![];
+        };
+
+        let mut symbols = Vec::new();
+        let mut cursor = tree.walk();
+        'walk: loop {
+            let node = cursor.node();
+            if let Some(name) = declaration_name(node) {
+                symbols.push(self.symbol(node, name.byte_range()));
+            }
+            if cursor.goto_first_child() {
+                continue;
+            }
+            while !cursor.goto_next_sibling() {
+                if !cursor.goto_parent() {
+                    break 'walk;
+                }
+            }
+        }
+        symbols
+    }
+
+    /// Named declarations around char `offset`, outermost first, such as
+    /// module, impl and function, for a breadcrumbs bar.
+    ///
+    /// Declarations and names come from the language's `breadcrumbs.scm`
+    /// (or `"<lang>.breadcrumbs"` in the custom highlights): `@breadcrumb`
+    /// nodes named by the text from their first `@name` capture to the last.
+    /// Without one, the declarations of [`Code::symbols`] are used. Empty
+    /// without a syntax tree.
+    pub fn breadcrumbs(&self, offset: usize) -> Vec<Symbol> {
+        let Some(tree) = &self.tree else {
+            return vec![];
+        };
+        let byte = self
+            .content
+            .char_to_byte(offset.min(self.content.len_chars()));
+        let Some(node) = tree.root_node().named_descendant_for_byte_range(byte, byte) else {
+            return vec![];
+        };
+        let mut ancestors: Vec<Node> = std::iter::successors(Some(node), Node::parent).collect();
+        ancestors.reverse();
+
+        let Some(query) = &self.breadcrumbs_query else {
+            return ancestors
+                .into_iter()
+                .filter_map(|node| Some(self.symbol(node, declaration_name(node)?.byte_range())))
+                .collect();
+        };
+
+        let text = self.content.slice(..);
+        let names = query.capture_names();
+        let mut breadcrumbs = Vec::new();
+        for node in ancestors {
+            let mut cursor = QueryCursor::new();
+            cursor.set_max_start_depth(Some(0));
+            let mut matches = cursor.matches(query, node, RopeProvider(text));
+            while let Some(m) = matches.next() {
+                if !satisfies_predicates(query, m, text) {
+                    continue;
+                }
+                let captures = || {
+                    m.captures
+                        .iter()
+                        .filter(|capture| names[capture.index as usize] == "name")
+                };
+                let start = captures().map(|capture| capture.node.start_byte()).min();
+                let end = captures().map(|capture| capture.node.end_byte()).max();
+                let is_breadcrumb = m.captures.iter().any(|capture| {
+                    names[capture.index as usize] == "breadcrumb" && capture.node.id() == node.id()
+                });
+                if let (true, Some(start), Some(end)) = (is_breadcrumb, start, end) {
+                    breadcrumbs.push(self.symbol(node, start..end));
+                    break;
+                }
+            }
+        }
+        breadcrumbs
+    }
+
+    /// Symbol for the declaration `node` named by the `name` bytes.
+    fn symbol(&self, node: Node, name: Range<usize>) -> Symbol {
+        let offset = self.content.byte_to_char(name.start);
+        let name = self.content.byte_slice(name).to_string();
+        Symbol {
+            name: name.split_whitespace().collect::<Vec<_>>().join(" "),
+            kind: node.kind().to_string(),
+            line: self.content.char_to_line(offset),
+            offset,
+        }
+    }
+
+    pub fn line_boundaries(&self, pos: usize) -> (usize, usize) {
+        let total_chars = self.content.len_chars();
+        if pos >= total_chars {
+            return (pos, pos);
+        }
+
+        let line = self.content.char_to_line(pos);
+        let start = self.content.line_to_char(line);
+        let line_break = usize::from(line + 1 < self.content.len_lines());
+        let end = start + self.line_len(line) + line_break;
+
+        (start, end)
+    }
+
+    /// Character range of the paragraph at `pos`: the run of non-blank
+    /// lines around it with the line break after the last one. On a blank
+    /// line this is just that line.
+    pub fn paragraph_boundaries(&self, pos: usize) -> (usize, usize) {
+        if pos >= self.content.len_chars() {
+            return (pos, pos);
+        }
+        let is_blank = |line: usize| self.content.line(line).chars().all(char::is_whitespace);
+        let line = self.content.char_to_line(pos);
+        if is_blank(line) {
+            return self.line_boundaries(pos);
+        }
+        let mut first = line;
+        while first > 0 && !is_blank(first - 1) {
+            first -= 1;
+        }
+        let mut last = line;
+        while last + 1 < self.content.len_lines() && !is_blank(last + 1) {
+            last += 1;
+        }
+        let start = self.content.line_to_char(first);
+        let (_, end) = self.line_boundaries(self.content.line_to_char(last));
+        (start, end)
+    }
+
+    /// Start of the first blank line after the paragraph at or below `pos`,
+    /// or the end of the document.
+    pub fn next_paragraph_boundary(&self, pos: usize) -> usize {
+        let last = self.content.len_lines().saturating_sub(1);
+        let mut line = self.content.char_to_line(pos.min(self.content.len_chars()));
+        while line < last && self.is_blank_line(line) {
+            line += 1;
+        }
+        while line < last && !self.is_blank_line(line) {
+            line += 1;
+        }
+        if self.is_blank_line(line) {
+            self.content.line_to_char(line)
+        } else {
+            self.content.len_chars()
+        }
+    }
+
+    /// Start of the last blank line before the paragraph above `pos`, or the
+    /// start of the document.
+    pub fn prev_paragraph_boundary(&self, pos: usize) -> usize {
+        let mut line = self.content.char_to_line(pos.min(self.content.len_chars()));
+        if pos == self.content.line_to_char(line) || self.is_blank_line(line) {
+            // an empty line or the line start is not part of the paragraph
+            line = line.saturating_sub(1);
+        }
+        while line > 0 && self.is_blank_line(line) {
+            line -= 1;
+        }
+        while line > 0 && !self.is_blank_line(line) {
+            line -= 1;
+        }
+        if self.is_blank_line(line) {
+            self.content.line_to_char(line)
+        } else {
+            0
+        }
+    }
+
+    fn is_blank_line(&self, line: usize) -> bool {
+        self.content.line(line).chars().all(char::is_whitespace)
+    }
+
+    /// Character ranges of the blocks enclosing `pos`, innermost first,
+    /// without the surrounding whitespace. A block is a syntax node
+    /// delimited by one of the language's bracket pairs, like a `{ }` body,
+    /// or a `block` node such as a Python suite. Without a syntax tree,
+    /// blocks are runs of lines indented deeper than the line before them.
+    pub fn enclosing_blocks(&self, pos: usize) -> Vec<(usize, usize)> {
+        let pos = pos.min(self.content.len_chars());
+        let blocks = match &self.tree {
+            Some(tree) => self.syntax_blocks(tree, pos),
+            None => self.indentation_blocks(pos),
+        };
+        blocks
+            .into_iter()
+            .map(|(start, end)| self.trim_range(start, end))
+            .filter(|&(start, end)| start < end)
+            .collect()
+    }
+
+    fn syntax_blocks(&self, tree: &Tree, pos: usize) -> Vec<(usize, usize)> {
+        let byte = self.content.char_to_byte(pos);
+        let mut blocks: Vec<(usize, usize)> = Vec::new();
+        let mut node = tree.root_node().descendant_for_byte_range(byte, byte);
+        while let Some(n) = node {
+            node = n.parent();
+            let inner = match self.delimiters(n) {
+                Some((open, close)) => (open.end_byte(), close.start_byte()),
+                _ if n.kind().ends_with("block") => (n.start_byte(), n.end_byte()),
+                _ => continue,
+            };
+            let inner = (
+                self.content.byte_to_char(inner.0),
+                self.content.byte_to_char(inner.1),
+            );
+            if inner.0 <= pos && pos <= inner.1 && blocks.last() != Some(&inner) {
+                blocks.push(inner);
+            }
+        }
+        blocks
+    }
+
+    /// Opening and closing bracket of `node`, if it starts and ends with one
+    /// of the language's bracket pairs.
+    fn delimiters<'t>(&self, node: Node<'t>) -> Option<(Node<'t>, Node<'t>)> {
+        let count = node.child_count() as u32;
+        if count < 2 {
+            return None;
+        }
+        let (open, close) = node.child(0).zip(node.child(count - 1))?;
+        self.language_config
+            .brackets
+            .iter()
+            .any(|&(o, c)| open.kind().chars().eq([o]) && close.kind().chars().eq([c]))
+            .then_some((open, close))
+    }
+
+    /// The syntax node selected by `start..end` (chars), or the one at the
+    /// cursor if the selection is empty, as the outermost node of that range.
+    fn node_at<'t>(&self, tree: &'t Tree, start: usize, end: usize) -> Option<Node<'t>> {
+        let start = self
+            .content
+            .char_to_byte(start.min(self.content.len_chars()));
+        let end = self.content.char_to_byte(end.min(self.content.len_chars()));
+        let root = tree.root_node();
+        let mut node = root.named_descendant_for_byte_range(start, end)?;
+        while let Some(parent) = node.parent()
+            && parent.byte_range() == node.byte_range()
+            && parent.parent().is_some()
+        {
+            node = parent;
+        }
+        node.parent().is_some().then_some(node)
+    }
+
+    /// Swaps the node selected by `start..end` (chars), or the one at the
+    /// cursor, with its next (or previous) named sibling and selects it at
+    /// its new place.
+    pub fn swap_node(&self, start: usize, end: usize, forward: bool) -> Option<NodeEdit> {
+        let node = self.node_at(self.tree.as_ref()?, start, end)?;
+        let edit = if forward {
+            let sibling = node.next_named_sibling()?;
+            let between = node.end_byte()..sibling.start_byte();
+            let (text, offsets) = self.rearrange(
+                node.start_byte(),
+                &[sibling.byte_range(), between, node.byte_range()],
+            );
+            (
+                node.start_byte()..sibling.end_byte(),
+                text,
+                offsets[2],
+                offsets[3],
+            )
+        } else {
+            let sibling = node.prev_named_sibling()?;
+            let between = sibling.end_byte()..node.start_byte();
+            let (text, offsets) = self.rearrange(
+                sibling.start_byte(),
+                &[node.byte_range(), between, sibling.byte_range()],
+            );
+            (
+                sibling.start_byte()..node.end_byte(),
+                text,
+                offsets[0],
+                offsets[1],
+            )
+        };
+        let (range, text, start, end) = edit;
+        Some(self.node_edit(range, text, end, Some((start, end))))
+    }
+
+    /// Replaces the parent of the node selected by `start..end` (chars), or
+    /// the one at the cursor, with that node and selects it. Bracketed lists
+    /// around the node, like argument lists or blocks, are replaced along with
+    /// their parent, so `f(g(x))` with `x` raised becomes `f(x)`.
+    pub fn raise_node(&self, start: usize, end: usize) -> Option<NodeEdit> {
+        let node = self.node_at(self.tree.as_ref()?, start, end)?;
+        let parent = std::iter::successors(node.parent(), Node::parent)
+            .take_while(|parent| parent.parent().is_some())
+            .find(|parent| {
+                parent.is_named()
+                    && parent.byte_range() != node.byte_range()
+                    && self.delimiters(*parent).is_none()
+            })?;
+        let (text, offsets) = self.rearrange(parent.start_byte(), &[node.byte_range()]);
+        let selection = (offsets[0], offsets[1]);
+        Some(self.node_edit(parent.byte_range(), text, offsets[1], Some(selection)))
+    }
+
+    /// Moves the closing (or opening) bracket of the innermost bracketed node
+    /// around `pos` (chars) past the next (or previous) node, pulling it into
+    /// the brackets, e.g. `(a b) c` becomes `(a b c)`. Brackets with nothing
+    /// to pull in are skipped for the ones around them.
+    pub fn slurp(&self, pos: usize, forward: bool) -> Option<NodeEdit> {
+        self.delimited_ancestors(pos)
+            .into_iter()
+            .find_map(|(node, open, close)| {
+                let item = self.list_item(node, forward)?;
+                if forward {
+                    let sibling = item.next_named_sibling()?;
+                    let inner_end =
+                        last_named_child(node).map_or(open.end_byte(), |n| n.end_byte());
+                    let (text, offsets) = self.rearrange(
+                        inner_end,
+                        &[
+                            close.end_byte()..sibling.start_byte(),
+                            sibling.byte_range(),
+                            inner_end..close.start_byte(),
+                            close.byte_range(),
+                        ],
+                    );
+                    let cursor = pos.min(offsets[0]);
+                    Some(self.node_edit(inner_end..sibling.end_byte(), text, cursor, None))
+                } else {
+                    let sibling = item.prev_named_sibling()?;
+                    let inner_start =
+                        first_named_child(node).map_or(close.start_byte(), |n| n.start_byte());
+                    let (text, offsets) = self.rearrange(
+                        sibling.start_byte(),
+                        &[
+                            open.byte_range(),
+                            open.end_byte()..inner_start,
+                            sibling.byte_range(),
+                            sibling.end_byte()..open.start_byte(),
+                        ],
+                    );
+                    let cursor = pos.max(offsets[1]);
+                    Some(self.node_edit(sibling.start_byte()..inner_start, text, cursor, None))
+                }
+            })
+    }
+
+    /// Moves the closing (or opening) bracket of the innermost bracketed node
+    /// around `pos` (chars) before its last (or after its first) node,
+    /// pushing that node out, e.g. `(a b c)` becomes `(a b) c`.
+    pub fn barf(&self, pos: usize, forward: bool) -> Option<NodeEdit> {
+        self.delimited_ancestors(pos)
+            .into_iter()
+            .find_map(|(node, open, close)| {
+                self.list_item(node, forward)?;
+                if forward {
+                    let last = last_named_child(node)?;
+                    let inner_end = last
+                        .prev_named_sibling()
+                        .map_or(open.end_byte(), |n| n.end_byte());
+                    let (text, offsets) = self.rearrange(
+                        inner_end,
+                        &[
+                            last.end_byte()..close.start_byte(),
+                            close.byte_range(),
+                            inner_end..last.start_byte(),
+                            last.byte_range(),
+                        ],
+                    );
+                    let cursor = pos.min(offsets[1]);
+                    Some(self.node_edit(inner_end..close.end_byte(), text, cursor, None))
+                } else {
+                    let first = first_named_child(node)?;
+                    let inner_start = first
+                        .next_named_sibling()
+                        .map_or(close.start_byte(), |n| n.start_byte());
+                    let (text, offsets) = self.rearrange(
+                        open.start_byte(),
+                        &[
+                            first.byte_range(),
+                            first.end_byte()..inner_start,
+                            open.byte_range(),
+                            open.end_byte()..first.start_byte(),
+                        ],
+                    );
+                    let cursor = pos.max(offsets[3]);
+                    Some(self.node_edit(open.start_byte()..inner_start, text, cursor, None))
+                }
+            })
+    }
+
+    /// Bracketed nodes whose brackets enclose `pos` (chars), innermost first,
+    /// with their opening and closing bracket.
+    fn delimited_ancestors(&self, pos: usize) -> Vec<(Node<'_>, Node<'_>, Node<'_>)> {
+        let Some(tree) = &self.tree else {
+            return Vec::new();
+        };
+        let byte = self.content.char_to_byte(pos.min(self.content.len_chars()));
+        std::iter::successors(
+            tree.root_node().descendant_for_byte_range(byte, byte),
+            Node::parent,
+        )
+        .filter_map(|node| {
+            let (open, close) = self.delimiters(node)?;
+            (open.end_byte() <= byte && byte <= close.start_byte()).then_some((node, open, close))
+        })
+        .collect()
+    }
+
+    /// The element of the enclosing list (bracketed node, or the whole file)
+    /// that ends (or starts) with the bracketed `node`, i.e. the node whose
+    /// siblings `node` can pull in or push out. `None` if something follows
+    /// (or precedes) the brackets within the element, like the `;` after a
+    /// call, as the bracket could not move past it.
+    fn list_item<'t>(&self, node: Node<'t>, forward: bool) -> Option<Node<'t>> {
+        let mut item = node;
+        while let Some(parent) = item.parent()
+            && parent.parent().is_some()
+            && self.delimiters(parent).is_none()
+        {
+            item = parent;
+        }
+        let at_edge = if forward {
+            item.end_byte() == node.end_byte()
+        } else {
+            item.start_byte() == node.start_byte()
+        };
+        at_edge.then_some(item)
+    }
+
+    /// The original text at `pieces` (bytes) concatenated, with the char
+    /// offsets of each piece and of the end, for a replacement starting at
+    /// byte `start`.
+    fn rearrange(&self, start: usize, pieces: &[Range<usize>]) -> (String, Vec<usize>) {
+        let mut offset = self.content.byte_to_char(start);
+        let mut text = String::new();
+        let mut offsets = vec![offset];
+        for piece in pieces {
+            let slice = self.content.byte_slice(piece.clone());
+            text.extend(slice.chunks());
+            offset += slice.len_chars();
+            offsets.push(offset);
+        }
+        (text, offsets)
+    }
+
+    fn node_edit(
+        &self,
+        range: Range<usize>,
+        text: String,
+        cursor: usize,
+        selection: Option<(usize, usize)>,
+    ) -> NodeEdit {
+        NodeEdit {
+            start: self.content.byte_to_char(range.start),
+            end: self.content.byte_to_char(range.end),
+            text,
+            cursor,
+            selection,
+        }
+    }
+
+    fn indentation_blocks(&self, pos: usize) -> Vec<(usize, usize)> {
+        let indent_of = |line: usize| {
+            let text = self.content.line(line);
+            (!self.is_blank_line(line))
+                .then(|| text.chars().take_while(|c| c.is_whitespace()).count())
+        };
+        let last = self.content.len_lines().saturating_sub(1);
+        let mut line = self.content.char_to_line(pos);
+        while line > 0 && indent_of(line).is_none() {
+            line -= 1;
+        }
+        let mut blocks = Vec::new();
+        let mut indent = indent_of(line).unwrap_or(0);
+        while indent > 0 {
+            let inside = |line: usize| indent_of(line).is_none_or(|i| i >= indent);
+            let mut first = line;
+            while first > 0 && inside(first - 1) {
+                first -= 1;
+            }
+            let mut end_line = line;
+            while end_line < last && inside(end_line + 1) {
+                end_line += 1;
+            }
+            let start = self.content.line_to_char(first);
+            let end = self.content.line_to_char(end_line) + self.line_len(end_line);
+            blocks.push((start, end));
+            // the enclosing block is the one of the line introducing this one
+            let Some(header) = first.checked_sub(1) else {
+                break;
+            };
+            line = header;
+            while line > 0 && indent_of(line).is_none() {
+                line -= 1;
+            }
+            indent = indent_of(line).unwrap_or(0);
+        }
+        blocks
+    }
+
+    /// `start..end` without leading and trailing whitespace.
+    fn trim_range(&self, mut start: usize, mut end: usize) -> (usize, usize) {
+        while start < end && self.content.char(start).is_whitespace() {
+            start += 1;
+        }
+        while end > start && self.content.char(end - 1).is_whitespace() {
+            end -= 1;
+        }
+        (start, end)
+    }
+
+    pub fn indent(&self) -> String {
+        self.language_config.indent.clone()
+    }
+
+    pub fn comment(&self) -> String {
+        self.language_config.comment.clone()
+    }
+
+    /// The editing settings resolved for this buffer's language.
+    pub fn language_config(&self) -> &LanguageConfig {
+        &self.language_config
+    }
+
+    /// Overrides the language settings of this buffer only.
+    pub fn set_language_config(&mut self, config: LanguageConfig) {
+        self.language_config = config;
+    }
+
+    pub fn indentation_level(&self, line: usize, col: usize) -> usize {
+        if self.lang == "unknown" || self.lang.is_empty() {
+            return 0;
+        }
+        let line_str = self.line(line);
+        count_indent_units(line_str, &self.indent(), Some(col))
+    }
+
+    pub fn is_only_indentation_before(&self, r: usize, c: usize) -> bool {
+        if self.lang == "unknown" || self.lang.is_empty() {
+            return false;
+        }
+        if r >= self.len_lines() || c == 0 {
+            return false;
+        }
+
+        let line = self.line(r);
+        let indent_unit = self.indent();
+
+        if indent_unit.is_empty() {
+            return line.chars().take(c).all(|ch| ch.is_whitespace());
+        }
 
-        let line = self.line(r);
-        let indent_unit = self.indent();
-
-        if indent_unit.is_empty() {
-            return line.chars().take(c).all(|ch| ch.is_whitespace());
-        }
-
         let count_units = count_indent_units(line, &indent_unit, Some(c));
         let only_indent = count_units * indent_unit.chars().count() >= c;
         only_indent
@@ -880,37 +2238,235 @@ impl Code {
         self.change_callback = Some(callback);
     }
 
-    /// Notify about document changes
-    fn notify_changes(&self, edits: &[Edit]) {
-        if let Some(callback) = &self.change_callback {
-            let mut changes = Vec::new();
-
-            for edit in edits {
-                match edit.operation {
-                    Operation::Insert => {
-                        let (start_row, start_col) = self.point(edit.start);
-                        changes.push((
-                            start_row,
-                            start_col,
-                            start_row,
-                            start_col,
-                            edit.text.clone(),
-                        ));
-                    }
-                    Operation::Remove => {
-                        let (start_row, start_col) = self.point(edit.start);
-                        let (end_row, end_col) =
-                            calculate_end_position(start_row, start_col, &edit.text);
-                        changes.push((start_row, start_col, end_row, end_col, String::new()));
-                    }
-                }
+    /// Set a callback receiving versioned change notifications, see [`ChangeEvent`]
+    pub fn set_change_listener(&mut self, listener: ChangeListener) {
+        self.change_listener = Some(listener);
+    }
+
+    /// Choose between incremental and full-text change notifications
+    pub fn set_change_sync(&mut self, sync: ChangeSync) {
+        self.change_sync = sync;
+    }
+
+    pub fn change_sync(&self) -> ChangeSync {
+        self.change_sync
+    }
+
+    fn has_change_subscribers(&self) -> bool {
+        self.change_callback.is_some() || self.change_listener.is_some()
+    }
+
+    /// Remember a replacement of `from..to` in the coordinates of the current
+    /// document, before it is applied
+    fn record_change(&mut self, from: usize, to: usize, text: &str) {
+        if !self.has_change_subscribers() {
+            return;
+        }
+        if self.change_sync == ChangeSync::Full {
+            if self.pending_old_end.is_none() {
+                self.pending_old_end = Some(self.point(self.content.len_chars()));
             }
+            return;
+        }
+        let (start_row, start_col) = self.point(from);
+        let (end_row, end_col) = if to > from {
+            let removed = self.content.slice(from..to).to_string();
+            calculate_end_position(start_row, start_col, &removed)
+        } else {
+            (start_row, start_col)
+        };
+        self.pending_changes.push(TextEdit {
+            start_row,
+            start_col,
+            end_row,
+            end_col,
+            text: text.to_string(),
+        });
+    }
+
+    /// Notify about document changes made since the last notification
+    fn notify_changes(&mut self, origin: ChangeOrigin) {
+        self.version += 1;
+        let mut changes = std::mem::take(&mut self.pending_changes);
+        if let Some((end_row, end_col)) = self.pending_old_end.take() {
+            changes = vec![TextEdit {
+                start_row: 0,
+                start_col: 0,
+                end_row,
+                end_col,
+                text: self.content.to_string(),
+            }];
+        }
+        if changes.is_empty() {
+            return;
+        }
+
+        if let Some(callback) = &self.change_callback {
+            let tuples = changes
+                .iter()
+                .map(|c| (c.start_row, c.start_col, c.end_row, c.end_col, c.text.clone()))
+                .collect();
+            callback(tuples);
+        }
+
+        if let Some(listener) = &self.change_listener {
+            listener(&ChangeEvent {
+                version: self.version,
+                origin,
+                changes,
+            });
+        }
+    }
+}
+
+/// Parser and queries of a language embedded in another, e.g. a code block
+/// in Markdown or a script in HTML.
+struct InjectionLayer {
+    parser: RefCell<Parser>,
+    highlights: Query,
+    injections: Option<Query>,
+}
 
-            if !changes.is_empty() {
-                callback(changes);
+/// Languages and content ranges of the injections in `range` of `text`.
+/// The language comes from an `@injection.language` capture, or else from
+/// `(#set! injection.language "...")`, and the content from
+/// `@injection.content` captures.
+fn injection_ranges(
+    text: RopeSlice<'_>,
+    range: Range<usize>,
+    query: &Query,
+    root_node: Node,
+) -> Vec<(String, Range<usize>)> {
+    let mut cursor = QueryCursor::new();
+    cursor.set_byte_range(range);
+    let mut matches = cursor.matches(query, root_node, RopeProvider(text));
+    let capture_names = query.capture_names();
+
+    let mut results = Vec::new();
+    while let Some(m) = matches.next() {
+        if !satisfies_predicates(query, m, text) {
+            continue;
+        }
+        let mut language = query
+            .property_settings(m.pattern_index)
+            .iter()
+            .find(|property| &*property.key == "injection.language")
+            .and_then(|property| property.value.as_deref())
+            .map(str::to_owned);
+        let mut contents = Vec::new();
+        for capture in m.captures {
+            let range = capture.node.byte_range();
+            match capture_names[capture.index as usize] {
+                "injection.language" => language = Some(text.byte_slice(range).to_string()),
+                "injection.content" => contents.push(range),
+                _ => {}
             }
         }
+        let Some(lang) = language.as_deref().and_then(injection_language) else {
+            continue;
+        };
+        results.extend(contents.into_iter().map(|content| (lang.clone(), content)));
+    }
+    results
+}
+
+/// Language named by an injection, e.g. `py` or `c++` in the info string of
+/// a Markdown code block.
+fn injection_language(name: &str) -> Option<String> {
+    let name = name.trim().to_ascii_lowercase();
+    if Code::get_language(&name).is_some() {
+        return Some(name);
+    }
+    let lang = match name.as_str() {
+        "c++" => "cpp",
+        "c#" | "csharp" => "c_sharp",
+        "golang" => "go",
+        other => lang_from_extension(other)?,
+    };
+    Some(lang.to_string())
+}
+
+/// Name of a declaration (function, type, module) or Markdown heading.
+fn declaration_name(node: Node) -> Option<Node> {
+    let kind = node.kind();
+    if kind == "atx_heading" || kind == "setext_heading" {
+        return node.child_by_field_name("heading_content");
+    }
+    let is_declaration = ["_item", "_definition", "_declaration", "_spec"]
+        .iter()
+        .any(|suffix| kind.ends_with(suffix))
+        && !matches!(kind, "field_declaration" | "let_declaration");
+    if is_declaration {
+        node.child_by_field_name("name")
+    } else {
+        None
+    }
+}
+
+fn first_named_child(node: Node) -> Option<Node> {
+    node.named_child(0)
+}
+
+fn last_named_child(node: Node) -> Option<Node> {
+    node.named_child((node.named_child_count() as u32).checked_sub(1)?)
+}
+
+/// Priority of highlights from patterns without `(#set! "priority" N)`.
+const DEFAULT_HIGHLIGHT_PRIORITY: i32 = 100;
+
+/// A highlight capture before overlapping captures are resolved.
+struct HighlightCapture<T> {
+    start: usize,
+    end: usize,
+    /// `None` for `@none`, which clears the highlights below it
+    value: Option<T>,
+    /// Injection depth, priority, node length (shorter is greater), pattern
+    /// index and capture position in the match. The greatest is drawn on top.
+    precedence: (usize, i32, Reverse<usize>, usize, usize),
+}
+
+/// Priority set on a pattern with `(#set! "priority" N)`.
+fn pattern_priority(query: &Query, pattern_index: usize) -> i32 {
+    query
+        .property_settings(pattern_index)
+        .iter()
+        .find(|property| &*property.key == "priority")
+        .and_then(|property| property.value.as_deref()?.parse().ok())
+        .unwrap_or(DEFAULT_HIGHLIGHT_PRIORITY)
+}
+
+/// Paints `captures` over the bytes `start..end` from the lowest precedence to
+/// the highest and returns the runs of bytes that ended up highlighted.
+fn resolve_highlights<T: Copy>(
+    mut captures: Vec<HighlightCapture<T>>,
+    start: usize,
+    end: usize,
+) -> Vec<(usize, usize, T)> {
+    const UNSET: u32 = u32::MAX;
+    captures.sort_by_key(|capture| capture.precedence);
+
+    let mut winners = vec![UNSET; end - start];
+    for (idx, capture) in captures.iter().enumerate() {
+        let from = capture.start.clamp(start, end) - start;
+        let to = capture.end.clamp(start, end) - start;
+        winners[from..to].fill(idx as u32);
+    }
+
+    let mut results = Vec::new();
+    let mut run_start = 0;
+    for idx in 1..=winners.len() {
+        if idx < winners.len() && winners[idx] == winners[run_start] {
+            continue;
+        }
+        let winner = winners[run_start];
+        if winner != UNSET
+            && let Some(value) = captures[winner as usize].value
+        {
+            results.push((start + run_start, start + idx, value));
+        }
+        run_start = idx;
     }
+    results
 }
 
 /// An iterator over byte slices of Rope chunks.
@@ -1018,30 +2574,48 @@ impl<'a> Iterator for RopeGraphemes<'a> {
 
 pub fn grapheme_width_and_chars_len(g: RopeSlice) -> (usize, usize) {
     if let Some(g_str) = g.as_str() {
-        (UnicodeWidthStr::width(g_str), g_str.chars().count())
+        (grapheme_str_width(g_str), g_str.chars().count())
     } else {
         let g_string = g.to_string();
         let g_str = g_string.as_str();
-        (UnicodeWidthStr::width(g_str), g_str.chars().count())
+        (grapheme_str_width(g_str), g_str.chars().count())
     }
 }
 
 pub fn grapheme_width_and_bytes_len(g: RopeSlice) -> (usize, usize) {
     if let Some(g_str) = g.as_str() {
-        (UnicodeWidthStr::width(g_str), g_str.len())
+        (grapheme_str_width(g_str), g_str.len())
     } else {
         let g_string = g.to_string();
         let g_str = g_string.as_str();
-        (UnicodeWidthStr::width(g_str), g_str.len())
+        (grapheme_str_width(g_str), g_str.len())
     }
 }
 
 pub fn grapheme_width(g: RopeSlice) -> usize {
     if let Some(s) = g.as_str() {
-        UnicodeWidthStr::width(s)
+        grapheme_str_width(s)
     } else {
         let s = g.to_string();
-        UnicodeWidthStr::width(s.as_str())
+        grapheme_str_width(s.as_str())
+    }
+}
+
+/// Returns the placeholder drawn for a grapheme that would otherwise be invisible:
+/// byte order marks, zero-width and other format characters, and bidi controls.
+pub fn invisible_placeholder(g: &str) -> Option<char> {
+    let mut chars = g.chars();
+    let (Some(ch), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    match ch {
+        // bidi embeddings, overrides, isolates and marks
+        '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}' | '\u{200E}' | '\u{200F}' | '\u{061C}' => {
+            Some('⇆')
+        }
+        // byte order mark, zero-width spaces and joiners, word joiner, soft hyphen
+        '\u{FEFF}' | '\u{200B}'..='\u{200D}' | '\u{2060}'..='\u{2064}' | '\u{00AD}' => Some('·'),
+        _ => None,
     }
 }
 
@@ -1135,6 +2709,251 @@ mod tests {
         assert_eq!(code.is_only_indentation_before(0, 10), false);
     }
 
+    fn node_positions(tree: &Tree) -> Vec<(String, Point, Point)> {
+        let mut nodes = Vec::new();
+        let mut cursor = tree.walk();
+        loop {
+            let node = cursor.node();
+            nodes.push((
+                node.kind().to_string(),
+                node.start_position(),
+                node.end_position(),
+            ));
+            if cursor.goto_first_child() || cursor.goto_next_sibling() {
+                continue;
+            }
+            loop {
+                if !cursor.goto_parent() {
+                    return nodes;
+                }
+                if cursor.goto_next_sibling() {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn assert_matches_full_reparse(code: &Code) {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&Code::get_language(code.lang()).unwrap())
+            .unwrap();
+        let fresh = parser.parse(code.get_content(), None).unwrap();
+        let incremental = code.tree.as_ref().unwrap();
+        assert_eq!(incremental.root_node().to_sexp(), fresh.root_node().to_sexp());
+        assert_eq!(node_positions(incremental), node_positions(&fresh));
+    }
+
+    #[test]
+    fn test_byte_point() {
+        let code = Code::new("ab\nцд\nx", "", None).unwrap();
+        assert_eq!(code.byte_point(0), Point { row: 0, column: 0 });
+        assert_eq!(code.byte_point(3), Point { row: 1, column: 0 });
+        assert_eq!(code.byte_point(5), Point { row: 1, column: 2 });
+        assert_eq!(code.byte_point(8), Point { row: 2, column: 0 });
+
+        // Only `\n` starts a row, like in Tree-sitter
+        let code = Code::new("a\u{2028}b\rc\nd", "", None).unwrap();
+        assert_eq!(code.byte_point(6), Point { row: 0, column: 6 });
+        assert_eq!(code.byte_point(8), Point { row: 1, column: 0 });
+    }
+
+    #[test]
+    fn test_incremental_edits_match_full_reparse() {
+        let source = "fn main() {\n    let a = 1;\n}\n\nfn other() {\n    call(a);\n}\n";
+        let mut code = Code::new(source, "rust", None).unwrap();
+
+        code.insert(16, "\n    let b = \"ж\";\n");
+        assert_matches_full_reparse(&code);
+
+        code.insert(0, "// header\n");
+        assert_matches_full_reparse(&code);
+
+        let start = code.get_content().find("fn other").unwrap();
+        let start = code.content.byte_to_char(start);
+        code.remove(start, start + 9);
+        assert_matches_full_reparse(&code);
+
+        let end = code.len_chars();
+        code.remove(end - 5, end);
+        code.insert(end - 5, "}\nstruct S;\n");
+        assert_matches_full_reparse(&code);
+    }
+
+    #[test]
+    fn test_highlight_lines() {
+        let code = Code::new("let a = 1;\nlet b = \"x\";\n", "rust", None).unwrap();
+        let mut theme = HashMap::new();
+        theme.insert("keyword".to_string(), 1);
+        theme.insert("string".to_string(), 2);
+
+        let lines = code.highlight_lines(0, 3, &theme);
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains(&(0, 3, 1)));
+        assert!(lines[1].contains(&(11, 14, 1)));
+        assert!(lines[1].contains(&(19, 22, 2)));
+        assert!(lines[2].is_empty());
+
+        for (line, highlights) in lines.iter().enumerate().take(2) {
+            let (start, end) = code.lines_byte_range(line, line + 1);
+            let mut single = code.highlight_interval(start, end, &theme);
+            single.retain(|&(s, e, _)| s < end && e > start);
+            assert_eq!(*highlights, single);
+        }
+    }
+
+    #[test]
+    fn test_highlight_innermost_and_later_patterns_win() {
+        let code = Code::new("let s = \"a\\n\";\nfoo(s);\n", "rust", None).unwrap();
+        let mut theme = HashMap::new();
+        theme.insert("string".to_string(), 1);
+        theme.insert("constant.character.escape".to_string(), 2);
+        theme.insert("variable".to_string(), 3);
+        theme.insert("function".to_string(), 4);
+
+        let highlights = code.highlight_interval(0, code.content.len_bytes(), &theme);
+        assert_eq!(
+            highlights,
+            vec![
+                (4, 5, 3),
+                (8, 10, 1),
+                (10, 12, 2),
+                (12, 13, 1),
+                (15, 18, 4),
+                (19, 20, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_highlight_none_and_priority() {
+        let query = "(function_item) @function\n\
+            (block) @none\n\
+            ((string_literal) @string (#set! \"priority\" 101))\n\
+            (escape_sequence) @escape";
+        let custom = HashMap::from([("rust".to_string(), query.to_string())]);
+        let code = Code::new("fn f() { \"a\\n\" }", "rust", Some(custom)).unwrap();
+        let mut theme = HashMap::new();
+        theme.insert("function".to_string(), 1);
+        theme.insert("string".to_string(), 2);
+        theme.insert("escape".to_string(), 3);
+
+        let highlights = code.highlight_interval(0, code.content.len_bytes(), &theme);
+        assert_eq!(highlights, vec![(0, 7, 1), (9, 14, 2)]);
+    }
+
+    #[test]
+    fn test_highlight_local_references_like_definitions() {
+        let text = "fn f(x: u8) {\n    g(x);\n    let x = 1;\n    g(x);\n}\nfn h() { x; }\n";
+        let code = Code::new(text, "rust", None).unwrap();
+        let mut theme = HashMap::new();
+        theme.insert("variable".to_string(), 1);
+        theme.insert("variable.parameter".to_string(), 2);
+
+        let highlights = code.highlight_interval(0, code.content.len_bytes(), &theme);
+        let style_at = |needle: &str| {
+            let pos = text.find(needle).unwrap() + needle.find('x').unwrap();
+            highlights
+                .iter()
+                .find(|&&(start, end, _)| start <= pos && pos < end)
+                .map(|&(_, _, value)| value)
+        };
+        assert_eq!(style_at("(x:"), Some(2));
+        assert_eq!(style_at("g(x);\n    let"), Some(2));
+        assert_eq!(style_at("let x"), Some(1));
+        assert_eq!(style_at("g(x);\n}"), Some(1));
+        assert_eq!(style_at("{ x;"), Some(1));
+
+        let (start, end) = code.lines_byte_range(1, 2);
+        assert!(
+            code.highlight_interval(start, end, &theme)
+                .contains(&(20, 21, 2))
+        );
+    }
+
+    #[test]
+    fn test_custom_locals_query() {
+        let custom = HashMap::from([("rust.locals".to_string(), "".to_string())]);
+        let code = Code::new("fn f(x: u8) { x; }", "rust", Some(custom)).unwrap();
+        let mut theme = HashMap::new();
+        theme.insert("variable".to_string(), 1);
+        theme.insert("variable.parameter".to_string(), 2);
+
+        let highlights = code.highlight_interval(0, code.content.len_bytes(), &theme);
+        assert_eq!(highlights, vec![(3, 4, 1), (5, 6, 2), (14, 15, 1)]);
+    }
+
+    #[test]
+    fn test_highlight_injections() {
+        let text = "# T\n\n```py\nimport os\n```\n\n<script>\nlet a = 1;\n</script>\n";
+        let code = Code::new(text, "markdown", None).unwrap();
+        let mut theme = HashMap::new();
+        theme.insert("keyword".to_string(), 1);
+        theme.insert("keyword.control.import".to_string(), 1);
+        theme.insert("keyword.storage.type".to_string(), 1);
+
+        let highlights = code.highlight_interval(0, code.content.len_bytes(), &theme);
+        let import = text.find("import").unwrap();
+        let let_ = text.find("let").unwrap();
+        assert!(highlights.contains(&(import, import + 6, 1)));
+        assert!(highlights.contains(&(let_, let_ + 3, 1)));
+
+        let (start, end) = code.lines_byte_range(3, 4);
+        let line = code.highlight_interval(start, end, &theme);
+        assert_eq!(line, vec![(import, import + 6, 1)]);
+    }
+
+    #[test]
+    fn test_highlight_query_predicates() {
+        let highlights = |query: &str| {
+            let custom = HashMap::from([("rust".to_string(), query.to_string())]);
+            let code =
+                Code::new("fn f() { Ab; x; }\nconst C: u8 = 1;", "rust", Some(custom)).unwrap();
+            let theme = HashMap::from([("c".to_string(), 1)]);
+            code.highlight_interval(0, code.content.len_bytes(), &theme)
+        };
+
+        let lua = "((identifier) @c (#lua-match? @c \"^%u%w*$\"))";
+        assert_eq!(highlights(lua), vec![(9, 11, 1), (24, 25, 1)]);
+        let ancestor = "((identifier) @c (#has-ancestor? @c block))";
+        assert_eq!(highlights(ancestor), vec![(9, 11, 1), (13, 14, 1)]);
+        let parent = "((identifier) @c (#not-has-parent? @c expression_statement))";
+        assert_eq!(highlights(parent), vec![(3, 4, 1), (24, 25, 1)]);
+        let contains = "((identifier) @c (#contains? @c \"b\" \"C\"))";
+        assert_eq!(highlights(contains), vec![(9, 11, 1), (24, 25, 1)]);
+        let any_of = "((identifier) @c (#any-of? @c \"x\" \"f\"))";
+        assert_eq!(highlights(any_of), vec![(3, 4, 1), (13, 14, 1)]);
+    }
+
+    #[test]
+    fn test_is_in_comment_and_string() {
+        let text = "let s = \"ab\"; // note\n/* c */ let t = 1;\n";
+        let code = Code::new(text, "rust", None).unwrap();
+        let at = |needle: &str| text.find(needle).unwrap();
+
+        assert!(!code.is_in_string(at("\"ab")));
+        assert!(code.is_in_string(at("ab")));
+        assert!(code.is_in_string(at("\";")));
+        assert!(!code.is_in_string(at("; //")));
+        assert!(!code.is_in_comment(at("ab")));
+
+        assert!(!code.is_in_comment(at("// note")));
+        assert!(code.is_in_comment(at("note")));
+        assert!(code.is_in_comment(at("\n/*")));
+        assert!(code.is_in_comment(at(" c ")));
+        assert!(!code.is_in_comment(at(" let t")));
+        assert!(!code.is_in_string(at("note")));
+
+        let js = "let s = `a ${b} c`;";
+        let code = Code::new(js, "javascript", None).unwrap();
+        assert!(code.is_in_string(js.find("a $").unwrap()));
+        assert!(!code.is_in_string(js.find("b}").unwrap()));
+        assert!(code.is_in_string(js.find(" c").unwrap()));
+
+        let plain = Code::new("\"a\"", "text", None).unwrap();
+        assert!(!plain.is_in_string(1));
+    }
+
     #[test]
     fn test_smart_paste_1() {
         let initial = "fn foo() {\n    let x = 1;\n    \n}";
@@ -1160,4 +2979,23 @@ mod tests {
         let expected = "fn foo() {\n    let x = 1;\n    if start == end && start == self.code.len() {\n        return;\n    }\n}";
         assert_eq!(code.get_content(), expected);
     }
+
+    #[test]
+    fn test_line_metrics_follow_edits() {
+        let mut code = Code::new("ab\n日本\nxyz", "", None).unwrap();
+        assert_eq!(code.line_metrics(1), LineMetrics { len: 2, width: 4 });
+        assert_eq!(code.line_len(2), 3);
+
+        code.insert(1, "\n");
+        assert_eq!(code.line_len(0), 1);
+        assert_eq!(code.line_len(1), 1);
+        assert_eq!(code.line_width(2), 4);
+        assert_eq!(code.line_len(3), 3);
+
+        code.remove(2, 8);
+        assert_eq!(code.get_content(), "a\nyz");
+        assert_eq!(code.line_len(1), 2);
+        assert_eq!(code.line_boundaries(0), (0, 2));
+        assert_eq!(code.line_boundaries(3), (2, 4));
+    }
 }
@@ -0,0 +1,107 @@
+use crate::code::Code;
+use std::cell::{Ref, RefCell, RefMut};
+use std::ops::{Deref, DerefMut};
+use std::rc::Rc;
+
+/// The document of an editor. It is owned by the editor until it is shared
+/// with other editors or a workspace, see [`crate::editor::Editor::shared_code`].
+pub(crate) enum CodeCell {
+    Owned(Box<Code>),
+    Shared(Rc<RefCell<Code>>),
+}
+
+impl CodeCell {
+    pub(crate) fn borrow(&self) -> CodeRef<'_> {
+        match self {
+            Self::Owned(code) => CodeRef::Owned(code),
+            Self::Shared(code) => CodeRef::Shared(code.borrow()),
+        }
+    }
+
+    pub(crate) fn borrow_mut(&mut self) -> CodeRefMut<'_> {
+        match self {
+            Self::Owned(code) => CodeRefMut::Owned(code),
+            Self::Shared(code) => CodeRefMut::Shared(code.borrow_mut()),
+        }
+    }
+
+    /// The document if no other editor or workspace can reach it.
+    pub(crate) fn owned(&self) -> Option<&Code> {
+        match self {
+            Self::Owned(code) => Some(code),
+            Self::Shared(_) => None,
+        }
+    }
+
+    pub(crate) fn owned_mut(&mut self) -> Option<&mut Code> {
+        match self {
+            Self::Owned(code) => Some(code),
+            Self::Shared(_) => None,
+        }
+    }
+
+    /// Whether other editors may edit the document too.
+    pub(crate) fn is_shared(&self) -> bool {
+        matches!(self, Self::Shared(code) if Rc::strong_count(code) > 1)
+    }
+
+    pub(crate) fn ptr_eq(&self, other: &Rc<RefCell<Code>>) -> bool {
+        matches!(self, Self::Shared(code) if Rc::ptr_eq(code, other))
+    }
+
+    /// Moves an owned document into a shared one and returns it.
+    pub(crate) fn share(&mut self) -> Rc<RefCell<Code>> {
+        if let Self::Owned(code) = self {
+            let placeholder = Code::new("", "text", None).expect("plain text needs no parser");
+            let code = std::mem::replace(&mut **code, placeholder);
+            *self = Self::Shared(Rc::new(RefCell::new(code)));
+        }
+        match self {
+            Self::Shared(code) => Rc::clone(code),
+            Self::Owned(_) => unreachable!("the document was just shared"),
+        }
+    }
+}
+
+/// Read access to an editor's document, see [`CodeCell::borrow`].
+pub(crate) enum CodeRef<'a> {
+    Owned(&'a Code),
+    Shared(Ref<'a, Code>),
+}
+
+impl Deref for CodeRef<'_> {
+    type Target = Code;
+
+    fn deref(&self) -> &Code {
+        match self {
+            Self::Owned(code) => code,
+            Self::Shared(code) => code,
+        }
+    }
+}
+
+/// Write access to an editor's document, see [`CodeCell::borrow_mut`].
+pub(crate) enum CodeRefMut<'a> {
+    Owned(&'a mut Code),
+    Shared(RefMut<'a, Code>),
+}
+
+impl Deref for CodeRefMut<'_> {
+    type Target = Code;
+
+    fn deref(&self) -> &Code {
+        match self {
+            Self::Owned(code) => code,
+            Self::Shared(code) => code,
+        }
+    }
+}
+
+impl DerefMut for CodeRefMut<'_> {
+    fn deref_mut(&mut self) -> &mut Code {
+        match self {
+            Self::Owned(code) => code,
+            Self::Shared(code) => code,
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use crate::actions::*;
+use crate::editor::Editor;
+use crate::search::SearchOptions;
+
+/// A built-in editor action as plain data.
+///
+/// Commands can be stored, compared and (with the `serde` feature) serialized,
+/// which makes them usable for command palettes, key maps, macros and scripted
+/// tests. Run one with [`Editor::execute`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub enum Command {
+    MoveLeft {
+        shift: bool,
+    },
+    MoveRight {
+        shift: bool,
+    },
+    MoveUp {
+        shift: bool,
+    },
+    MoveDown {
+        shift: bool,
+    },
+    MoveSubWordLeft {
+        shift: bool,
+    },
+    MoveSubWordRight {
+        shift: bool,
+    },
+    MoveParagraphUp {
+        shift: bool,
+    },
+    MoveParagraphDown {
+        shift: bool,
+    },
+    MoveBlockStart {
+        shift: bool,
+    },
+    MoveBlockEnd {
+        shift: bool,
+    },
+    ScrollViewUp {
+        lines: usize,
+        keep_cursor: bool,
+    },
+    ScrollViewDown {
+        lines: usize,
+        keep_cursor: bool,
+    },
+    ScrollHalfPageUp,
+    ScrollHalfPageDown,
+    InsertText {
+        text: String,
+    },
+    OverwriteText {
+        text: String,
+    },
+    ToggleOverwrite,
+    InsertNewline,
+    Delete,
+    ToggleComment,
+    Indent,
+    UnIndent,
+    SelectAll,
+    Duplicate,
+    DeleteLine,
+    MoveSelection {
+        to: usize,
+        copy: bool,
+    },
+    Cut,
+    Copy,
+    Paste,
+    PasteLiteral,
+    PastePrimary,
+    Undo,
+    Redo,
+    RepeatLastEdit,
+    ReselectLast,
+    SelectNextOccurrence,
+    SelectAllOccurrences,
+    RenameInBuffer {
+        replacement: Option<String>,
+    },
+    InsertSnippet {
+        snippet: String,
+    },
+    NextTabstop,
+    PrevTabstop,
+    Reflow {
+        width: usize,
+    },
+    RenumberList,
+    Align {
+        pattern: String,
+    },
+    IncrementNumber {
+        count: usize,
+    },
+    DecrementNumber {
+        count: usize,
+    },
+    CycleValue {
+        forward: bool,
+    },
+    SwapNode {
+        forward: bool,
+    },
+    RaiseNode,
+    Slurp {
+        forward: bool,
+    },
+    Barf {
+        forward: bool,
+    },
+    Search {
+        query: String,
+        options: SearchOptions,
+    },
+    SearchNext,
+    SearchPrev,
+    ClearSearch,
+}
+
+impl Command {
+    /// Stable name of the command, e.g. `"move_down"`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Command::MoveLeft { .. } => "move_left",
+            Command::MoveRight { .. } => "move_right",
+            Command::MoveUp { .. } => "move_up",
+            Command::MoveDown { .. } => "move_down",
+            Command::MoveSubWordLeft { .. } => "move_sub_word_left",
+            Command::MoveSubWordRight { .. } => "move_sub_word_right",
+            Command::MoveParagraphUp { .. } => "move_paragraph_up",
+            Command::MoveParagraphDown { .. } => "move_paragraph_down",
+            Command::MoveBlockStart { .. } => "move_block_start",
+            Command::MoveBlockEnd { .. } => "move_block_end",
+            Command::ScrollViewUp { .. } => "scroll_view_up",
+            Command::ScrollViewDown { .. } => "scroll_view_down",
+            Command::ScrollHalfPageUp => "scroll_half_page_up",
+            Command::ScrollHalfPageDown => "scroll_half_page_down",
+            Command::InsertText { .. } => "insert_text",
+            Command::OverwriteText { .. } => "overwrite_text",
+            Command::ToggleOverwrite => "toggle_overwrite",
+            Command::InsertNewline => "insert_newline",
+            Command::Delete => "delete",
+            Command::ToggleComment => "toggle_comment",
+            Command::Indent => "indent",
+            Command::UnIndent => "unindent",
+            Command::SelectAll => "select_all",
+            Command::Duplicate => "duplicate",
+            Command::DeleteLine => "delete_line",
+            Command::MoveSelection { .. } => "move_selection",
+            Command::Cut => "cut",
+            Command::Copy => "copy",
+            Command::Paste => "paste",
+            Command::PasteLiteral => "paste_literal",
+            Command::PastePrimary => "paste_primary",
+            Command::Undo => "undo",
+            Command::Redo => "redo",
+            Command::RepeatLastEdit => "repeat_last_edit",
+            Command::ReselectLast => "reselect_last",
+            Command::SelectNextOccurrence => "select_next_occurrence",
+            Command::SelectAllOccurrences => "select_all_occurrences",
+            Command::RenameInBuffer { .. } => "rename_in_buffer",
+            Command::InsertSnippet { .. } => "insert_snippet",
+            Command::NextTabstop => "next_tabstop",
+            Command::PrevTabstop => "prev_tabstop",
+            Command::Reflow { .. } => "reflow",
+            Command::RenumberList => "renumber_list",
+            Command::Align { .. } => "align",
+            Command::IncrementNumber { .. } => "increment_number",
+            Command::DecrementNumber { .. } => "decrement_number",
+            Command::CycleValue { .. } => "cycle_value",
+            Command::SwapNode { .. } => "swap_node",
+            Command::RaiseNode => "raise_node",
+            Command::Slurp { .. } => "slurp",
+            Command::Barf { .. } => "barf",
+            Command::Search { .. } => "search",
+            Command::SearchNext => "search_next",
+            Command::SearchPrev => "search_prev",
+            Command::ClearSearch => "clear_search",
+        }
+    }
+
+    /// Whether the command changes the text and is recorded for `RepeatLastEdit`.
+    pub fn is_edit(&self) -> bool {
+        matches!(
+            self,
+            Command::InsertText { .. }
+                | Command::OverwriteText { .. }
+                | Command::InsertNewline
+                | Command::Delete
+                | Command::ToggleComment
+                | Command::Indent
+                | Command::UnIndent
+                | Command::Duplicate
+                | Command::DeleteLine
+                | Command::MoveSelection { .. }
+                | Command::Cut
+                | Command::Paste
+                | Command::PasteLiteral
+                | Command::PastePrimary
+                | Command::RenameInBuffer {
+                    replacement: Some(_)
+                }
+                | Command::InsertSnippet { .. }
+                | Command::Reflow { .. }
+                | Command::RenumberList
+                | Command::Align { .. }
+                | Command::IncrementNumber { .. }
+                | Command::DecrementNumber { .. }
+                | Command::CycleValue { .. }
+                | Command::SwapNode { .. }
+                | Command::RaiseNode
+                | Command::Slurp { .. }
+                | Command::Barf { .. }
+        )
+    }
+}
+
+impl Action for Command {
+    fn apply(&mut self, editor: &mut Editor) {
+        match self {
+            Command::MoveLeft { shift } => MoveLeft { shift: *shift }.apply(editor),
+            Command::MoveRight { shift } => MoveRight { shift: *shift }.apply(editor),
+            Command::MoveUp { shift } => MoveUp { shift: *shift }.apply(editor),
+            Command::MoveDown { shift } => MoveDown { shift: *shift }.apply(editor),
+            Command::MoveSubWordLeft { shift } => MoveSubWordLeft { shift: *shift }.apply(editor),
+            Command::MoveSubWordRight { shift } => MoveSubWordRight { shift: *shift }.apply(editor),
+            Command::MoveParagraphUp { shift } => MoveParagraphUp { shift: *shift }.apply(editor),
+            Command::MoveParagraphDown { shift } => {
+                MoveParagraphDown { shift: *shift }.apply(editor)
+            }
+            Command::MoveBlockStart { shift } => MoveBlockStart { shift: *shift }.apply(editor),
+            Command::MoveBlockEnd { shift } => MoveBlockEnd { shift: *shift }.apply(editor),
+            Command::ScrollViewUp { lines, keep_cursor } => ScrollViewUp {
+                lines: *lines,
+                keep_cursor: *keep_cursor,
+            }
+            .apply(editor),
+            Command::ScrollViewDown { lines, keep_cursor } => ScrollViewDown {
+                lines: *lines,
+                keep_cursor: *keep_cursor,
+            }
+            .apply(editor),
+            Command::ScrollHalfPageUp => ScrollHalfPageUp.apply(editor),
+            Command::ScrollHalfPageDown => ScrollHalfPageDown.apply(editor),
+            Command::InsertText { text } => InsertText { text: text.clone() }.apply(editor),
+            Command::OverwriteText { text } => OverwriteText { text: text.clone() }.apply(editor),
+            Command::ToggleOverwrite => editor.toggle_overwrite(),
+            Command::InsertNewline => InsertNewline.apply(editor),
+            Command::Delete => Delete.apply(editor),
+            Command::ToggleComment => ToggleComment.apply(editor),
+            Command::Indent => Indent.apply(editor),
+            Command::UnIndent => UnIndent.apply(editor),
+            Command::SelectAll => SelectAll.apply(editor),
+            Command::Duplicate => Duplicate.apply(editor),
+            Command::DeleteLine => DeleteLine.apply(editor),
+            Command::MoveSelection { to, copy } => MoveSelection {
+                to: *to,
+                copy: *copy,
+            }
+            .apply(editor),
+            Command::Cut => Cut.apply(editor),
+            Command::Copy => Copy.apply(editor),
+            Command::Paste => Paste.apply(editor),
+            Command::PasteLiteral => PasteLiteral.apply(editor),
+            Command::PastePrimary => PastePrimary.apply(editor),
+            Command::Undo => Undo.apply(editor),
+            Command::Redo => Redo.apply(editor),
+            Command::RepeatLastEdit => RepeatLastEdit.apply(editor),
+            Command::ReselectLast => ReselectLast.apply(editor),
+            Command::SelectNextOccurrence => SelectNextOccurrence.apply(editor),
+            Command::SelectAllOccurrences => SelectAllOccurrences.apply(editor),
+            Command::RenameInBuffer { replacement } => RenameInBuffer {
+                replacement: replacement.clone(),
+            }
+            .apply(editor),
+            Command::InsertSnippet { snippet } => editor.insert_snippet(snippet),
+            Command::NextTabstop => NextTabstop.apply(editor),
+            Command::PrevTabstop => PrevTabstop.apply(editor),
+            Command::Reflow { width } => Reflow { width: *width }.apply(editor),
+            Command::RenumberList => RenumberList.apply(editor),
+            Command::Align { pattern } => Align {
+                pattern: pattern.clone(),
+            }
+            .apply(editor),
+            Command::IncrementNumber { count } => IncrementNumber { count: *count }.apply(editor),
+            Command::DecrementNumber { count } => DecrementNumber { count: *count }.apply(editor),
+            Command::CycleValue { forward } => CycleValue { forward: *forward }.apply(editor),
+            Command::SwapNode { forward } => SwapNode { forward: *forward }.apply(editor),
+            Command::RaiseNode => RaiseNode.apply(editor),
+            Command::Slurp { forward } => Slurp { forward: *forward }.apply(editor),
+            Command::Barf { forward } => Barf { forward: *forward }.apply(editor),
+            Command::Search { query, options } => {
+                editor.search(query, *options);
+            }
+            Command::SearchNext => {
+                editor.search_next();
+            }
+            Command::SearchPrev => {
+                editor.search_prev();
+            }
+            Command::ClearSearch => editor.clear_search(),
+        }
+    }
+
+    fn supports_multiple_selections(&self) -> bool {
+        matches!(
+            self,
+            Command::InsertText { .. }
+                | Command::OverwriteText { .. }
+                | Command::InsertNewline
+                | Command::Delete
+                | Command::SelectNextOccurrence
+                | Command::SelectAllOccurrences
+                | Command::RenameInBuffer { .. }
+                | Command::InsertSnippet { .. }
+                | Command::NextTabstop
+                | Command::PrevTabstop
+        )
+    }
+}
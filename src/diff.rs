@@ -1,4 +1,5 @@
-use crate::code::Code;
+use crate::code::{Code, EditTransaction};
+use crate::diff_editor::DiffRow;
 use crate::types::{VisualRow, LineDiff};
 use ropey::RopeSlice;
 use similar::{Algorithm, DiffOp};
@@ -142,6 +143,32 @@ fn lines(code: &Code) -> Vec<RopeSlice<'_>> {
         .collect()
 }
 
+/// Pairs the lines of `old` and `new` for a side-by-side view: equal lines
+/// share a row, a changed line faces the line that replaced it, and lines
+/// without a counterpart face a gap.
+pub(crate) fn align_lines(old: &Code, new: &Code) -> Vec<DiffRow> {
+    let diff = {
+        let old_lines = lines(old);
+        let new_lines = lines(new);
+        similar::capture_diff_slices(Algorithm::Myers, &old_lines, &new_lines)
+    };
+
+    let mut rows = Vec::new();
+    for op in diff {
+        let (old_range, new_range) = (op.old_range(), op.new_range());
+        let changed = !matches!(op, DiffOp::Equal { .. });
+        let len = old_range.len().max(new_range.len());
+        for i in 0..len {
+            rows.push(DiffRow {
+                old: Some(old_range.start + i).filter(|line| old_range.contains(line)),
+                new: Some(new_range.start + i).filter(|line| new_range.contains(line)),
+                changed,
+            });
+        }
+    }
+    rows
+}
+
 pub(crate) fn merge_ranges(ranges: Vec<(usize, usize)>, max_gap: usize) -> Vec<(usize, usize)> {
     if ranges.is_empty() {
         return ranges;
@@ -220,6 +247,82 @@ pub(crate) fn compute_line_diff(
     }
 }
 
+/// Builds the minimal edits that turn the content of `code` into `text`.
+///
+/// Lines are diffed first, then each changed hunk is trimmed to the characters
+/// that actually differ, so offsets outside the changes stay where they are.
+pub(crate) fn compute_reload_edits(code: &Code, text: &str) -> EditTransaction {
+    compute_edits(code, text, false)
+}
+
+/// Like [`compute_reload_edits`], also diffing each changed hunk character by
+/// character, so offsets inside reformatted lines move with their text.
+pub(crate) fn compute_format_edits(code: &Code, text: &str) -> EditTransaction {
+    compute_edits(code, text, true)
+}
+
+fn compute_edits(code: &Code, text: &str, by_char: bool) -> EditTransaction {
+    let current = code.get_content();
+    let old_lines: Vec<&str> = current.split_inclusive('\n').collect();
+    let new_lines: Vec<&str> = text.split_inclusive('\n').collect();
+
+    let mut line_starts = Vec::with_capacity(old_lines.len() + 1);
+    let mut offset = 0;
+    line_starts.push(0);
+    for line in &old_lines {
+        offset += line.chars().count();
+        line_starts.push(offset);
+    }
+
+    let mut tx = EditTransaction::new();
+    for op in similar::capture_diff_slices(Algorithm::Myers, &old_lines, &new_lines) {
+        if let DiffOp::Equal { .. } = op {
+            continue;
+        }
+        let old_range = op.old_range();
+        let new_range = op.new_range();
+        let old: String = old_lines[old_range.clone()].concat();
+        let new: String = new_lines[new_range].concat();
+
+        let prefix = old
+            .chars()
+            .zip(new.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let suffix = old
+            .chars()
+            .rev()
+            .zip(new.chars().rev())
+            .take_while(|(a, b)| a == b)
+            .count()
+            .min(old.chars().count() - prefix)
+            .min(new.chars().count() - prefix);
+
+        let start = line_starts[old_range.start] + prefix;
+        let end = line_starts[old_range.end] - suffix;
+        let replacement: String = new
+            .chars()
+            .skip(prefix)
+            .take(new.chars().count() - prefix - suffix)
+            .collect();
+        if !by_char {
+            tx.replace(start..end, &replacement);
+            continue;
+        }
+        let old_chars: Vec<char> = old.chars().skip(prefix).take(end - start).collect();
+        let new_chars: Vec<char> = replacement.chars().collect();
+        for op in similar::capture_diff_slices(Algorithm::Myers, &old_chars, &new_chars) {
+            if let DiffOp::Equal { .. } = op {
+                continue;
+            }
+            let old_range = op.old_range();
+            let inserted: String = new_chars[op.new_range()].iter().collect();
+            tx.replace(start + old_range.start..start + old_range.end, &inserted);
+        }
+    }
+    tx
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -317,4 +420,55 @@ mod tests {
         assert_eq!(diff.deletions, vec![(0, 10)]);
         assert_eq!(diff.additions, vec![(0, 12)]);
     }
+
+    fn apply_reload(content: &str, text: &str) -> String {
+        let mut code = Code::new(content, "unknown", None).unwrap();
+        let tx = compute_reload_edits(&code, text);
+        for (start, end, replacement) in tx.sorted_edits(code.len_chars()).unwrap().iter().rev() {
+            code.remove(*start, *end);
+            code.insert(*start, replacement);
+        }
+        code.get_content()
+    }
+
+    #[test]
+    fn test_compute_reload_edits() {
+        let code = Code::new("one\ntwo\nthree\n", "unknown", None).unwrap();
+        let tx = compute_reload_edits(&code, "one\ntwo!\nthree\nfour");
+        assert_eq!(
+            tx.sorted_edits(code.len_chars()).unwrap(),
+            vec![(7, 7, "!".to_string()), (14, 14, "four".to_string())]
+        );
+
+        for (old, new) in [
+            ("a\nb\nc", "a\nc"),
+            ("a\nb\nc", ""),
+            ("", "x\ny"),
+            ("héllo\nwörld\n", "hello\nwörld!\n\n"),
+            ("aaa\naaa\n", "aaa\n"),
+        ] {
+            assert_eq!(apply_reload(old, new), new);
+        }
+    }
+
+    #[test]
+    fn test_align_lines() {
+        let old = Code::new("a\nb\nc\nd\ne", "unknown", None).unwrap();
+        let new = Code::new("a\nB\nx\nc\ne", "unknown", None).unwrap();
+        let rows: Vec<_> = align_lines(&old, &new)
+            .into_iter()
+            .map(|row| (row.old, row.new, row.changed))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                (Some(0), Some(0), false),
+                (Some(1), Some(1), true),
+                (None, Some(2), true),
+                (Some(2), Some(3), false),
+                (Some(3), None, true),
+                (Some(4), Some(4), false),
+            ]
+        );
+    }
 }
@@ -0,0 +1,218 @@
+use crate::diff;
+use crate::editor::Editor;
+use crate::render::RowStyles;
+use anyhow::Result;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::style::{Color, Style};
+use ratatui_core::text::Line;
+use ratatui_core::widgets::Widget;
+use std::cell::RefCell;
+
+/// How a [`DiffEditor`] shows the two buffers.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffLayout {
+    /// The old buffer on the left and the new one on the right, with the
+    /// lines aligned and gaps facing added or removed lines
+    #[default]
+    SideBySide,
+    /// The new buffer with removed lines shown between its lines, as in the
+    /// editor's own diff mode
+    Inline,
+}
+
+/// One row of a side-by-side diff: the lines of each side shown on it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DiffRow {
+    /// Line of the old buffer, `None` for a gap facing an added line
+    pub old: Option<usize>,
+    /// Line of the new buffer, `None` for a gap facing a removed line
+    pub new: Option<usize>,
+    /// Whether the lines differ, or one side is a gap
+    pub changed: bool,
+}
+
+/// Aligned rows and the document versions they were computed for.
+type RowCache = ((u64, u64), Vec<DiffRow>);
+
+/// Compares two editors, e.g. the base and head version of a file under
+/// review. Both sides keep their syntax highlighting, and added and removed
+/// lines get the `diff_added` and `diff_deleted` theme backgrounds.
+pub struct DiffEditor {
+    old: Editor,
+    new: Editor,
+    layout: DiffLayout,
+    offset_y: usize,
+    rows: RefCell<Option<RowCache>>,
+}
+
+impl DiffEditor {
+    pub fn new(old: Editor, new: Editor) -> Self {
+        Self {
+            old,
+            new,
+            layout: DiffLayout::default(),
+            offset_y: 0,
+            rows: RefCell::new(None),
+        }
+    }
+
+    pub fn old_editor(&self) -> &Editor {
+        &self.old
+    }
+
+    pub fn new_editor(&self) -> &Editor {
+        &self.new
+    }
+
+    pub fn old_editor_mut(&mut self) -> &mut Editor {
+        &mut self.old
+    }
+
+    /// The new buffer, e.g. to edit it; in the inline layout call
+    /// [`DiffEditor::refresh`] after editing the old one.
+    pub fn new_editor_mut(&mut self) -> &mut Editor {
+        &mut self.new
+    }
+
+    pub fn layout(&self) -> DiffLayout {
+        self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: DiffLayout) -> Result<()> {
+        self.layout = layout;
+        self.refresh()
+    }
+
+    /// Brings the inline layout up to date with the old buffer.
+    pub fn refresh(&mut self) -> Result<()> {
+        match self.layout {
+            DiffLayout::Inline => {
+                self.new.set_original_code(&self.old.get_content())?;
+                self.new.set_diff_enabled(true);
+            }
+            DiffLayout::SideBySide => self.new.set_diff_enabled(false),
+        }
+        Ok(())
+    }
+
+    /// Rows of the side-by-side layout.
+    pub fn rows(&self) -> Vec<DiffRow> {
+        let old = self.old.borrow_code();
+        let new = self.new.borrow_code();
+        let versions = (old.version(), new.version());
+        let mut rows = self.rows.borrow_mut();
+        match &*rows {
+            Some((cached, rows)) if *cached == versions => rows.clone(),
+            _ => {
+                let aligned = diff::align_lines(&old, &new);
+                *rows = Some((versions, aligned.clone()));
+                aligned
+            }
+        }
+    }
+
+    /// Index of the first visible row of the side-by-side layout.
+    pub fn offset_y(&self) -> usize {
+        self.offset_y
+    }
+
+    pub fn set_offset_y(&mut self, offset_y: usize) {
+        self.offset_y = offset_y.min(self.rows().len().saturating_sub(1));
+    }
+
+    pub fn scroll_up(&mut self) {
+        match self.layout {
+            DiffLayout::SideBySide => self.offset_y = self.offset_y.saturating_sub(1),
+            DiffLayout::Inline => self.new.scroll_up(),
+        }
+    }
+
+    pub fn scroll_down(&mut self, area_height: usize) {
+        match self.layout {
+            DiffLayout::SideBySide => {
+                let max = self.rows().len().saturating_sub(area_height);
+                self.offset_y = (self.offset_y + 1).min(max.max(self.offset_y));
+            }
+            DiffLayout::Inline => self.new.scroll_down(area_height),
+        }
+    }
+
+    /// Draws one side of the side-by-side layout.
+    fn render_side(&self, side: Side, rows: &[DiffRow], area: Rect, buf: &mut Buffer) {
+        let editor = match side {
+            Side::Old => &self.old,
+            Side::New => &self.new,
+        };
+        let styles = RowStyles::new(editor);
+        let changed_bg = match side {
+            Side::Old => styles.diff_deleted_bg,
+            Side::New => styles.diff_added_bg,
+        };
+        let digits = editor.borrow_code().len_lines().max(1).to_string().len();
+        let gutter = (digits + 1).min(area.width as usize) as u16;
+        let text_x = area.left() + gutter;
+        let width = area.width.saturating_sub(gutter);
+        let line_number_style = Style::default().fg(Color::DarkGray);
+
+        for (row, y) in rows.iter().zip(area.top()..area.bottom()) {
+            let line = match side {
+                Side::Old => row.old,
+                Side::New => row.new,
+            };
+            let Some(line) = line else {
+                let gap = "╱".repeat(area.width as usize);
+                buf.set_string(area.left(), y, gap, line_number_style);
+                continue;
+            };
+            let number = format!("{:>digits$}", line + 1);
+            buf.set_stringn(area.left(), y, number, gutter as usize, line_number_style);
+            let mut spans = editor.render_line_spans(line, width as usize);
+            if row.changed {
+                for span in &mut spans {
+                    if span.style.bg.is_none() {
+                        span.style = span.style.bg(changed_bg);
+                    }
+                }
+                buf.set_style(
+                    Rect::new(text_x, y, width, 1),
+                    Style::default().bg(changed_bg),
+                );
+            }
+            buf.set_line(text_x, y, &Line::from(spans), width);
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Side {
+    Old,
+    New,
+}
+
+impl Widget for &DiffEditor {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if self.layout == DiffLayout::Inline {
+            (&self.new).render(area, buf);
+            return;
+        }
+        let rows = self.rows();
+        let visible = rows.get(self.offset_y..).unwrap_or_default();
+        let left_width = area.width.saturating_sub(1) / 2;
+        let left = Rect::new(area.left(), area.top(), left_width, area.height);
+        let separator_x = area.left() + left_width;
+        let right = Rect::new(
+            separator_x + 1,
+            area.top(),
+            area.width.saturating_sub(left_width + 1),
+            area.height,
+        );
+        self.render_side(Side::Old, visible, left, buf);
+        if separator_x < area.right() {
+            for y in area.top()..area.bottom() {
+                buf.set_string(separator_x, y, "│", Style::default().fg(Color::DarkGray));
+            }
+        }
+        self.render_side(Side::New, visible, right, buf);
+    }
+}
@@ -1,26 +1,73 @@
 use crate::actions::*;
+use crate::anchor::{Anchor, AnchorBias};
+use crate::autoscroll::AutoScroll;
+use crate::bidi::BidiLine;
 use crate::click::{ClickKind, ClickTracker};
 use crate::code::Code;
-use crate::code::{EditBatch, Operation};
+use crate::code::{EditBatch, EditTransaction, Operation};
 use crate::code::{RopeGraphemes, grapheme_width, grapheme_width_and_chars_len};
-use crate::selection::{Selection, SelectionSnap};
-use crate::types::{CodeFoldingOptions, DiffOptions, HightlightCache, Theme, VisualRow, LineDiffCache};
+use crate::code_cell::CodeCell;
+use crate::command::Command;
+use crate::diff;
+use crate::editor_view::EditorView;
+use crate::encoding::{self, Encoding};
+use crate::events::{EditorEvent, EditorMode, EventQueue, Observed};
+use crate::flash::{FLASH_FRAME, FLASH_MARK_LAYER, Flash};
+use crate::gutter::GutterColumn;
+use crate::hover::Hover;
+use crate::language::LanguageConfig;
+use crate::layout::EditorLayout;
+use crate::marks::{DEFAULT_MARK_LAYER, Mark, MarkLayers};
+use crate::picker::{Picker, PickerSource};
+use crate::preedit::Preedit;
+use crate::render::FrameCache;
+use crate::search::{self, Search, SearchOptions};
+use crate::selection::{Selection, SelectionHistory, SelectionSnap};
+use crate::smooth_scroll::ScrollAnimation;
+use crate::snippet::{Snippet, SnippetSession};
+use crate::spell::{self, Dictionary, SPELL_MARK_LAYER};
+use crate::types::{
+    ChangeListener, ChangeOrigin, ChangeSync, CodeFoldingOptions, CursorStyle, DiffOptions,
+    Hightlight, HightlightCache, LineDiffCache, LineEnding, LineHighlightCache, PasteMode,
+    ScrollFollow, Symbol, TextEdit, Theme, VisualRow,
+};
 use crate::utils;
 use crate::view::{View, ViewMode};
+use crate::width::str_width;
 use anyhow::{Result, anyhow};
-use ratatui_core::layout::Rect;
-use ratatui_core::style::{Color, Style};
-use std::cell::RefCell;
+use ratatui_core::layout::{Position, Rect};
+use ratatui_core::style::{Color, Modifier, Style};
+use ratatui_widgets::scrollbar::ScrollbarState;
+use std::cell::{Cell, RefCell};
 use std::cmp::Ordering;
-use std::collections::HashMap;
-use std::time::Duration;
-use unicode_width::UnicodeWidthStr;
+use std::collections::{BTreeMap, HashMap};
+use std::io::BufRead;
+use std::ops::{Deref, DerefMut, Range};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
 /// Represents the text editor, which holds the code buffer, cursor, selection,
 /// theme, scroll offsets, highlight cache, clipboard, and user mark intervals.
 pub struct Editor {
     /// Code buffer and editing/highlighting logic for the current language
-    pub(crate) code: Code,
+    pub(crate) code: CodeCell,
+
+    /// Document version the caches were built for, to notice edits made
+    /// through another editor sharing the code
+    pub(crate) code_version: Cell<u64>,
+
+    /// Document version the cursor and selection are current for
+    pub(crate) position_version: u64,
+
+    /// File the content was loaded from or last saved to
+    pub(crate) file_path: Option<PathBuf>,
+
+    /// Line ending emitted by `get_content`; the buffer always uses `\n`
+    pub(crate) line_ending: LineEnding,
+
+    /// Encoding and byte order mark used when saving to a file
+    pub(crate) encoding: (&'static Encoding, bool),
 
     /// Current cursor position as a character index in the document
     pub(crate) cursor: usize,
@@ -37,20 +84,58 @@ pub struct Editor {
     /// Current text selection, if any
     pub(crate) selection: Option<Selection>,
 
-    /// Click tracker to detect single/double/triple clicks
+    /// Click tracker to detect single to quadruple clicks
     pub(crate) clicks: ClickTracker,
 
     /// Selection snapping mode (to word, to line, or none)
     pub(crate) selection_snap: SelectionSnap,
 
-    /// Fallback clipboard storage when the system clipboard is unavailable
-    pub(crate) clipboard: Option<String>,
+    /// Whether pressing inside the selection starts dragging it
+    pub(crate) drag_selection: bool,
+
+    /// Fallback clipboard storage when the system clipboard is unavailable,
+    /// shared by the editors of a workspace
+    pub(crate) clipboard: Rc<RefCell<Option<String>>>,
+
+    /// Whether `Paste` re-indents the clipboard text
+    pub(crate) paste_mode: PasteMode,
+
+    /// Whether Enter continues list items
+    pub(crate) list_continuation: bool,
+
+    /// Whether `Copy` and `Cut` take the whole line when nothing is selected
+    pub(crate) line_wise_copy: bool,
+
+    /// Text last copied as whole lines, pasted above the cursor line
+    pub(crate) line_wise_clipboard: Option<String>,
+
+    /// Whether mouse selections are offered as the primary selection and
+    /// middle click pastes it
+    pub(crate) primary_selection: bool,
+
+    /// Fallback primary selection storage when the system one is unavailable
+    pub(crate) primary_clipboard: Option<String>,
+
+    /// Named layers of user marks, anchored so they follow edits
+    pub(crate) marks: MarkLayers,
 
-    /// User marks for intervals: (start, end, color)
-    pub(crate) marks: Option<Vec<(usize, usize, Color)>>,
+    /// Open fuzzy picker overlay, which takes key input while shown
+    pub(crate) picker: Option<Picker>,
+
+    /// Pending IME composition drawn at the cursor, not yet in the document
+    pub(crate) preedit: Option<Preedit>,
+
+    /// Commands of the most recent run of consecutive edits, replayed by `RepeatLastEdit`
+    pub(crate) last_edit: Vec<Command>,
+
+    /// Whether the previously executed command was an edit
+    pub(crate) recording_edit: bool,
 
     /// Syntax highlight cache by intervals to speed up rendering
     pub(crate) highlights_cache: RefCell<HightlightCache>,
+    /// Highlights of single lines of the code, filled on demand and ahead
+    /// of scrolling by the prefetcher
+    pub(crate) line_highlights_cache: RefCell<LineHighlightCache>,
 
     /// Cache for line diff highlights to speed up rendering
     pub(crate) line_diff_cache: RefCell<LineDiffCache>,
@@ -64,6 +149,83 @@ pub struct Editor {
     /// Controls when to show the line numbers
     pub(crate) show_line_numbers: bool,
 
+    /// Fewest digits the line numbers are right-aligned to
+    pub(crate) line_number_min_digits: usize,
+
+    /// Number of lines kept visible above and below the cursor while scrolling
+    pub(crate) scrolloff: usize,
+
+    /// How the view follows the cursor, see [`ScrollFollow`]
+    pub(crate) scroll_follow: ScrollFollow,
+
+    /// In [`ScrollFollow::Tail`] mode, whether the view sticks to the last line
+    pub(crate) tail_attached: bool,
+
+    /// Cursor when the view last followed it, to tell whether it moved since
+    pub(crate) tail_cursor: usize,
+
+    /// Number of lines or columns scrolled per mouse wheel tick
+    pub(crate) scroll_lines_per_tick: usize,
+
+    /// Controls whether invisible characters are drawn as placeholders
+    pub(crate) show_invisibles: bool,
+
+    /// Controls whether a vertical scrollbar is drawn on the right edge
+    pub(crate) show_scrollbar: bool,
+
+    /// Whether the scrollbar thumb is currently being dragged with the mouse
+    pub(crate) scrollbar_dragging: bool,
+
+    /// Active auto-scroll while a selection is dragged past the viewport edge
+    pub(crate) drag_autoscroll: Option<AutoScroll>,
+
+    /// Cursor blink interval, `None` keeps the cursor always visible
+    pub(crate) cursor_blink: Option<Duration>,
+
+    /// Cursor shape set by the host, e.g. a block in a modal normal mode
+    pub(crate) cursor_style: Option<CursorStyle>,
+
+    /// Whether typed characters replace the ones after the cursor
+    pub(crate) overwrite: bool,
+
+    /// Blink phase: whether the cursor is shown and when the phase started
+    pub(crate) cursor_blink_phase: (bool, Instant),
+
+    /// Transient message for the host to display and its expiry time
+    pub(crate) message: Option<(String, Instant)>,
+
+    /// Set when a tick changed what is drawn, cleared by rendering
+    pub(crate) needs_redraw: Cell<bool>,
+
+    /// Events not yet collected by the host
+    pub(crate) events: EventQueue,
+
+    /// Additional selections besides the primary one, with the cursor at their end
+    pub(crate) secondary_selections: Vec<Mark>,
+
+    /// Selections dropped by actions or clicks, restored by `ReselectLast`
+    pub(crate) selection_history: SelectionHistory,
+
+    /// Snippets expanded from a trigger word, keyed by language (`None` for
+    /// all languages) and trigger
+    pub(crate) abbreviations: HashMap<(Option<String>, String), String>,
+
+    /// Tabstops of the snippet being filled in
+    pub(crate) snippet: Option<SnippetSession>,
+
+    /// Dictionary used by `check_spelling`
+    pub(crate) dictionary: Option<Box<dyn Dictionary>>,
+
+    /// Fading highlight of the range last passed to `reveal`
+    pub(crate) flash: Option<Flash>,
+
+    /// How long a `reveal` flash stays visible
+    pub(crate) flash_duration: Duration,
+
+    /// Host hook consulted before key events are dispatched
+    #[cfg(feature = "crossterm")]
+    pub(crate) input_hook: Option<crate::editor_crossterm::InputHook>,
+
     /// Controls whether the code-fold gutter is shown and interactive.
     pub(crate) code_folding_options: CodeFoldingOptions,
 
@@ -80,30 +242,155 @@ pub struct Editor {
     pub(crate) diff_options: DiffOptions,
 
     /// Derived view rows and line mappings used for scrolling, rendering, and navigation.
-    pub(crate) view: View,
+    pub(crate) view: RefCell<View>,
+
+    /// Whether invariants are asserted after every action and mouse event
+    pub(crate) checked: bool,
+
+    /// Query of the active search, whose matches are in the `search` mark layer
+    pub(crate) search: Option<Search>,
+
+    /// Whether Ctrl+Click requests the definition of the clicked symbol
+    pub(crate) definition_on_ctrl_click: bool,
+
+    /// How long the pointer rests before a hover is reported, `None` disables hovers
+    pub(crate) hover_delay: Option<Duration>,
+
+    /// Character under the resting mouse pointer
+    pub(crate) hover: Option<Hover>,
+
+    /// Host gutter columns, drawn left of the line numbers
+    pub(crate) gutter_columns: Vec<GutterColumn>,
+
+    /// Host text drawn right-aligned after the end of a line, by line index
+    pub(crate) line_annotations: BTreeMap<usize, String>,
+
+    /// Starts of the lines with a breakpoint
+    pub(crate) breakpoints: Vec<Anchor>,
+
+    /// Start of the line a debugger is stopped at
+    pub(crate) execution_line: Option<Anchor>,
+
+    /// Whether the breakpoint column is drawn left of the line numbers
+    pub(crate) breakpoint_gutter: bool,
+
+    /// Whether URLs and file paths are underlined and opened with Ctrl+Click
+    pub(crate) link_detection: bool,
+
+    /// Whether links are written as OSC 8 terminal hyperlinks
+    pub(crate) terminal_hyperlinks: bool,
+
+    /// Duration of animated jumps of the vertical offset, instant if `None`
+    pub(crate) smooth_scroll: Option<Duration>,
+
+    /// Scroll animation in progress, advanced by [`Editor::tick`]
+    pub(crate) scroll_animation: Option<ScrollAnimation>,
+
+    /// Dimmed text shown while the buffer is empty
+    pub(crate) placeholder: Option<String>,
+
+    /// Whether the editor has focus, which shows its cursor and takes keys
+    pub(crate) focused: bool,
+
+    /// Area the editor was last rendered in as a widget
+    pub(crate) last_area: Cell<Rect>,
+
+    /// Rows drawn in the last frame, reused while nothing they show changed
+    pub(crate) frame_cache: RefCell<FrameCache>,
+
+    /// Lines above and below the viewport highlighted ahead of time
+    pub(crate) highlight_prefetch: usize,
+
+    /// Amount added or subtracted per count by IncrementNumber and DecrementNumber
+    pub(crate) number_step: i64,
 }
 
 impl Editor {
+    /// Creates an editor for `text` in `lang`. Languages without a grammar,
+    /// such as `"text"`, open as plain text with no parser: everything but
+    /// syntax-aware actions works, and `ToggleComment` does nothing unless a
+    /// comment prefix is registered. This only fails for invalid custom
+    /// queries.
     pub fn new(lang: &str, text: &str, theme: Vec<(&str, &str)>) -> Result<Self> {
         Self::new_with_highlights(lang, text, theme, None)
     }
 
+    /// Like [`Editor::new`] with highlight queries keyed by language, and
+    /// other queries keyed by `"<lang>.<kind>"`, e.g. `"rust.locals"`.
     pub fn new_with_highlights(
         lang: &str,
         text: &str,
         theme: Vec<(&str, &str)>,
         custom_highlights: Option<HashMap<String, String>>,
     ) -> Result<Self> {
-        let code = Code::new(text, lang, custom_highlights.clone())
-            .or_else(|_| Code::new(text, "text", custom_highlights))?;
+        let line_ending = LineEnding::detect(text);
+        let text = LineEnding::normalize(text);
+        let code = Code::new(&text, lang, custom_highlights)?;
+        let mut editor = Self::from_code(code, theme);
+        editor.line_ending = line_ending;
+        Ok(editor)
+    }
+
+    /// Opens a possibly huge file by streaming it into the buffer.
+    ///
+    /// Files above [`LARGE_FILE_THRESHOLD`](crate::code::LARGE_FILE_THRESHOLD) open
+    /// without syntax highlighting; call [`Editor::enable_syntax_highlighting`] to
+    /// parse them on demand.
+    pub fn open_large(path: impl AsRef<Path>, theme: Vec<(&str, &str)>) -> Result<Self> {
+        let path = path.as_ref();
+        let file = std::fs::File::open(path)
+            .map_err(|e| anyhow!("cant read {}: {}", path.display(), e))?;
+        let mut reader = std::io::BufReader::new(file);
+        let head = String::from_utf8_lossy(reader.fill_buf()?).into_owned();
+        let lang = utils::detect_lang(path, head.lines().next());
+        let code = Code::from_reader(reader, &lang, None)?;
+        let mut editor = Self::from_code(code, theme);
+        editor.file_path = Some(path.to_path_buf());
+        Ok(editor)
+    }
+
+    /// Parses the buffer and turns on highlighting if it was deferred by
+    /// [`Editor::open_large`].
+    pub fn enable_syntax_highlighting(&mut self) -> Result<()> {
+        self.code.borrow_mut().enable_syntax()?;
+        self.reset_highlight_cache();
+        Ok(())
+    }
+
+    fn from_code(code: Code, theme: Vec<(&str, &str)>) -> Self {
+        Self::with_code(CodeCell::Owned(Box::new(code)), theme)
+    }
+
+    /// Creates another view of a document shared with other editors, e.g.
+    /// `Editor::with_shared_code(first.shared_code(), theme)` for a split.
+    ///
+    /// Each editor keeps its own cursor, selection, scroll and caches; an edit
+    /// made through one editor is seen by the others on their next use.
+    pub fn with_shared_code(code: Rc<RefCell<Code>>, theme: Vec<(&str, &str)>) -> Self {
+        Self::with_code(CodeCell::Shared(code), theme)
+    }
 
+    fn with_code(code: CodeCell, theme: Vec<(&str, &str)>) -> Self {
         let theme = Self::build_theme(&theme);
         let highlights_cache = RefCell::new(HashMap::new());
         let line_diff_cache = RefCell::new(HashMap::new());
-        let view = View::new(&code, ViewMode::Plain);
+        let (view, list_continuation, code_version) = {
+            let code = code.borrow();
+            let view = View::new(&code, ViewMode::Plain);
+            (
+                view,
+                code.language_config().list_continuation,
+                code.version(),
+            )
+        };
 
-        Ok(Self {
+        Self {
             code,
+            code_version: Cell::new(code_version),
+            position_version: code_version,
+            file_path: None,
+            line_ending: LineEnding::Lf,
+            encoding: (encoding_rs::UTF_8, false),
             cursor: 0,
             offset_y: 0,
             offset_x: 0,
@@ -111,49 +398,268 @@ impl Editor {
             selection: None,
             clicks: ClickTracker::new(Duration::from_millis(700)),
             selection_snap: SelectionSnap::None,
-            clipboard: None,
-            marks: None,
+            drag_selection: false,
+            clipboard: Rc::new(RefCell::new(None)),
+            paste_mode: PasteMode::Smart,
+            list_continuation,
+            line_wise_copy: false,
+            line_wise_clipboard: None,
+            primary_selection: false,
+            primary_clipboard: None,
+            marks: MarkLayers::default(),
+            picker: None,
+            preedit: None,
+            last_edit: Vec::new(),
+            recording_edit: false,
             highlights_cache,
+            line_highlights_cache: RefCell::new(HashMap::new()),
             line_diff_cache,
             word_highlight_enabled: true,
             word_highlight_cache: RefCell::new(None),
             show_line_numbers: true,
+            line_number_min_digits: 5,
+            scrolloff: 0,
+            scroll_follow: ScrollFollow::Cursor,
+            tail_attached: true,
+            tail_cursor: 0,
+            scroll_lines_per_tick: 3,
+            show_invisibles: true,
+            show_scrollbar: false,
+            scrollbar_dragging: false,
+            drag_autoscroll: None,
+            cursor_blink: None,
+            cursor_style: None,
+            overwrite: false,
+            cursor_blink_phase: (true, Instant::now()),
+            message: None,
+            needs_redraw: Cell::new(false),
+            events: EventQueue::default(),
+            secondary_selections: Vec::new(),
+            selection_history: SelectionHistory::default(),
+            abbreviations: HashMap::new(),
+            snippet: None,
+            dictionary: None,
+            flash: None,
+            flash_duration: Duration::from_millis(600),
+            #[cfg(feature = "crossterm")]
+            input_hook: None,
             code_folding_options: CodeFoldingOptions::default(),
             left_code_padding: 2,
             view_mode: ViewMode::Plain,
             original_code: None,
             diff_options: DiffOptions::default(),
-            view,
-        })
+            view: RefCell::new(view),
+            checked: false,
+            search: None,
+            definition_on_ctrl_click: true,
+            hover_delay: Some(Duration::from_millis(500)),
+            hover: None,
+            gutter_columns: Vec::new(),
+            line_annotations: BTreeMap::new(),
+            breakpoints: Vec::new(),
+            execution_line: None,
+            breakpoint_gutter: false,
+            link_detection: true,
+            terminal_hyperlinks: false,
+            smooth_scroll: None,
+            scroll_animation: None,
+            placeholder: None,
+            focused: true,
+            last_area: Cell::new(Rect::default()),
+            frame_cache: RefCell::new(None),
+            highlight_prefetch: 0,
+            number_step: 1,
+        }
+    }
+
+    /// Loads a file, picking the language with [`utils::detect_lang`].
+    ///
+    /// The encoding is detected with [`encoding::decode`] and used again when saving.
+    pub fn new_from_file(path: impl AsRef<Path>, theme: Vec<(&str, &str)>) -> Result<Self> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).map_err(|e| anyhow!("cant read {}: {}", path.display(), e))?;
+        let (content, encoding, bom) = encoding::decode(&bytes);
+        let lang = utils::detect_lang(path, content.lines().next());
+        let mut editor = Self::new(&lang, &content, theme)?;
+        editor.file_path = Some(path.to_path_buf());
+        editor.encoding = (encoding, bom);
+        Ok(editor)
+    }
+
+    /// Encoding used by [`Editor::save_to`], UTF-8 unless detected otherwise on load.
+    pub fn encoding(&self) -> &'static Encoding {
+        self.encoding.0
+    }
+
+    /// Sets the encoding used when saving and whether to write a byte order mark.
+    pub fn set_encoding(&mut self, encoding: &'static Encoding, bom: bool) {
+        self.encoding = (encoding, bom);
+    }
+
+    /// Returns true if the file had a byte order mark, which is written back on save.
+    pub fn has_bom(&self) -> bool {
+        self.encoding.1
+    }
+
+    /// Returns the file the editor was loaded from or last saved to.
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file_path.as_deref()
+    }
+
+    /// Returns true if the content changed since it was loaded or last saved.
+    pub fn is_modified(&self) -> bool {
+        self.code.borrow().is_modified()
+    }
+
+    /// Clears the modified flag, e.g. after the host saved the content itself.
+    pub fn mark_saved(&mut self) {
+        self.code.borrow_mut().mark_saved();
+    }
+
+    /// Replaces the content with `text`, e.g. after the file changed on disk.
+    ///
+    /// Only the changed parts are edited, so the cursor, selection, marks and
+    /// anchors outside of them keep their positions, and the reload is a single
+    /// undo step. The modified flag is cleared since the content now matches `text`.
+    pub fn reload_content(&mut self, text: &str) -> Result<()> {
+        let tx = diff::compute_reload_edits(&self.code.borrow(), &LineEnding::normalize(text));
+        self.apply_transaction(&tx)?;
+        self.mark_saved();
+        Ok(())
+    }
+
+    /// Runs `formatter`, e.g. a closure piping the content through rustfmt,
+    /// prettier or black, and applies the result as a single undo step.
+    ///
+    /// Only the characters that differ are edited, so the cursor, selection
+    /// and marks stay on the text they were on. Formatter errors are returned
+    /// and leave the content unchanged.
+    pub fn format_with(&mut self, formatter: impl FnOnce(&str) -> Result<String>) -> Result<()> {
+        self.sync_shared_code();
+        let formatted = formatter(&self.get_content())?;
+        let formatted = LineEnding::normalize(&formatted);
+        let tx = diff::compute_format_edits(&self.code.borrow(), &formatted);
+        self.apply_transaction(&tx)
+    }
+
+    /// Writes the content to `path` in the editor's encoding, remembers it as the
+    /// file path and clears the modified flag.
+    pub fn save_to(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let (encoding, bom) = self.encoding;
+        let bytes = encoding::encode(&self.get_content(), encoding, bom)?;
+        std::fs::write(path, bytes)
+            .map_err(|e| anyhow!("cant write {}: {}", path.display(), e))?;
+        self.file_path = Some(path.to_path_buf());
+        self.mark_saved();
+        self.events.push(EditorEvent::Saved {
+            path: path.to_path_buf(),
+        });
+        Ok(())
+    }
+
+    /// Returns what changed since the previous call: cursor, selection,
+    /// scroll offset and mode changes, plus undo, redo and save events in
+    /// the order they happened. The first call only records the state.
+    pub fn drain_events(&mut self) -> Vec<EditorEvent> {
+        let current = Observed {
+            cursor: self.cursor,
+            selection: self.selection,
+            offset: (self.offset_x, self.offset_y),
+            mode: self.mode(),
+        };
+        self.events.drain(current)
+    }
+
+    /// Current diff and paste modes.
+    pub fn mode(&self) -> EditorMode {
+        EditorMode {
+            diff: self.view_mode.has_diff(),
+            diff_focus: self.view_mode.is_diff_focus(),
+            paste_mode: self.paste_mode,
+        }
+    }
+
+    pub(crate) fn push_event(&mut self, event: EditorEvent) {
+        self.events.push(event);
+    }
+
+    /// Writes the content back to [`Editor::file_path`].
+    pub fn save(&mut self) -> Result<()> {
+        let path = self.file_path.clone().ok_or_else(|| anyhow!("no file path"))?;
+        self.save_to(path)
     }
 
     pub(crate) fn get_line_number_width(&self) -> usize {
         let fold_gutter_width = self.fold_gutter_width();
+        let columns_width = self.gutter_columns_width();
         if self.show_line_numbers {
-            let total_lines = self.code.len_lines();
-            let max_line_number = total_lines.max(1);
-            let line_number_digits = max_line_number.to_string().len().max(5);
-            line_number_digits + self.left_code_padding + fold_gutter_width
+            columns_width + self.line_number_digits() + self.left_code_padding + fold_gutter_width
         } else {
-            self.left_code_padding + fold_gutter_width
+            columns_width + self.left_code_padding + fold_gutter_width
+        }
+    }
+
+    /// Width the line numbers are right-aligned to, at least
+    /// [`Editor::set_line_number_min_digits`] digits.
+    pub(crate) fn line_number_digits(&self) -> usize {
+        let max_line_number = self.code.borrow().len_lines().max(1);
+        let digits = max_line_number.to_string().len();
+        digits.max(self.line_number_min_digits)
+    }
+
+    /// Where the gutter, text and scrollbar are drawn when the editor is
+    /// rendered in `area`, e.g. to line up a host widget with the text.
+    pub fn layout(&self, area: Rect) -> EditorLayout {
+        let gutter_width = self.get_line_number_width() as u16;
+        let columns_width = self.gutter_columns_width() as u16;
+        let fold_start = gutter_width - self.fold_gutter_width() as u16;
+        let scrollbar_width = self.scrollbar_width() as u16;
+        // x of a column in the area, cut off at its right edge
+        let x = |column: u16| area.x + column.min(area.width);
+        let part =
+            |start: u16, end: u16| Rect::new(x(start), area.y, x(end) - x(start), area.height);
+
+        EditorLayout {
+            gutter_width,
+            columns: part(0, columns_width),
+            line_numbers: part(columns_width, fold_start),
+            fold_gutter: part(fold_start, gutter_width),
+            text_area: Rect::new(
+                x(gutter_width),
+                area.y,
+                area.width.saturating_sub(gutter_width + scrollbar_width),
+                area.height,
+            ),
+            scrollbar: (scrollbar_width > 0 && area.width > 0)
+                .then(|| Rect::new(area.right() - 1, area.y, 1, area.height)),
         }
     }
 
     pub fn focus(&mut self, area: &Rect) {
+        self.animate_scroll(|editor| editor.scroll_cursor_into_view(area));
+    }
+
+    /// Like [`Editor::focus`] for the last rendered area.
+    pub fn scroll_to_cursor(&mut self) {
+        self.focus(&self.last_area.get());
+    }
+
+    fn scroll_cursor_into_view(&mut self, area: &Rect) {
+        self.sync_shared_code();
         self.fit_cursor();
         if self.is_diff_focus_active() {
             self.clamp_cursor_to_focus_rows();
         }
         self.clamp_offset_y();
 
-        let width = area.width as usize;
         let height = area.height as usize;
-        let line_number_width = self.get_line_number_width();
 
-        let line = self.code.char_to_line(self.cursor);
-        let col = self.cursor - self.code.line_to_char(line);
+        let line = self.code.borrow().char_to_line(self.cursor);
+        let col = self.cursor - self.code.borrow().line_to_char(line);
 
-        let visible_width = width.saturating_sub(line_number_width);
+        let visible_width = self.layout(*area).text_area.width as usize;
         let visible_height = height;
 
         let step_size = 10;
@@ -168,23 +674,235 @@ impl Editor {
             return;
         }
 
-        if visual_line < self.offset_y {
-            self.offset_y = visual_line;
-        } else if visual_line >= self.offset_y + visible_height {
-            self.offset_y = visual_line.saturating_sub(visible_height.saturating_sub(1));
+        // Keep `scrolloff` lines of context, but never more than half the viewport
+        let margin = self.scrolloff.min(visible_height.saturating_sub(1) / 2);
+        let max_offset = self.visual_len_lines().saturating_sub(visible_height);
+        match self.scroll_follow {
+            ScrollFollow::Typewriter => {
+                self.offset_y = visual_line.saturating_sub(visible_height / 2).min(max_offset);
+                return;
+            }
+            // The cursor only pulls the view away from the end once it moves
+            ScrollFollow::Tail
+                if self.tail_attached
+                    && (visual_line >= max_offset || self.cursor == self.tail_cursor) =>
+            {
+                self.offset_y = max_offset;
+                self.tail_cursor = self.cursor;
+                return;
+            }
+            ScrollFollow::Tail => self.tail_attached = false,
+            ScrollFollow::Cursor => {}
+        }
+        if visual_line < self.offset_y + margin {
+            self.offset_y = visual_line.saturating_sub(margin);
+        } else if visual_line + margin >= self.offset_y + visible_height {
+            self.offset_y = (visual_line + margin)
+                .saturating_sub(visible_height.saturating_sub(1))
+                .min(max_offset.max(self.offset_y));
+        }
+        self.tail_attached = self.offset_y >= max_offset;
+        self.tail_cursor = self.cursor;
+    }
+
+    /// Scrolls vertically so that the cursor line is in the middle of the area.
+    /// The cursor itself does not move.
+    pub fn center_cursor(&mut self, area: &Rect) {
+        self.animate_scroll(|editor| editor.center_cursor_now(area));
+    }
+
+    fn center_cursor_now(&mut self, area: &Rect) {
+        let line = self
+            .code
+            .borrow()
+            .char_to_line(self.cursor.min(self.code.borrow().len_chars()));
+        let visual_line = self.visual_line_idx(line);
+        if visual_line == usize::MAX {
+            return;
+        }
+        self.offset_y = visual_line.saturating_sub(area.height as usize / 2);
+    }
+
+    /// Scrolls vertically so that `line_idx` is the first visible line.
+    /// The cursor does not move. Lines hidden by folds or diff focus are ignored.
+    pub fn scroll_to_line(&mut self, line_idx: usize) {
+        let line_idx = line_idx.min(self.code.borrow().len_lines().saturating_sub(1));
+        let visual_line = self.visual_line_idx(line_idx);
+        if visual_line == usize::MAX {
+            return;
         }
+        self.tail_attached = false;
+        self.animate_scroll(|editor| {
+            editor.offset_y = visual_line;
+            editor.clamp_offset_y();
+        });
+    }
+
+    /// Scrolls `range` into view, centering it if it is off screen, and flashes
+    /// it with the theme's `flash` background. The flash fades out as
+    /// [`Editor::tick`] is called. The cursor does not move.
+    pub fn reveal(&mut self, range: Range<usize>, area: &Rect) {
+        self.reveal_at(range, area, Instant::now());
+    }
+
+    /// Like [`Editor::reveal`], with the flash starting at `now`.
+    pub fn reveal_at(&mut self, range: Range<usize>, area: &Rect, now: Instant) {
+        let len = self.code.borrow().len_chars();
+        let (start, end) = (range.start.min(len), range.end.min(len));
+        let visual_line = self.visual_line_idx(self.code.borrow().char_to_line(start));
+        let height = area.height as usize;
+        self.animate_scroll(|editor| {
+            let offset_y = editor.offset_y;
+            if visual_line != usize::MAX
+                && (visual_line < offset_y || visual_line >= offset_y + height)
+            {
+                editor.offset_y = visual_line.saturating_sub(height / 2);
+                editor.clamp_offset_y();
+            }
+        });
+
+        let color = self.theme_style("flash").bg.unwrap_or(Color::Rgb(229, 192, 123));
+        let fade_to = self
+            .theme_style("word_highlight")
+            .bg
+            .unwrap_or(Color::Rgb(48, 54, 64));
+        let flash = Flash::new(color, fade_to, now, self.flash_duration);
+        self.set_mark_layer(FLASH_MARK_LAYER, vec![(start, end)], flash.style_at(now));
+        self.set_mark_layer_priority(FLASH_MARK_LAYER, i32::MAX);
+        self.flash = Some(flash);
+    }
+
+    /// Enables spell checking of comments and strings with `dictionary`, or
+    /// disables it with `None`. Call [`Editor::check_spelling`] to update.
+    pub fn set_dictionary(&mut self, dictionary: Option<Box<dyn Dictionary>>) {
+        self.dictionary = dictionary;
+        if self.dictionary.is_none() {
+            self.marks.remove(SPELL_MARK_LAYER);
+        }
+    }
+
+    /// Underlines misspelled words in comments and strings in the `spell` mark
+    /// layer. Marks follow edits, so hosts can re-check when idle rather than
+    /// after every keystroke.
+    pub fn check_spelling(&mut self) {
+        let Some(dictionary) = &self.dictionary else {
+            return;
+        };
+        let words = spell::misspelled_words(&self.code.borrow(), dictionary.as_ref());
+        let style = Style::default()
+            .add_modifier(Modifier::UNDERLINED)
+            .patch(self.theme_style("spell"));
+        self.set_mark_layer(SPELL_MARK_LAYER, words, style);
+    }
+
+    /// Replacement candidates for the misspelled word at `offset`.
+    pub fn suggestions(&self, offset: usize) -> Vec<String> {
+        let Some(dictionary) = &self.dictionary else {
+            return vec![];
+        };
+        self.marks
+            .get(SPELL_MARK_LAYER)
+            .and_then(|layer| layer.marks.iter().find(|mark| mark.contains(offset)))
+            .map(|mark| dictionary.suggest(&self.code.borrow().slice(mark.start(), mark.end())))
+            .unwrap_or_default()
+    }
+
+    /// Sets how long the highlight of [`Editor::reveal`] stays visible. Defaults to 600ms.
+    pub fn set_flash_duration(&mut self, duration: Duration) {
+        self.flash_duration = duration;
+    }
+
+    /// Sets how many lines of context `focus` keeps visible above and below the
+    /// cursor. The margin is capped at half of the viewport height.
+    pub fn set_scrolloff(&mut self, lines: usize) {
+        self.scrolloff = lines;
+    }
+
+    pub fn scrolloff(&self) -> usize {
+        self.scrolloff
+    }
+
+    /// Sets how `focus` scrolls after the cursor moved, see [`ScrollFollow`].
+    pub fn set_scroll_follow(&mut self, follow: ScrollFollow) {
+        self.scroll_follow = follow;
+        self.tail_attached = true;
+        self.tail_cursor = self.cursor;
+    }
+
+    pub fn scroll_follow(&self) -> ScrollFollow {
+        self.scroll_follow
+    }
+
+    /// Returns true in [`ScrollFollow::Tail`] mode while the view sticks to
+    /// the last line, e.g. to show whether new output will scroll into view.
+    pub fn is_following_tail(&self) -> bool {
+        self.scroll_follow == ScrollFollow::Tail && self.tail_attached
+    }
+
+    /// Sets how many clicks at one position chain up: double clicks select a
+    /// word, triple clicks a line and quadruple clicks a paragraph. The click
+    /// after the longest chain starts over. Defaults to 4.
+    pub fn set_max_click_chain(&mut self, clicks: usize) {
+        self.clicks.max_chain = clicks;
+    }
+
+    /// Sets the longest pause between two clicks of a chain. Defaults to 700ms.
+    pub fn set_click_timeout(&mut self, timeout: Duration) {
+        self.clicks.max_dt = timeout;
+    }
+
+    pub fn click_timeout(&self) -> Duration {
+        self.clicks.max_dt
     }
 
     /// Handles a mouse button press at the given cursor position, updating selection and click state.
     pub fn handle_mouse_down(&mut self, cursor: usize) {
+        self.mouse_down(cursor, false);
+    }
+
+    /// Like [`Editor::handle_mouse_down`], but a double click selects the
+    /// identifier part, e.g. `Request` of `parseHttpRequest`, and dragging
+    /// extends by parts. Alt+Click does this.
+    pub fn handle_sub_word_mouse_down(&mut self, cursor: usize) {
+        self.mouse_down(cursor, true);
+    }
+
+    fn mouse_down(&mut self, cursor: usize, sub_word: bool) {
+        if let Some(selection) = self.selection {
+            self.remember_selection(selection, self.cursor);
+        }
         let kind = self.clicks.register(cursor);
+        // A click collapses multiple cursors to the clicked one
+        let had_secondary = !self.secondary_selections.is_empty();
+        self.secondary_selections.clear();
+        // Pressing inside the selection starts dragging it
+        if kind == ClickKind::Single
+            && self.drag_selection
+            && let Some(selection) = self.selection
+            && !selection.is_empty()
+            && (selection.start..selection.end).contains(&cursor)
+            && !had_secondary
+        {
+            self.selection_snap = SelectionSnap::Move { origin: cursor };
+            self.cursor = cursor;
+            self.drag_autoscroll = None;
+            return;
+        }
         let (start, end, snap) = match kind {
+            ClickKind::Quadruple => {
+                let (start, end) = self.code.borrow().paragraph_boundaries(cursor);
+                (start, end, SelectionSnap::Paragraph { anchor: cursor })
+            }
             ClickKind::Triple => {
-                let (line_start, line_end) = self.code.line_boundaries(cursor);
+                let (line_start, line_end) = self.code.borrow().line_boundaries(cursor);
                 (line_start, line_end, SelectionSnap::Line { anchor: cursor })
             }
+            ClickKind::Double if sub_word => {
+                let (start, end) = self.code.borrow().sub_word_boundaries(cursor);
+                (start, end, SelectionSnap::SubWord { anchor: cursor })
+            }
             ClickKind::Double => {
-                let (word_start, word_end) = self.code.word_boundaries(cursor);
+                let (word_start, word_end) = self.code.borrow().word_boundaries(cursor);
                 (word_start, word_end, SelectionSnap::Word { anchor: cursor })
             }
             ClickKind::Single => (cursor, cursor, SelectionSnap::None),
@@ -193,14 +911,33 @@ impl Editor {
         self.selection = Some(Selection::from_anchor_and_cursor(start, end));
         self.cursor = end;
         self.selection_snap = snap;
+        self.drag_autoscroll = None;
+        self.reset_cursor_blink();
+        if self.checked {
+            self.debug_assert_invariants();
+        }
     }
 
     /// Handles a mouse drag event at the given cursor position, extending the selection.
     pub fn handle_mouse_drag(&mut self, cursor: usize) {
+        let code = self.code.borrow();
         match self.selection_snap {
+            SelectionSnap::Paragraph { anchor } => {
+                let (anchor_start, anchor_end) = code.paragraph_boundaries(anchor);
+                let (cur_start, cur_end) = code.paragraph_boundaries(cursor);
+
+                let (sel_start, sel_end, new_cursor) = match cursor.cmp(&anchor) {
+                    Ordering::Greater => (anchor_start, cur_end, cur_end), // forward
+                    Ordering::Less => (cur_start, anchor_end, cur_start),  // backward
+                    Ordering::Equal => (anchor_start, anchor_end, anchor_end),
+                };
+
+                self.selection = Some(Selection::from_anchor_and_cursor(sel_start, sel_end));
+                self.cursor = new_cursor;
+            }
             SelectionSnap::Line { anchor } => {
-                let (anchor_start, anchor_end) = self.code.line_boundaries(anchor);
-                let (cur_start, cur_end) = self.code.line_boundaries(cursor);
+                let (anchor_start, anchor_end) = code.line_boundaries(anchor);
+                let (cur_start, cur_end) = code.line_boundaries(cursor);
 
                 let (sel_start, sel_end, new_cursor) = match cursor.cmp(&anchor) {
                     Ordering::Greater => (anchor_start, cur_end, cur_end), // forward
@@ -211,9 +948,13 @@ impl Editor {
                 self.selection = Some(Selection::from_anchor_and_cursor(sel_start, sel_end));
                 self.cursor = new_cursor;
             }
-            SelectionSnap::Word { anchor } => {
-                let (anchor_start, anchor_end) = self.code.word_boundaries(anchor);
-                let (cur_start, cur_end) = self.code.word_boundaries(cursor);
+            SelectionSnap::Word { anchor } | SelectionSnap::SubWord { anchor } => {
+                let boundaries = |pos| match self.selection_snap {
+                    SelectionSnap::SubWord { .. } => code.sub_word_boundaries(pos),
+                    _ => code.word_boundaries(pos),
+                };
+                let (anchor_start, anchor_end) = boundaries(anchor);
+                let (cur_start, cur_end) = boundaries(cursor);
 
                 let (sel_start, sel_end, new_cursor) = match cursor.cmp(&anchor) {
                     Ordering::Greater => (anchor_start, cur_end, cur_end), // forward
@@ -224,35 +965,329 @@ impl Editor {
                 self.selection = Some(Selection::from_anchor_and_cursor(sel_start, sel_end));
                 self.cursor = new_cursor;
             }
+            SelectionSnap::Move { .. } => self.cursor = cursor,
             SelectionSnap::None => {
                 let anchor = self.selection_anchor();
                 self.selection = Some(Selection::from_anchor_and_cursor(anchor, cursor));
                 self.cursor = cursor;
             }
         }
+        drop(code);
+        if self.checked {
+            self.debug_assert_invariants();
+        }
+    }
+
+    /// Lets the mouse drag the selection to move it, or copy it with Ctrl.
+    /// Off by default, because the text is only dropped by
+    /// [`Editor::handle_mouse_up`]: enable it only if the host reports button
+    /// releases, as the crossterm `Editor::mouse` does.
+    pub fn set_drag_selection(&mut self, enabled: bool) {
+        self.drag_selection = enabled;
+    }
+
+    pub fn drag_selection(&self) -> bool {
+        self.drag_selection
+    }
+
+    /// Handles the release of the mouse button. Ends a selection drag
+    /// started inside the selection by moving the text to the cursor, or
+    /// copying it if `copy` is set. Released where it was pressed, the
+    /// selection is cleared as by a plain click.
+    pub fn handle_mouse_up(&mut self, copy: bool) {
+        let snap = std::mem::replace(&mut self.selection_snap, SelectionSnap::None);
+        let SelectionSnap::Move { origin } = snap else {
+            return;
+        };
+        let Some(selection) = self.selection else {
+            return;
+        };
+        if self.cursor == origin {
+            self.selection = Some(Selection::new(origin, origin));
+            return;
+        }
+        let to = self.cursor;
+        self.cursor = selection.end;
+        self.apply(MoveSelection { to, copy });
+    }
+
+    /// Handles a selection drag to the given mouse position at time `now`.
+    ///
+    /// Near or past the top and bottom edges this starts auto-scrolling with a speed
+    /// proportional to the distance from the edge; [`Editor::tick`] keeps it going
+    /// while the mouse does not move.
+    pub fn handle_mouse_drag_at(&mut self, mouse_x: u16, mouse_y: u16, area: &Rect, now: Instant) {
+        match AutoScroll::for_pointer(mouse_x, mouse_y, *area, now) {
+            Some(target) => match self.drag_autoscroll.as_mut() {
+                Some(autoscroll) => autoscroll.retarget(target),
+                None => {
+                    // Move one line right away so the edge reacts without waiting a tick
+                    self.scroll_by(target.velocity.signum() as isize, area.height as usize);
+                    self.drag_autoscroll = Some(target);
+                }
+            },
+            None => self.drag_autoscroll = None,
+        }
+        self.tick(now);
+
+        if let Some(cursor) = self.drag_cursor_at(mouse_x, mouse_y, area) {
+            self.handle_mouse_drag(cursor);
+        }
+    }
+
+    /// Position a drag to a screen cell selects to, with the cell moved
+    /// into the text: left of it onto the first visible column, above or below it
+    /// onto the first or last visible row.
+    fn drag_cursor_at(&self, mouse_x: u16, mouse_y: u16, area: &Rect) -> Option<usize> {
+        let text_x = self.layout(*area).text_area.x;
+        let row = mouse_y.clamp(area.top(), area.bottom().saturating_sub(1));
+        self.cursor_from_mouse(mouse_x.max(text_x), row, area)
+    }
+
+    /// Stops any drag auto-scroll, e.g. when the mouse button is released.
+    pub fn stop_drag_autoscroll(&mut self) {
+        self.drag_autoscroll = None;
+    }
+
+    /// Advances timed behaviors to `now`: drag auto-scroll, cursor blinking,
+    /// click sequence timeouts, transient message expiry, hover reports,
+    /// smooth scrolling and highlight prefetching.
+    ///
+    /// Call it when [`Editor::next_deadline`] is reached and redraw if
+    /// [`Editor::needs_redraw`] returns true.
+    pub fn tick(&mut self, now: Instant) {
+        if let Some(mut autoscroll) = self.drag_autoscroll {
+            let lines = autoscroll.advance(now);
+            self.drag_autoscroll = Some(autoscroll);
+            if lines != 0 {
+                let area = autoscroll.area;
+                self.scroll_by(lines, area.height as usize);
+                let (mouse_x, mouse_y) = autoscroll.mouse;
+                if let Some(cursor) = self.drag_cursor_at(mouse_x, mouse_y, &area) {
+                    self.handle_mouse_drag(cursor);
+                }
+                self.needs_redraw.set(true);
+            }
+        }
+
+        if let Some(interval) = self.cursor_blink {
+            let (visible, since) = self.cursor_blink_phase;
+            if now.saturating_duration_since(since) >= interval {
+                self.cursor_blink_phase = (!visible, now);
+                self.needs_redraw.set(true);
+            }
+        }
+
+        if let Some((last_click, _)) = self.clicks.last
+            && now.saturating_duration_since(last_click) >= self.clicks.max_dt
+        {
+            self.clicks.reset();
+        }
+
+        if let Some((_, expires)) = self.message
+            && now >= expires
+        {
+            self.message = None;
+            self.needs_redraw.set(true);
+        }
+
+        if let Some(delay) = self.hover_delay
+            && let Some(hover) = self.hover.as_mut()
+            && hover.is_due(delay, now)
+        {
+            hover.reported = true;
+            let (offset, screen_pos) = (hover.offset, hover.screen_pos);
+            self.push_event(EditorEvent::HoverRequested { offset, screen_pos });
+        }
+
+        if let Some(mut flash) = self.flash {
+            if now >= flash.expires() {
+                self.flash = None;
+                self.marks.remove(FLASH_MARK_LAYER);
+                self.needs_redraw.set(true);
+            } else if now >= flash.next_frame {
+                let style = flash.style_at(now);
+                for mark in &mut self.marks.get_or_insert(FLASH_MARK_LAYER).marks {
+                    mark.style = style;
+                }
+                flash.next_frame = now + FLASH_FRAME;
+                self.flash = Some(flash);
+                self.needs_redraw.set(true);
+            }
+        }
+
+        if self.advance_scroll_animation(now) {
+            self.needs_redraw.set(true);
+        }
+
+        self.prefetch_highlights();
+    }
+
+    /// Returns when the host should call [`Editor::tick`] next, or `None` if no
+    /// timed behavior is active.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let autoscroll = self.drag_autoscroll.map(|autoscroll| autoscroll.deadline());
+        let blink = self
+            .cursor_blink
+            .filter(|_| self.focused)
+            .map(|interval| self.cursor_blink_phase.1 + interval);
+        let clicks = self.clicks.last.map(|(at, _)| at + self.clicks.max_dt);
+        let message = self.message.as_ref().map(|(_, expires)| *expires);
+        let flash = self.flash.map(|flash| flash.deadline());
+        let hover = self
+            .hover
+            .zip(self.hover_delay)
+            .and_then(|(hover, delay)| hover.deadline(delay));
+        let scroll = self.scroll_animation.map(|scroll| scroll.deadline());
+        let prefetch = self.prefetch_range().map(|_| Instant::now());
+        [
+            autoscroll, blink, clicks, message, flash, hover, scroll, prefetch,
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
+
+    /// Returns true if a [`Editor::tick`] changed the editor since it was last rendered.
+    pub fn needs_redraw(&self) -> bool {
+        self.needs_redraw.get()
+    }
+
+    /// Enables cursor blinking with the given interval, or disables it with `None`.
+    /// While blinked off, [`Editor::get_visible_cursor`] returns `None`.
+    pub fn set_cursor_blink(&mut self, interval: Option<Duration>) {
+        self.cursor_blink = interval;
+        self.reset_cursor_blink();
+    }
+
+    /// Terminal cursor shape for the current mode, for hosts to forward to
+    /// the terminal, e.g. with crossterm's `SetCursorStyle`. A bar while
+    /// inserting and an underline in overwrite mode, unless the host set a
+    /// shape with [`Editor::set_cursor_style`].
+    pub fn cursor_style(&self) -> CursorStyle {
+        self.cursor_style.unwrap_or(if self.overwrite {
+            CursorStyle::Underline
+        } else {
+            CursorStyle::Bar
+        })
+    }
+
+    /// Switches between inserting and overwriting typed characters, see
+    /// [`OverwriteText`]. The Insert key toggles it.
+    pub fn set_overwrite(&mut self, overwrite: bool) {
+        self.overwrite = overwrite;
+    }
+
+    pub fn is_overwrite(&self) -> bool {
+        self.overwrite
+    }
+
+    pub fn toggle_overwrite(&mut self) {
+        self.overwrite = !self.overwrite;
+    }
+
+    /// Overrides the cursor shape, e.g. with a block while a modal host is
+    /// in its normal mode, or goes back to the editor's choice with `None`.
+    pub fn set_cursor_style(&mut self, style: Option<CursorStyle>) {
+        self.cursor_style = style;
+    }
+
+    /// Returns false while the cursor is in the hidden phase of a blink or
+    /// the editor is not focused.
+    pub fn cursor_visible(&self) -> bool {
+        self.focused && (self.cursor_blink.is_none() || self.cursor_blink_phase.0)
+    }
+
+    /// Marks the editor as focused or not, e.g. when the user switches panes.
+    /// An unfocused editor hides its cursor, dims its selections and ignores
+    /// key events passed to `input`. Editors are focused by default.
+    pub fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
+        self.reset_cursor_blink();
+        self.needs_redraw.set(true);
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Shows the cursor and restarts the blink phase, e.g. after input.
+    pub fn reset_cursor_blink(&mut self) {
+        self.cursor_blink_phase = (true, Instant::now());
+    }
+
+    /// Sets a transient message that expires after `duration`.
+    /// The editor does not draw it; hosts can show it in a status line.
+    pub fn set_message(&mut self, text: &str, duration: Duration) {
+        self.message = Some((text.to_string(), Instant::now() + duration));
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        self.message.as_ref().map(|(text, _)| text.as_str())
+    }
+
+    /// Sets dimmed text shown in place of the content while the buffer is
+    /// empty, e.g. "Type your query…" in an input field. An empty `text`
+    /// removes it.
+    pub fn set_placeholder(&mut self, text: &str) {
+        self.placeholder = (!text.is_empty()).then(|| text.to_string());
+    }
+
+    pub fn placeholder(&self) -> Option<&str> {
+        self.placeholder.as_deref()
+    }
+
+    /// Placeholder to draw now: the buffer is empty and nothing is being
+    /// composed into it.
+    pub(crate) fn visible_placeholder(&self) -> Option<&str> {
+        let empty = self.code.borrow().len_chars() == 0 && self.preedit.is_none();
+        self.placeholder.as_deref().filter(|_| empty)
+    }
+
+    fn scroll_by(&mut self, lines: isize, area_height: usize) {
+        for _ in 0..lines.unsigned_abs() {
+            if lines < 0 {
+                self.scroll_up();
+            } else {
+                self.scroll_down(area_height);
+            }
+        }
     }
 
     /// Converts mouse coordinates to a cursor position within the editor area, returning `None` if outside.
+    ///
+    /// Same as [`Editor::screen_to_doc`].
     pub fn cursor_from_mouse(&self, mouse_x: u16, mouse_y: u16, area: &Rect) -> Option<usize> {
-        let line_number_width = self.get_line_number_width() as u16;
+        self.screen_to_doc(mouse_x, mouse_y, area)
+    }
 
-        if mouse_y < area.top()
-            || mouse_y >= area.bottom()
-            || mouse_x < area.left() + line_number_width
-        {
+    /// Document offset (chars) drawn at the screen cell `(x, y)` when the
+    /// editor is rendered in `area`, e.g. for hover popups. Cells past the end
+    /// of a line map to its end. `None` outside the text area or below the
+    /// last line. The inverse of [`Editor::doc_to_screen`].
+    pub fn screen_to_doc(&self, x: u16, y: u16, area: &Rect) -> Option<usize> {
+        let code = self.code.borrow();
+        let text_x = self.layout(*area).text_area.x;
+
+        if y < area.top() || y >= area.bottom() || x < text_x {
             return None;
         }
 
-        let clicked_visual_row = (mouse_y - area.top()) as usize + self.offset_y;
+        let clicked_visual_row = (y - area.top()) as usize + self.offset_y;
         let clicked_row = self.line_for_visual_row(clicked_visual_row)?;
-        if clicked_row >= self.code.len_lines() {
+        if clicked_row >= code.len_lines() {
             return None;
         }
 
-        let clicked_col = (mouse_x - area.left() - line_number_width) as usize;
+        let clicked_col = (x - text_x) as usize;
 
-        let line_start_char = self.code.line_to_char(clicked_row);
-        let line_len = self.code.line_len(clicked_row);
+        let line_start_char = code.line_to_char(clicked_row);
+        let line_len = code.line_len(clicked_row);
+
+        if let Some(bidi) = BidiLine::for_line(&code, clicked_row) {
+            let col = bidi.skipped_width(self.offset_x) + clicked_col;
+            return Some(line_start_char + bidi.char_col_at(col));
+        }
 
         let start_col = self.offset_x.min(line_len);
         let end_col = line_len;
@@ -262,7 +1297,7 @@ impl Editor {
 
         let mut current_col = 0;
         let mut char_idx = start_col;
-        let visible_chars = self.code.char_slice(char_start, char_end);
+        let visible_chars = code.char_slice(char_start, char_end);
         for g in RopeGraphemes::new(&visible_chars) {
             let (g_width, g_chars) = grapheme_width_and_chars_len(g);
             if current_col + g_width > clicked_col {
@@ -272,17 +1307,8 @@ impl Editor {
             char_idx += g_chars;
         }
 
-        let line = self
-            .code
-            .char_slice(line_start_char, line_start_char + line_len);
-        let visual_width: usize = RopeGraphemes::new(&line).map(grapheme_width).sum();
-
-        if clicked_col + self.offset_x >= visual_width {
-            let mut end_idx = line.len_chars();
-            if end_idx > 0 && line.char(end_idx - 1) == '\n' {
-                end_idx -= 1;
-            }
-            char_idx = end_idx;
+        if clicked_col + self.offset_x >= code.line_width(clicked_row) {
+            char_idx = line_len;
         }
 
         Some(line_start_char + char_idx)
@@ -293,17 +1319,8 @@ impl Editor {
             return false;
         }
 
-        let line_number_width = self.get_line_number_width();
-        let fold_gutter_width = self.fold_gutter_width();
-        let Some(fold_gutter_start) = line_number_width.checked_sub(fold_gutter_width) else {
-            return false;
-        };
-
-        if mouse_x < area.left() + fold_gutter_start as u16
-            || mouse_x >= area.left() + line_number_width as u16
-            || mouse_y < area.top()
-            || mouse_y >= area.bottom()
-        {
+        let fold_gutter = self.layout(*area).fold_gutter;
+        if !fold_gutter.contains(Position::new(mouse_x, mouse_y)) {
             return false;
         }
 
@@ -320,21 +1337,17 @@ impl Editor {
             return false;
         }
 
-        let line_number_width = self.get_line_number_width() as u16;
-        if mouse_y < area.top()
-            || mouse_y >= area.bottom()
-            || mouse_x < area.left() + line_number_width
-        {
+        let text_area = self.layout(*area).text_area;
+        if mouse_y < area.top() || mouse_y >= area.bottom() || mouse_x < text_area.x {
             return false;
         }
 
         let clicked_visual_row = (mouse_y - area.top()) as usize + self.offset_y;
-        let text_x = area.left() + line_number_width;
-        let clicked_col = mouse_x.saturating_sub(text_x) as usize;
-        let visible_width = area.width.saturating_sub(line_number_width) as usize;
+        let clicked_col = mouse_x.saturating_sub(text_area.x) as usize;
+        let visible_width = text_area.width as usize;
 
-        let expanded = self.view.expand_hidden_at_visual_row(
-            &self.code,
+        let expanded = self.view.borrow_mut().expand_hidden_at_visual_row(
+            &self.code.borrow(),
             self.original_code.as_ref(),
             self.active_view_mode(),
             clicked_visual_row,
@@ -378,24 +1391,481 @@ impl Editor {
     }
 
     pub fn apply<A: Action>(&mut self, mut action: A) {
+        self.sync_shared_code();
+        let (selection, cursor) = (self.selection, self.cursor);
+        if !action.supports_multiple_selections() {
+            self.secondary_selections.clear();
+        }
         action.apply(self);
+        if let Some(selection) = selection
+            && !self.selection.is_some_and(|s| s.is_active())
+        {
+            self.remember_selection(selection, cursor);
+        }
+        self.update_search();
+        self.reset_cursor_blink();
+        if self.checked {
+            self.debug_assert_invariants();
+        }
+    }
+
+    /// Turns on checked mode, in which invariants are asserted after every
+    /// action and mouse event, see [`Editor::debug_assert_invariants`].
+    /// Meant for tests and fuzzing; the checks only run in debug builds.
+    pub fn set_checked(&mut self, checked: bool) {
+        self.checked = checked;
+    }
+
+    /// Checks internal consistency in debug builds: the cursor, selections and
+    /// pending composition lie within the text, the primary selection is
+    /// sorted, the scroll offset is within the view, and the document's own
+    /// invariants hold (see [`Code::debug_assert_invariants`]).
+    ///
+    /// # Panics
+    ///
+    /// In debug builds, if an invariant does not hold.
+    pub fn debug_assert_invariants(&self) {
+        let code = self.code.borrow();
+        code.debug_assert_invariants();
+        // Positions are mapped lazily after edits made through another editor
+        if self.position_version != code.version() {
+            return;
+        }
+        let len = code.len_chars();
+        debug_assert!(
+            self.cursor <= len,
+            "cursor {} past the end {len}",
+            self.cursor
+        );
+        if let Some(selection) = self.selection {
+            debug_assert!(
+                selection.start <= selection.end && selection.end <= len,
+                "selection {selection:?} unsorted or past the end {len}"
+            );
+        }
+        for mark in &self.secondary_selections {
+            debug_assert!(
+                mark.start() <= mark.end() && mark.end() <= len,
+                "secondary selection {}..{} unsorted or past the end {len}",
+                mark.start(),
+                mark.end()
+            );
+        }
+        if let Some(preedit) = &self.preedit {
+            debug_assert!(preedit.position() <= len, "composition past the end {len}");
+        }
+        drop(code);
+        debug_assert!(
+            self.offset_y <= self.visual_len_lines(),
+            "scrolled to row {} past the view",
+            self.offset_y
+        );
+    }
+
+    /// Saves an active selection for [`Editor::reselect_last`].
+    pub(crate) fn remember_selection(&mut self, selection: Selection, cursor: usize) {
+        if !selection.is_active() {
+            return;
+        }
+        let mut code = self.code.borrow_mut();
+        let start = code.create_anchor(selection.start, AnchorBias::Left);
+        let end = code.create_anchor(selection.end, AnchorBias::Left);
+        drop(code);
+        self.selection_history.push(start, end, cursor == selection.start);
+    }
+
+    /// All selections, the primary one first. An empty selection stands for a
+    /// cursor without selected text.
+    pub fn selections(&self) -> Vec<Selection> {
+        let primary = self
+            .selection
+            .filter(|selection| selection.is_active())
+            .unwrap_or(Selection::new(self.cursor, self.cursor));
+        let secondary = self
+            .secondary_selections
+            .iter()
+            .map(|mark| Selection::new(mark.start(), mark.end()));
+        std::iter::once(primary).chain(secondary).collect()
+    }
+
+    pub fn has_secondary_selections(&self) -> bool {
+        !self.secondary_selections.is_empty()
+    }
+
+    /// Makes `selection` the primary selection, keeping the current one as a
+    /// secondary selection.
+    pub fn add_selection(&mut self, selection: Selection) {
+        let primary = self.selections()[0];
+        let mut code = self.code.borrow_mut();
+        let mark = Mark::new(&mut code, primary.start, primary.end, Style::default());
+        drop(code);
+        self.secondary_selections.push(mark);
+        self.selection = Some(selection);
+        self.cursor = selection.end;
+    }
+
+    /// Replaces the secondary selections, keeping the primary one.
+    pub fn set_secondary_selections(&mut self, selections: Vec<Selection>) {
+        let mut code = self.code.borrow_mut();
+        self.secondary_selections = selections
+            .into_iter()
+            .map(|s| Mark::new(&mut code, s.start, s.end, Style::default()))
+            .collect();
+    }
+
+    pub fn clear_secondary_selections(&mut self) {
+        self.secondary_selections.clear();
+    }
+
+    /// Inserts an LSP-style snippet at the cursor, replacing the selection, and
+    /// selects its first tabstop. Lines after the first get the indentation of
+    /// the cursor line. See [`Snippet::parse`] for the syntax.
+    pub fn insert_snippet(&mut self, snippet: &str) {
+        let mut code = self.code.borrow_mut();
+        let line = code.char_to_line(self.cursor.min(code.len_chars()));
+        let indent: String = code
+            .line(line)
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let snippet = Snippet::parse(&snippet.replace('\n', &format!("\n{indent}")));
+
+        let selection = self.selection.filter(|s| s.is_active());
+        let at = selection.map_or(self.cursor, |s| s.start);
+        code.tx();
+        code.set_state_before(self.cursor, self.selection);
+        if let Some(selection) = selection {
+            code.remove(selection.start, selection.end);
+        }
+        code.insert(at, &snippet.text);
+        let end = at + snippet.text.chars().count();
+        code.set_state_after(end, None);
+        code.commit();
+
+        let tabstops = snippet
+            .tabstops
+            .iter()
+            .map(|ranges| {
+                ranges
+                    .iter()
+                    .map(|&(start, end)| {
+                        (
+                            code.create_anchor(at + start, AnchorBias::Left),
+                            code.create_anchor(at + end, AnchorBias::Right),
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        drop(code);
+        self.reset_highlight_cache();
+        self.snippet = Some(SnippetSession {
+            tabstops,
+            current: 0,
+        });
+        self.select_tabstop(0);
+    }
+
+    /// Expands `trigger` into `snippet` when it is typed as a whole word and
+    /// followed by a space or Tab, in any language.
+    pub fn add_abbreviation(&mut self, trigger: &str, snippet: &str) {
+        self.abbreviations.insert((None, trigger.to_string()), snippet.to_string());
+    }
+
+    /// Like [`Editor::add_abbreviation`], only for documents in `lang`. Language
+    /// abbreviations take precedence over global ones.
+    pub fn add_language_abbreviation(&mut self, lang: &str, trigger: &str, snippet: &str) {
+        self.abbreviations.insert(
+            (Some(lang.to_string()), trigger.to_string()),
+            snippet.to_string(),
+        );
+    }
+
+    pub fn remove_abbreviation(&mut self, lang: Option<&str>, trigger: &str) {
+        self.abbreviations.remove(&(lang.map(str::to_string), trigger.to_string()));
+    }
+
+    /// Replaces the abbreviation trigger before the cursor with its snippet.
+    /// Returns false if there is no trigger, a selection or several cursors.
+    pub(crate) fn expand_abbreviation(&mut self) -> bool {
+        if self.abbreviations.is_empty()
+            || self.selection.is_some_and(|s| s.is_active())
+            || self.has_secondary_selections()
+        {
+            return false;
+        }
+        let code = self.code.borrow();
+        let end = self.cursor.min(code.len_chars());
+        let mut start = end;
+        while start > 0 {
+            let c = code.content.char(start - 1);
+            if !code.is_word_char(c) {
+                break;
+            }
+            start -= 1;
+        }
+        if start == end {
+            return false;
+        }
+        let trigger = code.slice(start, end);
+        let lang = Some(code.lang().to_string());
+        drop(code);
+        let Some(snippet) = self
+            .abbreviations
+            .get(&(lang, trigger.clone()))
+            .or_else(|| self.abbreviations.get(&(None, trigger)))
+            .cloned()
+        else {
+            return false;
+        };
+        self.selection = Some(Selection::new(start, end));
+        self.insert_snippet(&snippet);
+        true
+    }
+
+    /// Whether a snippet's tabstops are being visited with `NextTabstop`.
+    pub fn has_snippet(&self) -> bool {
+        self.snippet.is_some()
+    }
+
+    /// Moves to the next tabstop of the active snippet. Reaching the final
+    /// tabstop ends the snippet.
+    pub fn next_tabstop(&mut self) {
+        if let Some(session) = &self.snippet {
+            self.select_tabstop(session.current + 1);
+        }
+    }
+
+    pub fn prev_tabstop(&mut self) {
+        if let Some(session) = &self.snippet {
+            self.select_tabstop(session.current.saturating_sub(1));
+        }
+    }
+
+    pub fn cancel_snippet(&mut self) {
+        self.snippet = None;
+    }
+
+    /// Selects every occurrence of a tabstop, the first as primary selection.
+    fn select_tabstop(&mut self, index: usize) {
+        let Some(session) = &mut self.snippet else {
+            return;
+        };
+        let index = index.min(session.tabstops.len() - 1);
+        session.current = index;
+        let ranges = session.ranges(index);
+        if index == session.tabstops.len() - 1 {
+            self.snippet = None;
+        }
+
+        let (start, end) = ranges[0];
+        self.selection = (start != end).then(|| Selection::new(start, end));
+        self.cursor = end;
+        let others = ranges[1..]
+            .iter()
+            .map(|&(start, end)| Selection::new(start, end))
+            .collect();
+        self.set_secondary_selections(others);
+    }
+
+    /// Selects the word at the cursor if nothing is selected, otherwise adds
+    /// the next occurrence of the selected text after the primary selection,
+    /// wrapping around the end of the document.
+    pub fn select_next_occurrence(&mut self) {
+        let had_selection = self.selection.is_some_and(|s| s.is_active());
+        let Some(needle) = self.occurrence_needle() else {
+            return;
+        };
+        if !had_selection {
+            return;
+        }
+        let selections = self.selections();
+        let primary = selections[0];
+        let overlaps = |start: usize, end: usize| {
+            selections.iter().any(|s| start < s.end && s.start < end)
+        };
+        let matches = self.find_occurrences(&needle);
+        let next = matches
+            .iter()
+            .filter(|&&(start, _)| start >= primary.end)
+            .chain(matches.iter())
+            .find(|&&(start, end)| !overlaps(start, end));
+        if let Some(&(start, end)) = next {
+            self.add_selection(Selection::new(start, end));
+        }
+    }
+
+    /// Selects every occurrence of the selected text, or of the word at the
+    /// cursor. The primary selection stays where it is.
+    pub fn select_all_occurrences(&mut self) {
+        let Some(needle) = self.occurrence_needle() else {
+            return;
+        };
+        let primary = self.selections()[0];
+        let occurrences = self.find_occurrences(&needle);
+        let mut code = self.code.borrow_mut();
+        self.secondary_selections = occurrences
+            .into_iter()
+            .filter(|&(start, end)| end <= primary.start || start >= primary.end)
+            .map(|(start, end)| Mark::new(&mut code, start, end, Style::default()))
+            .collect();
+    }
+
+    /// Text to look for when selecting occurrences. Selects the word at the
+    /// cursor first if there is no selection.
+    fn occurrence_needle(&mut self) -> Option<String> {
+        if !self.selection.is_some_and(|s| s.is_active()) {
+            let (start, end) = self.code.borrow().word_boundaries(self.cursor);
+            if start == end {
+                return None;
+            }
+            self.selection = Some(Selection::new(start, end));
+            self.cursor = end;
+        }
+        self.get_selection_text()
+    }
+
+    /// Non-overlapping occurrences of `needle` as character ranges.
+    fn find_occurrences(&self, needle: &str) -> Vec<(usize, usize)> {
+        search::find_matches(&self.code.borrow(), needle, SearchOptions::default())
+    }
+
+    /// Replaces every selection in one undo step and leaves a cursor after each
+    /// replacement. `edit` maps a selection to the range to replace and the new
+    /// text; overlapping replacements are merged into the first one.
+    pub(crate) fn edit_selections(
+        &mut self,
+        edit: impl Fn(&Code, Selection) -> (usize, usize, String),
+    ) {
+        let mut selections: Vec<(Selection, bool)> = self
+            .selections()
+            .into_iter()
+            .enumerate()
+            .map(|(i, selection)| (selection, i == 0))
+            .collect();
+        selections.sort_by_key(|(selection, _)| selection.start);
+
+        let mut code = self.code.borrow_mut();
+        let mut edits: Vec<(usize, usize, String, bool)> = Vec::new();
+        for (selection, primary) in selections {
+            let (from, to, text) = edit(&code, selection);
+            match edits.last_mut() {
+                Some(last) if from < last.1 || (from == last.0 && to == last.1) => {
+                    last.3 |= primary;
+                }
+                _ => edits.push((from, to, text, primary)),
+            }
+        }
+
+        code.tx();
+        code.set_state_before(self.cursor, self.selection);
+        for (from, to, text, _) in edits.iter().rev() {
+            if to > from {
+                code.remove(*from, *to);
+            }
+            if !text.is_empty() {
+                code.insert(*from, text);
+            }
+        }
+
+        let mut delta = 0isize;
+        let mut cursor = self.cursor;
+        let mut secondary = Vec::new();
+        for (from, to, text, primary) in &edits {
+            let len = text.chars().count();
+            let pos = (*from as isize + delta) as usize + len;
+            delta += len as isize - (to - from) as isize;
+            if *primary {
+                cursor = pos;
+            } else {
+                secondary.push(pos);
+            }
+        }
+
+        code.set_state_after(cursor, None);
+        code.commit();
+        self.cursor = cursor;
+        self.selection = None;
+        self.secondary_selections = secondary
+            .into_iter()
+            .map(|pos| Mark::new(&mut code, pos, pos, Style::default()))
+            .collect();
+        drop(code);
+        self.reset_highlight_cache();
+    }
+
+    /// Runs `transform` on the text of each selection, or of the word at a
+    /// cursor without one, and replaces it with the result in one undo step.
+    pub fn transform_selection(&mut self, transform: impl FnMut(&str) -> String) {
+        self.apply(TransformSelection { transform });
+    }
+
+    /// Restores the most recently dropped selection. An active selection is
+    /// saved in its place, so calling this twice swaps back.
+    pub fn reselect_last(&mut self) {
+        let Some((selection, cursor)) = self.selection_history.pop() else {
+            return;
+        };
+        if let Some(current) = self.selection {
+            self.remember_selection(current, self.cursor);
+        }
+        self.selection = Some(selection);
+        self.cursor = cursor;
+    }
+
+    /// Runs a built-in action described by a [`Command`].
+    ///
+    /// Consecutive editing commands are remembered so `RepeatLastEdit` can
+    /// replay them; any other command ends the sequence.
+    pub fn execute(&mut self, command: Command) {
+        self.sync_shared_code();
+        if command.is_edit() {
+            if !self.recording_edit {
+                self.last_edit.clear();
+                self.recording_edit = true;
+            }
+            self.last_edit.push(command.clone());
+        } else {
+            self.recording_edit = false;
+        }
+        self.apply(command);
+    }
+
+    /// Replays the last sequence of editing commands at the current cursor.
+    pub fn repeat_last_edit(&mut self) {
+        self.recording_edit = false;
+        for mut command in self.last_edit.clone() {
+            command.apply(self);
+        }
+    }
+
+    /// The editing commands that `RepeatLastEdit` would replay.
+    pub fn last_edit(&self) -> &[Command] {
+        &self.last_edit
     }
 
     pub fn set_content(&mut self, content: &str) {
-        self.code.tx();
-        self.code.set_state_before(self.cursor, self.selection);
-        self.code.remove(0, self.code.len());
-        self.code.insert(0, content);
-        self.code.set_state_after(self.cursor, self.selection);
-        self.code.commit();
+        let content = LineEnding::normalize(content);
+        let mut code = self.code.borrow_mut();
+        code.tx();
+        code.set_change_origin(ChangeOrigin::Programmatic);
+        code.set_state_before(self.cursor, self.selection);
+        let len = code.len();
+        code.remove(0, len);
+        code.insert(0, &content);
+        code.set_state_after(self.cursor, self.selection);
+        code.commit();
+        drop(code);
         self.reset_highlight_cache();
     }
 
     pub fn set_original_code(&mut self, content: &str) -> Result<()> {
-        let original = Code::new(content, self.code_ref().lang(), None)
+        let content = &LineEnding::normalize(content);
+        let original = Code::new(content, self.borrow_code().lang(), None)
             .or_else(|_| Code::new(content, "text", None))?;
         self.highlights_cache.borrow_mut().clear();
         self.line_diff_cache.borrow_mut().clear();
+        self.frame_cache.borrow_mut().take();
         self.original_code = Some(original);
         self.rebuild_view();
         Ok(())
@@ -404,6 +1874,7 @@ impl Editor {
     pub fn clear_original_code(&mut self) {
         self.highlights_cache.borrow_mut().clear();
         self.line_diff_cache.borrow_mut().clear();
+        self.frame_cache.borrow_mut().take();
         self.original_code = None;
         self.rebuild_view();
         self.clamp_offset_y();
@@ -480,28 +1951,122 @@ impl Editor {
     }
 
     pub fn apply_batch(&mut self, batch: &EditBatch) {
-        self.code.tx();
+        let mut code = self.code.borrow_mut();
+        code.tx();
 
         if let Some(state) = &batch.state_before {
-            self.code.set_state_before(state.offset, state.selection);
+            code.set_state_before(state.offset, state.selection);
         }
         if let Some(state) = &batch.state_after {
-            self.code.set_state_after(state.offset, state.selection);
+            code.set_state_after(state.offset, state.selection);
         }
 
         for edit in &batch.edits {
             match edit.operation {
                 Operation::Insert => {
-                    self.code.insert(edit.start, &edit.text);
+                    code.insert(edit.start, &edit.text);
                 }
                 Operation::Remove => {
-                    self.code
-                        .remove(edit.start, edit.start + edit.text.chars().count());
+                    code.remove(edit.start, edit.start + edit.text.chars().count());
                 }
             }
         }
-        self.code.commit();
+        code.commit();
+        drop(code);
+        self.reset_highlight_cache();
+    }
+
+    /// Runs `f` to queue programmatic edits and applies them as one undo step.
+    ///
+    /// Offsets passed to the transaction refer to the document before any of the
+    /// queued edits; cursor and selection are remapped through the edits.
+    pub fn edit<F: FnOnce(&mut EditTransaction)>(&mut self, f: F) -> Result<()> {
+        let mut tx = EditTransaction::new();
+        f(&mut tx);
+        self.apply_transaction(&tx)
+    }
+
+    /// Replaces the characters in `range` with `text` as one undo step.
+    pub fn replace_range(&mut self, range: std::ops::Range<usize>, text: &str) -> Result<()> {
+        self.edit(|tx| tx.replace(range, text))
+    }
+
+    /// Applies a list of `(range, text)` replacements as one undo step.
+    pub fn apply_edits<I>(&mut self, edits: I) -> Result<()>
+    where
+        I: IntoIterator<Item = (std::ops::Range<usize>, String)>,
+    {
+        self.edit(|tx| {
+            for (range, text) in edits {
+                tx.replace(range, &text);
+            }
+        })
+    }
+
+    /// Applies row/column based edits (e.g. LSP formatting results) as one undo step.
+    ///
+    /// Positions refer to the document before any of the edits. Columns past the
+    /// end of a line are clamped to the line end, rows past the end to the document end.
+    pub fn apply_text_edits(&mut self, edits: Vec<TextEdit>) -> Result<()> {
+        let ranges: Vec<(std::ops::Range<usize>, String)> = edits
+            .into_iter()
+            .map(|edit| {
+                let start = self.offset_at(edit.start_row, edit.start_col);
+                let end = self.offset_at(edit.end_row, edit.end_col);
+                (start..end, edit.text)
+            })
+            .collect();
+        self.apply_edits(ranges)
+    }
+
+    /// Converts a (row, column) position into a character offset, clamping it to the document.
+    fn offset_at(&self, row: usize, col: usize) -> usize {
+        let code = self.code.borrow();
+        if row >= code.len_lines() {
+            return code.len_chars();
+        }
+        code.line_to_char(row) + col.min(code.line_len(row))
+    }
+
+    /// Applies a prepared [`EditTransaction`] as one undo step.
+    pub fn apply_transaction(&mut self, tx: &EditTransaction) -> Result<()> {
+        let edits = tx.sorted_edits(self.code.borrow().len_chars())?;
+        if edits.is_empty() {
+            return Ok(());
+        }
+
+        let cursor = tx.map_offset(self.cursor);
+        let selection = self.selection.map(|selection| {
+            Selection::new(tx.map_offset(selection.start), tx.map_offset(selection.end))
+        });
+
+        let mut code = self.code.borrow_mut();
+        code.tx();
+        code.set_change_origin(ChangeOrigin::Programmatic);
+        code.set_state_before(self.cursor, self.selection);
+        // apply from the end so earlier offsets stay valid
+        for (start, end, text) in edits.iter().rev() {
+            if end > start {
+                code.remove(*start, *end);
+            }
+            if !text.is_empty() {
+                code.insert(*start, text);
+            }
+        }
+        code.set_state_after(cursor, selection);
+        code.commit();
+        drop(code);
+
+        self.cursor = cursor;
+        self.selection = selection;
         self.reset_highlight_cache();
+        Ok(())
+    }
+
+    /// Creates an anchor at `offset` whose position stays correct as text is
+    /// inserted or removed, including through undo and redo.
+    pub fn create_anchor(&mut self, offset: usize, bias: AnchorBias) -> Anchor {
+        self.code.borrow_mut().create_anchor(offset, bias)
     }
 
     pub fn set_cursor(&mut self, cursor: usize) {
@@ -515,8 +2080,8 @@ impl Editor {
             return false;
         }
         let top_line = self.line_for_visual_row(self.offset_y);
-        let toggled = self.view.toggle_code_fold(
-            &self.code,
+        let toggled = self.view.borrow_mut().toggle_code_fold(
+            &self.code.borrow(),
             self.original_code.as_ref(),
             self.active_view_mode(),
             line_idx,
@@ -534,14 +2099,14 @@ impl Editor {
     }
 
     pub fn toggle_fold_at_cursor(&mut self) -> bool {
-        let line_idx = self.code.char_to_line(self.cursor);
+        let line_idx = self.code.borrow().char_to_line(self.cursor);
         self.toggle_fold_at_line(line_idx)
     }
 
     pub fn set_code_folding_enabled(&mut self, enabled: bool) {
         self.code_folding_options.enabled = enabled;
         if !enabled {
-            self.view.clear_code_folds();
+            self.view.borrow_mut().clear_code_folds();
         }
         self.rebuild_view();
     }
@@ -554,7 +2119,7 @@ impl Editor {
         let enabled = options.enabled;
         self.code_folding_options = options;
         if !enabled {
-            self.view.clear_code_folds();
+            self.view.borrow_mut().clear_code_folds();
         }
         self.rebuild_view();
     }
@@ -567,44 +2132,168 @@ impl Editor {
         if !self.code_folding_options.enabled {
             return 0;
         }
-        self.code_folding_options
-            .indicators
-            .expanded
-            .width()
-            .max(self.code_folding_options.indicators.collapsed.width())
-            + 1
+        let indicators = &self.code_folding_options.indicators;
+        str_width(&indicators.expanded).max(str_width(&indicators.collapsed)) + 1
     }
 
     pub(crate) fn code_fold_indicator(&self, line_idx: usize) -> Option<bool> {
         self.code_folding_options
             .enabled
-            .then(|| self.view.code_fold_indicator(&self.code, line_idx))
+            .then(|| {
+                self.view
+                    .borrow()
+                    .code_fold_indicator(&self.code.borrow(), line_idx)
+            })
             .flatten()
     }
 
     pub fn fit_cursor(&mut self) {
+        let code = self.code.borrow();
         // make sure cursor is not out of bounds
-        let len = self.code.len_chars();
+        let len = code.len_chars();
         self.cursor = self.cursor.min(len);
 
         // make sure cursor is not out of bounds on the line
-        let (row, col) = self.code.point(self.cursor);
-        if col > self.code.line_len(row) {
-            self.cursor = self.code.line_to_char(row) + self.code.line_len(row);
+        let (row, col) = code.point(self.cursor);
+        if col > code.line_len(row) {
+            self.cursor = code.line_to_char(row) + code.line_len(row);
         }
     }
 
     pub fn scroll_up(&mut self) {
+        self.scroll_animation = None;
+        self.tail_attached = false;
         if self.offset_y > 0 {
             self.offset_y -= 1;
         }
     }
 
     pub fn scroll_down(&mut self, area_height: usize) {
+        self.scroll_animation = None;
         let len_lines = self.visual_len_lines();
         if self.offset_y < len_lines.saturating_sub(area_height) {
             self.offset_y += 1;
         }
+        self.tail_attached = self.offset_y >= len_lines.saturating_sub(area_height);
+    }
+
+    /// Scrolls the view by `lines` rows, up if negative, like Vim's `CTRL-E`
+    /// and `CTRL-Y`. The cursor stays where it is, unless `keep_cursor` is set
+    /// and it left the view or its `scrolloff` margin: then it moves to the
+    /// nearest line still in view. Uses the last rendered area.
+    pub fn scroll_view(&mut self, lines: isize, keep_cursor: bool) {
+        let height = self.last_area.get().height as usize;
+        let max = self.visual_len_lines() as isize;
+        self.scroll_by(lines.clamp(-max, max), height);
+        if keep_cursor {
+            self.keep_cursor_in_view(height);
+        }
+    }
+
+    /// Scrolls half a page down, or up if `down` is false, and moves the
+    /// cursor by as many lines, like Vim's `CTRL-D` and `CTRL-U`.
+    pub fn scroll_half_page(&mut self, down: bool) {
+        let height = self.last_area.get().height as usize;
+        let lines = (height / 2).max(1);
+        let lines = lines as isize;
+        self.scroll_by(if down { lines } else { -lines }, height);
+
+        let (row, col) = self.code.borrow().point(self.cursor);
+        let visual_col = self.code.borrow().char_col_to_visual(row, col);
+        let mut line = row;
+        for _ in 0..lines {
+            let next = if down {
+                self.next_line(line)
+            } else {
+                self.prev_line(line)
+            };
+            match next {
+                Some(next) => line = next,
+                None => break,
+            }
+        }
+        if line != row {
+            self.move_cursor_to_line(line, visual_col);
+        }
+        self.keep_cursor_in_view(height);
+    }
+
+    /// Moves the cursor to the nearest line in view, outside the `scrolloff`
+    /// margin, so that `focus` leaves the view where it is.
+    fn keep_cursor_in_view(&mut self, height: usize) {
+        let (row, col) = self.code.borrow().point(self.cursor);
+        let visual_line = self.visual_line_idx(row);
+        if height == 0 || visual_line == usize::MAX {
+            return;
+        }
+        let margin = self.scrolloff.min(height.saturating_sub(1) / 2);
+        let max_offset = self.visual_len_lines().saturating_sub(height);
+        let top = if self.offset_y == 0 {
+            0
+        } else {
+            self.offset_y + margin
+        };
+        let bottom = if self.offset_y >= max_offset {
+            self.offset_y + height - 1
+        } else {
+            self.offset_y + height - 1 - margin
+        };
+        let target = visual_line.clamp(top, bottom.max(top));
+        if target == visual_line {
+            return;
+        }
+        // rows without a line of their own, like deleted lines in a diff, are skipped
+        let line = if target > visual_line {
+            (target..=bottom).find_map(|row| self.line_for_visual_row(row))
+        } else {
+            (top..=target)
+                .rev()
+                .find_map(|row| self.line_for_visual_row(row))
+        };
+        if let Some(line) = line {
+            let visual_col = self.code.borrow().char_col_to_visual(row, col);
+            self.move_cursor_to_line(line, visual_col);
+        }
+    }
+
+    fn move_cursor_to_line(&mut self, line: usize, visual_col: usize) {
+        let code = self.code.borrow();
+        let col = code
+            .visual_to_char_col(line, visual_col)
+            .min(code.line_len(line));
+        let cursor = code.line_to_char(line) + col;
+        drop(code);
+        self.clear_selection();
+        self.set_cursor(cursor);
+    }
+
+    /// Scrolls left by up to `columns` characters.
+    pub fn scroll_left(&mut self, columns: usize) {
+        self.offset_x = self.offset_x.saturating_sub(columns);
+    }
+
+    /// Scrolls right by up to `columns` characters, stopping once the longest
+    /// visible line fits into the text area.
+    pub fn scroll_right(&mut self, columns: usize, area: &Rect) {
+        let text_width = self.layout(*area).text_area.width as usize;
+        let longest = (self.offset_y..self.offset_y + area.height as usize)
+            .filter_map(|visual_row| self.line_for_visual_row(visual_row))
+            .filter(|&line_idx| line_idx < self.code.borrow().len_lines())
+            .map(|line_idx| self.code.borrow().line_len(line_idx))
+            .max()
+            .unwrap_or(0);
+        let max_offset = longest.saturating_sub(text_width);
+        self.offset_x = (self.offset_x + columns).min(max_offset.max(self.offset_x));
+    }
+
+    /// Sets how many lines (or columns, for horizontal scrolling) one mouse
+    /// wheel tick scrolls. Defaults to 3.
+    pub fn set_scroll_lines_per_tick(&mut self, lines: usize) {
+        self.scroll_lines_per_tick = lines.max(1);
+    }
+
+    pub fn scroll_lines_per_tick(&self) -> usize {
+        self.scroll_lines_per_tick
     }
 
     pub fn build_theme(theme: &Vec<(&str, &str)>) -> Theme {
@@ -618,7 +2307,8 @@ impl Editor {
                     | "diff_added_word"
                     | "diff_deleted"
                     | "diff_deleted_word"
-                    | "word_highlight" => Style::default().bg(color),
+                    | "word_highlight"
+                    | "flash" => Style::default().bg(color),
                     _ => Style::default().fg(color),
                 };
                 (name.to_string(), style)
@@ -630,22 +2320,134 @@ impl Editor {
         self.theme.get(key).cloned().unwrap_or_default()
     }
 
+    /// Returns the content using the editor's [`LineEnding`].
     pub fn get_content(&self) -> String {
-        self.code.get_content()
+        self.line_ending.apply(&self.code.borrow().get_content())
+    }
+
+    /// Line ending detected when the content was loaded, used by `get_content`.
+    pub fn line_ending(&self) -> LineEnding {
+        self.line_ending
+    }
+
+    pub fn set_line_ending(&mut self, line_ending: LineEnding) {
+        self.line_ending = line_ending;
     }
 
     pub fn get_content_slice(&self, start: usize, end: usize) -> String {
-        self.code.slice(start, end)
+        self.code.borrow().slice(start, end)
     }
 
     pub fn get_cursor(&self) -> usize {
         self.cursor
     }
 
+    /// Syntax node kinds and highlight captures at `offset`, outermost first,
+    /// e.g. to debug a theme or to check whether the cursor is in a string.
+    /// See [`Code::scope_at`].
+    pub fn scope_at(&self, offset: usize) -> Vec<String> {
+        self.code.borrow().scope_at(offset)
+    }
+
+    /// Named declarations around the cursor, outermost first (module, impl,
+    /// function), for a breadcrumbs bar. See [`Code::breadcrumbs`].
+    pub fn breadcrumbs(&self) -> Vec<Symbol> {
+        self.code.borrow().breadcrumbs(self.cursor)
+    }
+
+    /// Cursor position as (row, column in characters), to send with a
+    /// go-to-definition or find-references request.
+    pub fn definition_request_position(&self) -> (usize, usize) {
+        let code = self.code.borrow();
+        code.point(self.cursor.min(code.len_chars()))
+    }
+
+    /// Moves the cursor to a location returned by a language server, given as
+    /// (row, column in characters) and clamped to the document. The selection
+    /// is dropped; call [`Editor::focus`] to scroll it into view.
+    pub fn apply_location(&mut self, row: usize, col: usize) {
+        self.sync_shared_code();
+        if let Some(selection) = self.selection.take() {
+            self.remember_selection(selection, self.cursor);
+        }
+        self.cursor = self.offset_at(row, col);
+    }
+
+    /// Reports a [`EditorEvent::DefinitionRequested`] for `offset`, as
+    /// Ctrl+Click does.
+    pub fn request_definition(&mut self, offset: usize) {
+        let code = self.code.borrow();
+        let offset = offset.min(code.len_chars());
+        let (row, col) = code.point(offset);
+        drop(code);
+        self.push_event(EditorEvent::DefinitionRequested { offset, row, col });
+    }
+
+    /// Handles a right click at `offset`: without a selection the word
+    /// there is selected, or the cursor moved there, then
+    /// [`EditorEvent::ContextMenuRequested`] is reported.
+    pub fn handle_context_click(&mut self, offset: usize, screen_pos: (u16, u16)) {
+        let code = self.code.borrow();
+        let offset = offset.min(code.len_chars());
+        let (word_start, word_end) = code.word_boundaries(offset);
+        drop(code);
+        if self.selection.is_none_or(|selection| selection.is_empty()) {
+            self.selection = (word_start < word_end)
+                .then(|| Selection::from_anchor_and_cursor(word_start, word_end));
+            self.cursor = word_end;
+            self.reset_cursor_blink();
+        }
+        self.push_event(EditorEvent::ContextMenuRequested { offset, screen_pos });
+    }
+
+    /// Sets how long the mouse pointer has to rest on a character before
+    /// [`EditorEvent::HoverRequested`] is reported by [`Editor::tick`], or
+    /// disables hovers with `None`. Defaults to 500ms.
+    pub fn set_hover_delay(&mut self, delay: Option<Duration>) {
+        self.hover_delay = delay;
+        if delay.is_none() {
+            self.end_hover();
+        }
+    }
+
+    /// Tracks the mouse pointer at a screen cell for hover events. Moving off
+    /// the text or to another character restarts the hover delay.
+    pub fn handle_mouse_move_at(&mut self, mouse_x: u16, mouse_y: u16, area: &Rect, now: Instant) {
+        if self.hover_delay.is_none() {
+            return;
+        }
+        let offset = self
+            .cursor_from_mouse(mouse_x, mouse_y, area)
+            .filter(|&offset| {
+                let code = self.code.borrow();
+                let (row, col) = code.point(offset);
+                col < code.line_len(row)
+            });
+        if offset.is_some() && offset == self.hover.map(|hover| hover.offset) {
+            return;
+        }
+        self.end_hover();
+        self.hover = offset.map(|offset| Hover::new(offset, (mouse_x, mouse_y), now));
+    }
+
+    /// Forgets the pointer position, reporting [`EditorEvent::HoverEnded`] if
+    /// a hover was reported for it.
+    pub fn end_hover(&mut self) {
+        if self.hover.take().is_some_and(|hover| hover.reported) {
+            self.push_event(EditorEvent::HoverEnded);
+        }
+    }
+
+    /// Sets whether Ctrl+Click reports [`EditorEvent::DefinitionRequested`]
+    /// instead of moving the cursor. Enabled by default.
+    pub fn set_definition_on_ctrl_click(&mut self, enabled: bool) {
+        self.definition_on_ctrl_click = enabled;
+    }
+
     pub fn set_clipboard(&mut self, text: &str) -> Result<()> {
         arboard::Clipboard::new()
             .and_then(|mut c| c.set_text(text.to_string()))
-            .unwrap_or_else(|_| self.clipboard = Some(text.to_string()));
+            .unwrap_or_else(|_| *self.clipboard.borrow_mut() = Some(text.to_string()));
         Ok(())
     }
 
@@ -653,39 +2455,317 @@ impl Editor {
         arboard::Clipboard::new()
             .and_then(|mut c| c.get_text())
             .ok()
-            .or_else(|| self.clipboard.clone())
+            .or_else(|| self.clipboard.borrow().clone())
             .ok_or_else(|| anyhow!("cant get clipboard"))
     }
 
+    /// Sets whether mouse selections are copied to the primary selection and
+    /// middle click pastes it, as is usual on Linux. Disabled by default.
+    pub fn set_primary_selection_enabled(&mut self, enabled: bool) {
+        self.primary_selection = enabled;
+    }
+
+    pub fn primary_selection_enabled(&self) -> bool {
+        self.primary_selection
+    }
+
+    /// Sets the primary selection, falling back to editor-local storage
+    /// where the system has none.
+    pub fn set_primary_selection(&mut self, text: &str) -> Result<()> {
+        #[cfg(all(
+            unix,
+            not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+        ))]
+        {
+            use arboard::{LinuxClipboardKind, SetExtLinux};
+            let set = arboard::Clipboard::new().and_then(|mut c| {
+                c.set()
+                    .clipboard(LinuxClipboardKind::Primary)
+                    .text(text.to_string())
+            });
+            if set.is_ok() {
+                return Ok(());
+            }
+        }
+        self.primary_clipboard = Some(text.to_string());
+        Ok(())
+    }
+
+    pub fn get_primary_selection(&self) -> Result<String> {
+        #[cfg(all(
+            unix,
+            not(any(target_os = "macos", target_os = "android", target_os = "emscripten"))
+        ))]
+        {
+            use arboard::{GetExtLinux, LinuxClipboardKind};
+            let text = arboard::Clipboard::new()
+                .and_then(|mut c| c.get().clipboard(LinuxClipboardKind::Primary).text());
+            if let Ok(text) = text {
+                return Ok(text);
+            }
+        }
+        self.primary_clipboard
+            .clone()
+            .ok_or_else(|| anyhow!("cant get primary selection"))
+    }
+
+    /// Pastes the primary selection at `offset`, as middle click does.
+    pub fn paste_primary_at(&mut self, offset: usize) {
+        self.sync_shared_code();
+        self.selection = None;
+        self.set_cursor(offset);
+        self.apply(PastePrimary);
+    }
+
+    /// Sets whether the `Paste` action re-indents text or inserts it unchanged.
+    pub fn set_paste_mode(&mut self, mode: PasteMode) {
+        self.paste_mode = mode;
+    }
+
+    pub fn paste_mode(&self) -> PasteMode {
+        self.paste_mode
+    }
+
+    /// Makes `Copy` and `Cut` without a selection take the cursor line. Such
+    /// text is pasted as whole lines above the cursor line.
+    pub fn set_line_wise_copy(&mut self, enabled: bool) {
+        self.line_wise_copy = enabled;
+    }
+
+    pub fn line_wise_copy(&self) -> bool {
+        self.line_wise_copy
+    }
+
+    /// Makes Enter continue list items such as `- `, `1. ` and `- [ ] `, and
+    /// end the list on an empty item. Enabled by default for Markdown.
+    pub fn set_list_continuation(&mut self, enabled: bool) {
+        self.list_continuation = enabled;
+    }
+
+    pub fn list_continuation(&self) -> bool {
+        self.list_continuation
+    }
+
+    /// Opens a fuzzy picker over the lines or symbols of the buffer, drawn on
+    /// top of the editor. While it is open, [`Editor::input`] edits its query.
+    pub fn open_picker(&mut self, source: PickerSource) {
+        self.picker = Some(Picker::new(&self.code.borrow(), source));
+        self.needs_redraw.set(true);
+    }
+
+    pub fn close_picker(&mut self) {
+        if self.picker.take().is_some() {
+            self.needs_redraw.set(true);
+        }
+    }
+
+    pub fn picker(&self) -> Option<&Picker> {
+        self.picker.as_ref()
+    }
+
+    pub fn picker_mut(&mut self) -> Option<&mut Picker> {
+        self.picker.as_mut()
+    }
+
+    /// Closes the picker and moves the cursor to its selected item.
+    /// Returns false if nothing was selected.
+    pub fn accept_picker(&mut self) -> bool {
+        let Some(picker) = self.picker.take() else {
+            return false;
+        };
+        self.needs_redraw.set(true);
+        let Some(item) = picker.selected_item() else {
+            return false;
+        };
+        if let Some(selection) = self.selection.take() {
+            self.remember_selection(selection, self.cursor);
+        }
+        self.set_cursor(item.offset);
+        true
+    }
+
+    /// Shows `text` as the pending IME composition at the cursor, with the
+    /// terminal cursor `cursor` chars into it. The document is unchanged until
+    /// [`Editor::commit_preedit`]; an empty `text` cancels the composition.
+    pub fn set_preedit(&mut self, text: &str, cursor: usize) {
+        self.sync_shared_code();
+        if text.is_empty() {
+            self.cancel_preedit();
+            return;
+        }
+        match &mut self.preedit {
+            Some(preedit) => preedit.update(text, cursor),
+            None => {
+                let anchor = self.create_anchor(self.cursor, AnchorBias::Right);
+                self.preedit = Some(Preedit::new(text, cursor, anchor));
+            }
+        }
+        self.needs_redraw.set(true);
+    }
+
+    pub fn preedit(&self) -> Option<&Preedit> {
+        self.preedit.as_ref()
+    }
+
+    /// Inserts the pending composition at its position as one undoable edit,
+    /// replacing the selection if any. Returns false if nothing was composed.
+    pub fn commit_preedit(&mut self) -> bool {
+        self.sync_shared_code();
+        let Some(preedit) = self.preedit.take() else {
+            return false;
+        };
+        // The anchor followed edits made while composing; the cursor may not have
+        if preedit.position() != self.cursor {
+            self.selection = None;
+            self.cursor = preedit.position();
+        }
+        self.apply(InsertText {
+            text: preedit.text().to_string(),
+        });
+        true
+    }
+
+    /// Drops the pending composition without touching the document.
+    pub fn cancel_preedit(&mut self) {
+        if self.preedit.take().is_some() {
+            self.needs_redraw.set(true);
+        }
+    }
+
+    /// Overrides the language settings (indent, comment, brackets, strings)
+    /// of this editor's buffer. List continuation follows the new config.
+    pub fn set_language_config(&mut self, config: LanguageConfig) {
+        self.list_continuation = config.list_continuation;
+        self.code.borrow_mut().set_language_config(config);
+    }
+
+    /// Replaces all marks of the default layer. Mark ranges are anchored and shift
+    /// with later edits.
     pub fn set_marks(&mut self, marks: Vec<(usize, usize, &str)>) {
-        self.marks = Some(
-            marks
-                .into_iter()
-                .map(|(start, end, color)| {
-                    let (r, g, b) = utils::rgb(color);
-                    (start, end, Color::Rgb(r, g, b))
-                })
-                .collect(),
-        );
+        self.marks.get_or_insert(DEFAULT_MARK_LAYER).marks.clear();
+        self.marks.as_set = None;
+        self.update_marks(marks);
+    }
+
+    /// Adds marks to the default layer, replacing existing marks with the same range.
+    pub fn update_marks(&mut self, marks: Vec<(usize, usize, &str)>) {
+        let marks: Vec<(usize, usize, Color)> = marks
+            .into_iter()
+            .map(|(start, end, color)| {
+                let (r, g, b) = utils::rgb(color);
+                (start, end, Color::Rgb(r, g, b))
+            })
+            .collect();
+        let as_set = self.marks.as_set.get_or_insert_with(Vec::new);
+        for &(start, end, color) in &marks {
+            as_set.retain(|&(s, e, _)| (s, e) != (start, end));
+            as_set.push((start, end, color));
+        }
+        let marks: Vec<Mark> = marks
+            .into_iter()
+            .map(|(start, end, color)| {
+                let style = Style::default().bg(color);
+                Mark::new(&mut self.code.borrow_mut(), start, end, style)
+            })
+            .collect();
+        let layer = self.marks.get_or_insert(DEFAULT_MARK_LAYER);
+        for mark in marks {
+            layer
+                .marks
+                .retain(|m| (m.start(), m.end()) != (mark.start(), mark.end()));
+            layer.marks.push(mark);
+        }
     }
 
     pub fn remove_marks(&mut self) {
-        self.marks = None;
+        self.marks.remove(DEFAULT_MARK_LAYER);
     }
 
     pub fn has_marks(&self) -> bool {
-        self.marks.is_some()
+        self.marks.get(DEFAULT_MARK_LAYER).is_some()
     }
 
+    /// Returns the default layer marks as passed to [`Editor::set_marks`] and
+    /// [`Editor::update_marks`], without following later edits.
+    #[deprecated(note = "use `Editor::marks`, whose ranges follow edits")]
     pub fn get_marks(&self) -> Option<&Vec<(usize, usize, Color)>> {
-        self.marks.as_ref()
+        self.marks.as_set.as_ref()
+    }
+
+    /// Returns the default layer mark ranges as `(start, end, color)`.
+    pub fn marks(&self) -> Option<Vec<(usize, usize, Color)>> {
+        self.marks
+            .get(DEFAULT_MARK_LAYER)
+            .map(|layer| layer.marks.iter().map(Mark::as_color_tuple).collect())
+    }
+
+    /// Returns the default layer mark ranges as `(start, end, style)`.
+    pub fn mark_styles(&self) -> Option<Vec<(usize, usize, Style)>> {
+        self.get_mark_layer(DEFAULT_MARK_LAYER)
+    }
+
+    /// Returns the marks of all layers covering the character at `offset`,
+    /// lowest priority first, with their background color.
+    pub fn marks_at(&self, offset: usize) -> Vec<(usize, usize, Color)> {
+        self.marks
+            .iter()
+            .filter(|mark| mark.contains(offset))
+            .map(Mark::as_color_tuple)
+            .collect()
+    }
+
+    /// Like [`Editor::marks_at`], with the full style of each mark.
+    pub fn mark_styles_at(&self, offset: usize) -> Vec<(usize, usize, Style)> {
+        self.marks
+            .iter()
+            .filter(|mark| mark.contains(offset))
+            .map(Mark::as_tuple)
+            .collect()
+    }
+
+    /// Replaces the marks of a named layer, e.g. `"search"` or `"diagnostics"`.
+    /// New layers start with priority 0.
+    ///
+    /// The style is patched over the code, so `Style::default().underlined()`
+    /// underlines the ranges without hiding syntax colors. An underline color is
+    /// used when ratatui's `underline-color` feature is enabled.
+    pub fn set_mark_layer(&mut self, name: &str, ranges: Vec<(usize, usize)>, style: Style) {
+        let marks = ranges
+            .into_iter()
+            .map(|(start, end)| Mark::new(&mut self.code.borrow_mut(), start, end, style))
+            .collect();
+        self.marks.get_or_insert(name).marks = marks;
+    }
+
+    /// Sets the paint priority of a layer. Higher priorities are drawn on top.
+    pub fn set_mark_layer_priority(&mut self, name: &str, priority: i32) {
+        self.marks.set_priority(name, priority);
+    }
+
+    pub fn get_mark_layer(&self, name: &str) -> Option<Vec<(usize, usize, Style)>> {
+        self.marks
+            .get(name)
+            .map(|layer| layer.marks.iter().map(Mark::as_tuple).collect())
+    }
+
+    /// Names of all mark layers, lowest priority first.
+    pub fn mark_layers(&self) -> Vec<String> {
+        self.marks.names().map(str::to_string).collect()
+    }
+
+    pub fn remove_mark_layer(&mut self, name: &str) {
+        self.marks.remove(name);
+    }
+
+    pub fn clear_mark_layers(&mut self) {
+        self.marks.clear();
     }
 
     pub fn get_selection_text(&mut self) -> Option<String> {
         if let Some(selection) = &self.selection
             && !selection.is_empty()
         {
-            let text = self.code.slice(selection.start, selection.end);
+            let text = self.code.borrow().slice(selection.start, selection.end);
             return Some(text);
         }
         None
@@ -700,6 +2780,8 @@ impl Editor {
     }
 
     pub fn set_offset_y(&mut self, offset_y: usize) {
+        self.scroll_animation = None;
+        self.tail_attached = false;
         self.offset_y = offset_y.min(self.visual_len_lines().saturating_sub(1));
     }
 
@@ -711,24 +2793,41 @@ impl Editor {
         self.offset_y
     }
 
+    /// Scroll position and focus the editor draws itself with, e.g. as the
+    /// starting point of a view for [`StatefulWidget`] rendering.
+    ///
+    /// [`StatefulWidget`]: ratatui_core::widgets::StatefulWidget
+    pub fn view_state(&self) -> EditorView {
+        EditorView {
+            offset_x: self.offset_x,
+            offset_y: self.offset_y,
+            area: self.last_area.get(),
+            focused: self.focused,
+        }
+    }
+
     pub fn get_offset_x(&self) -> usize {
         self.offset_x
     }
 
     pub(crate) fn visual_len_lines(&self) -> usize {
         self.view
-            .visual_len_lines(&self.code, self.active_view_mode())
+            .borrow()
+            .visual_len_lines(&self.code.borrow(), self.active_view_mode())
     }
 
     pub(crate) fn line_for_visual_row(&self, visual_row: usize) -> Option<usize> {
-        self.view
-            .line_for_visual_row(&self.code, self.active_view_mode(), visual_row)
+        self.view.borrow().line_for_visual_row(
+            &self.code.borrow(),
+            self.active_view_mode(),
+            visual_row,
+        )
     }
 
     pub(crate) fn visual_row(&self, visual_row: usize) -> Option<VisualRow> {
-        if self.has_diff() || !self.view.rows().is_empty() {
-            self.view.rows().get(visual_row).cloned()
-        } else if visual_row < self.code.len_lines() {
+        if self.has_diff() || !self.view.borrow().rows().is_empty() {
+            self.view.borrow().rows().get(visual_row).cloned()
+        } else if visual_row < self.code.borrow().len_lines() {
             Some(VisualRow::Real {
                 line_idx: visual_row,
                 is_added: false,
@@ -741,16 +2840,45 @@ impl Editor {
 
     pub(crate) fn visual_line_idx(&self, line_idx: usize) -> usize {
         self.view
+            .borrow()
             .visual_row_for_line(self.active_view_mode(), line_idx)
             .unwrap_or(usize::MAX)
     }
 
+    /// The document of an editor that does not share it. Panics once it is
+    /// shared, see [`Editor::borrow_code_mut`].
+    #[deprecated(note = "use `Editor::borrow_code_mut`, which also reaches a shared document")]
     pub fn code_mut(&mut self) -> &mut Code {
-        &mut self.code
+        self.code
+            .owned_mut()
+            .expect("the document is shared, use `Editor::borrow_code_mut`")
     }
 
+    /// The document of an editor that does not share it. Panics once it is
+    /// shared, see [`Editor::borrow_code`].
+    #[deprecated(note = "use `Editor::borrow_code`, which also reaches a shared document")]
     pub fn code_ref(&self) -> &Code {
-        &self.code
+        self.code
+            .owned()
+            .expect("the document is shared, use `Editor::borrow_code`")
+    }
+
+    /// The document, also while it is shared with other editors. Like a
+    /// [`RefCell`] borrow, it must be dropped before the editor edits again.
+    pub fn borrow_code(&self) -> impl Deref<Target = Code> + '_ {
+        self.code.borrow()
+    }
+
+    /// Like [`Editor::borrow_code`] with write access.
+    pub fn borrow_code_mut(&mut self) -> impl DerefMut<Target = Code> + '_ {
+        self.code.borrow_mut()
+    }
+
+    /// The document behind this editor, to open it in another view with
+    /// [`Editor::with_shared_code`]. From then on the editor shares it, so
+    /// [`Editor::borrow_code`] replaces [`Editor::code_ref`].
+    pub fn shared_code(&mut self) -> Rc<RefCell<Code>> {
+        self.code.share()
     }
 
     /// Set the change callback function for handling document changes
@@ -758,7 +2886,22 @@ impl Editor {
         &mut self,
         callback: Box<dyn Fn(Vec<(usize, usize, usize, usize, String)>)>,
     ) {
-        self.code.set_change_callback(callback);
+        self.code.borrow_mut().set_change_callback(callback);
+    }
+
+    /// Set a callback receiving versioned change notifications with their origin
+    pub fn set_change_listener(&mut self, listener: ChangeListener) {
+        self.code.borrow_mut().set_change_listener(listener);
+    }
+
+    /// Choose between incremental and full-text change notifications
+    pub fn set_change_sync(&mut self, sync: ChangeSync) {
+        self.code.borrow_mut().set_change_sync(sync);
+    }
+
+    /// Document version, incremented once per change notification
+    pub fn version(&self) -> u64 {
+        self.code.borrow().version()
     }
 
     pub fn highlight_interval(
@@ -773,7 +2916,7 @@ impl Editor {
             return v.clone();
         }
 
-        let highlights = self.code.highlight_interval(start, end, theme);
+        let highlights = self.code.borrow().highlight_interval(start, end, theme);
         cache.insert(key, highlights.clone());
         highlights
     }
@@ -798,6 +2941,60 @@ impl Editor {
         highlights
     }
 
+    /// Highlights lines `start_line..end_line` and returns the highlights
+    /// grouped per line. With the editor's own theme, lines are cached until
+    /// the next edit and only lines missing from the cache are queried, in one
+    /// query; any other theme queries all lines without the cache.
+    pub fn highlight_lines(
+        &self,
+        start_line: usize,
+        end_line: usize,
+        theme: &Theme,
+    ) -> Vec<Vec<(usize, usize, Style)>> {
+        if !std::ptr::eq(theme, &self.theme) && *theme != self.theme {
+            return self.query_highlight_lines(start_line, end_line, theme);
+        }
+        // Only lines missing from the cache are queried, e.g. the line
+        // scrolled into view or lines not prefetched yet
+        let mut cache = self.line_highlights_cache.borrow_mut();
+        let mut missing = (start_line..end_line).filter(|line| !cache.contains_key(line));
+        if let Some(first) = missing.next() {
+            let last = missing.next_back().unwrap_or(first);
+            let queried = self.query_highlight_lines(first, last + 1, theme);
+            cache.extend((first..).zip(queried));
+        }
+        (start_line..end_line)
+            .map(|line| cache.get(&line).cloned().unwrap_or_default())
+            .collect()
+    }
+
+    pub(crate) fn query_highlight_lines(
+        &self,
+        start_line: usize,
+        end_line: usize,
+        theme: &Theme,
+    ) -> Vec<Vec<Hightlight>> {
+        let code = self.code.borrow();
+        let (start, end) = code.lines_byte_range(start_line, end_line);
+        let highlights = code.highlight_interval(start, end, theme);
+        code.group_highlights_by_line(&highlights, start_line, end_line)
+    }
+
+    /// Same as [`Editor::highlight_lines`] but for the original code used by diff views.
+    pub fn highlight_lines_original(
+        &self,
+        start_line: usize,
+        end_line: usize,
+        theme: &Theme,
+    ) -> Vec<Vec<(usize, usize, Style)>> {
+        let Some(original) = &self.original_code else {
+            return Vec::new();
+        };
+        let (start, end) = original.lines_byte_range(start_line, end_line);
+        let highlights = self.highlight_interval_original(start, end, theme);
+        original.group_highlights_by_line(&highlights, start_line, end_line)
+    }
+
     pub fn word_highlight_ranges(&self) -> Vec<(usize, usize)> {
         if !self.word_highlight_enabled {
             return Vec::new();
@@ -810,19 +3007,22 @@ impl Editor {
             }
         }
 
-        let (start, end) = self.code.word_boundaries(self.cursor);
+        let code = self.code.borrow();
+        // the cursor can be past the end after an edit through a shared editor
+        let cursor = self.cursor.min(code.len_chars());
+        let (start, end) = code.word_boundaries(cursor);
         if start == end {
             *cache = Some((self.cursor, Vec::new()));
             return Vec::new();
         }
 
-        let word = self.code.slice(start, end);
+        let word = code.slice(start, end);
         if word.is_empty() {
             *cache = Some((self.cursor, Vec::new()));
             return Vec::new();
         }
 
-        let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+        let is_word_char = |c: char| code.is_word_char(c);
         if !word.chars().next().map_or(false, is_word_char) {
             *cache = Some((self.cursor, Vec::new()));
             return Vec::new();
@@ -831,7 +3031,7 @@ impl Editor {
         let mut ranges = Vec::new();
         let word_chars: Vec<char> = word.chars().collect();
         let word_len = word_chars.len();
-        let content = &self.code.content;
+        let content = &code.content;
         let total_chars = content.len_chars();
 
         if total_chars >= word_len {
@@ -874,12 +3074,52 @@ impl Editor {
     }
 
     pub fn reset_highlight_cache(&mut self) {
+        let version = self.code.borrow().version();
+        self.code_version.set(version);
+        self.position_version = version;
+        self.clear_caches();
+    }
+
+    pub(crate) fn clear_caches(&self) {
         self.highlights_cache.borrow_mut().clear();
+        self.line_highlights_cache.borrow_mut().clear();
         self.line_diff_cache.borrow_mut().clear();
         self.word_highlight_cache.borrow_mut().take();
+        self.frame_cache.borrow_mut().take();
         self.rebuild_view();
     }
 
+    /// Clears caches built for an older version of the document, i.e. before
+    /// an edit made through another editor sharing it.
+    pub(crate) fn refresh_stale_caches(&self) {
+        let version = self.code.borrow().version();
+        if self.code_version.replace(version) != version {
+            self.clear_caches();
+        }
+    }
+
+    /// Catches up with edits made through other editors sharing the document:
+    /// the cursor and selection follow the edits and caches are rebuilt.
+    ///
+    /// Input handling and actions call this first; hosts only need it before
+    /// querying positions of an editor that has not handled input since.
+    pub fn sync_shared_code(&mut self) {
+        self.refresh_stale_caches();
+        self.update_search();
+        let code = self.code.borrow();
+        let since = std::mem::replace(&mut self.position_version, code.version());
+        if since == self.position_version || !self.code.is_shared() {
+            return;
+        }
+        let map = |offset: usize| code.map_offset_since(since, offset).unwrap_or(offset);
+        self.cursor = map(self.cursor);
+        self.selection = self
+            .selection
+            .map(|selection| Selection::new(map(selection.start), map(selection.end)));
+        drop(code);
+        self.fit_cursor();
+    }
+
     pub fn get_line_diff(
         &self,
         orig_idx: usize,
@@ -894,7 +3134,7 @@ impl Editor {
         let key = (orig_idx, curr_idx);
 
         let diff = cache.entry(key).or_insert_with(|| {
-            crate::diff::compute_line_diff(orig_code, orig_idx, &self.code, curr_idx)
+            crate::diff::compute_line_diff(orig_code, orig_idx, &self.code.borrow(), curr_idx)
         });
 
         if is_ghost {
@@ -912,12 +3152,15 @@ impl Editor {
     }
 
     pub(crate) fn prev_line(&self, line_idx: usize) -> Option<usize> {
-        self.view.prev_line(self.active_view_mode(), line_idx)
+        self.view
+            .borrow()
+            .prev_line(self.active_view_mode(), line_idx)
     }
 
     pub(crate) fn next_line(&self, line_idx: usize) -> Option<usize> {
         self.view
-            .next_line(&self.code, self.active_view_mode(), line_idx)
+            .borrow()
+            .next_line(&self.code.borrow(), self.active_view_mode(), line_idx)
     }
 
     pub(crate) fn is_diff_focus_active(&self) -> bool {
@@ -926,21 +3169,22 @@ impl Editor {
 
     pub(crate) fn clamp_cursor_to_focus_rows(&mut self) {
         let clear_selection = self.is_diff_focus_active();
-        let (cursor_line, cursor_char_col) = self.code.point(self.cursor);
+        let code = self.code.borrow();
+        let (cursor_line, cursor_char_col) = code.point(self.cursor);
         if self.line_visible(cursor_line) {
             return;
         }
 
-        let current_visual_col = self.code.char_col_to_visual(cursor_line, cursor_char_col);
+        let current_visual_col = code.char_col_to_visual(cursor_line, cursor_char_col);
         let Some(target_line) = self.nearest_focus_real_line(cursor_line) else {
             return;
         };
-        let target_start = self.code.line_to_char(target_line);
-        let target_len = self.code.line_len(target_line);
-        let target_col = self
-            .code
+        let target_start = code.line_to_char(target_line);
+        let target_len = code.line_len(target_line);
+        let target_col = code
             .visual_to_char_col(target_line, current_visual_col)
             .min(target_len);
+        drop(code);
 
         self.cursor = target_start + target_col;
         if clear_selection {
@@ -949,50 +3193,68 @@ impl Editor {
     }
 
     pub(crate) fn line_visible(&self, line_idx: usize) -> bool {
-        self.view.line_visible(self.active_view_mode(), line_idx)
+        self.view
+            .borrow()
+            .line_visible(self.active_view_mode(), line_idx)
     }
 
     fn nearest_focus_real_line(&self, line_idx: usize) -> Option<usize> {
         self.view
-            .nearest_line(&self.code, self.active_view_mode(), line_idx)
+            .borrow()
+            .nearest_line(&self.code.borrow(), self.active_view_mode(), line_idx)
     }
 
     /// calculates visible cursor position
     pub fn get_visible_cursor(&self, area: &Rect) -> Option<(u16, u16)> {
-        let line_number_width = self.get_line_number_width();
+        self.visible_cursor_in(area, &self.view_state())
+    }
 
-        let (cursor_line, cursor_char_col) = self.code.point(self.cursor);
-        let cursor_visual_line = self.visual_line_idx(cursor_line);
+    /// Like [`Editor::get_visible_cursor`] for the last rendered area.
+    pub fn visible_cursor(&self) -> Option<(u16, u16)> {
+        self.get_visible_cursor(&self.last_area.get())
+    }
 
-        if cursor_visual_line >= self.offset_y
-            && cursor_visual_line < self.offset_y + area.height as usize
-        {
-            let line_start_char = self.code.line_to_char(cursor_line);
-            let line_len = self.code.line_len(cursor_line);
-
-            let max_x = (area.width as usize).saturating_sub(line_number_width);
-            let start_col = self.offset_x;
-
-            let cursor_visual_col: usize = {
-                let slice = self.code.char_slice(
-                    line_start_char,
-                    line_start_char + cursor_char_col.min(line_len),
-                );
-                RopeGraphemes::new(&slice).map(grapheme_width).sum()
-            };
+    /// Screen cell `(x, y)` at which the character at `offset` (chars) is
+    /// drawn when the editor is rendered in `area`, e.g. to place a popup or
+    /// tooltip next to it. `None` if it is scrolled out of view or folded.
+    pub fn doc_to_screen(&self, offset: usize, area: &Rect) -> Option<(u16, u16)> {
+        let (col, skipped, y) = self.screen_cell_in(offset, area, &self.view_state())?;
+        let text_area = self.layout(*area).text_area;
+        let x = col
+            .checked_sub(skipped)
+            .filter(|&x| x < text_area.width as usize)?;
+        Some((text_area.x + x as u16, y))
+    }
 
-            let offset_visual_col: usize = {
-                let slice = self
-                    .code
-                    .char_slice(line_start_char, line_start_char + start_col.min(line_len));
-                RopeGraphemes::new(&slice).map(grapheme_width).sum()
-            };
+    /// Area the editor was last rendered in as a widget, empty before the
+    /// first render.
+    pub fn last_area(&self) -> Rect {
+        self.last_area.get()
+    }
+
+    /// Screen position of the cursor when drawn with the scroll position and
+    /// focus of `view`.
+    pub(crate) fn visible_cursor_in(&self, area: &Rect, view: &EditorView) -> Option<(u16, u16)> {
+        let blinked_on = self.cursor_blink.is_none() || self.cursor_blink_phase.0;
+        if !view.focused || !blinked_on {
+            return None;
+        }
+        let text_area = self.layout(*area).text_area;
+
+        // A pending composition is drawn at its position with the cursor inside it
+        let (cursor, preedit_width) = match &self.preedit {
+            Some(preedit) => (preedit.position(), preedit.cursor_width()),
+            None => (self.cursor, 0),
+        };
 
-            let relative_visual_col = cursor_visual_col.saturating_sub(offset_visual_col);
-            let visible_x = relative_visual_col.min(max_x);
+        if let Some((cursor_visual_col, offset_visual_col, cursor_y)) =
+            self.screen_cell_in(cursor, area, view)
+        {
+            let relative_visual_col =
+                (cursor_visual_col + preedit_width).saturating_sub(offset_visual_col);
+            let visible_x = relative_visual_col.min(text_area.width as usize);
 
-            let cursor_x = area.left() + (line_number_width + visible_x) as u16;
-            let cursor_y = area.top() + (cursor_visual_line - self.offset_y) as u16;
+            let cursor_x = text_area.x + visible_x as u16;
 
             if cursor_x < area.right() && cursor_y < area.bottom() {
                 return Some((cursor_x, cursor_y));
@@ -1002,10 +3264,103 @@ impl Editor {
         return None;
     }
 
+    /// Where `pos` is drawn with the scroll position of `view`: its column in
+    /// cells from the line start, the cells scrolled off to the left and the
+    /// screen row. `None` if its line is not on screen.
+    fn screen_cell_in(
+        &self,
+        pos: usize,
+        area: &Rect,
+        view: &EditorView,
+    ) -> Option<(usize, usize, u16)> {
+        let code = self.code.borrow();
+        let (line, char_col) = code.point(pos.min(code.len_chars()));
+        let visual_line = self.visual_line_idx(line);
+        if visual_line < view.offset_y || visual_line >= view.offset_y + area.height as usize {
+            return None;
+        }
+        let line_start_char = code.line_to_char(line);
+        let line_len = code.line_len(line);
+        let start_col = view.offset_x;
+        let bidi = BidiLine::for_line(&code, line);
+
+        let visual_col: usize = if let Some(bidi) = &bidi {
+            bidi.visual_col(char_col)
+        } else {
+            let slice = code.char_slice(line_start_char, line_start_char + char_col.min(line_len));
+            RopeGraphemes::new(&slice).map(grapheme_width).sum()
+        };
+
+        let offset_visual_col: usize = if let Some(bidi) = &bidi {
+            bidi.skipped_width(start_col)
+        } else {
+            let slice = code.char_slice(line_start_char, line_start_char + start_col.min(line_len));
+            RopeGraphemes::new(&slice).map(grapheme_width).sum()
+        };
+
+        let y = area.top() + (visual_line - view.offset_y) as u16;
+        Some((visual_col, offset_visual_col, y))
+    }
+
     pub fn show_line_numbers(&mut self, show: bool) {
         self.show_line_numbers = show
     }
 
+    /// Sets the fewest digits the line numbers are padded to, 5 by default.
+    /// Files with more lines still get as many digits as they need, so 1
+    /// keeps the gutter as narrow as possible for small snippets.
+    pub fn set_line_number_min_digits(&mut self, digits: usize) {
+        self.line_number_min_digits = digits.max(1);
+    }
+
+    /// Shows or hides placeholders for invisible characters such as byte order
+    /// marks, zero-width spaces and bidi controls. They are styled with the
+    /// `invisible` theme key. Hidden invisibles still take one blank cell.
+    pub fn show_invisibles(&mut self, show: bool) {
+        self.show_invisibles = show;
+    }
+
+    /// Shows or hides the vertical scrollbar on the right edge of the editor area.
+    pub fn show_scrollbar(&mut self, show: bool) {
+        self.show_scrollbar = show;
+        self.scrollbar_dragging = false;
+    }
+
+    pub(crate) fn scrollbar_width(&self) -> usize {
+        if self.show_scrollbar { 1 } else { 0 }
+    }
+
+    /// Returns the scroll position as a [`ScrollbarState`] for hosts that draw
+    /// their own `ratatui::widgets::Scrollbar`.
+    ///
+    /// The content length is the number of visual rows and the position is the
+    /// first visible row.
+    pub fn scroll_state(&self) -> ScrollbarState {
+        ScrollbarState::new(self.visual_len_lines()).position(self.offset_y)
+    }
+
+    /// Returns true if the given point lies on the built-in scrollbar column.
+    #[cfg(feature = "crossterm")]
+    pub(crate) fn is_on_scrollbar(&self, mouse_x: u16, mouse_y: u16, area: &Rect) -> bool {
+        self.show_scrollbar
+            && area.width > 0
+            && mouse_x == area.right() - 1
+            && mouse_y >= area.top()
+            && mouse_y < area.bottom()
+    }
+
+    /// Scrolls so that the scrollbar thumb follows the mouse row.
+    #[cfg(feature = "crossterm")]
+    pub(crate) fn scroll_to_scrollbar_row(&mut self, mouse_y: u16, area: &Rect) {
+        let height = area.height as usize;
+        let max_offset = self.visual_len_lines().saturating_sub(height);
+        if height <= 1 {
+            return;
+        }
+        let row = (mouse_y.saturating_sub(area.top()) as usize).min(height - 1);
+        self.offset_y = (row * max_offset + (height - 1) / 2) / (height - 1);
+    }
+
     pub fn set_left_code_padding(&mut self, char_count: usize) {
         self.left_code_padding = char_count
     }
@@ -1018,9 +3373,9 @@ impl Editor {
         }
     }
 
-    pub(crate) fn rebuild_view(&mut self) {
-        self.view.rebuild(
-            &self.code,
+    pub(crate) fn rebuild_view(&self) {
+        self.view.borrow_mut().rebuild(
+            &self.code.borrow(),
             self.original_code.as_ref(),
             self.active_view_mode(),
         );
@@ -1,80 +1,279 @@
-use crate::actions::*;
+use crate::command::Command;
 use crate::editor::Editor;
-use crate::selection::SelectionSnap;
+use crate::types::CursorStyle;
 use anyhow::Result;
+use crossterm::cursor::SetCursorStyle;
 use crossterm::event::{KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use ratatui_core::layout::Rect;
+use std::time::Instant;
+
+/// What [`Editor::input`] does with a key after the input hook has seen it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InputDecision {
+    /// Handle the key with the default key bindings
+    Continue,
+    /// Drop the key, e.g. because the host handled it
+    Swallow,
+    /// Handle a different key instead
+    Remap(KeyEvent),
+    /// Run a command instead of the key's default binding
+    Execute(Command),
+}
+
+/// Host callback deciding how a key event is handled.
+pub type InputHook = Box<dyn Fn(&KeyEvent, &Editor) -> InputDecision>;
 
 impl Editor {
+    /// Installs a hook that sees every key before the editor handles it,
+    /// so hosts can intercept, remap or swallow keys.
+    pub fn set_input_hook(&mut self, hook: InputHook) {
+        self.input_hook = Some(hook);
+    }
+
+    pub fn remove_input_hook(&mut self) {
+        self.input_hook = None;
+    }
+
+    /// Handles a key in the area the editor was last rendered in, see
+    /// [`Editor::input`].
+    pub fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        let area = self.last_area.get();
+        self.input(key, &area)
+    }
+
+    /// Handles a mouse event in the area the editor was last rendered in,
+    /// see [`Editor::mouse`].
+    pub fn handle_mouse(&mut self, mouse: MouseEvent) -> Result<()> {
+        let area = self.last_area.get();
+        self.mouse(mouse, &area)
+    }
+
     pub fn input(&mut self, key: KeyEvent, area: &Rect) -> Result<()> {
         use crossterm::event::KeyCode;
 
+        if !self.focused {
+            return Ok(());
+        }
+        self.sync_shared_code();
+        self.end_hover();
+        let decision = match &self.input_hook {
+            Some(hook) => hook(&key, self),
+            None => InputDecision::Continue,
+        };
+        let key = match decision {
+            InputDecision::Continue => key,
+            InputDecision::Swallow => return Ok(()),
+            InputDecision::Remap(key) => key,
+            InputDecision::Execute(command) => {
+                self.execute(command);
+                self.focus(area);
+                return Ok(());
+            }
+        };
+
         let shift = key.modifiers.contains(KeyModifiers::SHIFT);
         let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
-        let _alt = key.modifiers.contains(KeyModifiers::ALT);
 
-        match key.code {
-            KeyCode::Char('÷') => self.apply(ToggleComment {}),
-            KeyCode::Char('z') if ctrl => self.apply(Undo {}),
-            KeyCode::Char('y') if ctrl => self.apply(Redo {}),
-            KeyCode::Char('c') if ctrl => self.apply(Copy {}),
-            KeyCode::Char('v') if ctrl => self.apply(Paste {}),
-            KeyCode::Char('x') if ctrl => self.apply(Cut {}),
-            KeyCode::Char('k') if ctrl => self.apply(DeleteLine {}),
-            KeyCode::Char('d') if ctrl => self.apply(Duplicate {}),
-            KeyCode::Char('a') if ctrl => self.apply(SelectAll {}),
-            KeyCode::Char('u') if ctrl => self.apply(UnIndent {}),
-            KeyCode::Left => self.apply(MoveLeft { shift }),
-            KeyCode::Right => self.apply(MoveRight { shift }),
-            KeyCode::Up => self.apply(MoveUp { shift }),
-            KeyCode::Down => self.apply(MoveDown { shift }),
-            KeyCode::Backspace => self.apply(Delete {}),
-            KeyCode::Enter => self.apply(InsertNewline {}),
-            KeyCode::Char(c) => self.apply(InsertText {
-                text: c.to_string(),
-            }),
-            KeyCode::Tab => self.apply(Indent {}),
-            KeyCode::BackTab => self.apply(UnIndent {}),
-            _ => {}
+        if self.picker.is_some() {
+            self.picker_input(key, area);
+            return Ok(());
+        }
+
+        // The host owns a pending IME composition; raw keys must not leak into the text
+        if self.preedit.is_some() {
+            match key.code {
+                KeyCode::Esc => self.cancel_preedit(),
+                KeyCode::Enter => {
+                    self.commit_preedit();
+                    self.focus(area);
+                }
+                _ => {}
+            }
+            return Ok(());
         }
+
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+
+        let command = match key.code {
+            KeyCode::Char('÷') => Command::ToggleComment,
+            KeyCode::Char('z') if ctrl => Command::Undo,
+            KeyCode::Char('y') if ctrl => Command::Redo,
+            KeyCode::Char('c') if ctrl => Command::Copy,
+            KeyCode::Char('v') if ctrl && shift => Command::PasteLiteral,
+            KeyCode::Char('V') if ctrl => Command::PasteLiteral,
+            KeyCode::Char('v') if ctrl => Command::Paste,
+            KeyCode::Char('x') if ctrl => Command::Cut,
+            KeyCode::Char('k') if ctrl => Command::DeleteLine,
+            KeyCode::Char('d') if ctrl => Command::Duplicate,
+            KeyCode::Char('a') if ctrl => Command::SelectAll,
+            KeyCode::Char('u') if ctrl => Command::UnIndent,
+            KeyCode::Up if ctrl => Command::MoveParagraphUp { shift },
+            KeyCode::Down if ctrl => Command::MoveParagraphDown { shift },
+            KeyCode::Up if alt => Command::MoveBlockStart { shift },
+            KeyCode::Down if alt => Command::MoveBlockEnd { shift },
+            KeyCode::Left if alt => Command::MoveSubWordLeft { shift },
+            KeyCode::Right if alt => Command::MoveSubWordRight { shift },
+            KeyCode::Left => Command::MoveLeft { shift },
+            KeyCode::Right => Command::MoveRight { shift },
+            KeyCode::Up => Command::MoveUp { shift },
+            KeyCode::Down => Command::MoveDown { shift },
+            KeyCode::Backspace => Command::Delete,
+            KeyCode::Enter => Command::InsertNewline,
+            KeyCode::Insert => Command::ToggleOverwrite,
+            KeyCode::Char(c) if self.overwrite => Command::OverwriteText {
+                text: c.to_string(),
+            },
+            KeyCode::Char(c) => Command::InsertText {
+                text: c.to_string(),
+            },
+            KeyCode::Tab if self.has_snippet() => Command::NextTabstop,
+            KeyCode::BackTab if self.has_snippet() => Command::PrevTabstop,
+            KeyCode::Esc if self.has_snippet() => {
+                self.cancel_snippet();
+                return Ok(());
+            }
+            KeyCode::Tab => Command::Indent,
+            KeyCode::BackTab => Command::UnIndent,
+            _ => {
+                self.focus(area);
+                return Ok(());
+            }
+        };
+        self.execute(command);
         self.focus(&area);
         Ok(())
     }
 
+    /// Keys while the picker is open edit its query and move its selection.
+    fn picker_input(&mut self, key: KeyEvent, area: &Rect) {
+        use crossterm::event::KeyCode;
+
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => self.close_picker(),
+            KeyCode::Enter => {
+                if self.accept_picker() {
+                    self.center_cursor(area);
+                }
+            }
+            code => {
+                let Some(picker) = &mut self.picker else {
+                    return;
+                };
+                match code {
+                    KeyCode::Up => picker.select_prev(),
+                    KeyCode::Char('p') if ctrl => picker.select_prev(),
+                    KeyCode::Down | KeyCode::Tab => picker.select_next(),
+                    KeyCode::Char('n') if ctrl => picker.select_next(),
+                    KeyCode::Backspace => picker.pop_char(),
+                    KeyCode::Char(c) => picker.push_char(c),
+                    _ => {}
+                }
+            }
+        }
+        self.needs_redraw.set(true);
+    }
+
     pub fn mouse(&mut self, mouse: MouseEvent, area: &Rect) -> Result<()> {
+        self.sync_shared_code();
+        let shift = mouse.modifiers.contains(KeyModifiers::SHIFT);
+        let ticks = self.scroll_lines_per_tick;
+        if mouse.kind != MouseEventKind::Moved {
+            self.end_hover();
+        }
+
         match mouse.kind {
-            MouseEventKind::ScrollUp => self.scroll_up(),
-            MouseEventKind::ScrollDown => self.scroll_down(area.height as usize),
+            MouseEventKind::Moved => {
+                self.handle_mouse_move_at(mouse.column, mouse.row, area, Instant::now());
+            }
+            // Shift+wheel scrolls horizontally
+            MouseEventKind::ScrollLeft => self.scroll_left(ticks),
+            MouseEventKind::ScrollRight => self.scroll_right(ticks, area),
+            MouseEventKind::ScrollUp if shift => self.scroll_left(ticks),
+            MouseEventKind::ScrollDown if shift => self.scroll_right(ticks, area),
+            MouseEventKind::ScrollUp => (0..ticks).for_each(|_| self.scroll_up()),
+            MouseEventKind::ScrollDown => {
+                (0..ticks).for_each(|_| self.scroll_down(area.height as usize))
+            }
+            MouseEventKind::Down(MouseButton::Left)
+                if (self.definition_on_ctrl_click || self.link_detection)
+                    && mouse.modifiers.contains(KeyModifiers::CONTROL) =>
+            {
+                // a link opens, otherwise the click looks up a definition
+                if let Some(offset) = self.cursor_from_mouse(mouse.column, mouse.row, area)
+                    && !self.open_link_at(offset)
+                {
+                    if self.definition_on_ctrl_click {
+                        self.request_definition(offset);
+                    } else {
+                        self.handle_mouse_down(offset);
+                    }
+                }
+            }
             MouseEventKind::Down(MouseButton::Left) => {
+                if self.is_on_scrollbar(mouse.column, mouse.row, area) {
+                    self.scrollbar_dragging = true;
+                    self.scroll_to_scrollbar_row(mouse.row, area);
+                    return Ok(());
+                }
                 if self.toggle_fold_at_mouse(mouse.column, mouse.row, area) {
                     return Ok(());
                 }
+                if self.handle_gutter_click(mouse.column, mouse.row, area) {
+                    return Ok(());
+                }
                 if self.expand_hidden_diff_at_mouse(mouse.column, mouse.row, area) {
                     return Ok(());
                 }
                 let pos = self.cursor_from_mouse(mouse.column, mouse.row, area);
-                if let Some(cursor) = pos {
-                    self.handle_mouse_down(cursor);
+                match pos {
+                    Some(cursor) if mouse.modifiers.contains(KeyModifiers::ALT) => {
+                        self.handle_sub_word_mouse_down(cursor)
+                    }
+                    Some(cursor) => self.handle_mouse_down(cursor),
+                    None => {}
                 }
             }
-            MouseEventKind::Drag(MouseButton::Left) => {
-                // Auto-scroll when dragging on the last or first visible row
-                if mouse.row == area.top() {
-                    self.scroll_up();
+            MouseEventKind::Down(MouseButton::Right) => {
+                if let Some(offset) = self.cursor_from_mouse(mouse.column, mouse.row, area) {
+                    self.handle_context_click(offset, (mouse.column, mouse.row));
                 }
-                if mouse.row == area.bottom().saturating_sub(1) {
-                    self.scroll_down(area.height as usize);
-                }
-                let pos = self.cursor_from_mouse(mouse.column, mouse.row, area);
-                if let Some(cursor) = pos {
-                    self.handle_mouse_drag(cursor);
+            }
+            MouseEventKind::Drag(MouseButton::Left) if self.scrollbar_dragging => {
+                self.scroll_to_scrollbar_row(mouse.row, area);
+            }
+            MouseEventKind::Drag(MouseButton::Left) => {
+                // Auto-scroll when dragging on or past the first or last visible row
+                self.handle_mouse_drag_at(mouse.column, mouse.row, area, Instant::now());
+            }
+            MouseEventKind::Down(MouseButton::Middle) if self.primary_selection => {
+                if let Some(offset) = self.cursor_from_mouse(mouse.column, mouse.row, area) {
+                    self.paste_primary_at(offset);
                 }
             }
             MouseEventKind::Up(MouseButton::Left) => {
-                self.selection_snap = SelectionSnap::None;
+                self.handle_mouse_up(mouse.modifiers.contains(KeyModifiers::CONTROL));
+                self.scrollbar_dragging = false;
+                self.stop_drag_autoscroll();
+                if self.primary_selection
+                    && let Some(text) = self.get_selection_text()
+                {
+                    let _ = self.set_primary_selection(&text);
+                }
             }
             _ => {}
         }
         Ok(())
     }
 }
+
+/// Steady shapes, as blinking is left to [`Editor::set_cursor_blink`].
+impl From<CursorStyle> for SetCursorStyle {
+    fn from(style: CursorStyle) -> Self {
+        match style {
+            CursorStyle::Bar => SetCursorStyle::SteadyBar,
+            CursorStyle::Block => SetCursorStyle::SteadyBlock,
+            CursorStyle::Underline => SetCursorStyle::SteadyUnderScore,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::editor::Editor;
+use ratatui_core::layout::Rect;
+
+/// Scroll position, area and focus of one rendering of an [`Editor`].
+///
+/// `&Editor` implements [`StatefulWidget`] with this state, so one document
+/// can be shown in several panes that scroll independently, while the cursor,
+/// selections and text stay shared.
+///
+/// ```ignore
+/// let mut view = editor.view_state();
+/// frame.render_stateful_widget(&editor, area, &mut view);
+/// ```
+///
+/// [`StatefulWidget`]: ratatui_core::widgets::StatefulWidget
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorView {
+    /// First visible column
+    pub offset_x: usize,
+    /// First visible visual row
+    pub offset_y: usize,
+    /// Area the view was last rendered in
+    pub area: Rect,
+    /// Whether the cursor is shown and selections are drawn as focused
+    pub focused: bool,
+}
+
+impl EditorView {
+    /// Scrolls as little as needed to bring the cursor into the last
+    /// rendered area.
+    pub fn scroll_to_cursor(&mut self, editor: &Editor) {
+        let code = editor.borrow_code();
+        let (line, col) = code.point(editor.get_cursor().min(code.len_chars()));
+        drop(code);
+        let visual_line = editor.visual_line_idx(line);
+        if visual_line == usize::MAX {
+            return;
+        }
+
+        let height = (self.area.height as usize).max(1);
+        if visual_line < self.offset_y {
+            self.offset_y = visual_line;
+        } else if visual_line >= self.offset_y + height {
+            self.offset_y = visual_line + 1 - height;
+        }
+
+        let width = (editor.layout(self.area).text_area.width as usize).max(1);
+        if col < self.offset_x {
+            self.offset_x = col;
+        } else if col >= self.offset_x + width {
+            self.offset_x = col + 1 - width;
+        }
+    }
+
+    /// Screen position of the cursor in the last rendered area, or `None` if
+    /// the view is not focused or the cursor is scrolled out of it.
+    pub fn cursor_position(&self, editor: &Editor) -> Option<(u16, u16)> {
+        editor.visible_cursor_in(&self.area, self)
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::{Result, anyhow};
+pub use encoding_rs::Encoding;
+use encoding_rs::{UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252};
+
+/// Decodes file bytes into text, detecting the encoding.
+///
+/// A byte order mark wins. Otherwise UTF-16 is guessed from the pattern of zero
+/// bytes, valid UTF-8 is kept as is, and anything else is read as Windows-1252
+/// (a superset of Latin-1), which never fails.
+///
+/// Returns the text, the encoding and whether a byte order mark was present.
+pub fn decode(bytes: &[u8]) -> (String, &'static Encoding, bool) {
+    if let Some((encoding, bom_len)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom_len..]);
+        return (text.into_owned(), encoding, true);
+    }
+    let encoding = guess_utf16(bytes).unwrap_or_else(|| match std::str::from_utf8(bytes) {
+        Ok(_) => UTF_8,
+        Err(_) => WINDOWS_1252,
+    });
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    (text.into_owned(), encoding, false)
+}
+
+/// Encodes text for writing to disk, optionally prefixed with a byte order mark.
+///
+/// Fails if the text contains characters the encoding cannot represent.
+pub fn encode(text: &str, encoding: &'static Encoding, bom: bool) -> Result<Vec<u8>> {
+    // encoding_rs only decodes UTF-16, so it is encoded by hand
+    if encoding == UTF_16LE || encoding == UTF_16BE {
+        let le = encoding == UTF_16LE;
+        let mut bytes = Vec::with_capacity(text.len() * 2 + 2);
+        let units = bom.then_some(0xFEFF).into_iter().chain(text.encode_utf16());
+        for unit in units {
+            let pair = if le {
+                unit.to_le_bytes()
+            } else {
+                unit.to_be_bytes()
+            };
+            bytes.extend_from_slice(&pair);
+        }
+        return Ok(bytes);
+    }
+
+    let (encoded, _, had_errors) = encoding.encode(text);
+    if had_errors {
+        return Err(anyhow!("text cant be encoded as {}", encoding.name()));
+    }
+    let mut bytes = Vec::with_capacity(encoded.len() + 3);
+    if bom && encoding == UTF_8 {
+        bytes.extend_from_slice(b"\xEF\xBB\xBF");
+    }
+    bytes.extend_from_slice(&encoded);
+    Ok(bytes)
+}
+
+/// Guesses BOM-less UTF-16 from mostly-ASCII text, where every other byte is zero.
+fn guess_utf16(bytes: &[u8]) -> Option<&'static Encoding> {
+    if bytes.len() < 2 || !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+    let pairs = bytes.len() / 2;
+    let zeros_at = |parity: usize| {
+        bytes
+            .iter()
+            .skip(parity)
+            .step_by(2)
+            .filter(|&&byte| byte == 0)
+            .count()
+    };
+    let (even, odd) = (zeros_at(0), zeros_at(1));
+    if odd * 2 > pairs && even == 0 {
+        Some(UTF_16LE)
+    } else if even * 2 > pairs && odd == 0 {
+        Some(UTF_16BE)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_encode_roundtrip() {
+        let cases: [(&[u8], &Encoding, bool, &str); 5] = [
+            (b"caf\xc3\xa9", UTF_8, false, "café"),
+            (b"\xEF\xBB\xBFcaf\xc3\xa9", UTF_8, true, "café"),
+            (b"caf\xe9", WINDOWS_1252, false, "café"),
+            (b"\xFF\xFEh\0i\0", UTF_16LE, true, "hi"),
+            (b"\0h\0i", UTF_16BE, false, "hi"),
+        ];
+        for (bytes, encoding, bom, text) in cases {
+            assert_eq!(decode(bytes), (text.to_string(), encoding, bom));
+            assert_eq!(encode(text, encoding, bom).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn test_encode_unmappable() {
+        assert!(encode("λ", WINDOWS_1252, false).is_err());
+    }
+}
@@ -0,0 +1,111 @@
+use crate::selection::Selection;
+use crate::types::PasteMode;
+use std::path::PathBuf;
+
+/// A state change reported by [`Editor::drain_events`](crate::editor::Editor::drain_events).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditorEvent {
+    CursorMoved {
+        cursor: usize,
+    },
+    SelectionChanged {
+        selection: Option<Selection>,
+    },
+    Scrolled {
+        offset_x: usize,
+        offset_y: usize,
+    },
+    ModeChanged(EditorMode),
+    Undo,
+    Redo,
+    Saved {
+        path: PathBuf,
+    },
+    /// Ctrl+Click on the text, for the host to look up the definition of
+    /// the symbol at the (row, column in characters) position
+    DefinitionRequested {
+        offset: usize,
+        row: usize,
+        col: usize,
+    },
+    /// The mouse pointer rested on the character at `offset` for the hover
+    /// delay, e.g. to request hover information from a language server
+    HoverRequested {
+        offset: usize,
+        screen_pos: (u16, u16),
+    },
+    /// The pointer left the character of the last `HoverRequested`, or the
+    /// editor was clicked, scrolled or typed into
+    HoverEnded,
+    /// The editor was right clicked at `offset`, for the host to show a
+    /// context menu at `screen_pos`
+    ContextMenuRequested {
+        offset: usize,
+        screen_pos: (u16, u16),
+    },
+    /// A host gutter column, see [`crate::gutter::GutterColumn`], was
+    /// clicked on `line`
+    GutterClicked {
+        line: usize,
+        column_id: String,
+    },
+    /// A URL or file path in the text was Ctrl+Clicked, for the host to open
+    OpenLink(String),
+}
+
+/// Editor modes reported by [`EditorEvent::ModeChanged`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EditorMode {
+    pub diff: bool,
+    pub diff_focus: bool,
+    pub paste_mode: PasteMode,
+}
+
+/// State last reported to the host, used to detect changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Observed {
+    pub(crate) cursor: usize,
+    pub(crate) selection: Option<Selection>,
+    pub(crate) offset: (usize, usize),
+    pub(crate) mode: EditorMode,
+}
+
+/// Events raised explicitly plus the state they were last compared against.
+#[derive(Debug, Default)]
+pub(crate) struct EventQueue {
+    pub(crate) pending: Vec<EditorEvent>,
+    pub(crate) observed: Option<Observed>,
+}
+
+impl EventQueue {
+    pub(crate) fn push(&mut self, event: EditorEvent) {
+        self.pending.push(event);
+    }
+
+    /// Returns pending events followed by changes between `current` and the
+    /// previously observed state.
+    pub(crate) fn drain(&mut self, current: Observed) -> Vec<EditorEvent> {
+        let mut events = std::mem::take(&mut self.pending);
+        if let Some(prev) = &self.observed {
+            if prev.cursor != current.cursor {
+                events.push(EditorEvent::CursorMoved {
+                    cursor: current.cursor,
+                });
+            }
+            if prev.selection != current.selection {
+                events.push(EditorEvent::SelectionChanged {
+                    selection: current.selection,
+                });
+            }
+            if prev.offset != current.offset {
+                let (offset_x, offset_y) = current.offset;
+                events.push(EditorEvent::Scrolled { offset_x, offset_y });
+            }
+            if prev.mode != current.mode {
+                events.push(EditorEvent::ModeChanged(current.mode));
+            }
+        }
+        self.observed = Some(current);
+        events
+    }
+}
@@ -0,0 +1,258 @@
+use crate::code::Code;
+use crate::types::Theme;
+use ratatui_core::style::{Color, Modifier, Style};
+use std::fmt::Write;
+
+impl Code {
+    /// Renders the whole buffer as text colored with ANSI escape sequences.
+    ///
+    /// Every styled run is closed with a reset before line breaks, so the output
+    /// can be printed line by line or embedded into other terminal output.
+    pub fn to_ansi(&self, theme: &Theme) -> String {
+        let mut out = String::new();
+        for (text, style) in self.styled_runs(theme) {
+            match style.map(ansi_sgr).filter(|sgr| !sgr.is_empty()) {
+                Some(sgr) => {
+                    let _ = write!(out, "\x1b[{}m{}\x1b[0m", sgr, text);
+                }
+                None => out.push_str(&text),
+            }
+        }
+        out
+    }
+
+    /// Renders the whole buffer as an HTML `<pre>` block with inline styles.
+    pub fn to_html(&self, theme: &Theme) -> String {
+        let mut out = String::from("<pre><code>");
+        for (text, style) in self.styled_runs(theme) {
+            let text = html_escape(&text);
+            match style.map(css).filter(|css| !css.is_empty()) {
+                Some(css) => {
+                    let _ = write!(out, "<span style=\"{}\">{}</span>", css, text);
+                }
+                None => out.push_str(&text),
+            }
+        }
+        out.push_str("</code></pre>");
+        out
+    }
+
+    /// Splits the buffer into runs of text sharing the same highlight style.
+    /// Line breaks are always emitted as separate unstyled runs.
+    fn styled_runs(&self, theme: &Theme) -> Vec<(String, Option<Style>)> {
+        let text = self.content.slice(..);
+        let mut byte_styles: Vec<Option<Style>> = vec![None; text.len_bytes()];
+
+        // The first matching highlight wins, so paint them in reverse order
+        let highlights = self.highlight_interval(0, text.len_bytes(), theme);
+        for &(start, end, style) in highlights.iter().rev() {
+            let end = end.min(byte_styles.len());
+            for slot in &mut byte_styles[start.min(end)..end] {
+                *slot = Some(style);
+            }
+        }
+
+        let mut runs: Vec<(String, Option<Style>)> = Vec::new();
+        let mut byte_idx = 0;
+        for ch in text.chars() {
+            let style = if ch == '\n' {
+                None
+            } else {
+                byte_styles[byte_idx]
+            };
+            match runs.last_mut() {
+                Some((run, run_style))
+                    if *run_style == style && ch != '\n' && !run.ends_with('\n') =>
+                {
+                    run.push(ch);
+                }
+                _ => runs.push((ch.to_string(), style)),
+            }
+            byte_idx += ch.len_utf8();
+        }
+        runs
+    }
+}
+
+/// Builds the SGR parameters (without `ESC[` and `m`) for a style.
+fn ansi_sgr(style: Style) -> String {
+    let mut params: Vec<String> = Vec::new();
+    let modifiers = [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+        (Modifier::CROSSED_OUT, "9"),
+    ];
+    for (modifier, code) in modifiers {
+        if style.add_modifier.contains(modifier) {
+            params.push(code.to_string());
+        }
+    }
+    if let Some(fg) = style.fg.and_then(|color| ansi_color(color, false)) {
+        params.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|color| ansi_color(color, true)) {
+        params.push(bg);
+    }
+    params.join(";")
+}
+
+fn ansi_color(color: Color, background: bool) -> Option<String> {
+    let base = if background { 40 } else { 30 };
+    let code = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => return Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+        Color::Indexed(i) => return Some(format!("{};5;{}", base + 8, i)),
+        Color::Black => base,
+        Color::Red => base + 1,
+        Color::Green => base + 2,
+        Color::Yellow => base + 3,
+        Color::Blue => base + 4,
+        Color::Magenta => base + 5,
+        Color::Cyan => base + 6,
+        Color::Gray => base + 7,
+        Color::DarkGray => base + 60,
+        Color::LightRed => base + 61,
+        Color::LightGreen => base + 62,
+        Color::LightYellow => base + 63,
+        Color::LightBlue => base + 64,
+        Color::LightMagenta => base + 65,
+        Color::LightCyan => base + 66,
+        Color::White => base + 67,
+    };
+    Some(code.to_string())
+}
+
+/// Builds an inline CSS declaration list for a style.
+fn css(style: Style) -> String {
+    let mut decls = Vec::new();
+    if let Some(fg) = style.fg.and_then(css_color) {
+        decls.push(format!("color:{}", fg));
+    }
+    if let Some(bg) = style.bg.and_then(css_color) {
+        decls.push(format!("background-color:{}", bg));
+    }
+    if style.add_modifier.contains(Modifier::BOLD) {
+        decls.push("font-weight:bold".to_string());
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        decls.push("font-style:italic".to_string());
+    }
+    if style.add_modifier.contains(Modifier::UNDERLINED) {
+        decls.push("text-decoration:underline".to_string());
+    } else if style.add_modifier.contains(Modifier::CROSSED_OUT) {
+        decls.push("text-decoration:line-through".to_string());
+    }
+    decls.join(";")
+}
+
+fn css_color(color: Color) -> Option<String> {
+    // xterm defaults for the 16 named colors
+    const PALETTE: [(u8, u8, u8); 16] = [
+        (0, 0, 0),
+        (205, 0, 0),
+        (0, 205, 0),
+        (205, 205, 0),
+        (0, 0, 238),
+        (205, 0, 205),
+        (0, 205, 205),
+        (229, 229, 229),
+        (127, 127, 127),
+        (255, 0, 0),
+        (0, 255, 0),
+        (255, 255, 0),
+        (92, 92, 255),
+        (255, 0, 255),
+        (0, 255, 255),
+        (255, 255, 255),
+    ];
+    let (r, g, b) = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(i) => match i {
+            0..=15 => PALETTE[i as usize],
+            16..=231 => {
+                let level = |v: u8| if v == 0 { 0 } else { 55 + v * 40 };
+                let i = i - 16;
+                (level(i / 36), level((i / 6) % 6), level(i % 6))
+            }
+            _ => {
+                let v = 8 + (i - 232) * 10;
+                (v, v, v)
+            }
+        },
+        Color::Black => PALETTE[0],
+        Color::Red => PALETTE[1],
+        Color::Green => PALETTE[2],
+        Color::Yellow => PALETTE[3],
+        Color::Blue => PALETTE[4],
+        Color::Magenta => PALETTE[5],
+        Color::Cyan => PALETTE[6],
+        Color::Gray => PALETTE[7],
+        Color::DarkGray => PALETTE[8],
+        Color::LightRed => PALETTE[9],
+        Color::LightGreen => PALETTE[10],
+        Color::LightYellow => PALETTE[11],
+        Color::LightBlue => PALETTE[12],
+        Color::LightMagenta => PALETTE[13],
+        Color::LightCyan => PALETTE[14],
+        Color::White => PALETTE[15],
+    };
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+fn html_escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(ch),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn theme() -> Theme {
+        let mut theme = Theme::new();
+        theme.insert(
+            "keyword".to_string(),
+            Style::default().fg(Color::Rgb(1, 2, 3)),
+        );
+        theme
+    }
+
+    #[test]
+    fn test_to_ansi() {
+        let code = Code::new("let a;\nlet b;", "rust", None).unwrap();
+        assert_eq!(
+            code.to_ansi(&theme()),
+            "\x1b[38;2;1;2;3mlet\x1b[0m a;\n\x1b[38;2;1;2;3mlet\x1b[0m b;"
+        );
+    }
+
+    #[test]
+    fn test_to_html() {
+        let code = Code::new("let a = \"<b>\";", "rust", None).unwrap();
+        assert_eq!(
+            code.to_html(&theme()),
+            "<pre><code><span style=\"color:#010203\">let</span> a = &quot;&lt;b&gt;&quot;;</code></pre>"
+        );
+    }
+
+    #[test]
+    fn test_export_plain_text() {
+        let code = Code::new("a < b\n", "unknown", None).unwrap();
+        assert_eq!(code.to_ansi(&theme()), "a < b\n");
+        assert_eq!(code.to_html(&theme()), "<pre><code>a &lt; b\n</code></pre>");
+    }
+}
@@ -0,0 +1,94 @@
+use ratatui_core::style::{Color, Style};
+use std::time::{Duration, Instant};
+
+/// Mark layer holding the range highlighted by [`Editor::reveal`](crate::editor::Editor::reveal).
+pub const FLASH_MARK_LAYER: &str = "flash";
+
+/// How often a fading flash is repainted.
+pub(crate) const FLASH_FRAME: Duration = Duration::from_millis(50);
+
+/// A temporary highlight that holds its color for the first half of its
+/// duration and then fades towards `fade_to`.
+///
+/// Fading needs both colors to be RGB; other colors stay solid until the
+/// flash expires.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Flash {
+    color: Color,
+    fade_to: Color,
+    started: Instant,
+    duration: Duration,
+    pub(crate) next_frame: Instant,
+}
+
+impl Flash {
+    pub(crate) fn new(color: Color, fade_to: Color, now: Instant, duration: Duration) -> Self {
+        Self {
+            color,
+            fade_to,
+            started: now,
+            duration,
+            next_frame: now + FLASH_FRAME,
+        }
+    }
+
+    pub(crate) fn expires(&self) -> Instant {
+        self.started + self.duration
+    }
+
+    pub(crate) fn deadline(&self) -> Instant {
+        self.next_frame.min(self.expires())
+    }
+
+    pub(crate) fn style_at(&self, now: Instant) -> Style {
+        let elapsed = now.saturating_duration_since(self.started).as_secs_f32();
+        let half = self.duration.as_secs_f32() / 2.0;
+        let t = if half > 0.0 {
+            ((elapsed - half) / half).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        Style::default().bg(blend(self.color, self.fade_to, t))
+    }
+}
+
+fn blend(from: Color, to: Color, t: f32) -> Color {
+    match (from, to) {
+        (Color::Rgb(r1, g1, b1), Color::Rgb(r2, g2, b2)) => {
+            let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+            Color::Rgb(mix(r1, r2), mix(g1, g2), mix(b1, b2))
+        }
+        _ => from,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flash_fades_in_second_half() {
+        let now = Instant::now();
+        let flash = Flash::new(
+            Color::Rgb(200, 100, 0),
+            Color::Rgb(0, 100, 200),
+            now,
+            Duration::from_millis(400),
+        );
+        let bg = |ms| flash.style_at(now + Duration::from_millis(ms)).bg;
+        assert_eq!(bg(100), Some(Color::Rgb(200, 100, 0)));
+        assert_eq!(bg(300), Some(Color::Rgb(100, 100, 100)));
+        assert_eq!(bg(400), Some(Color::Rgb(0, 100, 200)));
+
+        let solid = Flash::new(
+            Color::Yellow,
+            Color::Rgb(0, 0, 0),
+            now,
+            Duration::from_millis(400),
+        );
+        assert_eq!(
+            solid.style_at(now + Duration::from_millis(300)).bg,
+            Some(Color::Yellow)
+        );
+    }
+}
@@ -0,0 +1,128 @@
+use crate::command::Command;
+use crate::editor::Editor;
+use arbitrary::{Arbitrary, Unstructured};
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+/// Languages a fuzzed document is opened as.
+const LANGS: &[&str] = &["text", "rust", "python", "markdown", "json"];
+
+/// One step of a fuzzing run.
+#[derive(Debug, Clone, Arbitrary)]
+pub enum FuzzOp {
+    /// Runs a command; clipboard commands are skipped as they reach the
+    /// system clipboard
+    Command(Command),
+    /// Clicks a screen cell, possibly past the text or the area
+    Click {
+        column: u8,
+        row: u8,
+    },
+    /// Drags the selection to a screen cell
+    Drag {
+        column: u8,
+        row: u8,
+    },
+    /// Releases the mouse button, dropping a dragged selection
+    Release {
+        copy: bool,
+    },
+    ScrollUp,
+    ScrollDown,
+    ToggleFold {
+        line: u16,
+    },
+    Render,
+    Resize {
+        width: u8,
+        height: u8,
+    },
+}
+
+/// Applies `ops` to `editor` in checked mode, so the first operation that
+/// breaks an invariant panics (in debug builds) instead of a later one.
+pub fn apply_ops(editor: &mut Editor, ops: &[FuzzOp]) {
+    editor.set_checked(true);
+    let mut area = Rect::new(0, 0, 40, 10);
+    for op in ops {
+        match op {
+            FuzzOp::Command(Command::Copy | Command::Cut | Command::Paste)
+            | FuzzOp::Command(Command::PasteLiteral | Command::PastePrimary) => {}
+            FuzzOp::Command(command) => editor.execute(command.clone()),
+            FuzzOp::Click { column, row } => {
+                if let Some(cursor) = editor.cursor_from_mouse(*column as u16, *row as u16, &area) {
+                    editor.handle_mouse_down(cursor);
+                }
+            }
+            FuzzOp::Drag { column, row } => {
+                if let Some(cursor) = editor.cursor_from_mouse(*column as u16, *row as u16, &area) {
+                    editor.handle_mouse_drag(cursor);
+                }
+            }
+            FuzzOp::Release { copy } => editor.handle_mouse_up(*copy),
+            FuzzOp::ScrollUp => editor.scroll_up(),
+            FuzzOp::ScrollDown => editor.scroll_down(area.height as usize),
+            FuzzOp::ToggleFold { line } => {
+                editor.toggle_fold_at_line(*line as usize);
+            }
+            FuzzOp::Render => (&*editor).render(area, &mut Buffer::empty(area)),
+            FuzzOp::Resize { width, height } => {
+                area = Rect::new(0, 0, *width as u16, *height as u16);
+            }
+        }
+        editor.focus(&area);
+        editor.debug_assert_invariants();
+    }
+}
+
+/// Fuzz target body: builds a document and a list of operations from raw
+/// fuzzer input and applies them. Use it from a `cargo fuzz` target as
+/// `fuzz_target!(|data: &[u8]| ratatui_code_editor::fuzz::run(data));`.
+pub fn run(data: &[u8]) {
+    let mut input = Unstructured::new(data);
+    let (Ok(lang), Ok(text), Ok(ops)) = (
+        input.choose(LANGS),
+        input.arbitrary::<String>(),
+        input.arbitrary::<Vec<FuzzOp>>(),
+    ) else {
+        return;
+    };
+    let mut editor = Editor::new(lang, &text, vec![]).expect("built-in language");
+    apply_ops(&mut editor, &ops);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn applies_operations_past_the_text() {
+        let mut editor = Editor::new("rust", "fn main() {\n    let x = 1;\n}", vec![]).unwrap();
+        apply_ops(
+            &mut editor,
+            &[
+                FuzzOp::Click { column: 30, row: 9 },
+                FuzzOp::Drag { column: 0, row: 0 },
+                FuzzOp::Command(Command::Delete),
+                FuzzOp::Resize {
+                    width: 0,
+                    height: 0,
+                },
+                FuzzOp::Render,
+                FuzzOp::Command(Command::Undo),
+                FuzzOp::ToggleFold { line: 0 },
+                FuzzOp::ScrollDown,
+                FuzzOp::Render,
+            ],
+        );
+        assert_eq!(editor.get_content(), "fn main() {\n    let x = 1;\n}");
+
+        for seed in 0..20u32 {
+            let data: Vec<u8> = (0..256u32)
+                .map(|i| (i.wrapping_mul(31).wrapping_add(seed * 97) % 251) as u8)
+                .collect();
+            run(&data);
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use crate::breakpoints::BREAKPOINT_COLUMN;
+use crate::editor::Editor;
+use crate::events::EditorEvent;
+use crate::layout::EditorLayout;
+use crate::selection::{Selection, SelectionSnap};
+use ratatui_core::layout::Rect;
+use ratatui_core::style::Style;
+use std::collections::BTreeMap;
+
+/// A gutter column defined by the host, drawn left of the line numbers, e.g.
+/// for breakpoints or bookmarks. Clicks on it are reported as
+/// [`EditorEvent::GutterClicked`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GutterColumn {
+    pub id: String,
+    /// Width in columns; longer cells are cut
+    pub width: usize,
+    /// Text drawn on a line, by line index
+    pub cells: BTreeMap<usize, (String, Style)>,
+}
+
+impl GutterColumn {
+    pub fn new(id: impl Into<String>, width: usize) -> Self {
+        Self {
+            id: id.into(),
+            width,
+            cells: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_cell(&mut self, line: usize, text: impl Into<String>, style: Style) {
+        self.cells.insert(line, (text.into(), style));
+    }
+
+    pub fn clear_cell(&mut self, line: usize) {
+        self.cells.remove(&line);
+    }
+}
+
+/// Part of the gutter under the mouse pointer.
+enum GutterHit {
+    Column(String),
+    LineNumber,
+}
+
+impl Editor {
+    /// Adds a gutter column, replacing the one with the same id.
+    pub fn add_gutter_column(&mut self, column: GutterColumn) {
+        match self.gutter_columns.iter_mut().find(|c| c.id == column.id) {
+            Some(existing) => *existing = column,
+            None => self.gutter_columns.push(column),
+        }
+    }
+
+    pub fn remove_gutter_column(&mut self, id: &str) -> Option<GutterColumn> {
+        let index = self.gutter_columns.iter().position(|c| c.id == id)?;
+        Some(self.gutter_columns.remove(index))
+    }
+
+    pub fn gutter_column(&self, id: &str) -> Option<&GutterColumn> {
+        self.gutter_columns.iter().find(|c| c.id == id)
+    }
+
+    pub fn gutter_column_mut(&mut self, id: &str) -> Option<&mut GutterColumn> {
+        self.gutter_columns.iter_mut().find(|c| c.id == id)
+    }
+
+    /// Total width of the host gutter columns and the breakpoint column.
+    pub(crate) fn gutter_columns_width(&self) -> usize {
+        let columns: usize = self.gutter_columns.iter().map(|c| c.width).sum();
+        columns + self.breakpoint_gutter_width()
+    }
+
+    /// Handles a click in the gutter: a click on a line number selects the
+    /// line, so dragging from there extends the selection line-wise, and a
+    /// click on a host column reports [`EditorEvent::GutterClicked`].
+    /// Returns false if the click is not on the gutter of a line.
+    pub fn handle_gutter_click(&mut self, mouse_x: u16, mouse_y: u16, area: &Rect) -> bool {
+        if mouse_y < area.top() || mouse_y >= area.bottom() || mouse_x < area.left() {
+            return false;
+        }
+        let visual_row = self.offset_y + (mouse_y - area.top()) as usize;
+        let Some(line) = self.line_for_visual_row(visual_row) else {
+            return false;
+        };
+        match self.gutter_hit(mouse_x, &self.layout(*area)) {
+            Some(GutterHit::Column(column_id)) => {
+                self.push_event(EditorEvent::GutterClicked { line, column_id });
+            }
+            Some(GutterHit::LineNumber) => {
+                let code = self.code.borrow();
+                let anchor = code.line_to_char(line);
+                let (start, end) = code.line_boundaries(anchor);
+                drop(code);
+                if let Some(selection) = self.selection {
+                    self.remember_selection(selection, self.cursor);
+                }
+                self.selection = Some(Selection::from_anchor_and_cursor(start, end));
+                self.cursor = end;
+                self.selection_snap = SelectionSnap::Line { anchor };
+                self.drag_autoscroll = None;
+                self.reset_cursor_blink();
+            }
+            None => return false,
+        }
+        true
+    }
+
+    fn gutter_hit(&self, x: u16, layout: &EditorLayout) -> Option<GutterHit> {
+        let x = (x - layout.columns.x) as usize;
+        let mut left = 0;
+        for column in &self.gutter_columns {
+            if x < left + column.width {
+                return Some(GutterHit::Column(column.id.clone()));
+            }
+            left += column.width;
+        }
+        if x < left + self.breakpoint_gutter_width() {
+            return Some(GutterHit::Column(BREAKPOINT_COLUMN.to_string()));
+        }
+        let numbers_end = (layout.line_numbers.right() - layout.columns.x) as usize;
+        (self.show_line_numbers && x < numbers_end).then_some(GutterHit::LineNumber)
+    }
+}
@@ -5,6 +5,8 @@ pub struct History {
     index: usize,
     max_items: usize,
     edits: VecDeque<EditBatch>,
+    /// Undo position of the saved content, `None` once it can't be reached
+    saved: Option<usize>,
 }
 
 impl History {
@@ -13,6 +15,7 @@ impl History {
             index: 0,
             max_items,
             edits: VecDeque::new(),
+            saved: Some(0),
         }
     }
 
@@ -20,16 +23,48 @@ impl History {
         while self.edits.len() > self.index {
             self.edits.pop_back();
         }
+        // the saved content was in the redo steps just dropped
+        self.saved = self.saved.filter(|&saved| saved <= self.index);
 
         if self.edits.len() == self.max_items {
             self.edits.pop_front();
             self.index -= 1;
+            self.saved = self.saved.and_then(|saved| saved.checked_sub(1));
         }
 
         self.edits.push_back(batch);
         self.index += 1;
     }
 
+    /// Stored batches and the undo position, see [`History::restore`].
+    pub(crate) fn batches(&self) -> (Vec<EditBatch>, usize) {
+        (self.edits.iter().cloned().collect(), self.index)
+    }
+
+    /// Replaces the stored batches, keeping the newest `max_items` and
+    /// clamping the undo position to them.
+    pub(crate) fn restore(&mut self, batches: Vec<EditBatch>, index: usize) {
+        let dropped = batches.len().saturating_sub(self.max_items);
+        self.edits = batches.into_iter().skip(dropped).collect();
+        self.index = index.saturating_sub(dropped).min(self.edits.len());
+        self.saved = None;
+    }
+
+    /// Remembers the current undo position as the saved content.
+    pub(crate) fn mark_saved(&mut self) {
+        self.saved = Some(self.index);
+    }
+
+    /// Whether undo and redo led back to the saved content.
+    pub(crate) fn is_saved(&self) -> bool {
+        self.saved == Some(self.index)
+    }
+
+    /// Whether the undo position lies within the stored batches.
+    pub(crate) fn is_consistent(&self) -> bool {
+        self.index <= self.edits.len() && self.edits.len() <= self.max_items
+    }
+
     pub fn undo(&mut self) -> Option<EditBatch> {
         if self.index == 0 {
             None
@@ -0,0 +1,32 @@
+use std::time::{Duration, Instant};
+
+/// Where the mouse pointer rests over the text, reported as a hover once it
+/// stayed there for the hover delay.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Hover {
+    pub(crate) offset: usize,
+    pub(crate) screen_pos: (u16, u16),
+    since: Instant,
+    /// Whether the hover event was emitted
+    pub(crate) reported: bool,
+}
+
+impl Hover {
+    pub(crate) fn new(offset: usize, screen_pos: (u16, u16), now: Instant) -> Self {
+        Self {
+            offset,
+            screen_pos,
+            since: now,
+            reported: false,
+        }
+    }
+
+    /// When the hover is due, or `None` once it was reported.
+    pub(crate) fn deadline(&self, delay: Duration) -> Option<Instant> {
+        (!self.reported).then_some(self.since + delay)
+    }
+
+    pub(crate) fn is_due(&self, delay: Duration, now: Instant) -> bool {
+        self.deadline(delay).is_some_and(|deadline| now >= deadline)
+    }
+}
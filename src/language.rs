@@ -0,0 +1,180 @@
+use crate::code::Code;
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+/// Per-language editing settings: indentation, comments, brackets and words.
+///
+/// Every `Code` resolves its config from the registry when it is created.
+/// Hosts can [`register`] configs for their own languages or override the
+/// built-in ones, or replace the config of a single buffer with
+/// `Code::set_language_config`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LanguageConfig {
+    /// One level of indentation, e.g. `"    "` or `"\t"`
+    pub indent: String,
+    /// Line comment prefix, e.g. `"//"`; empty for plain text, which turns
+    /// `ToggleComment` off
+    pub comment: String,
+    /// Matching bracket pairs, opening character first
+    pub brackets: Vec<(char, char)>,
+    /// Whether Enter continues list items (`- `, `1. `, `- [ ] `)
+    pub list_continuation: bool,
+    /// Characters besides letters, digits and `_` that are part of words,
+    /// e.g. `-` in CSS; used by double-click selection and word navigation
+    pub word_chars: Vec<char>,
+    /// Values `CycleValue` steps through, e.g. `["true", "false"]`; the last
+    /// one is followed by the first
+    pub cycles: Vec<Vec<String>>,
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        Self {
+            indent: "  ".to_string(),
+            comment: "//".to_string(),
+            brackets: vec![('(', ')'), ('[', ']'), ('{', '}')],
+            list_continuation: false,
+            word_chars: Vec::new(),
+            cycles: cycles(&[&["true", "false"], &["==", "!="]]),
+        }
+    }
+}
+
+/// Languages whose logical operators are `&&` and `||`.
+const C_LIKE: &[&str] = &[
+    "c",
+    "cpp",
+    "c_sharp",
+    "go",
+    "java",
+    "javascript",
+    "typescript",
+    "kotlin",
+    "php",
+    "rust",
+];
+
+/// Languages without a grammar that still have built-in settings.
+const CONFIGURED_WITHOUT_GRAMMAR: &[&str] = &[
+    "lua", "php", "kotlin", "zig", "erlang", "sql", "scss", "lisp", "clojure", "ruby",
+];
+
+impl LanguageConfig {
+    /// Settings for plain text: no comment prefix, so `ToggleComment` does
+    /// nothing.
+    pub fn plain_text() -> Self {
+        Self {
+            comment: String::new(),
+            ..Self::default()
+        }
+    }
+
+    /// The built-in config for `lang`, ignoring registered overrides.
+    /// Languages without a grammar or settings of their own, such as
+    /// `"text"`, get [`LanguageConfig::plain_text`].
+    pub fn builtin(lang: &str) -> Self {
+        if Code::get_language(lang).is_none() && !CONFIGURED_WITHOUT_GRAMMAR.contains(&lang) {
+            return Self::plain_text();
+        }
+        let indent = match lang {
+            "rust" | "python" | "php" | "toml" | "c" | "cpp" | "zig" | "kotlin" | "erlang"
+            | "html" | "sql" => "    ",
+            "go" | "c_sharp" => "\t",
+            _ => "  ",
+        };
+        let comment = match lang {
+            "python" | "shell" => "#",
+            "lua" => "--",
+            _ => "//",
+        };
+        let mut config = Self {
+            indent: indent.to_string(),
+            comment: comment.to_string(),
+            ..Self::default()
+        };
+
+        match lang {
+            "javascript" | "typescript" => config.word_chars = vec!['$'],
+            "html" => config.brackets.push(('<', '>')),
+            "css" | "scss" | "lisp" | "clojure" => config.word_chars = vec!['-'],
+            "ruby" => config.word_chars = vec![':'],
+            "markdown" => config.list_continuation = true,
+            _ => {}
+        }
+
+        let extra_cycles: &[&[&str]] = match lang {
+            "rust" => &[&["Some", "None"], &["Ok", "Err"], &["pub", "pub(crate)"]],
+            "python" => &[&["True", "False"], &["and", "or"], &["is", "is not"]],
+            "javascript" | "typescript" => &[&["const", "let", "var"], &["===", "!=="]],
+            "lua" => &[&["and", "or"]],
+            "yaml" => &[&["yes", "no"], &["on", "off"]],
+            _ => &[],
+        };
+        if C_LIKE.contains(&lang) {
+            config.cycles.extend(cycles(&[&["&&", "||"]]));
+        }
+        config.cycles.extend(cycles(extra_cycles));
+
+        config
+    }
+}
+
+fn cycles(values: &[&[&str]]) -> Vec<Vec<String>> {
+    values
+        .iter()
+        .map(|cycle| cycle.iter().map(|value| value.to_string()).collect())
+        .collect()
+}
+
+static REGISTRY: LazyLock<RwLock<HashMap<String, LanguageConfig>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Registers `config` for `lang`, replacing the built-in or any earlier
+/// registered config. Buffers created afterwards pick it up.
+pub fn register(lang: &str, config: LanguageConfig) {
+    REGISTRY
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(lang.to_string(), config);
+}
+
+/// Removes a registered config, falling back to the built-in one again.
+pub fn unregister(lang: &str) -> Option<LanguageConfig> {
+    REGISTRY
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(lang)
+}
+
+/// The config for `lang`: the registered one if any, else the built-in one.
+pub fn config(lang: &str) -> LanguageConfig {
+    REGISTRY
+        .read()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(lang)
+        .cloned()
+        .unwrap_or_else(|| LanguageConfig::builtin(lang))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registered_config_overrides_builtin() {
+        assert_eq!(config("python").comment, "#");
+        assert_eq!(config("go").indent, "\t");
+        assert!(config("markdown").list_continuation);
+        assert_eq!(config("css").word_chars, vec!['-']);
+
+        let dsl = LanguageConfig {
+            indent: "   ".to_string(),
+            comment: ";".to_string(),
+            ..LanguageConfig::default()
+        };
+        register("test_dsl", dsl.clone());
+        assert_eq!(config("test_dsl"), dsl);
+        assert_eq!(unregister("test_dsl"), Some(dsl));
+        assert_eq!(config("test_dsl"), LanguageConfig::builtin("test_dsl"));
+    }
+}
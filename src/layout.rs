@@ -0,0 +1,82 @@
+use crate::code::{RopeGraphemes, grapheme_width};
+use crate::width::{AmbiguousWidth, global_ambiguous_width};
+use ratatui_core::layout::Rect;
+use ropey::Rope;
+use std::cell::{Cell, RefCell};
+
+/// Where the parts of an editor are drawn in an area, from
+/// [`Editor::layout`](crate::editor::Editor::layout). Parts that are turned
+/// off, or cut off by a narrow area, are empty.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EditorLayout {
+    /// Width of everything left of the text: gutter columns, line numbers
+    /// and fold indicators
+    pub gutter_width: u16,
+    /// Host gutter columns and the breakpoint column, at the left edge
+    pub columns: Rect,
+    /// Line numbers and the padding after them
+    pub line_numbers: Rect,
+    /// Fold indicators, between the line numbers and the text
+    pub fold_gutter: Rect,
+    /// The text, without the gutter and scrollbar
+    pub text_area: Rect,
+    /// The scrollbar at the right edge, if shown
+    pub scrollbar: Option<Rect>,
+}
+
+/// Length in chars and display width of a line, without its line break.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineMetrics {
+    pub len: usize,
+    pub width: usize,
+}
+
+/// Line metrics measured on first use and kept until an edit touches the line.
+///
+/// Rendering, cursor placement and mouse hits ask for the same lines many
+/// times per frame; measuring a line walks all of its graphemes.
+#[derive(Debug, Default)]
+pub(crate) struct LineMetricsCache {
+    lines: RefCell<Vec<Option<LineMetrics>>>,
+    /// Ambiguous width the cached widths were measured with
+    ambiguous_width: Cell<AmbiguousWidth>,
+}
+
+impl LineMetricsCache {
+    pub(crate) fn get(&self, content: &Rope, line_idx: usize) -> LineMetrics {
+        let mut lines = self.lines.borrow_mut();
+        let current = global_ambiguous_width();
+        if self.ambiguous_width.replace(current) != current {
+            lines.clear();
+        }
+        lines.resize(content.len_lines(), None);
+        if let Some(metrics) = lines[line_idx] {
+            return metrics;
+        }
+
+        let line = content.line(line_idx);
+        let mut len = line.len_chars();
+        if line_idx + 1 < content.len_lines() {
+            len = len.saturating_sub(1);
+        }
+        let metrics = LineMetrics {
+            len,
+            width: RopeGraphemes::new(&line.slice(..len))
+                .map(grapheme_width)
+                .sum(),
+        };
+        lines[line_idx] = Some(metrics);
+        metrics
+    }
+
+    /// Forgets lines `first..=old_last` replaced by `first..=new_last` in an
+    /// edit. Lines before and after keep their metrics.
+    pub(crate) fn on_edit(&mut self, first: usize, old_last: usize, new_last: usize) {
+        let lines = self.lines.get_mut();
+        if first >= lines.len() {
+            return;
+        }
+        let end = (old_last + 1).min(lines.len());
+        lines.splice(first..end, std::iter::repeat_n(None, new_last - first + 1));
+    }
+}
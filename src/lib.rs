@@ -1,16 +1,58 @@
 pub mod actions;
+mod annotations;
+pub mod anchor;
+pub mod autoscroll;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod breakpoints;
+pub mod builder;
 pub mod click;
 pub mod code;
+mod code_cell;
+pub mod command;
+mod bidi;
 mod diff;
+pub mod diff_editor;
 #[cfg(feature = "bench-internals")]
 pub use diff::{bench_build_diff_rows, bench_build_diff_rows_fast};
 pub mod editor;
 #[cfg(feature = "crossterm")]
 pub mod editor_crossterm;
+pub mod editor_view;
+pub mod encoding;
+pub mod events;
+mod export;
+pub mod flash;
+#[cfg(feature = "fuzz")]
+pub mod fuzz;
+pub mod gutter;
 pub mod history;
+mod hover;
+pub mod language;
+pub mod layout;
+mod links;
+mod list;
+pub mod marks;
+mod number;
+pub mod picker;
+pub mod preedit;
+/// `use ratatui_code_editor::prelude::*;` imports the types most hosts need.
+pub mod prelude;
+mod predicates;
+mod prefetch;
 pub mod render;
+pub mod search;
 pub mod selection;
+pub mod semantic_tokens;
+mod smooth_scroll;
+pub mod snapshot;
+pub mod snippet;
+pub mod spell;
+#[cfg(feature = "crossterm")]
+pub mod testing;
 pub mod theme;
 pub mod types;
 pub mod utils;
 mod view;
+pub mod width;
+pub mod workspace;
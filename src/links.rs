@@ -0,0 +1,216 @@
+use crate::bidi::BidiLine;
+use crate::editor::Editor;
+use crate::events::EditorEvent;
+use crate::utils::is_word_char;
+use crate::width::str_width;
+use ratatui_core::buffer::{Buffer, CellDiffOption};
+use ratatui_core::style::{Modifier, Style};
+use std::num::NonZeroU16;
+
+/// Schemes recognised at the start of a URL.
+const SCHEMES: [&str; 5] = ["https://", "http://", "file://", "ftp://", "mailto:"];
+
+/// Prefixes of file paths relative to the working or home directory.
+const PATH_PREFIXES: [&str; 3] = ["./", "../", "~/"];
+
+/// URLs and file paths in `line` as character ranges. A path has to start
+/// with `/`, `./`, `../` or `~/` so that divisions and comments are not
+/// taken for paths.
+pub(crate) fn find_links(line: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<char> = line.chars().collect();
+    let mut links = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let at_boundary = i == 0 || !is_word_char(chars[i - 1]) && chars[i - 1] != '/';
+        let link = at_boundary.then(|| link_at(&chars, i)).flatten();
+        match link {
+            Some(end) => {
+                links.push((i, end));
+                i = end;
+            }
+            None => i += 1,
+        }
+    }
+    links
+}
+
+/// End of the URL or path starting at `start`, if one does.
+fn link_at(chars: &[char], start: usize) -> Option<usize> {
+    let rest = &chars[start..];
+    let is_url = SCHEMES.iter().any(|scheme| starts_with(rest, scheme));
+    let is_path =
+        !is_url && (PATH_PREFIXES.iter().any(|prefix| starts_with(rest, prefix)) || rest[0] == '/');
+    if !is_url && !is_path {
+        return None;
+    }
+    let mut end = start;
+    while end < chars.len() && is_link_char(chars[end], is_url) {
+        end += 1;
+    }
+    // punctuation ending a sentence and unbalanced closing brackets are not
+    // part of the link
+    while end > start {
+        let last = chars[end - 1];
+        let text = &chars[start..end - 1];
+        let count = |c: char| text.iter().filter(|&&t| t == c).count();
+        let unbalanced = match last {
+            ')' => count('(') <= count(')'),
+            ']' => count('[') <= count(']'),
+            _ => false,
+        };
+        if !(unbalanced || ".,;:!?'\"".contains(last)) {
+            break;
+        }
+        end -= 1;
+    }
+
+    let text: String = chars[start..end].iter().collect();
+    let valid = if is_url {
+        SCHEMES
+            .iter()
+            .any(|scheme| text.len() > scheme.len() && text.starts_with(scheme))
+    } else {
+        // an absolute path needs two named segments, e.g. `/usr/bin`
+        let named = text.split('/').filter(|s| s.chars().any(is_word_char));
+        let min = if text.starts_with('/') { 2 } else { 1 };
+        named.count() >= min
+    };
+    valid.then_some(end)
+}
+
+fn starts_with(chars: &[char], prefix: &str) -> bool {
+    prefix.chars().count() <= chars.len() && prefix.chars().zip(chars).all(|(p, &c)| p == c)
+}
+
+fn is_link_char(c: char, is_url: bool) -> bool {
+    if c.is_whitespace() || c.is_control() || "<>\"`{}".contains(c) {
+        return false;
+    }
+    is_url || !"'(),;[]".contains(c)
+}
+
+impl Editor {
+    /// Sets whether URLs and file paths are underlined and opened with
+    /// Ctrl+Click, see [`EditorEvent::OpenLink`]. Enabled by default.
+    pub fn set_link_detection(&mut self, enabled: bool) {
+        self.link_detection = enabled;
+    }
+
+    /// Sets whether links are written as OSC 8 terminal hyperlinks, so the
+    /// terminal itself can open them. Only enable this for backends and
+    /// terminals that support it; disabled by default.
+    pub fn set_terminal_hyperlinks(&mut self, enabled: bool) {
+        self.terminal_hyperlinks = enabled;
+    }
+
+    /// Links on line `line_idx` as character ranges of the document and
+    /// their text.
+    pub fn line_links(&self, line_idx: usize) -> Vec<(usize, usize, String)> {
+        let code = self.code.borrow();
+        if !self.link_detection || line_idx >= code.len_lines() {
+            return Vec::new();
+        }
+        let line_start = code.line_to_char(line_idx);
+        let line = code.line(line_idx).to_string();
+        find_links(&line)
+            .into_iter()
+            .map(|(start, end)| {
+                let url = line.chars().skip(start).take(end - start).collect();
+                (line_start + start, line_start + end, url)
+            })
+            .collect()
+    }
+
+    /// Link containing the character at `offset`.
+    pub fn link_at(&self, offset: usize) -> Option<String> {
+        let line_idx = self.code.borrow().char_to_line(offset);
+        self.line_links(line_idx)
+            .into_iter()
+            .find(|&(start, end, _)| offset >= start && offset < end)
+            .map(|(_, _, url)| url)
+    }
+
+    /// Reports [`EditorEvent::OpenLink`] if there is a link at `offset`, as
+    /// Ctrl+Click does. Returns false if there is none.
+    pub fn open_link_at(&mut self, offset: usize) -> bool {
+        let Some(url) = self.link_at(offset) else {
+            return false;
+        };
+        self.push_event(EditorEvent::OpenLink(url));
+        true
+    }
+
+    /// Wraps the links drawn on row `y` in OSC 8 hyperlink sequences. The
+    /// opening sequence goes into the first cell of a link and the closing
+    /// one into its last cell, both forced to one column so the buffer diff
+    /// is not thrown off by the escape characters. The line is drawn from
+    /// column `offset_x` at `text_x`.
+    pub(crate) fn render_hyperlinks(
+        &self,
+        line_idx: usize,
+        (offset_x, text_x): (usize, u16),
+        y: u16,
+        width: usize,
+        buf: &mut Buffer,
+    ) {
+        let links = self.line_links(line_idx);
+        let code = self.code.borrow();
+        if links.is_empty() || BidiLine::for_line(&code, line_idx).is_some() {
+            return;
+        }
+        let line_start = code.line_to_char(line_idx);
+        let visible_start = line_start + offset_x.min(code.line_len(line_idx));
+        let one = CellDiffOption::ForcedWidth(NonZeroU16::MIN);
+        for (start, end, url) in links {
+            // cells only map to characters one to one for narrow text
+            if start < visible_start || str_width(&url) != end - start {
+                continue;
+            }
+            let x = str_width(&code.char_slice(visible_start, start).to_string());
+            let len = (end - start).min(width.saturating_sub(x));
+            if len == 0 {
+                continue;
+            }
+            let first = (text_x + x as u16, y);
+            let last = (first.0 + len as u16 - 1, y);
+            let open = format!("\x1b]8;;{url}\x1b\\{}", buf[first].symbol());
+            buf[first].set_symbol(&open).set_diff_option(one);
+            let close = format!("{}\x1b]8;;\x1b\\", buf[last].symbol());
+            buf[last].set_symbol(&close).set_diff_option(one);
+        }
+    }
+
+    pub(crate) fn link_style(&self) -> Style {
+        Style::default()
+            .add_modifier(Modifier::UNDERLINED)
+            .patch(self.theme_style("link"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(line: &str) -> Vec<String> {
+        find_links(line)
+            .into_iter()
+            .map(|(start, end)| line.chars().skip(start).take(end - start).collect())
+            .collect()
+    }
+
+    #[test]
+    fn finds_urls_and_paths() {
+        assert_eq!(
+            links("see https://example.com/a?b=1, or (http://x.io/wiki_(x)))."),
+            vec!["https://example.com/a?b=1", "http://x.io/wiki_(x)"]
+        );
+        assert_eq!(
+            links("open ./src/main.rs: or ~/notes.md and /usr/bin."),
+            vec!["./src/main.rs", "~/notes.md", "/usr/bin"]
+        );
+        assert_eq!(
+            links("a / b, 1/2, // comment, /tmp, http://"),
+            Vec::<String>::new()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+/// The start of a list item line such as `  - [ ] `, `1. ` or `* `.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ListItem {
+    pub(crate) indent: String,
+    /// Bullet character or the number of an ordered item with its delimiter
+    pub(crate) marker: Marker,
+    /// `Some(checked)` for task list items
+    pub(crate) checkbox: Option<bool>,
+    /// Byte length of indentation, marker and checkbox including spaces
+    pub(crate) prefix_len: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Marker {
+    Bullet(char),
+    Number(u64, char),
+}
+
+impl ListItem {
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let indent_len = line.len() - line.trim_start_matches([' ', '\t']).len();
+        let rest = &line[indent_len..];
+
+        let (marker, marker_len) = match rest.chars().next()? {
+            c @ ('-' | '*' | '+') => (Marker::Bullet(c), 1),
+            _ => {
+                let digits =
+                    rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+                let delimiter = rest[digits..].chars().next()?;
+                if digits == 0 || digits > 9 || !matches!(delimiter, '.' | ')') {
+                    return None;
+                }
+                (
+                    Marker::Number(rest[..digits].parse().ok()?, delimiter),
+                    digits + 1,
+                )
+            }
+        };
+        let after = &rest[marker_len..];
+        if !after.starts_with(' ') && !after.is_empty() {
+            return None;
+        }
+        let mut prefix_len =
+            indent_len + marker_len + after.len() - after.trim_start_matches(' ').len();
+
+        let body = &line[prefix_len..];
+        let checkbox = ["[ ]", "[x]", "[X]"]
+            .iter()
+            .find(|box_| {
+                body.starts_with(*box_) && body[3..].chars().next().is_none_or(|c| c == ' ')
+            })
+            .map(|box_| *box_ != "[ ]");
+        if checkbox.is_some() {
+            let after = &body[3..];
+            prefix_len += 3 + after.len() - after.trim_start_matches(' ').len();
+        }
+
+        Some(Self {
+            indent: line[..indent_len].to_string(),
+            marker,
+            checkbox,
+            prefix_len,
+        })
+    }
+
+    /// Prefix for the item following this one: the next number, and an
+    /// unchecked box for task items.
+    pub(crate) fn next_prefix(&self) -> String {
+        let marker = match self.marker {
+            Marker::Bullet(c) => c.to_string(),
+            Marker::Number(n, delimiter) => format!("{}{delimiter}", n + 1),
+        };
+        let checkbox = if self.checkbox.is_some() { "[ ] " } else { "" };
+        format!("{}{marker} {checkbox}", self.indent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_list_item() {
+        let item = ListItem::parse("  - [x] done").unwrap();
+        assert_eq!(item.indent, "  ");
+        assert_eq!(item.marker, Marker::Bullet('-'));
+        assert_eq!(item.checkbox, Some(true));
+        assert_eq!(item.prefix_len, 8);
+        assert_eq!(item.next_prefix(), "  - [ ] ");
+
+        let item = ListItem::parse("9) nine").unwrap();
+        assert_eq!(item.next_prefix(), "10) ");
+        assert_eq!(ListItem::parse("-").unwrap().prefix_len, 1);
+
+        assert!(ListItem::parse("-not a list").is_none());
+        assert!(ListItem::parse("1.5 apples").is_none());
+        assert!(ListItem::parse("text").is_none());
+    }
+}
@@ -0,0 +1,138 @@
+use crate::anchor::{Anchor, AnchorBias};
+use crate::code::Code;
+use ratatui_core::style::{Color, Style};
+
+/// A styled interval of the document that follows edits.
+///
+/// The style is patched over the syntax style, so a mark may set only a
+/// background, or only an underline to leave the code colors visible.
+///
+/// Text inserted at either boundary is kept outside the mark, and a mark whose
+/// text is removed entirely collapses to an empty range.
+#[derive(Debug, Clone)]
+pub struct Mark {
+    start: Anchor,
+    end: Anchor,
+    pub style: Style,
+}
+
+impl Mark {
+    pub(crate) fn new(code: &mut Code, start: usize, end: usize, style: Style) -> Self {
+        Self {
+            start: code.create_anchor(start.min(end), AnchorBias::Right),
+            end: code.create_anchor(start.max(end), AnchorBias::Left),
+            style,
+        }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start.position()
+    }
+
+    pub fn end(&self) -> usize {
+        self.end.position().max(self.start())
+    }
+
+    pub fn contains(&self, offset: usize) -> bool {
+        offset >= self.start() && offset < self.end()
+    }
+
+    pub(crate) fn as_tuple(&self) -> (usize, usize, Style) {
+        (self.start(), self.end(), self.style)
+    }
+
+    /// The mark with its background color, or `Color::Reset` if it has none.
+    pub(crate) fn as_color_tuple(&self) -> (usize, usize, Color) {
+        let color = self.style.bg.unwrap_or(Color::Reset);
+        (self.start(), self.end(), color)
+    }
+}
+
+/// Name of the layer used by [`Editor::set_marks`](crate::editor::Editor::set_marks).
+pub const DEFAULT_MARK_LAYER: &str = "marks";
+
+/// A named group of marks managed and cleared together.
+///
+/// Layers are drawn from the lowest to the highest priority, so marks of a
+/// higher priority layer are painted over lower ones.
+#[derive(Debug, Clone)]
+pub(crate) struct MarkLayer {
+    pub(crate) name: String,
+    pub(crate) priority: i32,
+    pub(crate) marks: Vec<Mark>,
+}
+
+/// Ordered collection of mark layers.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MarkLayers {
+    layers: Vec<MarkLayer>,
+    /// Bumped whenever a layer may have been changed
+    version: u64,
+    /// Default layer ranges as passed to `set_marks` and `update_marks`, for
+    /// the deprecated `Editor::get_marks`
+    pub(crate) as_set: Option<Vec<(usize, usize, Color)>>,
+}
+
+impl MarkLayers {
+    pub(crate) fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<&MarkLayer> {
+        self.layers.iter().find(|layer| layer.name == name)
+    }
+
+    /// Returns the layer, creating an empty one with priority 0 if needed.
+    pub(crate) fn get_or_insert(&mut self, name: &str) -> &mut MarkLayer {
+        self.version += 1;
+        let idx = match self.layers.iter().position(|layer| layer.name == name) {
+            Some(idx) => idx,
+            None => {
+                self.layers.push(MarkLayer {
+                    name: name.to_string(),
+                    priority: 0,
+                    marks: Vec::new(),
+                });
+                self.sort();
+                self.layers
+                    .iter()
+                    .position(|layer| layer.name == name)
+                    .unwrap()
+            }
+        };
+        &mut self.layers[idx]
+    }
+
+    pub(crate) fn set_priority(&mut self, name: &str, priority: i32) {
+        self.get_or_insert(name).priority = priority;
+        self.sort();
+    }
+
+    pub(crate) fn remove(&mut self, name: &str) {
+        self.version += 1;
+        self.layers.retain(|layer| layer.name != name);
+        if name == DEFAULT_MARK_LAYER {
+            self.as_set = None;
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.version += 1;
+        self.layers.clear();
+        self.as_set = None;
+    }
+
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.layers.iter().map(|layer| layer.name.as_str())
+    }
+
+    /// All marks in paint order, lowest priority first.
+    pub(crate) fn iter(&self) -> impl Iterator<Item = &Mark> {
+        self.layers.iter().flat_map(|layer| layer.marks.iter())
+    }
+
+    fn sort(&mut self) {
+        // Stable, so layers with equal priority keep their creation order
+        self.layers.sort_by_key(|layer| layer.priority);
+    }
+}
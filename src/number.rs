@@ -0,0 +1,126 @@
+use crate::editor::Editor;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Decimal, float, hex, binary and octal literals with an optional sign.
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"-?(?:0[xX][0-9a-fA-F]+|0[bB][01]+|0[oO][0-7]+|[0-9]+(?:\.[0-9]+)?)").unwrap()
+});
+
+impl Editor {
+    /// Sets the amount `IncrementNumber` and `DecrementNumber` add or
+    /// subtract per count, 1 by default.
+    pub fn set_number_step(&mut self, step: i64) {
+        self.number_step = step;
+    }
+
+    pub fn number_step(&self) -> i64 {
+        self.number_step
+    }
+}
+
+/// A number literal in a line, e.g. `0x0f`, `-12` or `3.50`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct NumberLiteral {
+    /// Byte range in the line, including the sign
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    text: String,
+}
+
+impl NumberLiteral {
+    /// The number at or after byte `col` of `line`, like Vim's `CTRL-A`. A
+    /// `-` is a sign unless it follows a word or a closing bracket, as in
+    /// `a-1`.
+    pub(crate) fn find(line: &str, col: usize) -> Option<Self> {
+        NUMBER.find_iter(line).find_map(|m| {
+            let mut start = m.start();
+            let after_operand = line[..start]
+                .chars()
+                .next_back()
+                .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | ')' | ']'));
+            if m.as_str().starts_with('-') && after_operand {
+                start += 1;
+            }
+            (m.end() > col).then(|| Self {
+                start,
+                end: m.end(),
+                text: line[start..m.end()].to_string(),
+            })
+        })
+    }
+
+    /// The literal with `delta` added, keeping its prefix, letter case,
+    /// zero padding and number of decimals. `None` on overflow.
+    pub(crate) fn add(&self, delta: i64) -> Option<String> {
+        let (negative, unsigned) = match self.text.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, self.text.as_str()),
+        };
+        let (prefix, digits, radix) = match unsigned.get(..2) {
+            Some("0x" | "0X") => (&unsigned[..2], &unsigned[2..], 16),
+            Some("0b" | "0B") => (&unsigned[..2], &unsigned[2..], 2),
+            Some("0o" | "0O") => (&unsigned[..2], &unsigned[2..], 8),
+            _ => ("", unsigned, 10),
+        };
+        // floats are added as integers scaled by their decimals
+        let (int, decimals) = digits.split_once('.').unwrap_or((digits, ""));
+        let scale = 10i128.checked_pow(decimals.len() as u32)?;
+        let magnitude = i128::from_str_radix(&format!("{int}{decimals}"), radix).ok()?;
+        let value = if negative { -magnitude } else { magnitude };
+        let value = value.checked_add(i128::from(delta).checked_mul(scale)?)?;
+
+        let magnitude = value.unsigned_abs();
+        let mut formatted = match radix {
+            16 if digits.chars().any(|c| c.is_ascii_uppercase()) => format!("{magnitude:X}"),
+            16 => format!("{magnitude:x}"),
+            2 => format!("{magnitude:b}"),
+            8 => format!("{magnitude:o}"),
+            _ => magnitude.to_string(),
+        };
+        // zero padding to the old width, for prefixed literals and `007`
+        let padded = !prefix.is_empty() || (int.len() > 1 && int.starts_with('0'));
+        let width = if padded { int.len() } else { 1 } + decimals.len();
+        if formatted.len() < width {
+            formatted = format!("{}{formatted}", "0".repeat(width - formatted.len()));
+        }
+        if !decimals.is_empty() {
+            formatted.insert(formatted.len() - decimals.len(), '.');
+        }
+        let sign = if value < 0 { "-" } else { "" };
+        Some(format!("{sign}{prefix}{formatted}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn add(line: &str, col: usize, delta: i64) -> String {
+        let number = NumberLiteral::find(line, col).unwrap();
+        let text = number.add(delta).unwrap();
+        format!("{}{text}{}", &line[..number.start], &line[number.end..])
+    }
+
+    #[test]
+    fn test_number_literals() {
+        assert_eq!(add("x = 41;", 0, 1), "x = 42;");
+        assert_eq!(add("a = 1, b = 9", 6, 1), "a = 1, b = 10");
+        assert_eq!(add("-1", 0, 3), "2");
+        assert_eq!(add("x-1", 0, -2), "x--1");
+        assert_eq!(add("0x0f", 1, 1), "0x10");
+        assert_eq!(add("0xFF", 0, 1), "0x100");
+        assert_eq!(add("0b0110", 0, -1), "0b0101");
+        assert_eq!(add("007", 0, 1), "008");
+        assert_eq!(add("1.50", 0, 1), "2.50");
+        assert_eq!(add("0.5", 0, -1), "-0.5");
+        assert_eq!(add("v1.9", 0, 10), "v11.9");
+        assert!(NumberLiteral::find("x = 1", 5).is_none());
+        assert!(
+            NumberLiteral::find("99999999999999999999999999999999999999999", 0)
+                .unwrap()
+                .add(1)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,252 @@
+use crate::code::Code;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::style::{Modifier, Style};
+use ratatui_core::widgets::Widget;
+use ratatui_widgets::block::Block;
+use ratatui_widgets::clear::Clear;
+
+/// Most rows of items shown by the picker overlay.
+const PICKER_MAX_ROWS: usize = 10;
+/// Widest the picker overlay gets, borders included.
+const PICKER_MAX_WIDTH: u16 = 60;
+
+/// What the picker lists.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PickerSource {
+    /// Every non-blank line of the buffer
+    Lines,
+    /// Declarations from the syntax tree, see [`Code::symbols`]
+    Symbols,
+}
+
+/// An entry of the picker and where it jumps to.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PickerItem {
+    pub label: String,
+    pub line: usize,
+    /// Char offset the cursor moves to
+    pub offset: usize,
+}
+
+/// A fuzzy-filtered list of lines or symbols of the buffer, drawn over the editor.
+///
+/// Opened with [`Editor::open_picker`](crate::editor::Editor::open_picker);
+/// [`Editor::accept_picker`](crate::editor::Editor::accept_picker) jumps to the
+/// selected item.
+#[derive(Clone, Debug)]
+pub struct Picker {
+    source: PickerSource,
+    query: String,
+    items: Vec<PickerItem>,
+    /// Indices into `items` matching the query, best first
+    matches: Vec<usize>,
+    selected: usize,
+}
+
+impl Picker {
+    pub(crate) fn new(code: &Code, source: PickerSource) -> Self {
+        let items = match source {
+            PickerSource::Lines => (0..code.len_lines())
+                .filter_map(|line| {
+                    let text = code.line(line).to_string();
+                    let label = text.trim();
+                    if label.is_empty() {
+                        return None;
+                    }
+                    let indent = text.chars().take_while(|c| c.is_whitespace()).count();
+                    Some(PickerItem {
+                        label: format!("{}: {}", line + 1, label),
+                        line,
+                        offset: code.line_to_char(line) + indent,
+                    })
+                })
+                .collect(),
+            PickerSource::Symbols => code
+                .symbols()
+                .into_iter()
+                .map(|symbol| PickerItem {
+                    label: symbol.name,
+                    line: symbol.line,
+                    offset: symbol.offset,
+                })
+                .collect(),
+        };
+        let mut picker = Self {
+            source,
+            query: String::new(),
+            items,
+            matches: Vec::new(),
+            selected: 0,
+        };
+        picker.filter();
+        picker
+    }
+
+    pub fn source(&self) -> PickerSource {
+        self.source
+    }
+
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+
+    pub fn set_query(&mut self, query: &str) {
+        self.query = query.to_string();
+        self.filter();
+    }
+
+    pub fn push_char(&mut self, c: char) {
+        self.query.push(c);
+        self.filter();
+    }
+
+    pub fn pop_char(&mut self) {
+        self.query.pop();
+        self.filter();
+    }
+
+    /// Items matching the query, best match first.
+    pub fn matches(&self) -> impl Iterator<Item = &PickerItem> {
+        self.matches.iter().map(|&i| &self.items[i])
+    }
+
+    /// Index of the selected item within [`Picker::matches`].
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    pub fn selected_item(&self) -> Option<&PickerItem> {
+        self.matches.get(self.selected).map(|&i| &self.items[i])
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + 1) % self.matches.len();
+        }
+    }
+
+    pub fn select_prev(&mut self) {
+        if !self.matches.is_empty() {
+            self.selected = (self.selected + self.matches.len() - 1) % self.matches.len();
+        }
+    }
+
+    fn filter(&mut self) {
+        let mut scored: Vec<(i64, usize)> = self
+            .items
+            .iter()
+            .enumerate()
+            .filter_map(|(i, item)| fuzzy_score(&self.query, &item.label).map(|score| (score, i)))
+            .collect();
+        // stable, so equal scores keep document order
+        scored.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.selected = 0;
+    }
+
+    /// Draws the picker as a bordered box near the top of `area`.
+    pub(crate) fn render(&self, area: Rect, buf: &mut Buffer) {
+        let width = area.width.saturating_sub(4).min(PICKER_MAX_WIDTH);
+        let rows = self.matches.len().clamp(1, PICKER_MAX_ROWS);
+        // borders and the query line
+        let height = (rows as u16 + 3).min(area.height);
+        if width < 10 || height < 4 {
+            return;
+        }
+        let popup = Rect::new(area.x + (area.width - width) / 2, area.y + 1, width, height)
+            .intersection(area);
+
+        let title = match self.source {
+            PickerSource::Lines => " Go to line ",
+            PickerSource::Symbols => " Go to symbol ",
+        };
+        let block = Block::bordered().title(title);
+        let inner = block.inner(popup);
+        Clear.render(popup, buf);
+        block.render(popup, buf);
+
+        let text_width = inner.width as usize;
+        let query: String = format!("> {}", self.query)
+            .chars()
+            .take(text_width)
+            .collect();
+        buf.set_string(inner.x, inner.y, query, Style::default());
+
+        let visible = (inner.height as usize).saturating_sub(1);
+        let first = self.selected.saturating_sub(visible.saturating_sub(1));
+        for (row, (index, item)) in self
+            .matches()
+            .enumerate()
+            .skip(first)
+            .take(visible)
+            .enumerate()
+        {
+            let style = if index == self.selected {
+                Style::default().add_modifier(Modifier::REVERSED)
+            } else {
+                Style::default()
+            };
+            let label = format!("{:width$}", item.label, width = text_width);
+            let label: String = label.chars().take(text_width).collect();
+            buf.set_string(inner.x, inner.y + 1 + row as u16, label, style);
+        }
+    }
+}
+
+/// Scores `candidate` against `query` as a case-insensitive subsequence match.
+///
+/// Returns `None` if some query character is missing. Consecutive matches and
+/// matches at word starts score higher; an empty query matches everything.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let mut score = 0;
+    let mut candidate_chars = candidate.chars().enumerate();
+    let mut prev: Option<char> = None;
+    let mut last_match: Option<usize> = None;
+
+    for q in query.chars().flat_map(char::to_lowercase) {
+        loop {
+            let (i, c) = candidate_chars.next()?;
+            let before = prev.replace(c);
+            if !c.to_lowercase().eq(std::iter::once(q)) {
+                continue;
+            }
+            score += 1;
+            if last_match.is_some_and(|last| last + 1 == i) {
+                score += 4;
+            }
+            let word_start = match before {
+                None => true,
+                Some(b) => !b.is_alphanumeric() || (b.is_lowercase() && c.is_uppercase()),
+            };
+            if word_start {
+                score += 5;
+            }
+            if let Some(last) = last_match {
+                score -= (i - last - 1).min(3) as i64;
+            }
+            last_match = Some(i);
+            break;
+        }
+    }
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fuzzy_score_prefers_consecutive_and_word_start_matches() {
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert_eq!(fuzzy_score("xyz", "main"), None);
+
+        let compact = fuzzy_score("mai", "main").unwrap();
+        let spread = fuzzy_score("mai", "my_animal").unwrap();
+        assert!(compact > spread);
+
+        let camel = fuzzy_score("gl", "getLine").unwrap();
+        let inner = fuzzy_score("gl", "angle").unwrap();
+        assert!(camel > inner);
+    }
+}
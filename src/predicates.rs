@@ -0,0 +1,186 @@
+use regex::Regex;
+use ropey::RopeSlice;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use tree_sitter::{Node, Query, QueryMatch, QueryPredicate, QueryPredicateArg};
+
+thread_local! {
+    /// Lua patterns converted to regexes, `None` for ones that can't be converted
+    static LUA_PATTERNS: RefCell<HashMap<String, Option<Regex>>> = RefCell::new(HashMap::new());
+}
+
+/// Checks the predicates of `m` that tree-sitter leaves to the caller.
+///
+/// `#eq?`, `#match?`, `#any-of?` and their `not-`/`any-` forms are already
+/// applied by the query cursor. This adds the ones Neovim and Helix queries
+/// use: `#lua-match?`, `#contains?`, `#has-parent?` and `#has-ancestor?`,
+/// again with `not-` and `any-` forms. `#is?` and `#is-not?` always pass, as
+/// local references are highlighted separately. Unknown predicates pass too.
+pub(crate) fn satisfies_predicates(query: &Query, m: &QueryMatch, text: RopeSlice) -> bool {
+    query
+        .general_predicates(m.pattern_index)
+        .iter()
+        .all(|predicate| satisfies(predicate, m, text))
+}
+
+fn satisfies(predicate: &QueryPredicate, m: &QueryMatch, text: RopeSlice) -> bool {
+    let operator = predicate.operator.as_ref();
+    let (negated, operator) = match operator.strip_prefix("not-") {
+        Some(rest) => (true, rest),
+        None => (false, operator),
+    };
+    let (any, operator) = match operator.strip_prefix("any-") {
+        Some(rest) => (true, rest),
+        None => (false, operator),
+    };
+
+    let Some((QueryPredicateArg::Capture(index), rest)) = predicate.args.split_first() else {
+        return true;
+    };
+    let strings: Vec<&str> = rest
+        .iter()
+        .filter_map(|arg| match arg {
+            QueryPredicateArg::String(value) => Some(value.as_ref()),
+            QueryPredicateArg::Capture(_) => None,
+        })
+        .collect();
+
+    let test: Box<dyn Fn(Node) -> bool> = match operator {
+        "lua-match?" => {
+            let Some(regex) = strings.first().and_then(|pattern| lua_pattern(pattern)) else {
+                return negated;
+            };
+            Box::new(move |node| regex.is_match(&node_text(node, text)))
+        }
+        "contains?" => Box::new(|node| {
+            let node_text = node_text(node, text);
+            strings.iter().any(|value| node_text.contains(value))
+        }),
+        "has-parent?" => Box::new(|node| {
+            node.parent()
+                .is_some_and(|parent| strings.contains(&parent.kind()))
+        }),
+        "has-ancestor?" => Box::new(|node| {
+            std::iter::successors(node.parent(), Node::parent)
+                .any(|ancestor| strings.contains(&ancestor.kind()))
+        }),
+        _ => return true,
+    };
+
+    let mut nodes = m.nodes_for_capture_index(*index);
+    if any {
+        nodes.any(|node| test(node) != negated)
+    } else {
+        nodes.all(|node| test(node) != negated)
+    }
+}
+
+fn node_text(node: Node, text: RopeSlice) -> String {
+    text.byte_slice(node.byte_range()).to_string()
+}
+
+/// Compiled regex for the Lua pattern `pattern`, cached per thread.
+fn lua_pattern(pattern: &str) -> Option<Regex> {
+    LUA_PATTERNS.with(|patterns| {
+        patterns
+            .borrow_mut()
+            .entry(pattern.to_string())
+            .or_insert_with(|| Regex::new(&lua_to_regex(pattern)?).ok())
+            .clone()
+    })
+}
+
+/// Translates a Lua pattern to regex syntax, with classes in sets nested as
+/// regex allows. `%b` and `%f` have no regex counterpart and give `None`.
+fn lua_to_regex(pattern: &str) -> Option<String> {
+    let mut out = String::new();
+    let mut chars = pattern.chars().peekable();
+    let mut in_set = false;
+    while let Some(ch) = chars.next() {
+        match ch {
+            '%' => {
+                let class = chars.next()?;
+                match lua_class(class) {
+                    Some(class) => out.push_str(class),
+                    None if class == 'b' || class == 'f' => return None,
+                    None => out.push_str(&regex::escape(&class.to_string())),
+                }
+            }
+            '[' if !in_set => {
+                in_set = true;
+                out.push('[');
+                if chars.peek() == Some(&'^') {
+                    out.push(chars.next()?);
+                }
+                if chars.peek() == Some(&']') {
+                    chars.next();
+                    out.push_str("\\]");
+                }
+            }
+            ']' if in_set => {
+                in_set = false;
+                out.push(']');
+            }
+            '-' if !in_set => out.push_str("*?"),
+            '\\' | '{' | '}' | '|' => {
+                out.push('\\');
+                out.push(ch);
+            }
+            '[' | '&' | '~' if in_set => {
+                out.push('\\');
+                out.push(ch);
+            }
+            _ => out.push(ch),
+        }
+    }
+    (!in_set).then_some(out)
+}
+
+/// Regex class for the Lua class letter `class`, e.g. `%d`.
+fn lua_class(class: char) -> Option<&'static str> {
+    let regex = match class {
+        'a' => "[a-zA-Z]",
+        'A' => "[^a-zA-Z]",
+        'd' => "[0-9]",
+        'D' => "[^0-9]",
+        'l' => "[a-z]",
+        'L' => "[^a-z]",
+        'u' => "[A-Z]",
+        'U' => "[^A-Z]",
+        's' => "[\\s]",
+        'S' => "[^\\s]",
+        'w' => "[a-zA-Z0-9]",
+        'W' => "[^a-zA-Z0-9]",
+        'x' => "[0-9a-fA-F]",
+        'X' => "[^0-9a-fA-F]",
+        'p' => "[[:punct:]]",
+        'P' => "[^[:punct:]]",
+        'c' => "[[:cntrl:]]",
+        'C' => "[^[:cntrl:]]",
+        _ => return None,
+    };
+    Some(regex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lua_to_regex() {
+        assert_eq!(
+            lua_to_regex("^[A-Z][A-Z0-9_]*$").unwrap(),
+            "^[A-Z][A-Z0-9_]*$"
+        );
+        assert_eq!(lua_to_regex("^%u%w*$").unwrap(), "^[A-Z][a-zA-Z0-9]*$");
+        assert_eq!(lua_to_regex("^[%a_]").unwrap(), "^[[a-zA-Z]_]");
+        assert_eq!(lua_to_regex("^#!/").unwrap(), "^#!/");
+        assert_eq!(lua_to_regex("a.-b%.").unwrap(), "a.*?b\\.");
+        assert_eq!(lua_to_regex("{%d}").unwrap(), "\\{[0-9]\\}");
+        assert!(lua_to_regex("%b()").is_none());
+
+        let regex = lua_pattern("^/[*][*][^*].*[*]/$").unwrap();
+        assert!(regex.is_match("/** doc */"));
+        assert!(!regex.is_match("/* plain */"));
+    }
+}
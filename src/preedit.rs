@@ -0,0 +1,60 @@
+use crate::anchor::Anchor;
+use crate::code::{RopeGraphemes, grapheme_width};
+use ropey::RopeSlice;
+
+/// Uncommitted IME composition text shown inline at the cursor.
+///
+/// The composition is not part of the document until it is committed with
+/// [`Editor::commit_preedit`](crate::editor::Editor::commit_preedit). Its
+/// position is an [`Anchor`], so edits made while composing, e.g. by another
+/// editor sharing the code, move it along with the surrounding text.
+#[derive(Debug, Clone)]
+pub struct Preedit {
+    text: String,
+    /// Cursor within the composition, in chars
+    cursor: usize,
+    anchor: Anchor,
+}
+
+impl Preedit {
+    pub(crate) fn new(text: &str, cursor: usize, anchor: Anchor) -> Self {
+        let mut preedit = Self {
+            text: String::new(),
+            cursor: 0,
+            anchor,
+        };
+        preedit.update(text, cursor);
+        preedit
+    }
+
+    pub(crate) fn update(&mut self, text: &str, cursor: usize) {
+        self.text = text.to_string();
+        self.cursor = cursor.min(text.chars().count());
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Cursor position within the composition text, in chars.
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Char offset in the document the composition is drawn at.
+    pub fn position(&self) -> usize {
+        self.anchor.position()
+    }
+
+    /// Display width of the composition text before its cursor.
+    pub(crate) fn cursor_width(&self) -> usize {
+        let end = self
+            .text
+            .char_indices()
+            .nth(self.cursor)
+            .map_or(self.text.len(), |(i, _)| i);
+        RopeGraphemes::new(&RopeSlice::from(&self.text[..end]))
+            .map(grapheme_width)
+            .sum()
+    }
+}
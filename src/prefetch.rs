@@ -0,0 +1,106 @@
+use crate::editor::Editor;
+use crate::types::VisualRow;
+use std::ops::Range;
+
+/// Most lines highlighted by one [`Editor::tick`], so a tick never blocks
+/// input for long even on grammars with heavy queries.
+const PREFETCH_CHUNK_LINES: usize = 100;
+
+impl Editor {
+    /// Highlights up to `lines` lines above and below the viewport ahead of
+    /// time, a chunk per [`Editor::tick`] while [`Editor::next_deadline`]
+    /// asks for ticks right away, so fast scrolling finds them cached.
+    /// Disabled with 0, the default.
+    pub fn set_highlight_prefetch(&mut self, lines: usize) {
+        self.highlight_prefetch = lines;
+    }
+
+    pub fn highlight_prefetch(&self) -> usize {
+        self.highlight_prefetch
+    }
+
+    /// Next run of lines around the viewport that is not highlighted yet,
+    /// lines below the viewport first. `None` if there is nothing to do.
+    pub(crate) fn prefetch_range(&self) -> Option<Range<usize>> {
+        if self.highlight_prefetch == 0 || !self.borrow_code().is_highlight() {
+            return None;
+        }
+        let len_lines = self.borrow_code().len_lines();
+        let height = self.last_area.get().height as usize;
+        let top = match self.visual_row(self.offset_y) {
+            Some(VisualRow::Real { line_idx, .. }) => line_idx,
+            _ => self.offset_y.min(len_lines),
+        };
+        let below = top..(top + height + self.highlight_prefetch).min(len_lines);
+        let above = top.saturating_sub(self.highlight_prefetch)..top;
+
+        let cache = self.line_highlights_cache.borrow();
+        [below, above].into_iter().find_map(|window| {
+            let first = window.clone().find(|line| !cache.contains_key(line))?;
+            let end = (first..window.end)
+                .take(PREFETCH_CHUNK_LINES)
+                .take_while(|line| !cache.contains_key(line))
+                .count();
+            Some(first..first + end)
+        })
+    }
+
+    /// Highlights the next chunk of lines around the viewport into the
+    /// line cache.
+    pub(crate) fn prefetch_highlights(&self) {
+        self.refresh_stale_caches();
+        let Some(range) = self.prefetch_range() else {
+            return;
+        };
+        let highlights = self.query_highlight_lines(range.start, range.end, &self.theme);
+        let mut cache = self.line_highlights_cache.borrow_mut();
+        cache.extend(range.zip(highlights));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui_core::buffer::Buffer;
+    use ratatui_core::layout::Rect;
+    use ratatui_core::widgets::Widget;
+    use std::time::Instant;
+
+    #[test]
+    fn test_prefetch_highlights_lines_around_viewport() {
+        let content = "fn f() {}\n".repeat(1000);
+        let mut editor = Editor::new("rust", &content, vec![]).unwrap();
+        editor.set_highlight_prefetch(150);
+        editor.set_offset_y(500);
+        let area = Rect::new(0, 0, 40, 10);
+        Widget::render(&editor, area, &mut Buffer::empty(area));
+
+        assert_eq!(editor.prefetch_range(), Some(510..610));
+        assert!(editor.next_deadline().is_some());
+        let mut ticks = 0;
+        while editor.next_deadline().is_some() {
+            editor.tick(Instant::now());
+            ticks += 1;
+        }
+        assert_eq!(ticks, 4);
+
+        let cache = editor.line_highlights_cache.borrow();
+        assert!((350..660).all(|line| cache.contains_key(&line)));
+        assert!(!cache.contains_key(&349) && !cache.contains_key(&660));
+        let direct = editor.query_highlight_lines(600, 601, &editor.theme);
+        assert_eq!(cache[&600], direct[0]);
+    }
+
+    #[test]
+    fn test_highlight_lines_with_other_theme_skips_cache() {
+        let editor = Editor::new("rust", "fn f() {}\n", vec![("keyword", "#010203")]).unwrap();
+        let own = editor.highlight_lines(0, 1, &editor.theme);
+        assert!(editor.line_highlights_cache.borrow().contains_key(&0));
+
+        let other = Editor::build_theme(&vec![("keyword", "#040506")]);
+        let styled = editor.highlight_lines(0, 1, &other);
+        assert_ne!(own, styled);
+        assert_eq!(styled, editor.query_highlight_lines(0, 1, &other));
+        assert_eq!(editor.highlight_lines(0, 1, &editor.theme.clone()), own);
+    }
+}
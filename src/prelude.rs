@@ -0,0 +1,12 @@
+pub use crate::builder::EditorBuilder;
+pub use crate::command::Command;
+pub use crate::editor::Editor;
+pub use crate::editor_view::EditorView;
+pub use crate::events::EditorEvent;
+pub use crate::language::LanguageConfig;
+pub use crate::layout::EditorLayout;
+pub use crate::selection::Selection;
+pub use crate::theme::vesper;
+pub use crate::types::{
+    ChangeEvent, CodeFoldingOptions, CursorStyle, LineEnding, PasteMode, ScrollFollow,
+};
@@ -1,17 +1,29 @@
-use crate::code::{RopeGraphemes, grapheme_width_and_bytes_len, grapheme_width_and_chars_len};
+use crate::bidi::BidiLine;
+use crate::code::{
+    RopeGraphemes, grapheme_width, grapheme_width_and_bytes_len, grapheme_width_and_chars_len,
+    invisible_placeholder,
+};
 use crate::editor::Editor;
-use crate::types::VisualRow;
+use crate::editor_view::EditorView;
+use crate::selection::Selection;
+use crate::types::{Hightlight, VisualRow};
 use crate::view::View;
+use crate::width::{self, AmbiguousWidth, str_width};
 use ratatui_core::buffer::Buffer;
 use ratatui_core::layout::Rect;
-use ratatui_core::style::{Color, Style};
-use ratatui_core::widgets::Widget;
+use ratatui_core::style::{Color, Modifier, Style};
+use ratatui_core::text::{Line, Span};
+use ratatui_core::widgets::{StatefulWidget, Widget};
+use ratatui_widgets::scrollbar::{Scrollbar, ScrollbarOrientation, ScrollbarState};
+use ropey::RopeSlice;
 
 /// Draws the main editor view in the provided area using the ratatui rendering buffer.
 ///
 /// Renders visible [`VisualRow`]s, including fold separators and deleted diff rows.
+/// Each code row is built as a [`Line`] of styled [`Span`] runs and written in one go.
 /// Added and deleted rows receive a diff background before syntax highlighting is
-/// applied. Selections and user marks are then drawn over real editor rows.
+/// applied. Selections and user marks are then drawn over real editor rows, and an
+/// optional scrollbar is drawn on the right edge.
 ///
 /// # Arguments
 ///
@@ -21,261 +33,784 @@ use ratatui_core::widgets::Widget;
 ///
 impl Widget for &Editor {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let code = self.code_ref();
-        let total_lines = code.len_lines();
-        let max_line_number = total_lines.max(1);
-        let line_number_digits = max_line_number.to_string().len().max(5);
-        let line_number_width = self.get_line_number_width();
-        let fold_gutter_width = self.fold_gutter_width();
+        self.last_area.set(area);
+        self.render_view(area, buf, &self.view_state());
+    }
+}
+
+/// Draws the editor with the scroll position and focus of `state` instead of
+/// its own, e.g. to show one document in several panes. The state records the
+/// area it was drawn in.
+impl StatefulWidget for &Editor {
+    type State = EditorView;
+
+    fn render(self, area: Rect, buf: &mut Buffer, state: &mut EditorView) {
+        state.area = area;
+        state.offset_y = state
+            .offset_y
+            .min(self.visual_len_lines().saturating_sub(1));
+        self.render_view(area, buf, state);
+    }
+}
+
+impl Editor {
+    fn render_view(&self, area: Rect, buf: &mut Buffer, view: &EditorView) {
+        self.needs_redraw.set(false);
+        self.refresh_stale_caches();
+        let line_number_digits = self.line_number_digits();
+        let layout = self.layout(area);
+        let numbers_x = layout.line_numbers.x;
         let total_visual_lines = self.visual_len_lines();
         let mut draw_y = area.top();
 
         let line_number_style = Style::default().fg(Color::DarkGray);
-        let default_text_style = Style::default().fg(Color::White);
-
-        let diff_added_bg = self.theme_style("diff_added").bg
-            .or(self.theme_style("diff_added").fg)
-            .unwrap_or(Color::Rgb(1, 125, 78));
-        let diff_added_word_bg = self.theme_style("diff_added_word").bg
-            .or(self.theme_style("diff_added_word").fg)
-            .unwrap_or(Color::Rgb(19, 163, 111));
-        let diff_deleted_bg = self.theme_style("diff_deleted").bg
-            .or(self.theme_style("diff_deleted").fg)
-            .unwrap_or(Color::Rgb(217, 75, 75));
-        let diff_deleted_word_bg = self.theme_style("diff_deleted_word").bg
-            .or(self.theme_style("diff_deleted_word").fg)
-            .unwrap_or(Color::Rgb(248, 99, 99));
-
-        let word_highlights = self.word_highlight_ranges();
-        let word_highlight_bg = self.theme_style("word_highlight").bg
-            .or(self.theme_style("word_highlight").fg)
-            .unwrap_or(Color::Rgb(48, 54, 64));
-
         let fold_separator_style = Style::default().fg(Color::DarkGray);
-
-        // draw lines, syntax highlighting, selection and marks in a single unified loop
-        for visual_row_idx in self.offset_y..total_visual_lines {
+        let styles = RowStyles {
+            focused: view.focused,
+            ..RowStyles::new(self)
+        };
+        let word_highlights = self.word_highlight_ranges();
+        let breakpoint_lines = self.breakpoint_lines();
+        let execution_line = self.execution_line();
+        let breakpoint_style = self.breakpoint_style();
+        let (execution_style, execution_bg) = self.execution_line_styles();
+
+        let visible_rows: Vec<VisualRow> = (view.offset_y..total_visual_lines)
+            .map_while(|visual_row_idx| self.visual_row(visual_row_idx))
+            .take(area.height as usize)
+            .collect();
+
+        let text_x = layout.text_area.x;
+        let width = layout.text_area.width as usize;
+
+        // Spans are only rebuilt when something they are drawn from changed
+        let key = self.frame_key(&visible_rows, view, width, &word_highlights);
+        let cached = self.frame_cache.borrow_mut().take();
+        let frame = match cached {
+            Some((cached, frame)) if cached == key => frame,
+            _ => self.frame_spans(
+                &visible_rows,
+                view.offset_x,
+                width,
+                &word_highlights,
+                &styles,
+            ),
+        };
+
+        // draw gutters and the prebuilt lines row by row
+        for (row, spans) in visible_rows.iter().zip(&frame) {
             if draw_y >= area.bottom() {
                 break;
             }
 
-            let row = match self.visual_row(visual_row_idx) {
-                Some(row) => row,
-                None => break,
-            };
-
             if let VisualRow::FoldSeparator { hidden_lines, .. } = &row {
                 if self.show_line_numbers {
-                    buf.set_string(
-                        area.left(),
+                    buf.set_stringn(
+                        numbers_x,
                         draw_y,
-                        &format!("{:>width$}", "...", width = line_number_digits),
+                        format!("{:>width$}", "...", width = line_number_digits),
+                        line_number_digits,
                         line_number_style,
                     );
                 }
-                let text_x = area.left() + line_number_width as u16;
                 let text =
                     View::fold_separator_text(*hidden_lines, self.diff_options.expand_amount);
-                let width = (area.width as usize).saturating_sub(line_number_width);
                 let visible_text = text.chars().take(width).collect::<String>();
                 if text_x < area.right() {
                     buf.set_string(text_x, draw_y, &visible_text, fold_separator_style);
                 }
             } else {
-                let (line_idx, is_added, is_ghost, partner_line_idx) = match &row {
-                    VisualRow::Real { line_idx, is_added, orig_line_idx } => (*line_idx, *is_added, false, *orig_line_idx),
+                let (line_idx, is_ghost) = match row {
+                    VisualRow::Real { line_idx, .. } => (*line_idx, false),
                     VisualRow::GhostDeleted {
-                        original_line_idx, curr_line_idx, ..
-                    } => (*original_line_idx, false, true, *curr_line_idx),
+                        original_line_idx, ..
+                    } => (*original_line_idx, true),
                     _ => unreachable!(),
                 };
-                let source_code = if is_ghost {
-                    self.original_code.as_ref().unwrap_or(code)
-                } else {
-                    code
-                };
 
-                // 1. Draw line numbers
+                // 1. Draw line numbers and host gutter columns
                 if self.show_line_numbers {
                     let line_number = if is_ghost {
                         format!("{:>width$}", " ", width = line_number_digits)
                     } else {
                         format!("{:>width$}", line_idx + 1, width = line_number_digits)
                     };
-                    buf.set_string(area.left(), draw_y, &line_number, line_number_style);
+                    buf.set_string(numbers_x, draw_y, &line_number, line_number_style);
                 }
                 if !is_ghost {
-                    if let Some(collapsed) = self.code_fold_indicator(line_idx) {
-                        let indicator = if collapsed {
-                            &self.code_folding_options.indicators.collapsed
-                        } else {
-                            &self.code_folding_options.indicators.expanded
-                        };
-                        buf.set_string(
-                            area.left() + (line_number_width - fold_gutter_width) as u16,
-                            draw_y,
-                            indicator,
-                            line_number_style,
-                        );
+                    let mut column_x = layout.columns.x;
+                    for column in &self.gutter_columns {
+                        if let Some((text, style)) = column.cells.get(&line_idx) {
+                            buf.set_stringn(column_x, draw_y, text, column.width, *style);
+                        }
+                        column_x += column.width as u16;
                     }
+                    if self.breakpoint_gutter {
+                        if breakpoint_lines.contains(&line_idx) {
+                            buf.set_string(column_x, draw_y, "●", breakpoint_style);
+                        }
+                        if execution_line == Some(line_idx) {
+                            buf.set_string(column_x + 1, draw_y, "▶", execution_style);
+                        }
+                    }
+                }
+                if !is_ghost && let Some(collapsed) = self.code_fold_indicator(line_idx) {
+                    let indicator = if collapsed {
+                        &self.code_folding_options.indicators.collapsed
+                    } else {
+                        &self.code_folding_options.indicators.expanded
+                    };
+                    buf.set_string(layout.fold_gutter.x, draw_y, indicator, line_number_style);
                 }
 
-                let text_x = area.left() + line_number_width as u16;
-                let width = (area.width as usize).saturating_sub(line_number_width);
+                // 2. Write the styled spans of the whole row at once
+                if text_x < area.right() {
+                    let line = Line::from(spans.clone());
+                    if !is_ghost && execution_line == Some(line_idx) {
+                        let row_area = Rect::new(text_x, draw_y, width as u16, 1);
+                        buf.set_style(row_area, Style::default().bg(execution_bg));
+                    }
+                    buf.set_line(text_x, draw_y, &line, width as u16);
+                    if !is_ghost && let Some(text) = self.line_annotation(line_idx) {
+                        self.render_annotation(text, &line, text_x, draw_y, width, buf);
+                    }
+                    if !is_ghost && self.terminal_hyperlinks {
+                        let x = (view.offset_x, text_x);
+                        self.render_hyperlinks(line_idx, x, draw_y, width, buf);
+                    }
+                    if !is_ghost && let Some(text) = self.visible_placeholder() {
+                        let style = Style::default()
+                            .fg(Color::DarkGray)
+                            .patch(self.theme_style("placeholder"));
+                        buf.set_stringn(text_x, draw_y, text, width, style);
+                    }
+                }
+            }
+            draw_y += 1;
+        }
+        *self.frame_cache.borrow_mut() = Some((key, frame));
+
+        if let Some(scrollbar_area) = layout.scrollbar {
+            let mut state = ScrollbarState::new(total_visual_lines)
+                .position(view.offset_y)
+                .content_length(total_visual_lines.saturating_sub(area.height as usize) + 1)
+                .viewport_content_length(area.height as usize);
+            Scrollbar::new(ScrollbarOrientation::VerticalRight)
+                .begin_symbol(None)
+                .end_symbol(None)
+                .render(scrollbar_area, buf, &mut state);
+        }
+
+        if let Some(picker) = &self.picker {
+            picker.render(area, buf);
+        }
+    }
+}
 
-                let line_len = source_code.line_len(line_idx);
-                let start_col = self.offset_x.min(line_len);
-                let end_col = (start_col + width).min(line_len);
+/// Everything the spans of a frame are built from besides the theme, which
+/// only changes together with [`Editor::clear_caches`].
+#[derive(PartialEq)]
+pub(crate) struct FrameKey {
+    version: u64,
+    original_version: Option<u64>,
+    rows: Vec<VisualRow>,
+    offset_x: usize,
+    width: usize,
+    word_highlights: Vec<(usize, usize)>,
+    marks_version: u64,
+    selection: Option<Selection>,
+    secondary_selections: Vec<(usize, usize)>,
+    preedit: Option<(usize, String)>,
+    focused: bool,
+    show_invisibles: bool,
+    link_detection: bool,
+    ambiguous_width: AmbiguousWidth,
+}
 
-                let line_start_char = source_code.line_to_char(line_idx);
-                let char_slice_start = line_start_char + start_col;
-                let char_slice_end = line_start_char + end_col;
-                let visible_chars = source_code.char_slice(char_slice_start, char_slice_end);
+/// Spans of the rows drawn last and the state they were built from.
+pub(crate) type FrameCache = Option<(FrameKey, Vec<Vec<Span<'static>>>)>;
+
+/// Colors resolved from the theme once per frame and shared by all rows.
+pub(crate) struct RowStyles {
+    default_text: Style,
+    pub(crate) diff_added_bg: Color,
+    diff_added_word_bg: Color,
+    pub(crate) diff_deleted_bg: Color,
+    diff_deleted_word_bg: Color,
+    word_highlight_bg: Color,
+    selection_bg: Color,
+    inactive_selection_bg: Color,
+    invisible: Style,
+    preedit: Style,
+    /// Whether selections are drawn as in a focused editor
+    pub(crate) focused: bool,
+}
 
-                let start_byte = source_code.char_to_byte(char_slice_start);
-                let end_byte = source_code.char_to_byte(char_slice_end);
+impl RowStyles {
+    pub(crate) fn new(editor: &Editor) -> Self {
+        let bg = |key: &str, fallback: Color| {
+            let style = editor.theme_style(key);
+            style.bg.or(style.fg).unwrap_or(fallback)
+        };
+        Self {
+            default_text: Style::default().fg(Color::White),
+            diff_added_bg: bg("diff_added", Color::Rgb(1, 125, 78)),
+            diff_added_word_bg: bg("diff_added_word", Color::Rgb(19, 163, 111)),
+            diff_deleted_bg: bg("diff_deleted", Color::Rgb(217, 75, 75)),
+            diff_deleted_word_bg: bg("diff_deleted_word", Color::Rgb(248, 99, 99)),
+            word_highlight_bg: bg("word_highlight", Color::Rgb(48, 54, 64)),
+            selection_bg: Color::DarkGray,
+            inactive_selection_bg: bg("inactive_selection", Color::Rgb(58, 58, 58)),
+            invisible: editor
+                .theme
+                .get("invisible")
+                .copied()
+                .unwrap_or(Style::default().fg(Color::Yellow)),
+            preedit: Style::default().fg(Color::White).patch(
+                editor
+                    .theme
+                    .get("preedit")
+                    .copied()
+                    .unwrap_or(Style::default().add_modifier(Modifier::UNDERLINED)),
+            ),
+            focused: editor.focused,
+        }
+    }
+}
 
-                let line_end_char = line_start_char + line_len;
-                let line_word_highlights: Vec<(usize, usize)> = if is_ghost {
-                    Vec::new()
-                } else {
-                    word_highlights
-                        .iter()
-                        .filter(|&&(start, end)| start < line_end_char && end > line_start_char)
-                        .cloned()
-                        .collect()
-                };
+impl Editor {
+    fn frame_key(
+        &self,
+        rows: &[VisualRow],
+        view: &EditorView,
+        width: usize,
+        word_highlights: &[(usize, usize)],
+    ) -> FrameKey {
+        FrameKey {
+            version: self.borrow_code().version(),
+            original_version: self.original_code.as_ref().map(|code| code.version()),
+            rows: rows.to_vec(),
+            offset_x: view.offset_x,
+            width,
+            word_highlights: word_highlights.to_vec(),
+            marks_version: self.marks.version(),
+            selection: self.selection,
+            secondary_selections: self
+                .secondary_selections
+                .iter()
+                .map(|mark| (mark.start(), mark.end()))
+                .collect(),
+            preedit: self
+                .preedit
+                .as_ref()
+                .map(|preedit| (preedit.position(), preedit.text().to_string())),
+            focused: view.focused,
+            show_invisibles: self.show_invisibles,
+            link_detection: self.link_detection,
+            ambiguous_width: width::global_ambiguous_width(),
+        }
+    }
 
-                // Fetch highlights
-                let highlights = if code.is_highlight() {
-                    if is_ghost {
-                        self.highlight_interval_original(start_byte, end_byte, &self.theme)
-                    } else {
-                        self.highlight_interval(start_byte, end_byte, &self.theme)
-                    }
+    /// Builds the spans of all visible rows, highlighting the whole viewport
+    /// with one query per source instead of one per line. Fold separators
+    /// get no spans.
+    fn frame_spans(
+        &self,
+        rows: &[VisualRow],
+        offset_x: usize,
+        width: usize,
+        word_highlights: &[(usize, usize)],
+        styles: &RowStyles,
+    ) -> Vec<Vec<Span<'static>>> {
+        let (real_lines, ghost_lines) = visible_line_ranges(rows);
+        let (real_highlights, ghost_highlights) = if self.borrow_code().is_highlight() {
+            (
+                real_lines
+                    .map(|(start, end)| self.highlight_lines(start, end + 1, &self.theme))
+                    .unwrap_or_default(),
+                ghost_lines
+                    .map(|(start, end)| self.highlight_lines_original(start, end + 1, &self.theme))
+                    .unwrap_or_default(),
+            )
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        rows.iter()
+            .map(|row| {
+                let (line_idx, is_ghost) = match row {
+                    VisualRow::Real { line_idx, .. } => (*line_idx, false),
+                    VisualRow::GhostDeleted {
+                        original_line_idx, ..
+                    } => (*original_line_idx, true),
+                    VisualRow::FoldSeparator { .. } => return Vec::new(),
+                };
+                let (line_highlights, first_line) = if is_ghost {
+                    (&ghost_highlights, ghost_lines.map_or(0, |(start, _)| start))
                 } else {
-                    Vec::new()
+                    (&real_highlights, real_lines.map_or(0, |(start, _)| start))
                 };
+                let highlights = line_idx
+                    .checked_sub(first_line)
+                    .and_then(|idx| line_highlights.get(idx))
+                    .map(Vec::as_slice)
+                    .unwrap_or_default();
+                self.row_spans(row, offset_x, width, highlights, word_highlights, styles)
+            })
+            .collect()
+    }
 
-                // Fetch intra-line diff highlights on the fly from cache
-                let intra_highlights = partner_line_idx.map(|partner_idx| {
-                    if is_ghost {
-                        self.get_line_diff(line_idx, partner_idx, true)
-                    } else {
-                        self.get_line_diff(partner_idx, line_idx, false)
-                    }
-                });
+    /// Renders line `line_idx` of the current code into styled spans without a `Buffer`.
+    ///
+    /// The spans start at column 0 and cover at most `width` columns. They carry the
+    /// same syntax highlighting, selection, word highlight and mark styles as the
+    /// editor widget, so hosts can reuse them in other widgets or for export.
+    /// Returns an empty vector if `line_idx` is out of range.
+    pub fn render_line_spans(&self, line_idx: usize, width: usize) -> Vec<Span<'static>> {
+        if line_idx >= self.borrow_code().len_lines() {
+            return Vec::new();
+        }
+        let highlights = if self.borrow_code().is_highlight() {
+            self.highlight_lines(line_idx, line_idx + 1, &self.theme)
+                .pop()
+                .unwrap_or_default()
+        } else {
+            Vec::new()
+        };
+        let row = VisualRow::Real {
+            line_idx,
+            is_added: false,
+            orig_line_idx: None,
+        };
+        self.row_spans(
+            &row,
+            0,
+            width,
+            &highlights,
+            &self.word_highlight_ranges(),
+            &RowStyles::new(self),
+        )
+    }
 
-                // Base style background color
-                let base_bg = match is_ghost {
-                    true => Some(diff_deleted_bg),
-                    false if is_added => Some(diff_added_bg),
-                    false => None,
-                };
+    /// Draws a line annotation right-aligned in the columns `line` leaves
+    /// free, keeping a gap of two columns and cutting it with an ellipsis.
+    fn render_annotation(
+        &self,
+        text: &str,
+        line: &Line,
+        text_x: u16,
+        y: u16,
+        width: usize,
+        buf: &mut Buffer,
+    ) {
+        let content: String = line
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        let free = width.saturating_sub(str_width(content.trim_end()) + 2);
+        let (text, text_width) = if str_width(text) <= free {
+            clip_to_width(text, free)
+        } else if free > 1 {
+            let (clipped, clipped_width) = clip_to_width(text, free - 1);
+            (clipped + "…", clipped_width + 1)
+        } else {
+            return;
+        };
+        let style = Style::default()
+            .fg(Color::DarkGray)
+            .patch(self.theme_style("annotation"));
+        let x = text_x + (width - text_width) as u16;
+        buf.set_stringn(x, y, text, text_width, style);
+    }
+
+    /// Builds the styled spans of a real or ghost row, starting at visual column
+    /// `offset_x` and covering at most `width` columns.
+    ///
+    /// Consecutive graphemes sharing a style are merged into one [`Span`]. Diff rows
+    /// are padded with their background up to `width`.
+    pub(crate) fn row_spans(
+        &self,
+        row: &VisualRow,
+        offset_x: usize,
+        width: usize,
+        highlights: &[Hightlight],
+        word_highlights: &[(usize, usize)],
+        styles: &RowStyles,
+    ) -> Vec<Span<'static>> {
+        let (line_idx, is_added, is_ghost, partner_line_idx) = match row {
+            VisualRow::Real {
+                line_idx,
+                is_added,
+                orig_line_idx,
+            } => (*line_idx, *is_added, false, *orig_line_idx),
+            VisualRow::GhostDeleted {
+                original_line_idx,
+                curr_line_idx,
+                ..
+            } => (*original_line_idx, false, true, *curr_line_idx),
+            VisualRow::FoldSeparator { .. } => return Vec::new(),
+        };
+        let code = self.borrow_code();
+        let source_code = if is_ghost {
+            self.original_code.as_ref().unwrap_or(&code)
+        } else {
+            &code
+        };
+        if line_idx >= source_code.len_lines() {
+            return Vec::new();
+        }
+
+        let line_len = source_code.line_len(line_idx);
+        // Right-to-left text is reordered over the whole line, then cut to the viewport
+        let bidi = BidiLine::for_line(source_code, line_idx);
+        let (start_col, end_col) = match bidi {
+            Some(_) => (0, line_len),
+            None => {
+                let start_col = offset_x.min(line_len);
+                (start_col, (start_col + width).min(line_len))
+            }
+        };
+
+        let line_start_char = source_code.line_to_char(line_idx);
+        let char_slice_start = line_start_char + start_col;
+        let char_slice_end = line_start_char + end_col;
+        let visible_chars = source_code.char_slice(char_slice_start, char_slice_end);
+        let start_byte = source_code.char_to_byte(char_slice_start);
+        let end_byte = source_code.char_to_byte(char_slice_end);
+
+        let line_end_char = line_start_char + line_len;
+        let line_word_highlights: Vec<(usize, usize)> = if is_ghost {
+            Vec::new()
+        } else {
+            word_highlights
+                .iter()
+                .filter(|&&(start, end)| start < line_end_char && end > line_start_char)
+                .cloned()
+                .collect()
+        };
+
+        // Resolve syntax styles per byte once; the first matching highlight wins,
+        // so paint them in reverse order.
+        let mut syntax_styles: Vec<Option<Style>> = vec![None; end_byte - start_byte];
+        for &(start, end, style) in highlights.iter().rev() {
+            let from = start.clamp(start_byte, end_byte) - start_byte;
+            let to = end.clamp(start_byte, end_byte) - start_byte;
+            for slot in &mut syntax_styles[from..to] {
+                *slot = Some(style);
+            }
+        }
 
-                let mut x = 0;
+        // Fetch intra-line diff highlights on the fly from cache
+        let intra_highlights = partner_line_idx.map(|partner_idx| {
+            if is_ghost {
+                self.get_line_diff(line_idx, partner_idx, true)
+            } else {
+                self.get_line_diff(partner_idx, line_idx, false)
+            }
+        });
+
+        // Base style background color
+        let base_bg = match is_ghost {
+            true => Some(styles.diff_deleted_bg),
+            false if is_added => Some(styles.diff_added_bg),
+            false => None,
+        };
+
+        // Links are underlined below the marks
+        let line_marks: Vec<(usize, usize, Style)> = if is_ghost {
+            Vec::new()
+        } else {
+            let link_style = self.link_style();
+            let links = self
+                .line_links(line_idx)
+                .into_iter()
+                .map(|(start, end, _)| (start, end, link_style));
+            let marks = self
+                .marks
+                .iter()
+                .map(|mark| (mark.start(), mark.end(), mark.style))
+                .filter(|&(start, end, _)| start <= line_end_char && end > line_start_char);
+            links.chain(marks).collect()
+        };
+
+        let selection = self
+            .selection
+            .filter(|selection| !selection.is_empty())
+            .map(|selection| selection.sorted());
+        let selection_bg = if styles.focused {
+            styles.selection_bg
+        } else {
+            styles.inactive_selection_bg
+        };
+
+        // Secondary selections, their cursors sit at the end
+        let secondary: Vec<(usize, usize)> = if is_ghost {
+            Vec::new()
+        } else {
+            self.secondary_selections
+                .iter()
+                .map(|mark| (mark.start(), mark.end()))
+                .filter(|&(start, end)| start <= line_end_char && end >= line_start_char)
+                .collect()
+        };
+
+        // Pending IME composition, drawn before the char at its position
+        let preedit = self
+            .preedit
+            .as_ref()
+            .filter(|_| !is_ghost)
+            .map(|preedit| (preedit.position(), preedit.text()));
+
+        let mut spans = Vec::new();
+        let mut run = String::new();
+        let mut run_style = Style::default();
+
+        // Visible graphemes in display order with their char column and byte offset
+        let graphemes: Vec<(RopeSlice, usize, usize)> = match &bidi {
+            Some(bidi) => bidi.cells[bidi.skip(offset_x)..]
+                .iter()
+                .map(|cell| {
+                    let g = &bidi.text[cell.byte..cell.byte + cell.bytes];
+                    (RopeSlice::from(g), cell.char_col, start_byte + cell.byte)
+                })
+                .collect(),
+            None => {
                 let mut byte_idx_in_rope = start_byte;
                 let mut char_col = start_col;
+                RopeGraphemes::new(&visible_chars)
+                    .map(|g| {
+                        let (_, g_bytes) = grapheme_width_and_bytes_len(g);
+                        let (_, g_chars) = grapheme_width_and_chars_len(g);
+                        let grapheme = (g, char_col, byte_idx_in_rope);
+                        byte_idx_in_rope += g_bytes;
+                        char_col += g_chars;
+                        grapheme
+                    })
+                    .collect()
+            }
+        };
 
-                // 3. Single loop over the graphemes of the line
-                for g in RopeGraphemes::new(&visible_chars) {
-                    let (g_width, g_bytes) = grapheme_width_and_bytes_len(g);
-                    let (_, g_chars) = grapheme_width_and_chars_len(g);
-
-                    if x >= width {
-                        break;
-                    }
+        let mut x = 0;
 
-                    let start_x = text_x + x as u16;
+        // Single loop over the graphemes of the line
+        for (g, char_col, byte_idx_in_rope) in graphemes {
+            let g_width = grapheme_width(g);
+            let global_char_idx = line_start_char + char_col;
 
-                    // Check if current character falls within an intra-line highlight range
-                    let is_word_highlight = intra_highlights.as_ref().map_or(false, |ranges| {
-                        ranges.iter().any(|&(start, end)| char_col >= start && char_col < end)
-                    });
+            if let Some((at, text)) = preedit
+                && at == global_char_idx
+            {
+                if !run.is_empty() {
+                    spans.push(Span::styled(std::mem::take(&mut run), run_style));
+                }
+                let (text, text_width) = clip_to_width(text, width.saturating_sub(x));
+                spans.push(Span::styled(text, styles.preedit));
+                x += text_width;
+            }
 
-                    let active_bg = if is_word_highlight {
-                        if is_ghost { Some(diff_deleted_word_bg) } else { Some(diff_added_word_bg) }
-                    } else {
-                        base_bg
-                    };
+            if x >= width {
+                break;
+            }
 
-                    // Compose style
-                    let mut style = if let Some(bg) = active_bg {
-                        Style::default().bg(bg)
-                    } else {
-                        default_text_style
-                    };
+            // Check if current character falls within an intra-line highlight range
+            let is_word_highlight = intra_highlights.as_ref().is_some_and(|ranges| {
+                ranges
+                    .iter()
+                    .any(|&(start, end)| char_col >= start && char_col < end)
+            });
 
-                    // Layer A: Syntax highlights
-                    for &(start, end, s) in &highlights {
-                        if start <= byte_idx_in_rope && byte_idx_in_rope < end {
-                            style = style.patch(s);
-                            if let Some(bg) = active_bg {
-                                style = style.bg(bg); // Keep active diff background
-                            }
-                            break;
-                        }
-                    }
+            let active_bg = if is_word_highlight {
+                if is_ghost {
+                    Some(styles.diff_deleted_word_bg)
+                } else {
+                    Some(styles.diff_added_word_bg)
+                }
+            } else {
+                base_bg
+            };
 
-                    let global_char_idx = line_start_char + char_col;
+            // Compose style
+            let mut style = if let Some(bg) = active_bg {
+                Style::default().bg(bg)
+            } else {
+                styles.default_text
+            };
 
-                    if !is_ghost {
-                        // Layer D: Word Highlight
-                        let is_in_word_highlight = line_word_highlights.iter().any(|&(start, end)| {
-                            global_char_idx >= start && global_char_idx < end
-                        });
-                        if is_in_word_highlight {
-                            style = style.bg(word_highlight_bg);
-                        }
+            // Layer A: Syntax highlights
+            if let Some(s) = syntax_styles[byte_idx_in_rope - start_byte] {
+                style = style.patch(s);
+                if let Some(bg) = active_bg {
+                    style = style.bg(bg); // Keep active diff background
+                }
+            }
 
-                        // Layer B: Selection
-                        if let Some(selection) = self.selection
-                            && !selection.is_empty()
-                        {
-                            let start = selection.start.min(selection.end);
-                            let end = selection.start.max(selection.end);
-                            if global_char_idx >= start && global_char_idx < end {
-                                style = style.bg(Color::DarkGray);
-                            }
-                        }
+            if !is_ghost {
+                // Layer D: Word Highlight
+                let is_in_word_highlight = line_word_highlights
+                    .iter()
+                    .any(|&(start, end)| global_char_idx >= start && global_char_idx < end);
+                if is_in_word_highlight {
+                    style = style.bg(styles.word_highlight_bg);
+                }
 
-                        // Layer C: Marks
-                        if let Some(ref marks) = self.marks {
-                            for &(m_start, m_end, m_color) in marks {
-                                if global_char_idx >= m_start && global_char_idx < m_end {
-                                    style = style.bg(m_color);
-                                }
-                            }
-                        }
+                // Layer B: Selection
+                if let Some((start, end)) = selection
+                    && global_char_idx >= start
+                    && global_char_idx < end
+                {
+                    style = style.bg(selection_bg);
+                }
+                for &(start, end) in &secondary {
+                    if global_char_idx >= start && global_char_idx < end {
+                        style = style.bg(selection_bg);
                     }
-
-                    // Draw character
-                    let display_g = g.to_string().replace('\t', " ");
-                    if start_x < area.right() {
-                        buf.set_string(start_x, draw_y, &display_g, style);
+                    if global_char_idx == end && styles.focused {
+                        style = style.add_modifier(Modifier::REVERSED);
                     }
-
-                    x = x.saturating_add(g_width);
-                    byte_idx_in_rope += g_bytes;
-                    char_col += g_chars;
                 }
 
-                // 4. Fill remaining width with background if needed
-                if let Some(bg) = base_bg
-                    && x < width
-                    && text_x + (x as u16) < area.right()
-                {
-                    let fill_x = text_x + (x as u16);
-                    let fill_width = width - x;
-                    buf.set_string(
-                        fill_x,
-                        draw_y,
-                        &" ".repeat(fill_width),
-                        Style::default().bg(bg),
-                    );
+                // Layer C: Marks, in layer priority order
+                for &(m_start, m_end, m_style) in &line_marks {
+                    if global_char_idx >= m_start && global_char_idx < m_end {
+                        style = style.patch(m_style);
+                    }
                 }
             }
-            draw_y += 1;
+
+            // Invisible characters are drawn as a placeholder in a warning style
+            let g_str = g.to_string();
+            let placeholder = invisible_placeholder(&g_str);
+            if placeholder.is_some() && self.show_invisibles {
+                style = style.patch(styles.invisible);
+            }
+
+            if style != run_style && !run.is_empty() {
+                spans.push(Span::styled(std::mem::take(&mut run), run_style));
+            }
+            run_style = style;
+
+            // Control characters (tabs, stray carriage returns) occupy one blank cell
+            match placeholder {
+                Some(placeholder) if self.show_invisibles => run.push(placeholder),
+                Some(_) => run.push(' '),
+                None if g_str.chars().any(char::is_control) => run.push(' '),
+                None => run.push_str(&g_str),
+            }
+
+            x = x.saturating_add(g_width);
+        }
+
+        if !run.is_empty() {
+            spans.push(Span::styled(run, run_style));
+        }
+
+        // Composition at the end of the line
+        if let Some((at, text)) = preedit
+            && at == line_end_char
+            && x < width
+        {
+            let (text, text_width) = clip_to_width(text, width - x);
+            spans.push(Span::styled(text, styles.preedit));
+            x += text_width;
         }
+
+        // Fill remaining width with background if needed
+        if let Some(bg) = base_bg
+            && x < width
+        {
+            spans.push(Span::styled(" ".repeat(width - x), Style::default().bg(bg)));
+        }
+
+        spans
+    }
+}
+
+// first line, last line (inclusive)
+type LineRange = (usize, usize);
+
+/// Returns the line ranges of real and ghost rows among `rows`.
+fn visible_line_ranges(rows: &[VisualRow]) -> (Option<LineRange>, Option<LineRange>) {
+    let mut real: Option<LineRange> = None;
+    let mut ghost: Option<LineRange> = None;
+    for row in rows {
+        let (range, line_idx) = match row {
+            VisualRow::Real { line_idx, .. } => (&mut real, *line_idx),
+            VisualRow::GhostDeleted {
+                original_line_idx, ..
+            } => (&mut ghost, *original_line_idx),
+            VisualRow::FoldSeparator { .. } => continue,
+        };
+        *range = Some(match *range {
+            Some((start, end)) => (start.min(line_idx), end.max(line_idx)),
+            None => (line_idx, line_idx),
+        });
+    }
+    (real, ghost)
+}
+
+/// Cuts `text` to the graphemes fitting in `width` columns, returning them
+/// and the columns they take.
+fn clip_to_width(text: &str, width: usize) -> (String, usize) {
+    let mut clipped = String::new();
+    let mut used = 0;
+    for g in RopeGraphemes::new(&RopeSlice::from(text)) {
+        let g_width = grapheme_width(g);
+        if used + g_width > width {
+            break;
+        }
+        clipped.extend(g.chars());
+        used += g_width;
+    }
+    (clipped, used)
+}
+
+#[cfg(all(test, feature = "crossterm"))]
+mod tests {
+    use super::*;
+    use crate::testing::TestHarness;
+
+    /// Replaces the cached spans so that a reused frame shows up on screen.
+    fn tamper_frame_cache(editor: &Editor) {
+        let mut cache = editor.frame_cache.borrow_mut();
+        let (_, frame) = cache.as_mut().unwrap();
+        frame[0] = vec![Span::raw("cached")];
+    }
+
+    #[test]
+    fn test_frame_is_reused_until_its_inputs_change() {
+        let editor = Editor::builder()
+            .content("first\nsecond")
+            .line_numbers(false)
+            .code_folding(false)
+            .left_padding(0)
+            .build()
+            .unwrap();
+        let mut harness = TestHarness::new(editor, 12, 2);
+        assert_eq!(harness.screen(), ["first", "second"]);
+
+        tamper_frame_cache(harness.editor());
+        assert_eq!(harness.screen(), ["cached", "second"]);
+
+        // A blink or focus change of another pane leaves the spans alone
+        harness
+            .editor_mut()
+            .set_cursor_blink(Some(std::time::Duration::from_millis(500)));
+        assert_eq!(harness.screen(), ["cached", "second"]);
+
+        harness
+            .editor_mut()
+            .set_selection(Some(Selection::new(0, 2)));
+        assert_eq!(harness.screen(), ["first", "second"]);
+
+        tamper_frame_cache(harness.editor());
+        harness
+            .editor_mut()
+            .set_mark_layer("search", vec![(6, 8)], Style::default());
+        assert_eq!(harness.screen(), ["first", "second"]);
+
+        tamper_frame_cache(harness.editor());
+        harness.editor_mut().set_content("changed\nsecond");
+        assert_eq!(harness.screen(), ["changed", "second"]);
     }
 }
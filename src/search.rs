@@ -0,0 +1,363 @@
+use crate::code::Code;
+use crate::editor::Editor;
+use crate::marks::Mark;
+use crate::selection::Selection;
+use ratatui_core::style::{Color, Style};
+use std::ops::Range;
+
+/// Mark layer holding the matches of [`Editor::search`].
+pub const SEARCH_MARK_LAYER: &str = "search";
+
+/// How [`Editor::search`] matches the query. The default matches the text
+/// exactly, anywhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "fuzz", derive(arbitrary::Arbitrary))]
+pub struct SearchOptions {
+    /// Ignore case unless the query contains an uppercase letter
+    pub smart_case: bool,
+    /// Only match where the query is not part of a longer word
+    pub whole_word: bool,
+}
+
+/// Query and match count of an editor's search, e.g. for a status bar
+/// showing "3/27".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchState {
+    pub query: String,
+    /// Index of the selected match, if the selection is one
+    pub current: Option<usize>,
+    pub total: usize,
+}
+
+/// Active search of an editor; its matches live in [`SEARCH_MARK_LAYER`].
+#[derive(Debug, Clone)]
+pub(crate) struct Search {
+    query: String,
+    options: SearchOptions,
+    /// Document version the matches were found in
+    version: Option<u64>,
+}
+
+/// Non-overlapping occurrences of `query` as character ranges.
+pub(crate) fn find_matches(
+    code: &Code,
+    query: &str,
+    options: SearchOptions,
+) -> Vec<(usize, usize)> {
+    Matcher::new(code, query, options).find(code, 0..code.content.len_chars())
+}
+
+/// Streams the characters of a rope through a Knuth-Morris-Pratt automaton,
+/// so a search never copies the document into a `String`.
+struct Matcher {
+    query: Vec<char>,
+    /// Length of the longest proper prefix of `query[..=i]` that is also its suffix
+    fallback: Vec<usize>,
+    ignore_case: bool,
+    check_start: bool,
+    check_end: bool,
+}
+
+impl Matcher {
+    fn new(code: &Code, query: &str, options: SearchOptions) -> Self {
+        let ignore_case = options.smart_case && !query.chars().any(char::is_uppercase);
+        let query: Vec<char> = query
+            .chars()
+            .map(|c| if ignore_case { fold_case(c) } else { c })
+            .collect();
+        let mut fallback = vec![0; query.len()];
+        let mut matched = 0;
+        for i in 1..query.len() {
+            while matched > 0 && query[i] != query[matched] {
+                matched = fallback[matched - 1];
+            }
+            if query[i] == query[matched] {
+                matched += 1;
+            }
+            fallback[i] = matched;
+        }
+        // only check the boundaries at the ends of the query that are word characters
+        let check_start =
+            options.whole_word && query.first().is_some_and(|&c| code.is_word_char(c));
+        let check_end = options.whole_word && query.last().is_some_and(|&c| code.is_word_char(c));
+        Self {
+            query,
+            fallback,
+            ignore_case,
+            check_start,
+            check_end,
+        }
+    }
+
+    /// Matches lying entirely within the character range `range`.
+    fn find(&self, code: &Code, range: Range<usize>) -> Vec<(usize, usize)> {
+        let mut matches = Vec::new();
+        if self.query.is_empty() {
+            return matches;
+        }
+        let mut matched = 0;
+        let chars = code.content.chars_at(range.start).take(range.len());
+        for (offset, c) in (range.start..).zip(chars) {
+            let c = if self.ignore_case { fold_case(c) } else { c };
+            while matched > 0 && self.query[matched] != c {
+                matched = self.fallback[matched - 1];
+            }
+            if self.query[matched] == c {
+                matched += 1;
+            }
+            if matched == self.query.len() {
+                matched = 0;
+                let (start, end) = (offset + 1 - self.query.len(), offset + 1);
+                if self.is_whole_word(code, start, end) {
+                    matches.push((start, end));
+                }
+            }
+        }
+        matches
+    }
+
+    fn is_whole_word(&self, code: &Code, start: usize, end: usize) -> bool {
+        let content = &code.content;
+        !(self.check_start && start > 0 && code.is_word_char(content.char(start - 1))
+            || self.check_end && end < content.len_chars() && code.is_word_char(content.char(end)))
+    }
+}
+
+/// Character ranges of the current text touched by `edits`, see
+/// [`Code::edits_since`], sorted and merged.
+fn dirty_ranges(edits: &[(usize, usize, usize)]) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = Vec::new();
+    for &(from, removed, inserted) in edits {
+        let map = |offset: usize| {
+            if offset >= from + removed {
+                offset - removed + inserted
+            } else {
+                offset.min(from)
+            }
+        };
+        for range in ranges.iter_mut() {
+            *range = (map(range.0), map(range.1).max(map(range.0)));
+        }
+        ranges.push((from, from + inserted));
+    }
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// Lowercases `c` when that gives a single character, so folding keeps the
+/// character offsets.
+fn fold_case(c: char) -> char {
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => lower,
+        _ => c,
+    }
+}
+
+impl Editor {
+    /// Searches the document for `query`, highlights all matches in the
+    /// `search` mark layer and selects the first one at or after the cursor.
+    /// The matches are kept up to date as the document changes. An empty
+    /// query clears the search. Returns the number of matches.
+    pub fn search(&mut self, query: &str, options: SearchOptions) -> usize {
+        if query.is_empty() {
+            self.clear_search();
+            return 0;
+        }
+        self.search = Some(Search {
+            query: query.to_string(),
+            options,
+            version: None,
+        });
+        self.update_search();
+        let from = self.search_origin();
+        let matches = self.search_matches();
+        let next = matches.iter().find(|&&(start, _)| start >= from);
+        if let Some(&(start, end)) = next.or(matches.first()) {
+            self.select_match(start, end);
+        }
+        matches.len()
+    }
+
+    /// Selects the next match after the selection or cursor, wrapping around
+    /// the end of the document. Returns false if there is none.
+    pub fn search_next(&mut self) -> bool {
+        self.sync_shared_code();
+        let from = self.search_origin();
+        let matches = self.search_matches();
+        let next = matches.iter().find(|&&(start, _)| start > from);
+        let Some(&(start, end)) = next.or(matches.first()) else {
+            return false;
+        };
+        self.select_match(start, end);
+        true
+    }
+
+    /// Selects the match before the selection or cursor, wrapping around the
+    /// start of the document. Returns false if there is none.
+    pub fn search_prev(&mut self) -> bool {
+        self.sync_shared_code();
+        let from = self.search_origin();
+        let matches = self.search_matches();
+        let prev = matches.iter().rev().find(|&&(start, _)| start < from);
+        let Some(&(start, end)) = prev.or(matches.last()) else {
+            return false;
+        };
+        self.select_match(start, end);
+        true
+    }
+
+    /// Ends the search and removes the match highlights.
+    pub fn clear_search(&mut self) {
+        self.search = None;
+        self.marks.remove(SEARCH_MARK_LAYER);
+    }
+
+    /// Query, selected match and match count of the search, if one is active.
+    pub fn search_state(&self) -> Option<SearchState> {
+        let search = self.search.as_ref()?;
+        let matches = self.search_matches();
+        let current = self.selection.and_then(|selection| {
+            matches
+                .iter()
+                .position(|&(start, end)| start == selection.start && end == selection.end)
+        });
+        Some(SearchState {
+            query: search.query.clone(),
+            current,
+            total: matches.len(),
+        })
+    }
+
+    /// Brings the matches up to date with the document. After edits only
+    /// the text around them is scanned again.
+    pub(crate) fn update_search(&mut self) {
+        let version = self.code.borrow().version();
+        let Some(search) = self
+            .search
+            .as_mut()
+            .filter(|search| search.version != Some(version))
+        else {
+            return;
+        };
+        let edits = search
+            .version
+            .and_then(|since| self.code.borrow().edits_since(since));
+        search.version = Some(version);
+        let matcher = Matcher::new(&self.code.borrow(), &search.query, search.options);
+        let style = Style::default()
+            .bg(Color::Rgb(81, 70, 44))
+            .patch(self.theme_style("search"));
+        let code = &mut *self.code.borrow_mut();
+        let len = code.content.len_chars();
+        let layer = self.marks.get_or_insert(SEARCH_MARK_LAYER);
+        let Some(edits) = edits else {
+            layer.marks = matcher
+                .find(code, 0..len)
+                .into_iter()
+                .map(|(start, end)| Mark::new(code, start, end, style))
+                .collect();
+            return;
+        };
+
+        // A match touching an edited character, or its word boundary, starts
+        // less than a query length before it
+        let margin = matcher.query.len() + 1;
+        for (start, end) in dirty_ranges(&edits) {
+            let mut scan = (start.saturating_sub(margin), (end + margin).min(len));
+            let mut kept = Vec::with_capacity(layer.marks.len());
+            // matches whose text was removed collapsed and are dropped
+            for mark in layer.marks.drain(..) {
+                if mark.start() < scan.1 && mark.end() > scan.0 {
+                    scan = (scan.0.min(mark.start()), scan.1.max(mark.end()));
+                } else if mark.start() < mark.end() {
+                    kept.push(mark);
+                }
+            }
+            let found = matcher.find(code, scan.0..scan.1);
+            kept.extend(
+                found
+                    .into_iter()
+                    .map(|(start, end)| Mark::new(code, start, end, style)),
+            );
+            kept.sort_by_key(Mark::start);
+            layer.marks = kept;
+        }
+    }
+
+    fn search_matches(&self) -> Vec<(usize, usize)> {
+        self.marks
+            .get(SEARCH_MARK_LAYER)
+            .map(|layer| {
+                layer
+                    .marks
+                    .iter()
+                    .map(|mark| (mark.start(), mark.end()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Where the next search starts: the selection start or the cursor.
+    fn search_origin(&self) -> usize {
+        self.selection
+            .map_or(self.cursor, |selection| selection.start)
+    }
+
+    fn select_match(&mut self, start: usize, end: usize) {
+        self.selection = Some(Selection::new(start, end));
+        self.cursor = end;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::{Delete, InsertText};
+
+    #[test]
+    fn incremental_matches_equal_a_full_search() {
+        let text = "let value = compute(value);\nif value > 0 { print(value) }\n";
+        let mut editor = Editor::new("rust", &text.repeat(20), vec![]).unwrap();
+        let options = SearchOptions {
+            smart_case: true,
+            whole_word: true,
+        };
+        editor.search("value", options);
+        let edits = [
+            (5, "Value"),
+            (40, "val"),
+            (300, "ue"),
+            (12, " value "),
+            (0, ""),
+        ];
+        for (i, &(offset, text)) in edits.iter().enumerate() {
+            editor.set_selection(Some(Selection::new(offset, offset + i)));
+            if text.is_empty() {
+                editor.apply(Delete);
+            } else {
+                editor.apply(InsertText {
+                    text: text.to_string(),
+                });
+            }
+            let full = find_matches(&editor.borrow_code(), "value", options);
+            assert_eq!(editor.search_matches(), full, "after edit {i}");
+        }
+    }
+
+    #[test]
+    fn dirty_ranges_follow_later_edits() {
+        // insert 3 at 10, then remove 5 chars at 2 and insert 1
+        assert_eq!(dirty_ranges(&[(10, 0, 3), (2, 5, 1)]), vec![(2, 3), (6, 9)]);
+        // the second edit removes the first insertion
+        assert_eq!(dirty_ranges(&[(10, 0, 3), (8, 10, 0)]), vec![(8, 8)]);
+    }
+}
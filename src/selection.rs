@@ -1,11 +1,29 @@
+use crate::anchor::Anchor;
+
 #[derive(Debug, Clone, Copy)]
 pub enum SelectionSnap {
     None,
-    Word { anchor: usize },
-    Line { anchor: usize },
+    Word {
+        anchor: usize,
+    },
+    /// Like `Word`, for identifier parts, see [`crate::code::Code::sub_word_boundaries`]
+    SubWord {
+        anchor: usize,
+    },
+    Line {
+        anchor: usize,
+    },
+    Paragraph {
+        anchor: usize,
+    },
+    /// Dragging the selection pressed at `origin` to a drop position
+    Move {
+        origin: usize,
+    },
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Selection {
     pub start: usize,
     pub end: usize,
@@ -53,3 +71,33 @@ impl Selection {
         }
     }
 }
+
+/// Recently cleared selections, newest last, anchored so they follow edits.
+#[derive(Debug, Default)]
+pub(crate) struct SelectionHistory {
+    /// Start and end anchors, and whether the cursor was at the start
+    entries: Vec<(Anchor, Anchor, bool)>,
+}
+
+impl SelectionHistory {
+    const CAPACITY: usize = 16;
+
+    pub(crate) fn push(&mut self, start: Anchor, end: Anchor, cursor_at_start: bool) {
+        if self.entries.len() == Self::CAPACITY {
+            self.entries.remove(0);
+        }
+        self.entries.push((start, end, cursor_at_start));
+    }
+
+    /// Removes the newest entry, returning its selection and cursor offset.
+    pub(crate) fn pop(&mut self) -> Option<(Selection, usize)> {
+        let (start, end, cursor_at_start) = self.entries.pop()?;
+        let selection = Selection::new(start.position(), end.position());
+        let cursor = if cursor_at_start {
+            selection.start
+        } else {
+            selection.end
+        };
+        Some((selection, cursor))
+    }
+}
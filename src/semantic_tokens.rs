@@ -0,0 +1,77 @@
+use crate::editor::Editor;
+use crate::marks::Mark;
+use crate::theme::resolve_capture;
+use ratatui_core::style::Style;
+use std::ops::Range;
+
+/// Mark layer holding the styles of [`Editor::set_semantic_tokens`].
+pub const SEMANTIC_TOKENS_MARK_LAYER: &str = "semantic_tokens";
+
+impl Editor {
+    /// Replaces the semantic tokens, e.g. from an LSP
+    /// `textDocument/semanticTokens` response, as `(char range, token type,
+    /// modifiers)`.
+    ///
+    /// A token is styled with the theme entry `lsp.type.<type>`, or else the
+    /// syntax entry for its type (`parameter` uses `variable.parameter` or
+    /// `variable`, `enumMember` uses `constant`, ...), patched with
+    /// `lsp.mod.<modifier>` and `lsp.typemod.<type>.<modifier>` for each
+    /// modifier. Tokens without any of these keep their syntax highlighting.
+    ///
+    /// The styles are patched over the syntax highlighting and below all mark
+    /// layers, and follow edits until the next update.
+    pub fn set_semantic_tokens(&mut self, tokens: Vec<(Range<usize>, &str, Vec<&str>)>) {
+        let marks: Vec<Mark> = tokens
+            .into_iter()
+            .filter_map(|(range, token_type, modifiers)| {
+                let style = self.semantic_token_style(token_type, &modifiers)?;
+                let mut code = self.code.borrow_mut();
+                Some(Mark::new(&mut code, range.start, range.end, style))
+            })
+            .collect();
+        self.marks.get_or_insert(SEMANTIC_TOKENS_MARK_LAYER).marks = marks;
+        self.set_mark_layer_priority(SEMANTIC_TOKENS_MARK_LAYER, i32::MIN);
+    }
+
+    pub fn clear_semantic_tokens(&mut self) {
+        self.marks.remove(SEMANTIC_TOKENS_MARK_LAYER);
+    }
+
+    /// Theme style of a semantic token, `None` if the theme has none for it.
+    fn semantic_token_style(&self, token_type: &str, modifiers: &[&str]) -> Option<Style> {
+        let mut style = self
+            .theme
+            .get(&format!("lsp.type.{token_type}"))
+            .or_else(|| {
+                resolve_capture(&self.theme, syntax_fallback(token_type)).map(|(_, style)| style)
+            })
+            .copied();
+        for modifier in modifiers {
+            let keys = [
+                format!("lsp.mod.{modifier}"),
+                format!("lsp.typemod.{token_type}.{modifier}"),
+            ];
+            for key in keys {
+                if let Some(modifier_style) = self.theme.get(&key) {
+                    style = Some(style.unwrap_or_default().patch(*modifier_style));
+                }
+            }
+        }
+        style
+    }
+}
+
+/// Syntax highlight name closest to an LSP token type.
+fn syntax_fallback(token_type: &str) -> &str {
+    match token_type {
+        "class" | "struct" | "enum" | "interface" | "typeAlias" => "type",
+        "typeParameter" => "type.parameter",
+        "builtinType" => "type.builtin",
+        "parameter" => "variable.parameter",
+        "enumMember" => "constant",
+        "macro" => "function.macro",
+        "decorator" => "attribute",
+        "regexp" => "string.regexp",
+        other => other,
+    }
+}
@@ -0,0 +1,131 @@
+use crate::editor::Editor;
+use std::time::{Duration, Instant};
+
+/// How often the offset moves during a scroll animation.
+pub(crate) const SCROLL_FRAME: Duration = Duration::from_millis(16);
+
+/// A vertical scroll from one offset to another, fast at first and slowing
+/// down towards the target.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ScrollAnimation {
+    from: usize,
+    pub(crate) to: usize,
+    started: Instant,
+    duration: Duration,
+    pub(crate) next_frame: Instant,
+}
+
+impl ScrollAnimation {
+    pub(crate) fn new(from: usize, to: usize, now: Instant, duration: Duration) -> Self {
+        Self {
+            from,
+            to,
+            started: now,
+            duration,
+            next_frame: now + SCROLL_FRAME,
+        }
+    }
+
+    pub(crate) fn expires(&self) -> Instant {
+        self.started + self.duration
+    }
+
+    pub(crate) fn deadline(&self) -> Instant {
+        self.next_frame.min(self.expires())
+    }
+
+    pub(crate) fn offset_at(&self, now: Instant) -> usize {
+        let elapsed = now.saturating_duration_since(self.started).as_secs_f32();
+        let total = self.duration.as_secs_f32();
+        let t = if total > 0.0 {
+            (elapsed / total).min(1.0)
+        } else {
+            1.0
+        };
+        let eased = 1.0 - (1.0 - t).powi(3);
+        let distance = (self.to as f32 - self.from as f32) * eased;
+        (self.from as f32 + distance).round() as usize
+    }
+}
+
+impl Editor {
+    /// Animates jumps of the vertical offset, e.g. when the cursor moves a
+    /// page or to a far away line, over `duration`, or scrolls at once with
+    /// `None`, the default. The offset moves as [`Editor::tick`] is called.
+    pub fn set_smooth_scroll(&mut self, duration: Option<Duration>) {
+        self.smooth_scroll = duration;
+        if duration.is_none()
+            && let Some(animation) = self.scroll_animation.take()
+        {
+            self.offset_y = animation.to;
+        }
+    }
+
+    /// Vertical offset the editor is scrolling to, which is the current one
+    /// unless a smooth scroll is under way.
+    pub fn scroll_target(&self) -> usize {
+        self.scroll_animation
+            .map_or(self.offset_y, |animation| animation.to)
+    }
+
+    /// Runs `scroll`, which may move the vertical offset, as if any running
+    /// animation had finished, and animates the change when smooth scrolling
+    /// is enabled. Moves of a single line are not animated.
+    pub(crate) fn animate_scroll(&mut self, scroll: impl FnOnce(&mut Self)) {
+        let Some(duration) = self.smooth_scroll else {
+            scroll(self);
+            return;
+        };
+        let shown = self.offset_y;
+        let animation = self.scroll_animation.take();
+        if let Some(animation) = animation {
+            self.offset_y = animation.to;
+        }
+        scroll(self);
+        let target = self.offset_y;
+        if animation.is_some_and(|animation| animation.to == target) {
+            self.offset_y = shown;
+            self.scroll_animation = animation;
+        } else if target.abs_diff(shown) > 1 {
+            self.offset_y = shown;
+            let animation = ScrollAnimation::new(shown, target, Instant::now(), duration);
+            self.scroll_animation = Some(animation);
+        }
+    }
+
+    /// Moves the offset along a running animation. Returns true if it moved.
+    pub(crate) fn advance_scroll_animation(&mut self, now: Instant) -> bool {
+        let Some(mut animation) = self.scroll_animation else {
+            return false;
+        };
+        let before = self.offset_y;
+        if now >= animation.expires() {
+            self.offset_y = animation.to;
+            self.scroll_animation = None;
+        } else if now >= animation.next_frame {
+            self.offset_y = animation.offset_at(now);
+            animation.next_frame = now + SCROLL_FRAME;
+            self.scroll_animation = Some(animation);
+        }
+        self.offset_y != before
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scroll_animation_eases_out() {
+        let now = Instant::now();
+        let animation = ScrollAnimation::new(10, 110, now, Duration::from_millis(200));
+        let offset = |ms| animation.offset_at(now + Duration::from_millis(ms));
+        assert_eq!(offset(0), 10);
+        assert_eq!(offset(100), 98);
+        assert_eq!(offset(200), 110);
+        assert_eq!(offset(300), 110);
+
+        let up = ScrollAnimation::new(110, 10, now, Duration::from_millis(200));
+        assert_eq!(up.offset_at(now + Duration::from_millis(100)), 23);
+    }
+}
@@ -0,0 +1,143 @@
+use crate::code::{EditBatch, Operation};
+use crate::editor::Editor;
+use crate::selection::Selection;
+use crate::types::{ChangeOrigin, LineEnding};
+use anyhow::{Result, anyhow};
+
+/// Editor state captured by [`Editor::snapshot`], for session restore and
+/// per-tab state that outlives the application. With the `serde` feature it
+/// can be serialized with any serde format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    /// Document text with `\n` line endings
+    pub content: String,
+    /// Line ending the content is saved with, see [`Editor::line_ending`]
+    pub line_ending: LineEnding,
+    /// Cursor as a character index
+    pub cursor: usize,
+    pub selection: Option<Selection>,
+    /// Index of the first visible line
+    pub offset_y: usize,
+    /// Horizontal scroll offset in columns
+    pub offset_x: usize,
+    /// Whether the content differs from the last saved version
+    pub modified: bool,
+    /// Undo history, if it was captured
+    pub history: Option<HistorySnapshot>,
+}
+
+/// Undo history of a [`Snapshot`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HistorySnapshot {
+    /// Stored undo steps, oldest first
+    pub batches: Vec<EditBatch>,
+    /// Number of batches that are applied; the rest can be redone
+    pub index: usize,
+}
+
+impl Editor {
+    /// Captures the content, cursor, selection and scroll offsets, without
+    /// the undo history.
+    pub fn snapshot(&self) -> Snapshot {
+        let code = self.code.borrow();
+        Snapshot {
+            content: code.get_content(),
+            line_ending: self.line_ending,
+            cursor: self.cursor,
+            selection: self.selection,
+            offset_y: self.offset_y,
+            offset_x: self.offset_x,
+            modified: code.is_modified(),
+            history: None,
+        }
+    }
+
+    /// Like [`Editor::snapshot`], also capturing the undo history.
+    pub fn snapshot_with_history(&self) -> Snapshot {
+        let (batches, index) = self.code.borrow().history_batches();
+        Snapshot {
+            history: Some(HistorySnapshot { batches, index }),
+            ..self.snapshot()
+        }
+    }
+
+    /// Restores a state captured by [`Editor::snapshot`]. The undo history
+    /// is replaced by the one in the snapshot, or cleared if it has none.
+    /// Positions past the restored content are clamped.
+    ///
+    /// Fails without changing the editor if the history does not fit the
+    /// content, e.g. a snapshot edited after it was serialized.
+    pub fn restore(&mut self, snapshot: Snapshot) -> Result<()> {
+        self.sync_shared_code();
+        let content = LineEnding::normalize(&snapshot.content);
+        if let Some(history) = &snapshot.history {
+            history.check(content.chars().count())?;
+        }
+        let mut code = self.code.borrow_mut();
+        code.tx();
+        code.set_change_origin(ChangeOrigin::Programmatic);
+        let len = code.len();
+        code.remove(0, len);
+        code.insert(0, &content);
+        code.commit();
+        let (batches, index) = snapshot
+            .history
+            .map(|history| (history.batches, history.index))
+            .unwrap_or_default();
+        code.restore_history(batches, index);
+        code.set_modified(snapshot.modified);
+        let len = code.len_chars();
+        drop(code);
+        self.reset_highlight_cache();
+
+        self.selection = snapshot
+            .selection
+            .map(|selection| Selection::new(selection.start.min(len), selection.end.min(len)))
+            .filter(|selection| selection.start < selection.end);
+        self.offset_x = snapshot.offset_x;
+        self.set_offset_y(snapshot.offset_y);
+        self.set_cursor(snapshot.cursor);
+        self.line_ending = snapshot.line_ending;
+        Ok(())
+    }
+}
+
+impl HistorySnapshot {
+    /// Checks that undoing the applied batches from a document of `len`
+    /// characters, and redoing the rest, stays within the document.
+    fn check(&self, len: usize) -> Result<()> {
+        if self.index > self.batches.len() {
+            return Err(anyhow!(
+                "history index {} is past its {} batches",
+                self.index,
+                self.batches.len()
+            ));
+        }
+        let (applied, undone) = self.batches.split_at(self.index);
+        let mut undo_len = len;
+        for edit in applied
+            .iter()
+            .rev()
+            .flat_map(|batch| batch.edits.iter().rev())
+        {
+            let chars = edit.text.chars().count();
+            undo_len = match edit.operation {
+                Operation::Insert => undo_len.checked_sub(chars).filter(|&l| edit.start <= l),
+                Operation::Remove => (edit.start <= undo_len).then_some(undo_len + chars),
+            }
+            .ok_or_else(|| anyhow!("history edit at {} is out of bounds", edit.start))?;
+        }
+        let mut redo_len = len;
+        for edit in undone.iter().flat_map(|batch| batch.edits.iter()) {
+            let chars = edit.text.chars().count();
+            redo_len = match edit.operation {
+                Operation::Insert => (edit.start <= redo_len).then_some(redo_len + chars),
+                Operation::Remove => redo_len.checked_sub(chars).filter(|&l| edit.start <= l),
+            }
+            .ok_or_else(|| anyhow!("history edit at {} is out of bounds", edit.start))?;
+        }
+        Ok(())
+    }
+}
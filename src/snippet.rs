@@ -0,0 +1,161 @@
+use crate::anchor::Anchor;
+
+/// A parsed LSP-style snippet such as `for ${1:item} in ${2:iter}:\n    $0`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// The text to insert, with placeholders filled in
+    pub text: String,
+    /// Tabstops in visiting order (`$1`, `$2`, ..., then `$0`), each with
+    /// the character ranges of all its occurrences within `text`
+    pub tabstops: Vec<Vec<(usize, usize)>>,
+}
+
+impl Snippet {
+    /// Parses `$1`, `${1}`, `${1:placeholder}` (placeholders may nest) and
+    /// `$0`, the final cursor position. `\$`, `\}` and `\\` are literals.
+    /// Without `$0` the cursor ends after the text.
+    pub fn parse(source: &str) -> Self {
+        let chars: Vec<char> = source.chars().collect();
+        let mut parser = Parser {
+            chars: &chars,
+            pos: 0,
+            text: String::new(),
+            len: 0,
+            stops: Vec::new(),
+        };
+        parser.parse(false);
+
+        let mut stops = parser.stops;
+        if !stops.iter().any(|&(index, _, _)| index == 0) {
+            stops.push((0, parser.len, parser.len));
+        }
+        let mut indexes: Vec<u32> = stops.iter().map(|&(index, _, _)| index).collect();
+        indexes.sort_by_key(|&index| (index == 0, index));
+        indexes.dedup();
+        let tabstops = indexes
+            .into_iter()
+            .map(|index| {
+                stops
+                    .iter()
+                    .filter(|&&(i, _, _)| i == index)
+                    .map(|&(_, start, end)| (start, end))
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            text: parser.text,
+            tabstops,
+        }
+    }
+}
+
+struct Parser<'a> {
+    chars: &'a [char],
+    pos: usize,
+    text: String,
+    /// Length of `text` in characters
+    len: usize,
+    stops: Vec<(u32, usize, usize)>,
+}
+
+impl Parser<'_> {
+    fn push(&mut self, c: char) {
+        self.text.push(c);
+        self.len += 1;
+    }
+
+    /// Parses until the end, or until an unescaped `}` inside a placeholder.
+    fn parse(&mut self, in_placeholder: bool) {
+        while let Some(&c) = self.chars.get(self.pos) {
+            match c {
+                '\\' if matches!(self.chars.get(self.pos + 1), Some('$' | '}' | '\\')) => {
+                    self.push(self.chars[self.pos + 1]);
+                    self.pos += 2;
+                }
+                '}' if in_placeholder => return,
+                '$' => self.tabstop(),
+                c => {
+                    self.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+
+    fn tabstop(&mut self) {
+        let start = self.pos;
+        self.pos += 1;
+        let braced = self.chars.get(self.pos) == Some(&'{');
+        if braced {
+            self.pos += 1;
+        }
+        let Some(index) = self.number() else {
+            // not a tabstop, keep the text as is
+            self.pos = start + 1;
+            self.push('$');
+            return;
+        };
+
+        let from = self.len;
+        if braced {
+            if self.chars.get(self.pos) == Some(&':') {
+                self.pos += 1;
+                self.parse(true);
+            }
+            if self.chars.get(self.pos) == Some(&'}') {
+                self.pos += 1;
+            }
+        }
+        self.stops.push((index, from, self.len));
+    }
+}
+
+/// Tabstops of an inserted snippet, anchored in the document.
+#[derive(Debug)]
+pub(crate) struct SnippetSession {
+    /// Start and end anchors of every occurrence, per tabstop
+    pub(crate) tabstops: Vec<Vec<(Anchor, Anchor)>>,
+    pub(crate) current: usize,
+}
+
+impl SnippetSession {
+    /// Current ranges of the tabstop at `index`.
+    pub(crate) fn ranges(&self, index: usize) -> Vec<(usize, usize)> {
+        self.tabstops[index]
+            .iter()
+            .map(|(start, end)| (start.position(), end.position().max(start.position())))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_snippet() {
+        let snippet = Snippet::parse("for ${1:item} in ${2:iter}:\n    $0");
+        assert_eq!(snippet.text, "for item in iter:\n    ");
+        assert_eq!(
+            snippet.tabstops,
+            [vec![(4, 8)], vec![(12, 16)], vec![(22, 22)]]
+        );
+
+        let snippet = Snippet::parse("${1:a ${2:b}} $1 \\$5 $x");
+        assert_eq!(snippet.text, "a b  $5 $x");
+        assert_eq!(
+            snippet.tabstops,
+            [vec![(0, 3), (4, 4)], vec![(2, 3)], vec![(10, 10)]]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use crate::code::Code;
+use std::collections::HashSet;
+
+/// Mark layer holding the misspelled words found by
+/// [`Editor::check_spelling`](crate::editor::Editor::check_spelling).
+pub const SPELL_MARK_LAYER: &str = "spell";
+
+/// Word list used by the spell checker.
+pub trait Dictionary {
+    /// Returns true if `word` is spelled correctly.
+    fn check(&self, word: &str) -> bool;
+
+    /// Replacement candidates for a misspelled word, best first.
+    fn suggest(&self, _word: &str) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// A set of known words. A word is found as written or in lowercase, so
+/// entries should be lowercase except for words that must be capitalized:
+/// `"hello"` accepts `Hello`, while `"Paris"` rejects `paris`.
+impl Dictionary for HashSet<String> {
+    fn check(&self, word: &str) -> bool {
+        self.contains(word) || self.contains(&word.to_lowercase())
+    }
+}
+
+/// Character ranges of misspelled words inside comments and strings.
+pub(crate) fn misspelled_words(code: &Code, dictionary: &dyn Dictionary) -> Vec<(usize, usize)> {
+    let mut words = Vec::new();
+    for (start, end) in code.comment_and_string_ranges() {
+        let text = code.slice(start, end);
+        for (offset, word) in split_words(&text) {
+            if !dictionary.check(word) {
+                let from = start + offset;
+                words.push((from, from + word.chars().count()));
+            }
+        }
+    }
+    words
+}
+
+/// Alphabetic words of `text` with their character offsets. Apostrophes inside
+/// a word are kept, words of one letter and words touching digits or
+/// underscores (likely identifiers) are skipped.
+fn split_words(text: &str) -> Vec<(usize, &str)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let is_part = |c: char| c.is_alphanumeric() || c == '_' || c == '\'';
+    let mut words = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        if !is_part(chars[i].1) {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < chars.len() && is_part(chars[i].1) {
+            i += 1;
+        }
+        let byte_start = chars[start].0;
+        let byte_end = chars.get(i).map_or(text.len(), |&(b, _)| b);
+        let word = text[byte_start..byte_end].trim_matches('\'');
+        let leading = text[byte_start..byte_end].len()
+            - text[byte_start..byte_end].trim_start_matches('\'').len();
+        if word.chars().count() > 1 && word.chars().all(|c| c.is_alphabetic() || c == '\'') {
+            words.push((start + leading, word));
+        }
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_words() {
+        let words = split_words("// don't parse foo_bar x 'quoted' v2 ünïcode");
+        assert_eq!(
+            words,
+            [(3, "don't"), (9, "parse"), (26, "quoted"), (37, "ünïcode")]
+        );
+    }
+
+    #[test]
+    fn test_word_set_matches_as_written_or_lowercase() {
+        let words: HashSet<String> = ["hello", "Paris"].map(String::from).into();
+        assert!(words.check("hello"));
+        assert!(words.check("Hello"));
+        assert!(words.check("Paris"));
+        assert!(!words.check("paris"));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::editor::Editor;
+use crate::events::EditorEvent;
+use crate::types::ChangeEvent;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Drives an [`Editor`] with synthetic key and mouse events in a fixed-size
+/// area, without a terminal, for integration tests of host applications.
+///
+/// The harness installs its own change listener to record
+/// [`ChangeEvent`]s, replacing any listener set on the editor before.
+///
+/// ```
+/// use ratatui_code_editor::editor::Editor;
+/// use ratatui_code_editor::testing::TestHarness;
+///
+/// let editor = Editor::new("text", "", vec![]).unwrap();
+/// let mut harness = TestHarness::new(editor, 20, 3);
+/// harness.editor_mut().show_line_numbers(false);
+/// harness.keys("hi<Enter>there");
+/// harness.assert_lines(&["hi", "there"]);
+/// harness.assert_cursor(1, 5);
+/// // one change notification per typed key
+/// assert_eq!(harness.take_changes().len(), 8);
+/// ```
+pub struct TestHarness {
+    editor: Editor,
+    area: Rect,
+    changes: Rc<RefCell<Vec<ChangeEvent>>>,
+}
+
+impl TestHarness {
+    pub fn new(mut editor: Editor, width: u16, height: u16) -> Self {
+        let changes = Rc::new(RefCell::new(Vec::new()));
+        let recorded = changes.clone();
+        editor.set_change_listener(Box::new(move |event| {
+            recorded.borrow_mut().push(event.clone());
+        }));
+        // Take the initial state as the baseline for later events
+        editor.drain_events();
+        Self {
+            editor,
+            area: Rect::new(0, 0, width, height),
+            changes,
+        }
+    }
+
+    pub fn editor(&self) -> &Editor {
+        &self.editor
+    }
+
+    pub fn editor_mut(&mut self) -> &mut Editor {
+        &mut self.editor
+    }
+
+    pub fn area(&self) -> Rect {
+        self.area
+    }
+
+    pub fn key(&mut self, key: KeyEvent) -> &mut Self {
+        self.editor
+            .input(key, &self.area)
+            .expect("editor failed to handle key");
+        self
+    }
+
+    /// Types a key script, see [`parse_keys`].
+    pub fn keys(&mut self, script: &str) -> &mut Self {
+        for key in parse_keys(script) {
+            self.key(key);
+        }
+        self
+    }
+
+    pub fn mouse(&mut self, kind: MouseEventKind, column: u16, row: u16) -> &mut Self {
+        let event = MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+        self.editor
+            .mouse(event, &self.area)
+            .expect("editor failed to handle mouse event");
+        self
+    }
+
+    /// Presses and releases the left button at a screen cell.
+    pub fn click(&mut self, column: u16, row: u16) -> &mut Self {
+        self.mouse(MouseEventKind::Down(MouseButton::Left), column, row)
+            .mouse(MouseEventKind::Up(MouseButton::Left), column, row)
+    }
+
+    /// Drags with the left button from one screen cell to another.
+    pub fn drag(&mut self, from: (u16, u16), to: (u16, u16)) -> &mut Self {
+        self.mouse(MouseEventKind::Down(MouseButton::Left), from.0, from.1)
+            .mouse(MouseEventKind::Drag(MouseButton::Left), to.0, to.1)
+            .mouse(MouseEventKind::Up(MouseButton::Left), to.0, to.1)
+    }
+
+    /// Renders the editor into a fresh buffer of the harness size.
+    pub fn render(&self) -> Buffer {
+        let mut buf = Buffer::empty(self.area);
+        (&self.editor).render(self.area, &mut buf);
+        buf
+    }
+
+    /// Rendered rows as text with trailing blanks trimmed, gutter included.
+    pub fn screen(&self) -> Vec<String> {
+        let buf = self.render();
+        (0..self.area.height)
+            .map(|y| {
+                let row: String = (0..self.area.width).map(|x| buf[(x, y)].symbol()).collect();
+                row.trim_end().to_string()
+            })
+            .collect()
+    }
+
+    /// Asserts the rendered rows, see [`TestHarness::screen`].
+    #[track_caller]
+    pub fn assert_screen(&self, expected: &[&str]) {
+        assert_eq!(self.screen(), expected, "rendered screen differs");
+    }
+
+    /// Asserts the document lines.
+    #[track_caller]
+    pub fn assert_lines(&self, expected: &[&str]) {
+        let content = self.editor.get_content();
+        let lines: Vec<&str> = content.split('\n').collect();
+        assert_eq!(lines, expected, "document content differs");
+    }
+
+    /// Cursor as (line, column in chars).
+    pub fn cursor(&self) -> (usize, usize) {
+        let code = self.editor.borrow_code();
+        code.point(self.editor.get_cursor().min(code.len_chars()))
+    }
+
+    #[track_caller]
+    pub fn assert_cursor(&self, line: usize, column: usize) {
+        assert_eq!(self.cursor(), (line, column), "cursor position differs");
+    }
+
+    /// Terminal cursor cell the host would show, if visible.
+    pub fn screen_cursor(&self) -> Option<(u16, u16)> {
+        self.editor.get_visible_cursor(&self.area)
+    }
+
+    /// Change notifications recorded since the last call.
+    pub fn take_changes(&mut self) -> Vec<ChangeEvent> {
+        std::mem::take(&mut self.changes.borrow_mut())
+    }
+
+    /// Editor events since the last call, see [`Editor::drain_events`].
+    pub fn take_events(&mut self) -> Vec<EditorEvent> {
+        self.editor.drain_events()
+    }
+}
+
+/// Parses a key script into key events.
+///
+/// Plain characters are typed as is. Special keys are written in angle
+/// brackets: `<Enter>`, `<Esc>`, `<Tab>`, `<BS>`, `<Del>`, `<Up>`, `<Down>`,
+/// `<Left>`, `<Right>`, `<Home>`, `<End>`, `<PageUp>`, `<PageDown>` and `<lt>`
+/// for a literal `<`. Modifiers are prefixed, e.g. `<C-z>`, `<S-Right>`,
+/// `<A-x>` or `<C-S-v>`. Unknown names are typed literally.
+pub fn parse_keys(script: &str) -> Vec<KeyEvent> {
+    let mut keys = Vec::new();
+    let mut rest = script;
+    while let Some(c) = rest.chars().next() {
+        if c == '<'
+            && let Some(end) = rest.find('>')
+            && let Some(key) = parse_key(&rest[1..end])
+        {
+            keys.push(key);
+            rest = &rest[end + 1..];
+            continue;
+        }
+        let code = match c {
+            '\n' => KeyCode::Enter,
+            '\t' => KeyCode::Tab,
+            c => KeyCode::Char(c),
+        };
+        keys.push(KeyEvent::new(code, KeyModifiers::NONE));
+        rest = &rest[c.len_utf8()..];
+    }
+    keys
+}
+
+fn parse_key(name: &str) -> Option<KeyEvent> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut name = name;
+    while let Some((prefix, rest)) = name.split_once('-')
+        && !rest.is_empty()
+    {
+        modifiers |= match prefix {
+            "C" => KeyModifiers::CONTROL,
+            "S" => KeyModifiers::SHIFT,
+            "A" => KeyModifiers::ALT,
+            _ => return None,
+        };
+        name = rest;
+    }
+    let code = match name {
+        "Enter" | "CR" => KeyCode::Enter,
+        "Esc" => KeyCode::Esc,
+        "Tab" => KeyCode::Tab,
+        "BS" => KeyCode::Backspace,
+        "Del" => KeyCode::Delete,
+        "Ins" => KeyCode::Insert,
+        "Up" => KeyCode::Up,
+        "Down" => KeyCode::Down,
+        "Left" => KeyCode::Left,
+        "Right" => KeyCode::Right,
+        "Home" => KeyCode::Home,
+        "End" => KeyCode::End,
+        "PageUp" => KeyCode::PageUp,
+        "PageDown" => KeyCode::PageDown,
+        "lt" => KeyCode::Char('<'),
+        _ => {
+            let mut chars = name.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if modifiers != KeyModifiers::NONE => KeyCode::Char(c),
+                _ => return None,
+            }
+        }
+    };
+    Some(KeyEvent::new(code, modifiers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_scripts() {
+        let keys = parse_keys("a<Enter><C-z><lt><S-Right><x");
+        assert_eq!(
+            keys,
+            vec![
+                KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Char('z'), KeyModifiers::CONTROL),
+                KeyEvent::new(KeyCode::Char('<'), KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Right, KeyModifiers::SHIFT),
+                KeyEvent::new(KeyCode::Char('<'), KeyModifiers::NONE),
+                KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE),
+            ]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 pub fn vesper() -> Vec<(&'static str, &'static str)> {
     vec![
         ("identifier", "#A5FCB6"),
@@ -12,6 +14,7 @@ pub fn vesper() -> Vec<(&'static str, &'static str)> {
         ("float", "#f6c99f"),
         ("variable", "#ffffff"),
         ("variable.builtin", "#ffffff"),
+        ("variable.parameter", "#c6a5fc"),
         ("function", "#f6c99f"),
         ("function.call", "#f6c99f"),
         ("method", "#f6c99f"),
@@ -30,3 +33,42 @@ pub fn vesper() -> Vec<(&'static str, &'static str)> {
         ("word_highlight", "#3a3a3a"),
     ]
 }
+
+/// Finds the theme entry for the highlight capture `capture`, falling back
+/// through its dotted parents, so `function.macro` uses `function` when the
+/// theme has no `function.macro`. Returns the matching key and its value.
+pub fn resolve_capture<'a, T>(
+    theme: &'a HashMap<String, T>,
+    capture: &str,
+) -> Option<(&'a str, &'a T)> {
+    let mut name = capture;
+    loop {
+        if let Some((key, value)) = theme.get_key_value(name) {
+            return Some((key.as_str(), value));
+        }
+        name = name.rsplit_once('.')?.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_capture_falls_back_to_parents() {
+        let theme = HashMap::from([
+            ("function".to_string(), 1),
+            ("keyword.control".to_string(), 2),
+        ]);
+        assert_eq!(
+            resolve_capture(&theme, "function.macro"),
+            Some(("function", &1))
+        );
+        assert_eq!(
+            resolve_capture(&theme, "keyword.control.import"),
+            Some(("keyword.control", &2))
+        );
+        assert_eq!(resolve_capture(&theme, "keyword"), None);
+        assert_eq!(resolve_capture(&theme, "variable.parameter"), None);
+    }
+}
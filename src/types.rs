@@ -7,6 +7,7 @@ pub type Theme = HashMap<String, Style>;
 pub(crate) type Hightlight = (usize, usize, Style);
 // source id, start offset, end offset
 pub(crate) type HightlightCache = HashMap<(u8, usize, usize), Vec<Hightlight>>;
+pub(crate) type LineHighlightCache = HashMap<usize, Vec<Hightlight>>;
 
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub(crate) struct LineDiff {
@@ -45,6 +46,144 @@ impl VisualRow {
     }
 }
 
+/// A replacement of a (row, column) range, as produced by formatters and LSP servers.
+/// Columns are character offsets within the line, matching the change callback.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct TextEdit {
+    pub start_row: usize,
+    pub start_col: usize,
+    pub end_row: usize,
+    pub end_col: usize,
+    pub text: String,
+}
+
+/// A named declaration found in the syntax tree, such as a function or struct.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Symbol {
+    pub name: String,
+    /// Tree-sitter node kind, e.g. `function_item`
+    pub kind: String,
+    pub line: usize,
+    /// Char offset of the name
+    pub offset: usize,
+}
+
+/// Where a document change came from.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ChangeOrigin {
+    /// An editing action such as typing or pasting
+    Edit,
+    Undo,
+    Redo,
+    /// An API call such as `set_content` or `apply_transaction`
+    Programmatic,
+}
+
+/// A change notification, sent once per committed transaction, undo or redo.
+/// `changes` are applied in order, each in the coordinates left by the previous
+/// one, as LSP `didChange` expects.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChangeEvent {
+    pub version: u64,
+    pub origin: ChangeOrigin,
+    pub changes: Vec<TextEdit>,
+}
+
+pub type ChangeListener = Box<dyn Fn(&ChangeEvent)>;
+
+/// How change notifications describe an edit.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ChangeSync {
+    /// Each edit of the transaction as a separate range replacement
+    #[default]
+    Incremental,
+    /// A single replacement of the whole previous document with the new text
+    Full,
+}
+
+/// Line terminator used when content leaves the editor.
+/// The buffer itself always stores `\n`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+}
+
+impl LineEnding {
+    /// Picks the ending used by most lines of `text`, preferring `Lf` on a tie.
+    pub fn detect(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        if crlf > lf { Self::CrLf } else { Self::Lf }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::CrLf => "\r\n",
+        }
+    }
+
+    /// Converts `\r\n` line endings to `\n`.
+    pub fn normalize(text: &str) -> std::borrow::Cow<'_, str> {
+        if text.contains("\r\n") {
+            text.replace("\r\n", "\n").into()
+        } else {
+            text.into()
+        }
+    }
+
+    /// Converts `\n` line endings to this ending.
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Self::Lf => text.to_string(),
+            Self::CrLf => text.replace('\n', "\r\n"),
+        }
+    }
+}
+
+/// How the `Paste` action inserts clipboard text.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum PasteMode {
+    /// Re-indent pasted lines to match the indentation at the cursor.
+    #[default]
+    Smart,
+    /// Insert the clipboard text unchanged.
+    Literal,
+}
+
+/// Terminal cursor shape the editor asks for, see [`Editor::cursor_style`].
+///
+/// [`Editor::cursor_style`]: crate::editor::Editor::cursor_style
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum CursorStyle {
+    /// A thin bar between characters, for inserting text.
+    #[default]
+    Bar,
+    /// A block over the character, e.g. for a normal mode or read-only view.
+    Block,
+    /// An underline below the character, for replacing text.
+    Underline,
+}
+
+/// How [`Editor::focus`] scrolls after the cursor moved.
+///
+/// [`Editor::focus`]: crate::editor::Editor::focus
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ScrollFollow {
+    /// Scroll only as far as needed to keep the cursor visible.
+    #[default]
+    Cursor,
+    /// Keep the cursor line vertically centered.
+    Typewriter,
+    /// Stay on the last line, e.g. for a live log or REPL, until the user
+    /// scrolls up or moves the cursor out of view. Scrolling back down to
+    /// the end follows it again.
+    Tail,
+}
+
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub struct DiffOptions {
     pub focus_context: usize,
@@ -1,47 +1,127 @@
+use std::collections::HashSet;
+use std::sync::{LazyLock, Mutex};
+
 pub fn get_lang(filename: &str) -> String {
-    let extension = std::path::Path::new(filename)
-        .extension()
-        .and_then(|ext| ext.to_str())
+    detect_lang(filename, None)
+}
+
+/// Detects the language of a file from its name and, failing that, from its
+/// first line.
+///
+/// Well-known file names (`Dockerfile`, `Makefile`, `Cargo.lock`) are checked
+/// first, then the extension. Template and backup suffixes such as `.in`,
+/// `.j2` or `.orig` are skipped, so `config.toml.in` is TOML. Files without a
+/// known extension fall back to the interpreter in a shebang line
+/// (`#!/usr/bin/env python3`). Returns `"unknown"` if nothing matches.
+pub fn detect_lang(path: impl AsRef<std::path::Path>, first_line: Option<&str>) -> String {
+    let name = path
+        .as_ref()
+        .file_name()
+        .and_then(|name| name.to_str())
         .unwrap_or("");
 
-    match extension {
+    let lang = lang_from_file_name(name)
+        .or_else(|| first_line.and_then(lang_from_shebang))
+        .unwrap_or("unknown");
+    lang.to_string()
+}
+
+fn lang_from_file_name(name: &str) -> Option<&'static str> {
+    let by_name = match name {
+        "Dockerfile" | "Containerfile" => Some("dockerfile"),
+        "Makefile" | "makefile" | "GNUmakefile" => Some("make"),
+        "Cargo.lock" | "Pipfile" | "poetry.lock" => Some("toml"),
+        ".bashrc" | ".bash_profile" | ".bash_aliases" | ".profile" | ".zshrc" | ".zprofile"
+        | "PKGBUILD" => Some("shell"),
+        "Gemfile" | "Rakefile" => Some("ruby"),
+        "CMakeLists.txt" => Some("cmake"),
+        _ => None,
+    };
+    if by_name.is_some() {
+        return by_name;
+    }
+    if name.starts_with("Dockerfile.") {
+        return Some("dockerfile");
+    }
+
+    let mut stem = name;
+    while let Some((rest, extension)) = stem.rsplit_once('.') {
+        if rest.is_empty() {
+            return None;
+        }
+        match extension {
+            "in" | "j2" | "jinja" | "tmpl" | "tpl" | "template" | "orig" | "bak" | "example"
+            | "sample" | "dist" => stem = rest,
+            _ => return lang_from_extension(extension),
+        }
+    }
+    None
+}
+
+pub(crate) fn lang_from_extension(extension: &str) -> Option<&'static str> {
+    let lang = match extension {
         "rs" => "rust",
-        "js" | "jsx" => "javascript",
-        "ts" | "tsx" => "typescript",
-        "py" => "python",
+        "js" | "jsx" | "mjs" | "cjs" => "javascript",
+        "ts" | "tsx" | "mts" | "cts" => "typescript",
+        "py" | "pyi" | "pyw" => "python",
         "go" => "go",
         "java" => "java",
-        "cpp" => "cpp",
-        "c" => "c",
+        "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => "cpp",
+        "c" | "h" => "c",
         "cs" => "c_sharp",
-        "html" => "html",
+        "html" | "htm" => "html",
         "css" => "css",
         "json" => "json",
         "toml" => "toml",
         "yaml" | "yml" => "yaml",
-        "sh" | "bash" => "shell",
-        "md" => "markdown",
-        _ => "unknown",
+        "sh" | "bash" | "zsh" => "shell",
+        "md" | "markdown" => "markdown",
+        _ => return None,
+    };
+    Some(lang)
+}
+
+fn lang_from_shebang(line: &str) -> Option<&'static str> {
+    let mut words = line.strip_prefix("#!")?.split_whitespace();
+    let mut interpreter = words.next()?.rsplit('/').next()?;
+    if interpreter == "env" {
+        interpreter = words.find(|word| !word.starts_with('-'))?;
     }
-    .to_string()
+    // python3.12 -> python
+    let interpreter = interpreter.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.');
+
+    let lang = match interpreter {
+        "python" | "pypy" => "python",
+        "sh" | "bash" | "zsh" | "dash" | "ksh" => "shell",
+        "node" | "nodejs" | "deno" | "bun" => "javascript",
+        "ts-node" | "tsx" => "typescript",
+        _ => return None,
+    };
+    Some(lang)
 }
 
+#[deprecated(note = "use `language::config(lang).indent`")]
 pub fn indent(lang: &str) -> String {
-    match lang {
-        "rust" | "python" | "php" | "toml" | "c" | "cpp" | "zig" | "kotlin" | "erlang" | "html"
-        | "sql" => "    ".to_string(),
-        "go" | "c_sharp" => "\t".to_string(),
-
-        _ => "  ".to_string(),
-    }
+    crate::language::config(lang).indent
 }
 
+/// Line comment prefix of `lang` from the registry. Prefixes not in the
+/// built-in table are leaked once each to keep the `&'static str` return.
+#[deprecated(note = "use `language::config(lang).comment`")]
 pub fn comment(lang: &str) -> &'static str {
-    match lang {
-        "python" | "shell" => "#",
-        "lua" => "--",
-        _ => "//",
+    static REGISTERED: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Default::default);
+
+    let comment = crate::language::config(lang).comment;
+    if let Some(builtin) = ["//", "#", "--", ""].into_iter().find(|c| *c == comment) {
+        return builtin;
+    }
+    let mut registered = REGISTERED.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(comment) = registered.get(comment.as_str()) {
+        return comment;
     }
+    let comment: &'static str = Box::leak(comment.into_boxed_str());
+    registered.insert(comment);
+    comment
 }
 
 pub fn count_indent_units(
@@ -76,6 +156,12 @@ pub fn count_indent_units(
     count
 }
 
+/// Whether `c` is part of a word in every language. [`crate::code::Code::is_word_char`]
+/// adds the language's own word characters.
+pub(crate) fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
 pub fn rgb(hex: &str) -> (u8, u8, u8) {
     let hex = hex.trim_start_matches('#');
     let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or(0);
@@ -0,0 +1,52 @@
+use crate::code::invisible_placeholder;
+use std::sync::atomic::{AtomicBool, Ordering};
+use unicode_segmentation::UnicodeSegmentation;
+use unicode_width::UnicodeWidthStr;
+
+/// How many cells East Asian ambiguous-width characters take, such as `°`,
+/// `±`, `→`, box drawing or Greek letters.
+///
+/// Terminals configured for CJK locales often draw them two cells wide; the
+/// editor has to agree with the terminal or the cursor and columns drift.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AmbiguousWidth {
+    #[default]
+    Narrow,
+    Wide,
+}
+
+static AMBIGUOUS_WIDE: AtomicBool = AtomicBool::new(false);
+
+/// Sets the width of ambiguous characters for the whole process, so for
+/// every editor, as it is a property of the terminal they are drawn on.
+pub fn set_global_ambiguous_width(width: AmbiguousWidth) {
+    AMBIGUOUS_WIDE.store(width == AmbiguousWidth::Wide, Ordering::Relaxed);
+}
+
+/// Width of ambiguous characters, see [`set_global_ambiguous_width`].
+pub fn global_ambiguous_width() -> AmbiguousWidth {
+    if AMBIGUOUS_WIDE.load(Ordering::Relaxed) {
+        AmbiguousWidth::Wide
+    } else {
+        AmbiguousWidth::Narrow
+    }
+}
+
+/// Display width of `text` in cells. All column math of the editor goes
+/// through this function so rendering, the cursor and mouse hits agree.
+pub fn str_width(text: &str) -> usize {
+    text.graphemes(true).map(grapheme_str_width).sum()
+}
+
+/// Display width of a single grapheme. Invisible characters take one cell so
+/// they can be drawn as a placeholder.
+pub(crate) fn grapheme_str_width(g: &str) -> usize {
+    if invisible_placeholder(g).is_some() {
+        1
+    } else if AMBIGUOUS_WIDE.load(Ordering::Relaxed) {
+        g.width_cjk()
+    } else {
+        g.width()
+    }
+}
@@ -0,0 +1,274 @@
+use crate::anchor::Anchor;
+use crate::code::Code;
+use crate::code_cell::CodeCell;
+use crate::editor::Editor;
+use crate::encoding::{self, Encoding};
+use crate::marks::{Mark, MarkLayers};
+use crate::search::Search;
+use crate::selection::{Selection, SelectionHistory, SelectionSnap};
+use crate::types::{LineEnding, Theme};
+use crate::utils;
+use crate::view::View;
+use anyhow::{Result, anyhow};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Identifies a document of a [`Workspace`], in the order they were opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DocumentId(u64);
+
+/// Open documents of a tabbed or split host, sharing one theme, the custom
+/// highlight queries and the fallback clipboard.
+///
+/// Each document is parsed once however many editors show it. An editor is
+/// moved between documents with [`Workspace::attach`]; the cursor, scroll,
+/// folds, marks and search it had on the previous document are kept and come
+/// back when an editor is attached to that document again.
+pub struct Workspace {
+    theme: Theme,
+    custom_highlights: Option<HashMap<String, String>>,
+    clipboard: Rc<RefCell<Option<String>>>,
+    documents: BTreeMap<DocumentId, Document>,
+    next_id: u64,
+}
+
+struct Document {
+    code: Rc<RefCell<Code>>,
+    file_path: Option<PathBuf>,
+    line_ending: LineEnding,
+    encoding: (&'static Encoding, bool),
+    /// State of the last editor detached from the document
+    view: Option<DocumentView>,
+}
+
+/// Per-document state of an editor, parked while it shows another document.
+struct DocumentView {
+    cursor: usize,
+    selection: Option<Selection>,
+    offset_y: usize,
+    offset_x: usize,
+    secondary_selections: Vec<Mark>,
+    marks: MarkLayers,
+    search: Option<Search>,
+    view: View,
+    original_code: Option<Code>,
+    breakpoints: Vec<Anchor>,
+    execution_line: Option<Anchor>,
+    /// Document version the positions belong to
+    version: u64,
+}
+
+impl Workspace {
+    pub fn new(theme: Vec<(&str, &str)>) -> Self {
+        Self {
+            theme: Editor::build_theme(&theme),
+            custom_highlights: None,
+            clipboard: Rc::new(RefCell::new(None)),
+            documents: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Highlight queries by language used for documents opened afterwards,
+    /// see [`Editor::new_with_highlights`].
+    pub fn set_custom_highlights(&mut self, highlights: Option<HashMap<String, String>>) {
+        self.custom_highlights = highlights;
+    }
+
+    /// Opens `text` as a new document, as plain text for unsupported
+    /// languages. Fails only for invalid custom queries.
+    pub fn open(&mut self, lang: &str, text: &str) -> Result<DocumentId> {
+        let line_ending = LineEnding::detect(text);
+        let text = LineEnding::normalize(text);
+        let code = Code::new(&text, lang, self.custom_highlights.clone())?;
+        let id = DocumentId(self.next_id);
+        self.next_id += 1;
+        let document = Document {
+            code: Rc::new(RefCell::new(code)),
+            file_path: None,
+            line_ending,
+            encoding: (encoding_rs::UTF_8, false),
+            view: None,
+        };
+        self.documents.insert(id, document);
+        Ok(id)
+    }
+
+    /// Loads a file as a new document, like [`Editor::new_from_file`].
+    pub fn open_file(&mut self, path: impl AsRef<Path>) -> Result<DocumentId> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).map_err(|e| anyhow!("cant read {}: {}", path.display(), e))?;
+        let (content, encoding, bom) = encoding::decode(&bytes);
+        let lang = utils::detect_lang(path, content.lines().next());
+        let id = self.open(&lang, &content)?;
+        let document = self.documents.get_mut(&id).expect("document just opened");
+        document.file_path = Some(path.to_path_buf());
+        document.encoding = (encoding, bom);
+        Ok(id)
+    }
+
+    /// Removes a document. Editors still showing it keep their copy alive.
+    pub fn close(&mut self, id: DocumentId) -> Option<Rc<RefCell<Code>>> {
+        self.documents.remove(&id).map(|document| document.code)
+    }
+
+    /// Ids of the open documents, in the order they were opened.
+    pub fn documents(&self) -> impl Iterator<Item = DocumentId> + '_ {
+        self.documents.keys().copied()
+    }
+
+    pub fn code(&self, id: DocumentId) -> Option<Rc<RefCell<Code>>> {
+        self.documents
+            .get(&id)
+            .map(|document| Rc::clone(&document.code))
+    }
+
+    /// File the document was loaded from or last saved to by a detached editor.
+    pub fn file_path(&self, id: DocumentId) -> Option<&Path> {
+        self.documents.get(&id)?.file_path.as_deref()
+    }
+
+    /// Document shown by `editor`, if it belongs to this workspace.
+    pub fn document_of(&self, editor: &Editor) -> Option<DocumentId> {
+        self.documents
+            .iter()
+            .find(|(_, document)| editor.code.ptr_eq(&document.code))
+            .map(|(id, _)| *id)
+    }
+
+    /// Creates an editor showing the document, with the workspace theme and
+    /// clipboard.
+    pub fn editor(&mut self, id: DocumentId) -> Result<Editor> {
+        let code = self
+            .code(id)
+            .ok_or_else(|| anyhow!("no document {:?}", id))?;
+        let mut editor = Editor::with_shared_code(Rc::clone(&code), vec![]);
+        editor.theme = self.theme.clone();
+        editor.clipboard = Rc::clone(&self.clipboard);
+        self.load(&mut editor, id, code);
+        Ok(editor)
+    }
+
+    /// Switches `editor` to the document, e.g. when the host changes tabs.
+    /// The state of its previous document is kept for later.
+    pub fn attach(&mut self, editor: &mut Editor, id: DocumentId) -> Result<()> {
+        let code = self
+            .code(id)
+            .ok_or_else(|| anyhow!("no document {:?}", id))?;
+        if editor.code.ptr_eq(&code) {
+            return Ok(());
+        }
+        self.detach(editor);
+        editor.clipboard = Rc::clone(&self.clipboard);
+        self.load(editor, id, code);
+        Ok(())
+    }
+
+    /// Parks the state of the editor's document and leaves the editor on an
+    /// empty plain text document. Returns the document it showed, if it
+    /// belongs to this workspace.
+    pub fn detach(&mut self, editor: &mut Editor) -> Option<DocumentId> {
+        let id = self.document_of(editor);
+        let view = DocumentView::take(editor);
+        if let Some(document) = id.and_then(|id| self.documents.get_mut(&id)) {
+            document.file_path = editor.file_path.take();
+            document.line_ending = editor.line_ending;
+            document.encoding = editor.encoding;
+            document.view = Some(view);
+        }
+        let empty = Code::new("", "text", None).expect("plain text needs no parser");
+        editor.code = CodeCell::Owned(Box::new(empty));
+        editor.file_path = None;
+        editor.line_ending = LineEnding::Lf;
+        editor.encoding = (encoding_rs::UTF_8, false);
+        let view = DocumentView::new(&editor.code.borrow());
+        view.restore(editor);
+        id
+    }
+
+    fn load(&mut self, editor: &mut Editor, id: DocumentId, code: Rc<RefCell<Code>>) {
+        let document = self.documents.get_mut(&id).expect("document exists");
+        let view = document
+            .view
+            .take()
+            .unwrap_or_else(|| DocumentView::new(&code.borrow()));
+        editor.list_continuation = code.borrow().language_config().list_continuation;
+        editor.code = CodeCell::Shared(code);
+        editor.file_path = document.file_path.clone();
+        editor.line_ending = document.line_ending;
+        editor.encoding = document.encoding;
+        view.restore(editor);
+    }
+}
+
+impl DocumentView {
+    fn new(code: &Code) -> Self {
+        Self {
+            cursor: 0,
+            selection: None,
+            offset_y: 0,
+            offset_x: 0,
+            secondary_selections: Vec::new(),
+            marks: MarkLayers::default(),
+            search: None,
+            view: View::default(),
+            original_code: None,
+            breakpoints: Vec::new(),
+            execution_line: None,
+            version: code.version(),
+        }
+    }
+
+    /// Takes the document state out of `editor` and drops what only makes
+    /// sense while the document is shown, like a pending snippet or preedit.
+    fn take(editor: &mut Editor) -> Self {
+        editor.sync_shared_code();
+        editor.snippet = None;
+        editor.preedit = None;
+        editor.picker = None;
+        editor.flash = None;
+        editor.hover = None;
+        editor.drag_autoscroll = None;
+        editor.scrollbar_dragging = false;
+        editor.selection_snap = SelectionSnap::None;
+        editor.selection_history = SelectionHistory::default();
+        Self {
+            cursor: editor.cursor,
+            selection: editor.selection.take(),
+            offset_y: editor.offset_y,
+            offset_x: editor.offset_x,
+            secondary_selections: std::mem::take(&mut editor.secondary_selections),
+            marks: std::mem::take(&mut editor.marks),
+            search: editor.search.take(),
+            view: editor.view.take(),
+            original_code: editor.original_code.take(),
+            breakpoints: std::mem::take(&mut editor.breakpoints),
+            execution_line: editor.execution_line.take(),
+            version: editor.position_version,
+        }
+    }
+
+    /// Puts the state back into `editor`, which already shows the document,
+    /// and catches up with edits made since it was taken.
+    fn restore(self, editor: &mut Editor) {
+        editor.cursor = self.cursor;
+        editor.selection = self.selection;
+        editor.offset_x = self.offset_x;
+        editor.secondary_selections = self.secondary_selections;
+        editor.marks = self.marks;
+        editor.search = self.search;
+        editor.view.replace(self.view);
+        editor.original_code = self.original_code;
+        editor.breakpoints = self.breakpoints;
+        editor.execution_line = self.execution_line;
+        editor.position_version = self.version;
+        editor.code_version.set(editor.code.borrow().version());
+        editor.clear_caches();
+        editor.sync_shared_code();
+        editor.fit_cursor();
+        editor.set_offset_y(self.offset_y);
+    }
+}
@@ -0,0 +1,46 @@
+use ratatui_code_editor::actions::{Align, Undo};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn align_assignments_in_selection() {
+    let source = "a = 1\nlong_name = 2\nbb = 3\nx\n";
+    let mut editor = Editor::new("text", source, vec![]).unwrap();
+    editor.set_selection(Some(Selection::new(0, 21)));
+    editor.apply(Align {
+        pattern: "=".to_string(),
+    });
+    assert_eq!(
+        editor.get_content(),
+        "a         = 1\nlong_name = 2\nbb        = 3\nx\n"
+    );
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 29)));
+
+    editor.apply(Undo);
+    assert_eq!(editor.get_content(), source);
+}
+
+#[test]
+fn align_capture_group_around_cursor() {
+    let source = "x: 1\nname: bob\nlonger_key: true\n\nz: 0\n";
+    let mut editor = Editor::new("yaml", source, vec![]).unwrap();
+    editor.set_cursor(7);
+    editor.apply(Align {
+        pattern: r":\s*(\S)".to_string(),
+    });
+    // Only the lines next to the cursor's line are aligned
+    assert_eq!(
+        editor.get_content(),
+        "x:          1\nname:       bob\nlonger_key: true\n\nz: 0\n"
+    );
+    assert_eq!(editor.get_cursor(), 16);
+
+    // Invalid patterns do nothing
+    editor.apply(Align {
+        pattern: "(".to_string(),
+    });
+    assert_eq!(
+        editor.get_content(),
+        "x:          1\nname:       bob\nlonger_key: true\n\nz: 0\n"
+    );
+}
@@ -0,0 +1,34 @@
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::width::{
+    AmbiguousWidth, global_ambiguous_width, set_global_ambiguous_width, str_width,
+};
+use ratatui_core::layout::Rect;
+
+#[test]
+fn ambiguous_width_applies_to_cursor_and_mouse_columns() {
+    let mut editor = Editor::new("text", "a°b", vec![]).unwrap();
+    editor.show_line_numbers(false);
+    let area = Rect::new(0, 0, 20, 2);
+    editor.set_cursor(0);
+    let (x, _) = editor.get_visible_cursor(&area).unwrap();
+
+    editor.set_cursor(2);
+    assert_eq!(global_ambiguous_width(), AmbiguousWidth::Narrow);
+    assert_eq!(str_width("a°b"), 3);
+    assert_eq!(editor.get_visible_cursor(&area), Some((x + 2, 0)));
+    assert_eq!(editor.cursor_from_mouse(x + 2, 0, &area), Some(2));
+
+    // the fold indicators in the gutter may be ambiguous too
+    set_global_ambiguous_width(AmbiguousWidth::Wide);
+    assert_eq!(str_width("a°b"), 4);
+    editor.set_cursor(0);
+    let (x, _) = editor.get_visible_cursor(&area).unwrap();
+    editor.set_cursor(2);
+    assert_eq!(editor.get_visible_cursor(&area), Some((x + 3, 0)));
+    assert_eq!(editor.cursor_from_mouse(x + 2, 0, &area), Some(1));
+    assert_eq!(editor.cursor_from_mouse(x + 3, 0, &area), Some(2));
+
+    // wide CJK characters are two cells either way
+    assert_eq!(str_width("日本"), 4);
+    set_global_ambiguous_width(AmbiguousWidth::Narrow);
+}
@@ -0,0 +1,51 @@
+use ratatui_code_editor::actions::{Delete, InsertText, Undo};
+use ratatui_code_editor::anchor::AnchorBias;
+use ratatui_code_editor::editor::Editor;
+
+#[test]
+fn anchors_follow_inserts_and_removes() {
+    let mut editor = Editor::new("text", "hello world", vec![]).unwrap();
+    let world = editor.create_anchor(6, AnchorBias::Left);
+    let end = editor.create_anchor(11, AnchorBias::Right);
+
+    editor.set_cursor(0);
+    editor.apply(InsertText { text: ">> ".into() });
+    assert_eq!(world.position(), 9);
+    assert_eq!(end.position(), 14);
+
+    editor.set_cursor(2);
+    editor.apply(Delete {});
+    assert_eq!(world.position(), 8);
+
+    editor.apply(Undo {});
+    assert_eq!(world.position(), 9);
+    assert_eq!(
+        editor.get_content_slice(world.position(), end.position()),
+        "world"
+    );
+}
+
+#[test]
+fn bias_decides_side_of_insertion_at_anchor() {
+    let mut editor = Editor::new("text", "ab", vec![]).unwrap();
+    let left = editor.create_anchor(1, AnchorBias::Left);
+    let right = editor.create_anchor(1, AnchorBias::Right);
+
+    editor.set_cursor(1);
+    editor.apply(InsertText { text: "xyz".into() });
+
+    assert_eq!(left.position(), 1);
+    assert_eq!(right.position(), 4);
+}
+
+#[test]
+fn anchors_inside_removed_text_collapse_to_start() {
+    let mut editor = Editor::new("text", "0123456789", vec![]).unwrap();
+    let anchor = editor.create_anchor(5, AnchorBias::Left);
+    let clone = anchor.clone();
+
+    editor.replace_range(2..8, "").unwrap();
+
+    assert_eq!(anchor.position(), 2);
+    assert_eq!(clone.position(), 2);
+}
@@ -0,0 +1,31 @@
+mod common;
+
+use common::bare_editor;
+use ratatui_code_editor::testing::TestHarness;
+
+fn harness(annotations: Vec<(usize, &str)>) -> TestHarness {
+    let mut editor = bare_editor("text", "one\na longer line\nthree");
+    editor.set_line_annotations(annotations);
+    TestHarness::new(editor, 20, 3)
+}
+
+#[test]
+fn annotations_are_right_aligned_after_the_line() {
+    let harness = harness(vec![(0, "jdoe 3 days ago"), (2, "me")]);
+    harness.assert_screen(&[
+        "one  jdoe 3 days ago",
+        "a longer line",
+        "three             me",
+    ]);
+    assert_eq!(harness.editor().line_annotation(2), Some("me"));
+    assert_eq!(harness.editor().line_annotation(1), None);
+}
+
+#[test]
+fn annotations_are_cut_to_the_free_columns() {
+    let mut harness = harness(vec![(1, "jdoe 3 days ago"), (0, "x".repeat(20).as_str())]);
+    harness.assert_screen(&["one  xxxxxxxxxxxxxx…", "a longer line  jdoe…", "three"]);
+
+    harness.editor_mut().clear_line_annotations();
+    harness.assert_screen(&["one", "a longer line", "three"]);
+}
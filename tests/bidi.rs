@@ -0,0 +1,31 @@
+mod common;
+
+use common::row;
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+#[test]
+fn right_to_left_text_is_reordered_for_render_cursor_and_mouse() {
+    // hebrew alef, bet, gimel between quotes
+    let mut editor = Editor::new("text", "s = \"\u{5d0}\u{5d1}\u{5d2}\";", vec![]).unwrap();
+    editor.show_line_numbers(false);
+
+    let area = Rect::new(0, 0, 20, 2);
+    let mut buf = Buffer::empty(area);
+    editor.render(area, &mut buf);
+    let row = row(&buf, 0);
+    assert_eq!(row.trim(), "s = \"\u{5d2}\u{5d1}\u{5d0}\";");
+    let x = row.find('s').unwrap() as u16;
+
+    // the cursor before alef is drawn at its cell, the rightmost of the run
+    editor.set_cursor(5);
+    assert_eq!(editor.get_visible_cursor(&area), Some((x + 7, 0)));
+    editor.set_cursor(8);
+    assert_eq!(editor.get_visible_cursor(&area), Some((x + 8, 0)));
+
+    // clicking the leftmost cell of the run lands on gimel
+    assert_eq!(editor.cursor_from_mouse(x + 5, 0, &area), Some(7));
+    assert_eq!(editor.cursor_from_mouse(x + 15, 0, &area), Some(10));
+}
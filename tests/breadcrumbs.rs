@@ -0,0 +1,55 @@
+use ratatui_code_editor::editor::Editor;
+
+fn names(editor: &Editor) -> Vec<(String, String)> {
+    editor
+        .breadcrumbs()
+        .into_iter()
+        .map(|symbol| (symbol.kind, symbol.name))
+        .collect()
+}
+
+#[test]
+fn breadcrumbs_list_the_declarations_around_the_cursor() {
+    let content = "mod net {\n    impl<T> Display for Conn<T> {\n        fn fmt(&self) {\n            todo!()\n        }\n    }\n}\n";
+    let mut editor = Editor::new("rust", content, vec![]).unwrap();
+    editor.set_cursor(content.find("todo").unwrap());
+    assert_eq!(
+        names(&editor),
+        [
+            ("mod_item".to_string(), "net".to_string()),
+            ("impl_item".to_string(), "Display for Conn<T>".to_string()),
+            ("function_item".to_string(), "fmt".to_string()),
+        ]
+    );
+    let fmt = &editor.breadcrumbs()[2];
+    assert_eq!((fmt.line, fmt.offset), (2, content.find("fmt").unwrap()));
+
+    editor.set_cursor(content.find("impl").unwrap());
+    assert_eq!(names(&editor).len(), 2);
+    editor.set_cursor(0);
+    assert_eq!(names(&editor).len(), 1);
+}
+
+#[test]
+fn breadcrumbs_for_python_and_markdown() {
+    let content = "class A:\n    def f(self):\n        pass\n";
+    let mut editor = Editor::new("python", content, vec![]).unwrap();
+    editor.set_cursor(content.find("pass").unwrap());
+    let names: Vec<String> = editor.breadcrumbs().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, ["A", "f"]);
+
+    let content = "# Guide\n\n## Install\n\nRun it.\n";
+    let mut editor = Editor::new("markdown", content, vec![]).unwrap();
+    editor.set_cursor(content.find("Run").unwrap());
+    let names: Vec<String> = editor.breadcrumbs().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, ["Guide", "Install"]);
+}
+
+#[test]
+fn breadcrumbs_fall_back_to_named_declarations() {
+    let content = "class A {\n  void f() {\n    int x;\n  }\n}\n";
+    let mut editor = Editor::new("java", content, vec![]).unwrap();
+    editor.set_cursor(content.find("int").unwrap());
+    let names: Vec<String> = editor.breadcrumbs().into_iter().map(|s| s.name).collect();
+    assert_eq!(names, ["A", "f"]);
+}
@@ -0,0 +1,85 @@
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::breakpoints::BREAKPOINT_COLUMN;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::events::EditorEvent;
+use ratatui_code_editor::gutter::GutterColumn;
+use ratatui_code_editor::testing::TestHarness;
+
+fn harness() -> TestHarness {
+    let mut editor = Editor::new("text", "one\ntwo\nthree\nfour", vec![]).unwrap();
+    editor.set_left_code_padding(1);
+    editor.set_code_folding_enabled(false);
+    editor.set_breakpoints([1, 3]);
+    editor.set_execution_line(Some(2));
+    TestHarness::new(editor, 20, 4)
+}
+
+#[test]
+fn breakpoints_and_execution_line_are_drawn_in_the_gutter() {
+    let harness = harness();
+    harness.assert_screen(&[
+        "      1 one",
+        "●     2 two",
+        " ▶    3 three",
+        "●     4 four",
+    ]);
+    // the execution line gets a background across the text area
+    let buf = harness.render();
+    assert_ne!(buf[(15, 2)].bg, buf[(15, 1)].bg);
+    assert_eq!(buf[(8, 2)].bg, buf[(15, 2)].bg);
+}
+
+#[test]
+fn breakpoints_follow_edits() {
+    let mut harness = harness();
+    let editor = harness.editor_mut();
+    editor.set_cursor(0);
+    editor.apply(InsertText {
+        text: "zero\n".to_string(),
+    });
+    assert_eq!(editor.breakpoints(), vec![2, 4]);
+    assert_eq!(editor.execution_line(), Some(3));
+
+    assert!(!editor.toggle_breakpoint(2));
+    assert!(editor.toggle_breakpoint(0));
+    assert_eq!(editor.breakpoints(), vec![0, 4]);
+    editor.set_execution_line(None);
+    assert_eq!(editor.execution_line(), None);
+}
+
+#[test]
+fn clicking_the_breakpoint_column_reports_the_line() {
+    let mut harness = harness();
+    harness.take_events();
+    harness.click(1, 2);
+    assert_eq!(
+        harness.take_events(),
+        vec![EditorEvent::GutterClicked {
+            line: 2,
+            column_id: BREAKPOINT_COLUMN.to_string()
+        }]
+    );
+}
+
+#[test]
+fn host_breakpoint_column_is_told_apart() {
+    let mut harness = harness();
+    let editor = harness.editor_mut();
+    editor.add_gutter_column(GutterColumn::new("breakpoints", 1));
+    harness.take_events();
+    harness.click(0, 1);
+    harness.click(2, 3);
+    assert_eq!(
+        harness.take_events(),
+        vec![
+            EditorEvent::GutterClicked {
+                line: 1,
+                column_id: "breakpoints".to_string()
+            },
+            EditorEvent::GutterClicked {
+                line: 3,
+                column_id: BREAKPOINT_COLUMN.to_string()
+            }
+        ]
+    );
+}
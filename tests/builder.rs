@@ -0,0 +1,45 @@
+use ratatui_code_editor::prelude::*;
+use ratatui_core::layout::Rect;
+
+#[test]
+fn builder_applies_options() {
+    let editor = Editor::builder()
+        .language("rust")
+        .content("fn main() {\r\n}\r\n")
+        .theme(vesper())
+        .line_number_min_digits(1)
+        .left_padding(1)
+        .code_folding(false)
+        .scrollbar(true)
+        .scroll_follow(ScrollFollow::Typewriter)
+        .build()
+        .unwrap();
+    assert_eq!(editor.get_content(), "fn main() {\r\n}\r\n");
+    assert_eq!(editor.line_ending(), LineEnding::CrLf);
+    assert_eq!(editor.scroll_follow(), ScrollFollow::Typewriter);
+    assert!(!editor.is_code_folding_enabled());
+
+    let layout = editor.layout(Rect::new(0, 0, 20, 5));
+    assert_eq!(layout.gutter_width, 2);
+    assert!(layout.scrollbar.is_some());
+}
+
+#[test]
+fn builder_defaults_match_new() {
+    let built = Editor::builder().content("x").build().unwrap();
+    let new = Editor::new("text", "x", vec![]).unwrap();
+    let area = Rect::new(0, 0, 20, 5);
+    assert_eq!(built.layout(area), new.layout(area));
+    assert_eq!(
+        built.is_code_folding_enabled(),
+        new.is_code_folding_enabled()
+    );
+
+    // Unknown languages fall back to plain text like Editor::new
+    let editor = Editor::builder()
+        .language("klingon")
+        .content("x")
+        .build()
+        .unwrap();
+    assert_eq!(editor.get_content(), "x");
+}
@@ -0,0 +1,109 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::types::{ChangeEvent, ChangeOrigin, ChangeSync, TextEdit};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn edit(start: (usize, usize), end: (usize, usize), text: &str) -> TextEdit {
+    TextEdit {
+        start_row: start.0,
+        start_col: start.1,
+        end_row: end.0,
+        end_col: end.1,
+        text: text.into(),
+    }
+}
+
+fn listen(editor: &mut Editor) -> Rc<RefCell<Vec<ChangeEvent>>> {
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let sink = events.clone();
+    editor.set_change_listener(Box::new(move |event| sink.borrow_mut().push(event.clone())));
+    events
+}
+
+#[test]
+fn change_events_carry_version_and_origin() {
+    let mut editor = Editor::new("text", "ab\ncd", vec![]).unwrap();
+    let events = listen(&mut editor);
+
+    editor.execute(Command::InsertText { text: "x".into() });
+    editor.execute(Command::Undo);
+    editor.execute(Command::Redo);
+    editor.set_content("new");
+
+    let events = events.borrow();
+    let summary: Vec<_> = events.iter().map(|e| (e.version, e.origin)).collect();
+    assert_eq!(
+        summary,
+        [
+            (1, ChangeOrigin::Edit),
+            (2, ChangeOrigin::Undo),
+            (3, ChangeOrigin::Redo),
+            (4, ChangeOrigin::Programmatic),
+        ]
+    );
+    assert_eq!(events[0].changes, [edit((0, 0), (0, 0), "x")]);
+    assert_eq!(events[1].changes, [edit((0, 0), (0, 1), "")]);
+    assert_eq!(events[2].changes, [edit((0, 0), (0, 0), "x")]);
+    assert_eq!(
+        events[3].changes,
+        [edit((0, 0), (1, 2), ""), edit((0, 0), (0, 0), "new")]
+    );
+    assert_eq!(editor.version(), 4);
+}
+
+#[test]
+fn change_positions_follow_earlier_changes_in_a_transaction() {
+    let mut editor = Editor::new("text", "a\nb\nc", vec![]).unwrap();
+    let events = listen(&mut editor);
+
+    // Duplicate inserts a line; deleting it afterwards in one undo step
+    editor.set_cursor(2);
+    editor.execute(Command::Duplicate);
+    editor.execute(Command::Undo);
+
+    let events = events.borrow();
+    assert_eq!(events.len(), 2);
+    assert_eq!(editor.get_content(), "a\nb\nc");
+    assert_eq!(events[1].origin, ChangeOrigin::Undo);
+    // Undoing the insertion removes exactly what was inserted
+    let inserted = &events[0].changes[0];
+    let removed = &events[1].changes[0];
+    assert_eq!(
+        (removed.start_row, removed.start_col),
+        (inserted.start_row, inserted.start_col)
+    );
+    assert_eq!(removed.text, "");
+}
+
+#[test]
+fn one_notification_per_transaction() {
+    let mut editor = Editor::new("text", "a\nb\nc", vec![]).unwrap();
+    let events = listen(&mut editor);
+
+    editor.execute(Command::SelectAll);
+    editor.execute(Command::Indent);
+
+    let events = events.borrow();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].changes.len(), 3);
+    // Lines are indented bottom-up, each change in the coordinates of the previous
+    assert_eq!(events[0].changes[0], edit((2, 0), (2, 0), "  "));
+    assert_eq!(events[0].changes[2], edit((0, 0), (0, 0), "  "));
+}
+
+#[test]
+fn full_sync_sends_whole_document() {
+    let mut editor = Editor::new("text", "a\nb", vec![]).unwrap();
+    let events = listen(&mut editor);
+    editor.set_change_sync(ChangeSync::Full);
+
+    editor.execute(Command::SelectAll);
+    editor.execute(Command::Indent);
+    editor.execute(Command::Undo);
+
+    let events = events.borrow();
+    assert_eq!(events.len(), 2);
+    assert_eq!(events[0].changes, [edit((0, 0), (1, 1), "  a\n  b")]);
+    assert_eq!(events[1].changes, [edit((0, 0), (1, 3), "a\nb")]);
+}
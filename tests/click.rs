@@ -0,0 +1,43 @@
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+use std::time::Duration;
+
+#[test]
+fn fourth_click_selects_the_paragraph() {
+    let text = "first line\nsecond line\n\nnext paragraph\n";
+    let mut editor = Editor::new("text", text, vec![]).unwrap();
+    for _ in 0..3 {
+        editor.handle_mouse_down(14);
+    }
+    assert_eq!(editor.get_selection(), Some(Selection::new(11, 23)));
+    editor.handle_mouse_down(14);
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 23)));
+
+    // dragging extends paragraph-wise
+    editor.handle_mouse_drag(30);
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 39)));
+
+    // the fifth click starts a new chain, in the selection until released
+    editor.handle_mouse_down(14);
+    editor.handle_mouse_up(false);
+    assert_eq!(editor.get_selection(), Some(Selection::new(14, 14)));
+}
+
+#[test]
+fn click_chain_length_and_timeout_are_configurable() {
+    let mut editor = Editor::new("text", "one two", vec![]).unwrap();
+    editor.set_max_click_chain(2);
+    editor.set_click_timeout(Duration::from_secs(60));
+    assert_eq!(editor.click_timeout(), Duration::from_secs(60));
+    editor.handle_mouse_down(1);
+    editor.handle_mouse_down(1);
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 3)));
+    editor.handle_mouse_down(1);
+    editor.handle_mouse_up(false);
+    assert_eq!(editor.get_selection(), Some(Selection::new(1, 1)));
+
+    editor.set_click_timeout(Duration::ZERO);
+    editor.handle_mouse_down(1);
+    editor.handle_mouse_down(1);
+    assert_eq!(editor.get_selection(), Some(Selection::new(1, 1)));
+}
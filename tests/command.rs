@@ -0,0 +1,90 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+
+#[test]
+fn execute_runs_built_in_actions() {
+    let mut editor = Editor::new("text", "one\ntwo", vec![]).unwrap();
+
+    let script = [
+        Command::MoveDown { shift: false },
+        Command::InsertText { text: "x".into() },
+        Command::InsertNewline,
+        Command::SelectAll,
+        Command::Copy,
+    ];
+    for command in script {
+        editor.execute(command);
+    }
+    assert_eq!(editor.get_content(), "one\nx\ntwo");
+
+    editor.execute(Command::Undo);
+    editor.execute(Command::Undo);
+    assert_eq!(editor.get_content(), "one\ntwo");
+    editor.execute(Command::Redo);
+    assert_eq!(editor.get_content(), "one\nxtwo");
+}
+
+#[test]
+fn command_names() {
+    assert_eq!(Command::MoveDown { shift: true }.name(), "move_down");
+    assert_eq!(Command::PasteLiteral.name(), "paste_literal");
+    assert_eq!(Command::Paste, Command::Paste);
+    assert_ne!(
+        Command::MoveUp { shift: true },
+        Command::MoveUp { shift: false }
+    );
+}
+
+#[test]
+fn repeat_last_edit_replays_at_cursor() {
+    let mut editor = Editor::new("text", "a\nb", vec![]).unwrap();
+
+    editor.execute(Command::InsertText { text: "x".into() });
+    editor.execute(Command::InsertText { text: "y".into() });
+    editor.execute(Command::MoveDown { shift: false });
+    assert_eq!(
+        editor.last_edit(),
+        [
+            Command::InsertText { text: "x".into() },
+            Command::InsertText { text: "y".into() },
+        ]
+    );
+
+    editor.set_cursor(editor.get_content().len());
+    editor.execute(Command::RepeatLastEdit);
+    assert_eq!(editor.get_content(), "xya\nbxy");
+
+    // Repeating does not replace the recorded edit; a new edit after a move does
+    editor.execute(Command::RepeatLastEdit);
+    assert_eq!(editor.get_content(), "xya\nbxyxy");
+    editor.execute(Command::Delete);
+    editor.execute(Command::MoveLeft { shift: false });
+    editor.execute(Command::RepeatLastEdit);
+    assert_eq!(editor.get_content(), "xya\nbxx");
+    assert_eq!(editor.last_edit(), [Command::Delete]);
+}
+
+#[test]
+fn reselect_last_restores_dropped_selection() {
+    let mut editor = Editor::new("text", "one\ntwo\nthree", vec![]).unwrap();
+    editor.execute(Command::MoveDown { shift: true });
+    editor.execute(Command::MoveRight { shift: true });
+    let selection = editor.get_selection();
+    assert_eq!(editor.get_cursor(), 5);
+
+    editor.execute(Command::MoveRight { shift: false });
+    assert!(editor.get_selection().is_none());
+
+    editor.execute(Command::ReselectLast);
+    assert_eq!(editor.get_selection(), selection);
+    assert_eq!(editor.get_cursor(), 5);
+
+    // The restored selection follows edits, and grows to include text
+    // inserted at its start, such as indentation
+    editor.execute(Command::MoveLeft { shift: false });
+    editor.set_cursor(0);
+    editor.execute(Command::InsertText { text: "> ".into() });
+    editor.execute(Command::ReselectLast);
+    let restored = editor.get_selection().unwrap();
+    assert_eq!((restored.start, restored.end), (0, 7));
+}
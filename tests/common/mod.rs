@@ -0,0 +1,37 @@
+#![allow(dead_code)]
+
+use ratatui_code_editor::builder::EditorBuilder;
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+
+/// An editor without line numbers, fold markers or left padding, so the
+/// text starts at the first column of the area.
+pub fn bare_editor(lang: &str, text: &str) -> Editor {
+    bare_builder(lang, text).build().unwrap()
+}
+
+/// [`bare_editor`] as a builder, for tests that need more options.
+pub fn bare_builder(lang: &str, text: &str) -> EditorBuilder {
+    Editor::builder()
+        .language(lang)
+        .content(text)
+        .line_numbers(false)
+        .code_folding(false)
+        .left_padding(0)
+}
+
+/// Row `y` of `buf` as text with trailing blanks trimmed.
+pub fn row(buf: &Buffer, y: u16) -> String {
+    (buf.area.left()..buf.area.right())
+        .map(|x| buf[(x, y)].symbol())
+        .collect::<String>()
+        .trim_end()
+        .to_string()
+}
+
+/// All rows of `buf`, see [`row`].
+pub fn rows(buf: &Buffer) -> Vec<String> {
+    (buf.area.top()..buf.area.bottom())
+        .map(|y| row(buf, y))
+        .collect()
+}
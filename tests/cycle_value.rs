@@ -0,0 +1,68 @@
+use ratatui_code_editor::actions::{CycleValue, Undo};
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::language::LanguageConfig;
+
+#[test]
+fn cycle_value_at_cursor() {
+    let source = "let x = Some(true) && Errors;";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_cursor(source.find("true").unwrap() + 2);
+    editor.apply(CycleValue { forward: true });
+    assert_eq!(editor.get_content(), "let x = Some(false) && Errors;");
+
+    editor.set_cursor(source.find("Some").unwrap());
+    editor.apply(CycleValue { forward: true });
+    assert_eq!(editor.get_content(), "let x = None(false) && Errors;");
+
+    // A cursor right after the operator still picks it and stays after it
+    let after_and = editor.get_content().find("&&").unwrap() + 2;
+    editor.set_cursor(after_and);
+    editor.execute(Command::CycleValue { forward: false });
+    assert_eq!(editor.get_content(), "let x = None(false) || Errors;");
+    assert_eq!(editor.get_cursor(), after_and);
+
+    // Only whole words match
+    editor.set_cursor(editor.get_content().find("Errors").unwrap() + 1);
+    editor.apply(CycleValue { forward: true });
+    assert_eq!(editor.get_content(), "let x = None(false) || Errors;");
+
+    editor.apply(Undo);
+    assert_eq!(editor.get_content(), "let x = None(false) && Errors;");
+}
+
+#[test]
+fn logical_operators_cycle_only_in_c_like_languages() {
+    let mut editor = Editor::new("python", "a && b", vec![]).unwrap();
+    editor.set_cursor(3);
+    editor.apply(CycleValue { forward: true });
+    assert_eq!(editor.get_content(), "a && b");
+
+    let mut editor = Editor::new("go", "a && b", vec![]).unwrap();
+    editor.set_cursor(3);
+    editor.apply(CycleValue { forward: true });
+    assert_eq!(editor.get_content(), "a || b");
+}
+
+#[test]
+fn cycle_value_prefers_longest_and_custom_cycles() {
+    let source = "if (a === b) { x = 1; }";
+    let mut editor = Editor::new("javascript", source, vec![]).unwrap();
+    editor.set_cursor(source.find("===").unwrap() + 1);
+    editor.apply(CycleValue { forward: true });
+    assert_eq!(editor.get_content(), "if (a !== b) { x = 1; }");
+
+    let source = "level: debug";
+    let mut editor = Editor::new("yaml", source, vec![]).unwrap();
+    editor.set_language_config(LanguageConfig {
+        cycles: vec![vec![
+            "debug".to_string(),
+            "info".to_string(),
+            "warn".to_string(),
+        ]],
+        ..LanguageConfig::builtin("yaml")
+    });
+    editor.set_cursor(8);
+    editor.apply(CycleValue { forward: false });
+    assert_eq!(editor.get_content(), "level: warn");
+}
@@ -0,0 +1,29 @@
+use ratatui_code_editor::utils::{detect_lang, get_lang};
+
+#[test]
+fn detects_language_from_name_extension_and_shebang() {
+    assert_eq!(get_lang("src/main.rs"), "rust");
+    assert_eq!(detect_lang("include/vec.hpp", None), "cpp");
+
+    assert_eq!(detect_lang("Dockerfile", None), "dockerfile");
+    assert_eq!(detect_lang("project/Makefile", None), "make");
+    assert_eq!(detect_lang("Cargo.lock", None), "toml");
+
+    assert_eq!(detect_lang("config.toml.in", None), "toml");
+    assert_eq!(detect_lang("values.yaml.j2", None), "yaml");
+    assert_eq!(detect_lang(".gitignore", None), "unknown");
+
+    assert_eq!(
+        detect_lang("bin/tool", Some("#!/usr/bin/env python3")),
+        "python"
+    );
+    assert_eq!(detect_lang("run", Some("#!/bin/bash -e")), "shell");
+    assert_eq!(
+        detect_lang("serve", Some("#!/usr/bin/env -S node --harmony")),
+        "javascript"
+    );
+    assert_eq!(detect_lang("notes", Some("hello")), "unknown");
+
+    // The extension wins over the shebang
+    assert_eq!(detect_lang("script.py", Some("#!/bin/sh")), "python");
+}
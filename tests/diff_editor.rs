@@ -0,0 +1,73 @@
+mod common;
+
+use common::row;
+use ratatui_code_editor::diff_editor::{DiffEditor, DiffLayout, DiffRow};
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+fn diff_editor() -> DiffEditor {
+    let old = Editor::new("text", "a\nb\nc", vec![]).unwrap();
+    let new = Editor::new("text", "a\nB\nc\nd", vec![]).unwrap();
+    DiffEditor::new(old, new)
+}
+
+#[test]
+fn side_by_side_aligns_lines_and_marks_changes() {
+    let diff = diff_editor();
+    assert_eq!(
+        diff.rows()[3],
+        DiffRow {
+            old: None,
+            new: Some(3),
+            changed: true
+        }
+    );
+
+    let area = Rect::new(0, 0, 21, 4);
+    let mut buf = Buffer::empty(area);
+    (&diff).render(area, &mut buf);
+    assert_eq!(row(&buf, 0), "1 a       │1 a");
+    assert_eq!(row(&buf, 1), "2 b       │2 B");
+    assert_eq!(row(&buf, 2), "3 c       │3 c");
+    assert_eq!(row(&buf, 3), "╱╱╱╱╱╱╱╱╱╱│4 d");
+
+    // removed and added lines get their background, unchanged ones keep theirs
+    let (removed, added) = (buf[(2, 1)].bg, buf[(13, 1)].bg);
+    assert_ne!(removed, buf[(2, 0)].bg);
+    assert_ne!(added, buf[(13, 0)].bg);
+    assert_ne!(removed, added);
+    assert_eq!(buf[(19, 3)].bg, added);
+}
+
+#[test]
+fn side_by_side_rows_follow_edits_and_scroll() {
+    let mut diff = diff_editor();
+    diff.new_editor_mut().set_content("a\nb\nc");
+    assert!(diff.rows().iter().all(|row| !row.changed));
+
+    diff.scroll_down(2);
+    assert_eq!(diff.offset_y(), 1);
+    diff.scroll_down(2);
+    assert_eq!(diff.offset_y(), 1);
+    let area = Rect::new(0, 0, 21, 2);
+    let mut buf = Buffer::empty(area);
+    (&diff).render(area, &mut buf);
+    assert_eq!(row(&buf, 0), "2 b       │2 b");
+}
+
+#[test]
+fn inline_layout_shows_removed_lines_in_the_new_editor() {
+    let mut diff = diff_editor();
+    diff.set_layout(DiffLayout::Inline).unwrap();
+    let area = Rect::new(0, 0, 20, 6);
+    let mut buf = Buffer::empty(area);
+    (&diff).render(area, &mut buf);
+    let screen: Vec<String> = (0..area.height).map(|y| row(&buf, y)).collect();
+    assert!(screen.iter().any(|line| line.ends_with(" b")));
+    assert!(screen.iter().any(|line| line.ends_with(" B")));
+
+    diff.set_layout(DiffLayout::SideBySide).unwrap();
+    assert!(!diff.new_editor().is_diff_enabled());
+}
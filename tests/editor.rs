@@ -1,4 +1,8 @@
+mod common;
+
+use common::row;
 use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::types::CursorStyle;
 use ratatui_core::style::Color;
 
 #[test]
@@ -42,3 +46,82 @@ fn test_build_theme_bg_fg() {
     assert_eq!(identifier_style.fg, Some(Color::Rgb(165, 252, 182)));
     assert_eq!(identifier_style.bg, None);
 }
+
+#[test]
+fn test_render_writes_highlighted_rows() {
+    use ratatui_core::buffer::Buffer;
+    use ratatui_core::layout::Rect;
+    use ratatui_core::widgets::Widget;
+
+    let theme = vec![("keyword", "#a0a0a0")];
+    let mut editor = Editor::new("rust", "let a = 1;\n\tlet b = 2;", theme).unwrap();
+    editor.set_word_highlight_enabled(false);
+    editor.set_code_folding_enabled(false);
+    editor.show_line_numbers(false);
+
+    let area = Rect::new(0, 0, 20, 3);
+    let mut buf = Buffer::empty(area);
+    (&editor).render(area, &mut buf);
+
+    assert_eq!(row(&buf, 0), "  let a = 1;");
+    assert_eq!(row(&buf, 1), "   let b = 2;");
+    assert_eq!(buf[(2, 0)].fg, Color::Rgb(160, 160, 160));
+    assert_eq!(buf[(6, 0)].fg, Color::White);
+    assert_eq!(buf[(3, 1)].fg, Color::Rgb(160, 160, 160));
+}
+
+#[test]
+fn test_render_line_spans_without_buffer() {
+    let theme = vec![("keyword", "#a0a0a0")];
+    let mut editor = Editor::new("rust", "let a = 1;\nlet b = 2;", theme).unwrap();
+    editor.set_word_highlight_enabled(false);
+    editor.set_offset_x(4);
+
+    let spans = editor.render_line_spans(1, 6);
+    let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+    assert_eq!(text, "let b ");
+    assert_eq!(spans[0].content, "let");
+    assert_eq!(spans[0].style.fg, Some(Color::Rgb(160, 160, 160)));
+
+    assert!(editor.render_line_spans(5, 10).is_empty());
+}
+
+#[test]
+fn test_render_invisible_characters() {
+    let mut editor = Editor::new("text", "\u{feff}a\u{200b}b\u{202e}c", vec![]).unwrap();
+    editor.set_word_highlight_enabled(false);
+
+    let spans = editor.render_line_spans(0, 20);
+    let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+    assert_eq!(text, "·a·b⇆c");
+    assert_eq!(spans[0].style.fg, Some(Color::Yellow));
+    assert_eq!(spans[1].content, "a");
+
+    // Placeholders take one cell, so the cursor after them lines up
+    editor.set_cursor(4);
+    let area = ratatui_core::layout::Rect::new(0, 0, 40, 2);
+    let (x, _) = editor.get_visible_cursor(&area).unwrap();
+    let (x0, _) = {
+        editor.set_cursor(0);
+        editor.get_visible_cursor(&area).unwrap()
+    };
+    assert_eq!(x - x0, 4);
+
+    editor.show_invisibles(false);
+    let text: String = editor
+        .render_line_spans(0, 20)
+        .iter()
+        .map(|span| span.content.as_ref())
+        .collect();
+    assert_eq!(text, " a b c");
+}
+
+#[test]
+fn cursor_style_defaults_to_a_bar_and_can_be_overridden() {
+    let mut editor = Editor::new("text", "abc", vec![]).unwrap();
+    assert_eq!(editor.cursor_style(), CursorStyle::Bar);
+    editor.set_cursor_style(Some(CursorStyle::Block));
+    assert_eq!(editor.cursor_style(), CursorStyle::Block);
+    editor.set_cursor_style(None);
+    assert_eq!(editor.cursor_style(), CursorStyle::Bar);
+}
@@ -0,0 +1,61 @@
+mod common;
+
+use common::{bare_editor, rows};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::editor_view::EditorView;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::StatefulWidget;
+
+fn editor() -> Editor {
+    let content = (0..20)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    bare_editor("text", &content)
+}
+
+#[test]
+fn one_document_renders_in_views_with_their_own_scroll() {
+    let editor = editor();
+    let area = Rect::new(0, 0, 10, 2);
+    let mut top = editor.view_state();
+    let mut bottom = EditorView {
+        offset_y: 10,
+        ..editor.view_state()
+    };
+
+    let mut buffer = Buffer::empty(area);
+    (&editor).render(area, &mut buffer, &mut top);
+    assert_eq!(rows(&buffer), ["line 0", "line 1"]);
+    assert_eq!(top.area, area);
+
+    let mut buffer = Buffer::empty(area);
+    (&editor).render(area, &mut buffer, &mut bottom);
+    assert_eq!(rows(&buffer), ["line 10", "line 11"]);
+    assert_eq!(editor.get_offset_y(), 0);
+
+    // Offsets past the end are clamped to the last line
+    bottom.offset_y = 100;
+    (&editor).render(area, &mut buffer, &mut bottom);
+    assert_eq!(bottom.offset_y, 19);
+}
+
+#[test]
+fn views_follow_the_shared_cursor_on_request() {
+    let mut editor = editor();
+    let area = Rect::new(0, 0, 10, 2);
+    let mut view = editor.view_state();
+    let mut buffer = Buffer::empty(area);
+    (&editor).render(area, &mut buffer, &mut view);
+    assert_eq!(view.cursor_position(&editor), Some((0, 0)));
+
+    editor.set_cursor(editor.get_content().find("line 5").unwrap() + 2);
+    assert_eq!(view.cursor_position(&editor), None);
+    view.scroll_to_cursor(&editor);
+    assert_eq!(view.offset_y, 4);
+    assert_eq!(view.cursor_position(&editor), Some((2, 1)));
+
+    view.focused = false;
+    assert_eq!(view.cursor_position(&editor), None);
+}
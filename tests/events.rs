@@ -0,0 +1,252 @@
+mod common;
+
+use common::bare_editor;
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::events::EditorEvent;
+use ratatui_code_editor::selection::Selection;
+use ratatui_code_editor::types::PasteMode;
+use ratatui_core::layout::Rect;
+use std::time::{Duration, Instant};
+
+#[test]
+fn drain_events_reports_state_changes() {
+    let mut editor = Editor::new("text", "one\ntwo\nthree", vec![]).unwrap();
+    assert!(editor.drain_events().is_empty());
+
+    editor.execute(Command::MoveRight { shift: true });
+    let events = editor.drain_events();
+    assert_eq!(events[0], EditorEvent::CursorMoved { cursor: 1 });
+    assert!(matches!(
+        events[1],
+        EditorEvent::SelectionChanged { selection: Some(_) }
+    ));
+    assert!(editor.drain_events().is_empty());
+
+    editor.set_offset_y(2);
+    editor.set_paste_mode(PasteMode::Literal);
+    let events = editor.drain_events();
+    assert_eq!(
+        events[0],
+        EditorEvent::Scrolled {
+            offset_x: 0,
+            offset_y: 2
+        }
+    );
+    assert!(
+        matches!(events[1], EditorEvent::ModeChanged(mode) if mode.paste_mode == PasteMode::Literal)
+    );
+
+    editor.execute(Command::InsertText { text: "x".into() });
+    editor.execute(Command::Undo);
+    editor.execute(Command::Redo);
+    editor.execute(Command::Redo);
+    let events = editor.drain_events();
+    assert_eq!(events[..2], [EditorEvent::Undo, EditorEvent::Redo]);
+}
+
+#[test]
+fn save_emits_saved_event() {
+    let path = std::env::temp_dir().join("ratatui_code_editor_events_save.txt");
+    let mut editor = Editor::new("text", "hello", vec![]).unwrap();
+    editor.save_to(&path).unwrap();
+    assert_eq!(
+        editor.drain_events(),
+        [EditorEvent::Saved { path: path.clone() }]
+    );
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn ctrl_click_requests_definition_without_moving_the_cursor() {
+    let mut editor = bare_editor("text", "let x = 1;\nprint(x)");
+    editor.drain_events();
+    let area = Rect::new(0, 0, 20, 5);
+    let click = MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 6,
+        row: 1,
+        modifiers: KeyModifiers::CONTROL,
+    };
+    editor.mouse(click, &area).unwrap();
+    assert_eq!(
+        editor.drain_events(),
+        vec![EditorEvent::DefinitionRequested {
+            offset: 17,
+            row: 1,
+            col: 6
+        }]
+    );
+    assert_eq!(editor.get_cursor(), 0);
+
+    editor.set_definition_on_ctrl_click(false);
+    editor.mouse(click, &area).unwrap();
+    assert_eq!(editor.get_cursor(), 17);
+}
+
+#[test]
+fn definition_positions_are_rows_and_columns() {
+    let mut editor = Editor::new("text", "one\ntwo\nthree", vec![]).unwrap();
+    editor.set_cursor(6);
+    assert_eq!(editor.definition_request_position(), (1, 2));
+    editor.apply_location(2, 10);
+    assert_eq!(editor.get_cursor(), 13);
+    editor.apply_location(9, 0);
+    assert_eq!(editor.get_cursor(), 13);
+}
+
+#[test]
+fn resting_pointer_requests_hover_after_the_delay() {
+    let mut editor = bare_editor("text", "let x = 1;\nprint(x)");
+    editor.set_hover_delay(Some(Duration::from_millis(300)));
+    editor.drain_events();
+    let area = Rect::new(0, 0, 20, 5);
+    let start = Instant::now();
+
+    editor.handle_mouse_move_at(6, 1, &area, start);
+    assert_eq!(
+        editor.next_deadline(),
+        Some(start + Duration::from_millis(300))
+    );
+    editor.tick(start + Duration::from_millis(200));
+    assert_eq!(editor.drain_events(), vec![]);
+    // moving within the same character keeps the hover
+    editor.handle_mouse_move_at(6, 1, &area, start + Duration::from_millis(250));
+    editor.tick(start + Duration::from_millis(300));
+    assert_eq!(
+        editor.drain_events(),
+        vec![EditorEvent::HoverRequested {
+            offset: 17,
+            screen_pos: (6, 1)
+        }]
+    );
+    editor.tick(start + Duration::from_millis(900));
+    assert_eq!(editor.drain_events(), vec![]);
+
+    // past the end of the line there is nothing to hover
+    editor.handle_mouse_move_at(15, 1, &area, start + Duration::from_millis(1000));
+    assert_eq!(editor.drain_events(), vec![EditorEvent::HoverEnded]);
+    editor.tick(start + Duration::from_millis(2000));
+    assert_eq!(editor.drain_events(), vec![]);
+}
+
+#[test]
+fn right_click_selects_the_word_and_requests_a_context_menu() {
+    let mut editor = bare_editor("text", "let value = 1;");
+    editor.drain_events();
+    let area = Rect::new(0, 0, 20, 5);
+    let click = |column| MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Right),
+        column,
+        row: 0,
+        modifiers: KeyModifiers::NONE,
+    };
+    editor.mouse(click(6), &area).unwrap();
+    assert_eq!(editor.get_selection(), Some(Selection::new(4, 9)));
+    assert!(
+        editor
+            .drain_events()
+            .contains(&EditorEvent::ContextMenuRequested {
+                offset: 6,
+                screen_pos: (6, 0)
+            })
+    );
+
+    // an existing selection is kept
+    editor.mouse(click(12), &area).unwrap();
+    assert_eq!(editor.get_selection(), Some(Selection::new(4, 9)));
+    assert_eq!(editor.get_cursor(), 9);
+}
+
+#[test]
+fn middle_click_pastes_the_mouse_selection() {
+    let mut editor = bare_editor("text", "copy me\n");
+    editor.set_primary_selection_enabled(true);
+    let area = Rect::new(0, 0, 20, 5);
+    let mouse = |kind, column, row| MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    };
+    editor
+        .mouse(mouse(MouseEventKind::Down(MouseButton::Left), 0, 0), &area)
+        .unwrap();
+    editor
+        .mouse(mouse(MouseEventKind::Drag(MouseButton::Left), 4, 0), &area)
+        .unwrap();
+    editor
+        .mouse(mouse(MouseEventKind::Up(MouseButton::Left), 4, 0), &area)
+        .unwrap();
+    assert_eq!(editor.get_primary_selection().unwrap(), "copy");
+
+    editor
+        .mouse(
+            mouse(MouseEventKind::Down(MouseButton::Middle), 0, 1),
+            &area,
+        )
+        .unwrap();
+    assert_eq!(editor.get_content(), "copy me\ncopy");
+    assert_eq!(editor.get_cursor(), 12);
+    assert_eq!(editor.get_selection(), None);
+}
+
+#[test]
+fn dragging_the_selection_moves_or_copies_it() {
+    let mut editor = bare_editor("text", "one two three");
+    let area = Rect::new(0, 0, 20, 5);
+    let mouse = |kind, column, modifiers| MouseEvent {
+        kind,
+        column,
+        row: 0,
+        modifiers,
+    };
+    // the modifier only matters when the button is released
+    let drag = |editor: &mut Editor, from, to, modifiers| {
+        for (kind, column, modifiers) in [
+            (
+                MouseEventKind::Down(MouseButton::Left),
+                from,
+                KeyModifiers::NONE,
+            ),
+            (
+                MouseEventKind::Drag(MouseButton::Left),
+                to,
+                KeyModifiers::NONE,
+            ),
+            (MouseEventKind::Up(MouseButton::Left), to, modifiers),
+        ] {
+            editor.mouse(mouse(kind, column, modifiers), &area).unwrap();
+        }
+    };
+
+    // off by default, pressing inside the selection is a plain click
+    editor.set_selection(Some(Selection::new(0, 4)));
+    drag(&mut editor, 2, 13, KeyModifiers::NONE);
+    assert_eq!(editor.get_content(), "one two three");
+    assert_eq!(editor.get_selection(), Some(Selection::new(2, 13)));
+
+    editor.set_drag_selection(true);
+    editor.set_selection(Some(Selection::new(0, 4)));
+    drag(&mut editor, 1, 13, KeyModifiers::NONE);
+    assert_eq!(editor.get_content(), "two threeone ");
+    assert_eq!(editor.get_selection(), Some(Selection::new(9, 13)));
+
+    drag(&mut editor, 10, 0, KeyModifiers::CONTROL);
+    assert_eq!(editor.get_content(), "one two threeone ");
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 4)));
+
+    // dropping into the selection itself leaves the text alone
+    drag(&mut editor, 1, 3, KeyModifiers::NONE);
+    assert_eq!(editor.get_content(), "one two threeone ");
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 4)));
+    // a click without dragging clears the selection
+    drag(&mut editor, 2, 2, KeyModifiers::NONE);
+    assert_eq!(editor.get_selection(), Some(Selection::new(2, 2)));
+    assert_eq!(editor.get_cursor(), 2);
+
+    editor.set_selection(Some(Selection::new(0, 4)));
+    editor.execute(Command::MoveSelection { to: 8, copy: false });
+    assert_eq!(editor.get_content(), "two one threeone ");
+}
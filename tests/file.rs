@@ -0,0 +1,207 @@
+use ratatui_code_editor::actions::{InsertText, Redo, Undo};
+use ratatui_code_editor::editor::Editor;
+use std::path::PathBuf;
+
+fn temp_file(name: &str, content: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("ratatui-code-editor-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn modified_flag_follows_edits_and_saves() {
+    let mut editor = Editor::new("text", "hello", vec![]).unwrap();
+    assert!(!editor.is_modified());
+
+    editor.apply(InsertText { text: "!".into() });
+    assert!(editor.is_modified());
+    editor.mark_saved();
+    assert!(!editor.is_modified());
+
+    editor.apply(Undo {});
+    assert!(editor.is_modified());
+    // redoing back to the saved content is unmodified again
+    editor.apply(Redo {});
+    assert!(!editor.is_modified());
+}
+
+#[test]
+fn undo_to_the_saved_content_is_unmodified() {
+    let mut editor = Editor::new("text", "hello", vec![]).unwrap();
+    editor.apply(InsertText { text: "!".into() });
+    editor.apply(Undo {});
+    assert!(!editor.is_modified());
+
+    editor.apply(InsertText { text: "?".into() });
+    editor.mark_saved();
+    editor.apply(Undo {});
+    editor.apply(InsertText { text: ".".into() });
+    // the saved content was in the discarded redo step
+    editor.apply(Undo {});
+    assert!(editor.is_modified());
+}
+
+#[test]
+fn load_and_save_file() {
+    let path = temp_file("main.rs", "fn main() {}\n");
+    let mut editor = Editor::new_from_file(&path, vec![]).unwrap();
+    assert_eq!(editor.borrow_code().lang(), "rust");
+    assert_eq!(editor.file_path(), Some(path.as_path()));
+    assert!(!editor.is_modified());
+
+    editor.set_cursor(0);
+    editor.apply(InsertText {
+        text: "pub ".into(),
+    });
+    editor.save().unwrap();
+    assert!(!editor.is_modified());
+    assert_eq!(
+        std::fs::read_to_string(&path).unwrap(),
+        "pub fn main() {}\n"
+    );
+
+    let copy = path.with_file_name("copy.rs");
+    editor.save_to(&copy).unwrap();
+    assert_eq!(editor.file_path(), Some(copy.as_path()));
+    assert_eq!(
+        std::fs::read_to_string(&copy).unwrap(),
+        "pub fn main() {}\n"
+    );
+
+    assert!(Editor::new_from_file(path.with_file_name("missing.rs"), vec![]).is_err());
+    assert!(Editor::new("text", "", vec![]).unwrap().save().is_err());
+}
+
+#[test]
+fn reload_content_keeps_cursor_marks_and_history() {
+    let mut editor = Editor::new("text", "first\nsecond\nthird\n", vec![]).unwrap();
+    editor.set_cursor(editor.get_content().find("third").unwrap() + 2);
+    editor.set_marks(vec![(6, 12, "#ff0000")]);
+    editor.apply(InsertText { text: "_".into() });
+    let cursor = editor.get_cursor();
+
+    editor
+        .reload_content("zeroth\nfirst\nsecond\nth_ird\n")
+        .unwrap();
+    assert_eq!(editor.get_content(), "zeroth\nfirst\nsecond\nth_ird\n");
+    assert_eq!(editor.get_cursor(), cursor + 7);
+    let (start, end, _) = editor.marks().unwrap()[0];
+    assert_eq!((start, end), (13, 19));
+    assert!(!editor.is_modified());
+
+    editor.apply(Undo {});
+    assert_eq!(editor.get_content(), "first\nsecond\nth_ird\n");
+    editor.apply(Undo {});
+    assert_eq!(editor.get_content(), "first\nsecond\nthird\n");
+}
+
+#[test]
+fn format_with_applies_the_formatter_output_as_one_step() {
+    let text = "fn main(){\nlet x=1;\n}\n";
+    let mut editor = Editor::new("rust", text, vec![]).unwrap();
+    // on the `1`
+    editor.set_cursor(text.find('1').unwrap());
+    editor
+        .format_with(|content| {
+            Ok(content
+                .replace("(){", "() {")
+                .replace("let x=1", "    let x = 1"))
+        })
+        .unwrap();
+    assert_eq!(editor.get_content(), "fn main() {\n    let x = 1;\n}\n");
+    assert_eq!(editor.get_cursor(), editor.get_content().find('1').unwrap());
+
+    assert!(
+        editor
+            .format_with(|_| Err(anyhow::anyhow!("syntax error")))
+            .is_err()
+    );
+    editor.apply(Undo {});
+    assert_eq!(editor.get_content(), text);
+}
+
+#[test]
+fn open_large_defers_parsing_above_threshold() {
+    use ratatui_code_editor::code::{Code, LARGE_FILE_THRESHOLD};
+
+    let path = temp_file("small.rs", "fn main() {}\n");
+    let editor = Editor::open_large(&path, vec![]).unwrap();
+    assert!(editor.borrow_code().is_highlight());
+    assert_eq!(editor.get_content(), "fn main() {}\n");
+
+    let line = "let x = 1;\n";
+    let big = line.repeat(LARGE_FILE_THRESHOLD / line.len() + 1);
+    let code = Code::from_reader(big.as_bytes(), "rust", None).unwrap();
+    assert!(!code.is_highlight());
+    assert_eq!(code.len_lines(), LARGE_FILE_THRESHOLD / line.len() + 2);
+
+    let mut code = Code::from_reader(line.as_bytes(), "rust", None).unwrap();
+    assert!(code.is_highlight());
+    code.enable_syntax().unwrap();
+    assert!(code.is_highlight());
+}
+
+#[test]
+fn crlf_content_is_normalized_and_reemitted() {
+    use ratatui_code_editor::types::LineEnding;
+
+    let mut editor = Editor::new("text", "one\r\ntwo\r\nthree", vec![]).unwrap();
+    assert_eq!(editor.line_ending(), LineEnding::CrLf);
+    assert_eq!(editor.borrow_code().len_chars(), 13);
+    assert_eq!(editor.borrow_code().line_len(0), 3);
+    assert_eq!(editor.get_content(), "one\r\ntwo\r\nthree");
+
+    let end = editor.borrow_code().len_chars();
+    editor.set_cursor(end);
+    editor.apply(InsertText { text: "\n".into() });
+    assert_eq!(editor.get_content(), "one\r\ntwo\r\nthree\r\n");
+
+    // Inserted CRLF text is normalized too, not emitted as `\r\r\n`
+    editor.apply(InsertText {
+        text: "four\r\nfive".into(),
+    });
+    assert_eq!(editor.get_content(), "one\r\ntwo\r\nthree\r\nfour\r\nfive");
+    assert_eq!(editor.get_cursor(), 23);
+    editor.apply(Undo);
+
+    editor.set_line_ending(LineEnding::Lf);
+    assert_eq!(editor.get_content(), "one\ntwo\nthree\n");
+
+    let path = temp_file("crlf.txt", "a\r\nb\nc\r\n");
+    let mut editor = Editor::new_from_file(&path, vec![]).unwrap();
+    assert_eq!(editor.line_ending(), LineEnding::CrLf);
+    editor.save().unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "a\r\nb\r\nc\r\n");
+
+    assert_eq!(LineEnding::detect("a\nb\r\n"), LineEnding::Lf);
+    assert_eq!(LineEnding::detect(""), LineEnding::Lf);
+}
+
+#[test]
+fn non_utf8_files_keep_their_encoding() {
+    let path = temp_file("latin1.txt", "");
+    std::fs::write(&path, b"caf\xe9\n").unwrap();
+    let mut editor = Editor::new_from_file(&path, vec![]).unwrap();
+    assert_eq!(editor.get_content(), "café\n");
+    assert_eq!(editor.encoding().name(), "windows-1252");
+
+    editor.set_cursor(4);
+    editor.apply(InsertText { text: "s".into() });
+    editor.save().unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"caf\xe9s\n");
+
+    editor.apply(InsertText { text: "λ".into() });
+    assert!(editor.save().is_err());
+    assert!(editor.is_modified());
+
+    let path = temp_file("utf16.txt", "");
+    std::fs::write(&path, b"\xFF\xFEo\0k\0").unwrap();
+    let mut editor = Editor::new_from_file(&path, vec![]).unwrap();
+    assert_eq!(editor.get_content(), "ok");
+    assert!(editor.has_bom());
+    editor.apply(InsertText { text: "!".into() });
+    editor.save().unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), b"\xFF\xFE!\0o\0k\0");
+}
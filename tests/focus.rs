@@ -0,0 +1,45 @@
+mod common;
+
+use common::bare_editor;
+use ratatui_code_editor::selection::Selection;
+use ratatui_code_editor::testing::TestHarness;
+use ratatui_core::style::{Color, Modifier};
+
+fn harness() -> TestHarness {
+    TestHarness::new(bare_editor("text", "hello world"), 20, 2)
+}
+
+#[test]
+fn unfocused_editor_ignores_keys_and_hides_cursor() {
+    let mut harness = harness();
+    assert!(harness.editor().is_focused());
+    assert_eq!(harness.screen_cursor(), Some((0, 0)));
+
+    harness.editor_mut().set_focused(false);
+    harness.keys("abc<Right>");
+    assert_eq!(harness.editor().get_content(), "hello world");
+    harness.assert_cursor(0, 0);
+    assert_eq!(harness.screen_cursor(), None);
+
+    harness.editor_mut().set_focused(true);
+    harness.keys("a");
+    assert_eq!(harness.editor().get_content(), "ahello world");
+    assert_eq!(harness.screen_cursor(), Some((1, 0)));
+}
+
+#[test]
+fn unfocused_editor_dims_selections() {
+    let mut harness = harness();
+    let editor = harness.editor_mut();
+    editor.set_selection(Some(Selection::new(0, 5)));
+    editor.set_secondary_selections(vec![Selection::new(6, 8)]);
+    assert_eq!(harness.render()[(0, 0)].bg, Color::DarkGray);
+    let secondary_cursor = harness.render()[(8, 0)].modifier;
+    assert!(secondary_cursor.contains(Modifier::REVERSED));
+
+    harness.editor_mut().set_focused(false);
+    let buffer = harness.render();
+    assert_eq!(buffer[(0, 0)].bg, Color::Rgb(58, 58, 58));
+    assert_eq!(buffer[(6, 0)].bg, Color::Rgb(58, 58, 58));
+    assert!(!buffer[(8, 0)].modifier.contains(Modifier::REVERSED));
+}
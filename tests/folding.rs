@@ -21,7 +21,7 @@ fn rust_reports_and_toggles_a_multiline_fold() {
     let mut editor = Editor::new("rust", source, vec![]).unwrap();
     editor.set_cursor(source.find("value").unwrap());
     assert!(editor.toggle_fold_at_line(0));
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 1);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 1);
     assert!(!editor.toggle_fold_at_line(9));
     assert!(editor.toggle_fold_at_line(0));
 
@@ -63,11 +63,11 @@ fn cursor_is_pulled_out_when_selection_endpoint_is_inside_fold() {
     editor.set_cursor(value);
     editor.set_selection(Some(Selection::from_anchor_and_cursor(0, value)));
     assert!(editor.toggle_fold_at_line(0));
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 1);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 1);
 
     editor.apply(MoveRight { shift: false });
 
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 0);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 0);
     assert!(
         editor
             .get_visible_cursor(&Rect::new(0, 0, 80, 10))
@@ -83,11 +83,11 @@ fn move_right_skips_hidden_fold_body() {
 
     editor.set_cursor(value);
     assert!(editor.toggle_fold_at_line(0));
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 1);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 1);
 
     editor.apply(MoveRight { shift: false });
 
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 3);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 3);
     assert!(
         editor
             .get_visible_cursor(&Rect::new(0, 0, 80, 10))
@@ -109,11 +109,11 @@ fn typing_inside_hidden_fold_keeps_cursor_inside_until_navigation() {
     });
 
     assert!(editor.get_content().contains("new_value"));
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 1);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 1);
 
     editor.apply(MoveRight { shift: false });
 
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 3);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 3);
 }
 
 #[test]
@@ -134,7 +134,7 @@ fn terminal_input_inside_hidden_fold_does_not_focus_cursor_out() {
         )
         .unwrap();
 
-    assert_eq!(editor.code_ref().point(editor.get_cursor()).0, 1);
+    assert_eq!(editor.borrow_code().point(editor.get_cursor()).0, 1);
     assert_eq!(editor.get_offset_y(), 0);
 }
 
@@ -0,0 +1,70 @@
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::events::EditorEvent;
+use ratatui_code_editor::gutter::GutterColumn;
+use ratatui_code_editor::selection::Selection;
+use ratatui_code_editor::testing::TestHarness;
+use ratatui_core::style::Style;
+
+fn harness() -> TestHarness {
+    let mut editor = Editor::new("text", "one\ntwo\nthree\nfour", vec![]).unwrap();
+    editor.set_left_code_padding(1);
+    editor.set_code_folding_enabled(false);
+    let mut breakpoints = GutterColumn::new("breakpoints", 2);
+    breakpoints.set_cell(1, "●", Style::default());
+    editor.add_gutter_column(breakpoints);
+    TestHarness::new(editor, 20, 4)
+}
+
+#[test]
+fn host_columns_are_drawn_left_of_the_line_numbers() {
+    let harness = harness();
+    harness.assert_screen(&[
+        "      1 one",
+        "●     2 two",
+        "      3 three",
+        "      4 four",
+    ]);
+}
+
+#[test]
+fn clicking_a_host_column_reports_the_line() {
+    let mut harness = harness();
+    harness.take_events();
+    harness.click(0, 2);
+    assert_eq!(
+        harness.take_events(),
+        vec![EditorEvent::GutterClicked {
+            line: 2,
+            column_id: "breakpoints".to_string()
+        }]
+    );
+    harness.assert_cursor(0, 0);
+
+    let mut editor = Editor::new("text", "", vec![]).unwrap();
+    editor.add_gutter_column(GutterColumn::new("breakpoints", 2));
+    editor.add_gutter_column(GutterColumn::new("breakpoints", 3));
+    assert_eq!(editor.gutter_column("breakpoints").unwrap().width, 3);
+    assert!(editor.remove_gutter_column("breakpoints").is_some());
+    assert!(editor.gutter_column("breakpoints").is_none());
+}
+
+#[test]
+fn line_numbers_select_lines_and_drag_line_wise() {
+    let mut harness = harness();
+    harness.click(4, 1);
+    assert_eq!(
+        harness.editor_mut().get_selection(),
+        Some(Selection::new(4, 8))
+    );
+
+    harness.drag((5, 1), (3, 2));
+    assert_eq!(
+        harness.editor_mut().get_selection(),
+        Some(Selection::new(4, 14))
+    );
+    harness.drag((5, 2), (9, 0));
+    assert_eq!(
+        harness.editor_mut().get_selection(),
+        Some(Selection::new(0, 14))
+    );
+}
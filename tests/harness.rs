@@ -0,0 +1,40 @@
+mod common;
+
+use common::bare_editor;
+use ratatui_code_editor::events::EditorEvent;
+use ratatui_code_editor::testing::TestHarness;
+use ratatui_code_editor::types::ChangeOrigin;
+
+#[test]
+fn harness_drives_keys_and_mouse_and_checks_the_screen() {
+    let mut harness = TestHarness::new(bare_editor("text", "one\ntwo"), 10, 3);
+    harness.assert_screen(&["one", "two", ""]);
+
+    harness.click(3, 1);
+    harness.assert_cursor(1, 3);
+    assert_eq!(harness.screen_cursor(), Some((3, 1)));
+    assert!(
+        harness
+            .take_events()
+            .contains(&EditorEvent::CursorMoved { cursor: 7 })
+    );
+
+    harness.keys("!<C-z>?");
+    harness.assert_lines(&["one", "two?"]);
+    let origins: Vec<ChangeOrigin> = harness
+        .take_changes()
+        .iter()
+        .map(|change| change.origin)
+        .collect();
+    assert_eq!(
+        origins,
+        [ChangeOrigin::Edit, ChangeOrigin::Undo, ChangeOrigin::Edit]
+    );
+
+    harness.drag((0, 0), (2, 0));
+    assert_eq!(
+        harness.editor_mut().get_selection_text().as_deref(),
+        Some("on")
+    );
+    harness.assert_screen(&["one", "two?", ""]);
+}
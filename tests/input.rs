@@ -1,3 +1,6 @@
+mod common;
+
+use common::bare_editor;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui_code_editor::editor::Editor;
 use ratatui_core::layout::Rect;
@@ -45,3 +48,43 @@ fn toggle_fold_at_cursor_toggles_cursor_line_fold() {
     editor.set_cursor(source.find("value").unwrap());
     assert!(editor.get_visible_cursor(&area).is_none());
 }
+
+#[test]
+fn keys_and_mouse_use_the_last_rendered_area() {
+    use crossterm::event::{MouseButton, MouseEvent, MouseEventKind};
+    use ratatui_core::buffer::Buffer;
+    use ratatui_core::widgets::Widget;
+
+    let content = (0..20)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut editor = bare_editor("text", &content);
+    assert_eq!(editor.last_area(), Rect::default());
+
+    let area = Rect::new(2, 1, 20, 5);
+    (&editor).render(area, &mut Buffer::empty(Rect::new(0, 0, 30, 10)));
+    assert_eq!(editor.last_area(), area);
+    assert_eq!(editor.visible_cursor(), Some((2, 1)));
+
+    for _ in 0..6 {
+        editor
+            .handle_key(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE))
+            .unwrap();
+    }
+    assert_eq!(editor.get_offset_y(), 2);
+    assert_eq!(editor.visible_cursor(), Some((2, 5)));
+
+    let click = MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column: 4,
+        row: 1,
+        modifiers: KeyModifiers::NONE,
+    };
+    editor.handle_mouse(click).unwrap();
+    assert_eq!(editor.get_cursor(), content.find("line 2").unwrap() + 2);
+
+    editor.set_cursor(content.find("line 19").unwrap());
+    editor.scroll_to_cursor();
+    assert_eq!(editor.get_offset_y(), 15);
+}
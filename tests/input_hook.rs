@@ -0,0 +1,49 @@
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::editor_crossterm::InputDecision;
+use ratatui_core::layout::Rect;
+
+fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+    KeyEvent::new(code, modifiers)
+}
+
+#[test]
+fn input_hook_can_swallow_remap_and_execute() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = Editor::new("text", "", vec![]).unwrap();
+    editor.set_input_hook(Box::new(|key, editor| match key.code {
+        KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+            InputDecision::Swallow
+        }
+        KeyCode::Char('q') => {
+            InputDecision::Remap(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE))
+        }
+        KeyCode::F(1) if editor.get_content().is_empty() => {
+            InputDecision::Execute(Command::InsertText { text: "hi".into() })
+        }
+        _ => InputDecision::Continue,
+    }));
+
+    editor
+        .input(key(KeyCode::Char('p'), KeyModifiers::CONTROL), &area)
+        .unwrap();
+    assert_eq!(editor.get_content(), "");
+
+    editor
+        .input(key(KeyCode::F(1), KeyModifiers::NONE), &area)
+        .unwrap();
+    editor
+        .input(key(KeyCode::Char('q'), KeyModifiers::NONE), &area)
+        .unwrap();
+    editor
+        .input(key(KeyCode::Char('a'), KeyModifiers::NONE), &area)
+        .unwrap();
+    assert_eq!(editor.get_content(), "hiwa");
+
+    editor.remove_input_hook();
+    editor
+        .input(key(KeyCode::Char('q'), KeyModifiers::NONE), &area)
+        .unwrap();
+    assert_eq!(editor.get_content(), "hiwaq");
+}
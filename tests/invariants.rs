@@ -0,0 +1,26 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn checked_mode_holds_through_editing_and_clicks() {
+    let mut editor = Editor::new("rust", "fn main() {\n    let x = 1;\n}", vec![]).unwrap();
+    editor.set_checked(true);
+    editor.handle_mouse_down(27);
+    editor.handle_mouse_drag(3);
+    editor.execute(Command::Delete);
+    editor.execute(Command::Undo);
+    editor.execute(Command::Redo);
+    editor.execute(Command::Undo);
+    editor.debug_assert_invariants();
+    assert_eq!(editor.get_content(), "fn main() {\n    let x = 1;\n}");
+}
+
+#[test]
+#[cfg(debug_assertions)]
+#[should_panic(expected = "past the end")]
+fn invariants_catch_a_selection_past_the_end() {
+    let mut editor = Editor::new("text", "abc", vec![]).unwrap();
+    editor.set_selection(Some(Selection::new(1, 10)));
+    editor.debug_assert_invariants();
+}
@@ -0,0 +1,65 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::language::{self, LanguageConfig};
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn editor_uses_overridden_language_config() {
+    let mut editor = Editor::new("text", "value", vec![]).unwrap();
+    editor.set_language_config(LanguageConfig {
+        indent: "\t".to_string(),
+        comment: ";".to_string(),
+        ..LanguageConfig::default()
+    });
+
+    editor.execute(Command::ToggleComment);
+    assert_eq!(editor.get_content(), ";value");
+
+    editor.execute(Command::Indent);
+    assert_eq!(editor.get_content(), "\t;value");
+}
+
+#[test]
+fn registered_config_applies_to_new_buffers() {
+    // The registry is global, so tests use a language of their own
+    let lang = "registered_config_test";
+    language::register(
+        lang,
+        LanguageConfig {
+            comment: "---".to_string(),
+            list_continuation: true,
+            ..LanguageConfig::builtin(lang)
+        },
+    );
+
+    let editor = Editor::new(lang, "x = 1", vec![]).unwrap();
+    assert_eq!(editor.borrow_code().comment(), "---");
+    assert!(editor.list_continuation());
+    #[allow(deprecated)]
+    let comment = ratatui_code_editor::utils::comment(lang);
+    assert_eq!(comment, "---");
+
+    language::unregister(lang);
+    let editor = Editor::new(lang, "x = 1", vec![]).unwrap();
+    assert_eq!(
+        editor.borrow_code().comment(),
+        LanguageConfig::builtin(lang).comment
+    );
+}
+
+#[test]
+fn word_chars_extend_double_click_selection() {
+    let mut editor = Editor::new("css", "a { margin-top: 0 }", vec![]).unwrap();
+    editor.handle_mouse_down(6);
+    editor.handle_mouse_down(6);
+    assert_eq!(editor.get_selection(), Some(Selection::new(4, 14)));
+
+    let mut editor = Editor::new("text", "let $el = x;", vec![]).unwrap();
+    assert_eq!(editor.borrow_code().word_boundaries(5), (5, 7));
+    editor.set_language_config(LanguageConfig {
+        word_chars: vec!['$'],
+        ..LanguageConfig::default()
+    });
+    assert!(editor.borrow_code().is_word_char('$'));
+    assert_eq!(editor.borrow_code().word_boundaries(5), (4, 7));
+}
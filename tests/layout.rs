@@ -0,0 +1,66 @@
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::gutter::GutterColumn;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+#[test]
+fn layout_matches_rendered_text() {
+    let mut editor = Editor::new("rust", "fn main() {\n    x\n}", vec![]).unwrap();
+    editor.add_gutter_column(GutterColumn::new("marks", 2));
+    editor.set_code_folding_enabled(true);
+    editor.show_scrollbar(true);
+    let area = Rect::new(3, 1, 40, 5);
+    let layout = editor.layout(area);
+
+    assert_eq!(layout.columns, Rect::new(3, 1, 2, 5));
+    assert_eq!(layout.line_numbers.x, layout.columns.right());
+    assert_eq!(layout.fold_gutter.x, layout.line_numbers.right());
+    assert!(layout.fold_gutter.width > 0);
+    assert_eq!(layout.text_area.x, layout.fold_gutter.right());
+    assert_eq!(layout.gutter_width, layout.text_area.x - area.x);
+    assert_eq!(layout.scrollbar, Some(Rect::new(42, 1, 1, 5)));
+    assert_eq!(layout.text_area.right(), 42);
+
+    let mut buf = Buffer::empty(area);
+    editor.render(area, &mut buf);
+    assert_eq!(buf[(layout.text_area.x, 1)].symbol(), "f");
+    assert_eq!(
+        editor.doc_to_screen(0, &area),
+        Some((layout.text_area.x, 1))
+    );
+
+    // A narrow area cuts the gutter off and leaves no room for text
+    let layout = editor.layout(Rect::new(0, 0, 4, 1));
+    assert_eq!(layout.columns.width, 2);
+    assert_eq!(layout.line_numbers.width, 2);
+    assert_eq!(layout.fold_gutter.width, 0);
+    assert_eq!(layout.text_area.width, 0);
+}
+
+#[test]
+fn narrow_line_numbers() {
+    let mut editor = Editor::new("text", "a\nb\nc", vec![]).unwrap();
+    editor.set_code_folding_enabled(false);
+    let area = Rect::new(0, 0, 20, 3);
+    assert_eq!(editor.layout(area).gutter_width, 7);
+
+    editor.set_line_number_min_digits(1);
+    editor.set_left_code_padding(1);
+    let layout = editor.layout(area);
+    assert_eq!(layout.line_numbers, Rect::new(0, 0, 2, 3));
+    assert_eq!(layout.text_area.x, 2);
+    let mut buf = Buffer::empty(area);
+    editor.render(area, &mut buf);
+    assert_eq!(buf[(0, 2)].symbol(), "3");
+    assert_eq!(buf[(2, 2)].symbol(), "c");
+    assert_eq!(editor.screen_to_doc(2, 1, &area), Some(2));
+
+    // Numbers still get the digits they need
+    editor.set_content(&"x\n".repeat(12));
+    assert_eq!(editor.layout(area).gutter_width, 3);
+
+    editor.show_line_numbers(false);
+    editor.set_left_code_padding(0);
+    assert_eq!(editor.layout(area).text_area, area);
+}
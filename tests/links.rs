@@ -0,0 +1,80 @@
+mod common;
+
+use common::bare_editor;
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::events::EditorEvent;
+use ratatui_core::buffer::{Buffer, CellDiffOption};
+use ratatui_core::layout::Rect;
+use ratatui_core::style::Modifier;
+use ratatui_core::widgets::Widget;
+
+fn editor() -> Editor {
+    bare_editor("text", "docs: https://x.io/a.\nsee ./README.md")
+}
+
+#[test]
+fn links_are_found_and_underlined() {
+    let editor = editor();
+    assert_eq!(
+        editor.line_links(0),
+        vec![(6, 20, "https://x.io/a".to_string())]
+    );
+    assert_eq!(editor.link_at(26).as_deref(), Some("./README.md"));
+    assert_eq!(editor.link_at(3), None);
+
+    let area = Rect::new(0, 0, 30, 2);
+    let mut buf = Buffer::empty(area);
+    (&editor).render(area, &mut buf);
+    assert!(buf[(6, 0)].modifier.contains(Modifier::UNDERLINED));
+    assert!(!buf[(20, 0)].modifier.contains(Modifier::UNDERLINED));
+    assert!(!buf[(0, 0)].modifier.contains(Modifier::UNDERLINED));
+}
+
+#[test]
+fn ctrl_click_on_a_link_opens_it() {
+    let mut editor = editor();
+    editor.drain_events();
+    let area = Rect::new(0, 0, 30, 2);
+    let click = |column, row| MouseEvent {
+        kind: MouseEventKind::Down(MouseButton::Left),
+        column,
+        row,
+        modifiers: KeyModifiers::CONTROL,
+    };
+    editor.mouse(click(8, 1), &area).unwrap();
+    assert_eq!(
+        editor.drain_events(),
+        vec![EditorEvent::OpenLink("./README.md".to_string())]
+    );
+
+    // outside of links Ctrl+Click still requests the definition
+    editor.mouse(click(1, 0), &area).unwrap();
+    assert!(matches!(
+        editor.drain_events()[..],
+        [EditorEvent::DefinitionRequested { offset: 1, .. }]
+    ));
+
+    editor.set_link_detection(false);
+    editor.mouse(click(8, 1), &area).unwrap();
+    assert!(matches!(
+        editor.drain_events()[..],
+        [EditorEvent::DefinitionRequested { .. }]
+    ));
+}
+
+#[test]
+fn terminal_hyperlinks_wrap_the_link_cells() {
+    let mut editor = editor();
+    editor.set_terminal_hyperlinks(true);
+    let area = Rect::new(0, 0, 30, 2);
+    let mut buf = Buffer::empty(area);
+    (&editor).render(area, &mut buf);
+    assert_eq!(buf[(6, 0)].symbol(), "\x1b]8;;https://x.io/a\x1b\\h");
+    assert_eq!(buf[(19, 0)].symbol(), "a\x1b]8;;\x1b\\");
+    assert_eq!(buf[(7, 0)].symbol(), "t");
+    assert!(matches!(
+        buf[(6, 0)].diff_option,
+        CellDiffOption::ForcedWidth(width) if width.get() == 1
+    ));
+}
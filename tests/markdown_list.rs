@@ -0,0 +1,50 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+
+fn at_end(content: &str) -> Editor {
+    let mut editor = Editor::new("markdown", content, vec![]).unwrap();
+    editor.set_cursor(content.chars().count());
+    editor
+}
+
+#[test]
+fn enter_continues_and_ends_lists() {
+    let mut editor = at_end("- [x] done");
+    editor.execute(Command::InsertNewline);
+    editor.execute(Command::InsertText {
+        text: "next".into(),
+    });
+    assert_eq!(editor.get_content(), "- [x] done\n- [ ] next");
+
+    let mut editor = at_end("  9. nine");
+    editor.execute(Command::InsertNewline);
+    assert_eq!(editor.get_content(), "  9. nine\n  10. ");
+
+    // Enter on an empty item removes it and ends the list
+    editor.execute(Command::InsertNewline);
+    assert_eq!(editor.get_content(), "  9. nine\n");
+    assert_eq!(editor.get_cursor(), 10);
+
+    // Tab nests the item
+    let mut editor = at_end("- a\n- b");
+    editor.execute(Command::Indent);
+    assert_eq!(editor.get_content(), "- a\n  - b");
+
+    let mut editor = at_end("- a");
+    editor.set_list_continuation(false);
+    editor.execute(Command::InsertNewline);
+    assert_eq!(editor.get_content(), "- a\n");
+}
+
+#[test]
+fn renumber_ordered_list() {
+    let content = "intro\n3. a\n   text\n1. b\n   1. nested\n7. c\n\n1. other";
+    let mut editor = Editor::new("markdown", content, vec![]).unwrap();
+    editor.set_cursor(content.find("b").unwrap());
+    editor.execute(Command::RenumberList);
+    assert_eq!(
+        editor.get_content(),
+        "intro\n3. a\n   text\n4. b\n   1. nested\n5. c\n\n1. other"
+    );
+    assert_eq!(editor.get_cursor(), content.find("b").unwrap());
+}
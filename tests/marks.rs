@@ -0,0 +1,143 @@
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::style::{Color, Modifier, Style};
+
+fn bg(r: u8, g: u8, b: u8) -> Style {
+    Style::default().bg(Color::Rgb(r, g, b))
+}
+
+#[test]
+fn marks_shift_with_edits() {
+    let mut editor = Editor::new("text", "hello world", vec![]).unwrap();
+    editor.set_marks(vec![(6, 11, "#ff0000")]);
+
+    editor.set_cursor(0);
+    editor.apply(InsertText { text: ">> ".into() });
+    assert_eq!(
+        editor.marks().unwrap(),
+        vec![(9, 14, Color::Rgb(255, 0, 0))]
+    );
+
+    // Typing right at the mark boundaries stays outside the mark
+    editor.set_cursor(9);
+    editor.apply(InsertText {
+        text: "big ".into(),
+    });
+    editor.set_cursor(18);
+    editor.apply(InsertText { text: "!".into() });
+    assert_eq!(editor.get_content_slice(13, 18), "world");
+    assert_eq!(
+        editor.marks().unwrap(),
+        vec![(13, 18, Color::Rgb(255, 0, 0))]
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn get_marks_keeps_the_ranges_as_set() {
+    let mut editor = Editor::new("text", "hello world", vec![]).unwrap();
+    assert_eq!(editor.get_marks(), None);
+    editor.set_marks(vec![(6, 11, "#ff0000")]);
+    editor.update_marks(vec![(0, 5, "#00ff00")]);
+
+    editor.set_cursor(0);
+    editor.apply(InsertText { text: ">> ".into() });
+    assert_eq!(
+        editor.get_marks(),
+        Some(&vec![
+            (6, 11, Color::Rgb(255, 0, 0)),
+            (0, 5, Color::Rgb(0, 255, 0))
+        ])
+    );
+    assert_eq!(editor.marks().unwrap()[0], (9, 14, Color::Rgb(255, 0, 0)));
+
+    editor.remove_marks();
+    assert_eq!(editor.get_marks(), None);
+}
+
+#[test]
+fn update_marks_and_marks_at() {
+    let mut editor = Editor::new("text", "0123456789", vec![]).unwrap();
+    editor.set_marks(vec![(0, 4, "#ff0000"), (2, 6, "#00ff00")]);
+    editor.update_marks(vec![(0, 4, "#0000ff"), (8, 9, "#ffffff")]);
+
+    let marks = editor.marks().unwrap();
+    assert_eq!(marks.len(), 3);
+    assert_eq!(editor.mark_styles().unwrap()[0], (2, 6, bg(0, 255, 0)));
+    assert_eq!(
+        editor.marks_at(3),
+        vec![(2, 6, Color::Rgb(0, 255, 0)), (0, 4, Color::Rgb(0, 0, 255))]
+    );
+    assert_eq!(editor.marks_at(7), vec![]);
+
+    editor.replace_range(1..9, "").unwrap();
+    assert_eq!(editor.marks_at(0), vec![(0, 1, Color::Rgb(0, 0, 255))]);
+}
+
+#[test]
+fn mark_layers_are_independent() {
+    let mut editor = Editor::new("text", "0123456789", vec![]).unwrap();
+    editor.set_marks(vec![(0, 2, "#ffffff")]);
+    editor.set_mark_layer("search", vec![(1, 3), (5, 6)], bg(255, 0, 0));
+    editor.set_mark_layer("lint", vec![(2, 4)], bg(0, 255, 0));
+    assert_eq!(editor.mark_layers(), vec!["marks", "search", "lint"]);
+
+    editor.remove_mark_layer("search");
+    assert_eq!(editor.get_mark_layer("search"), None);
+    assert_eq!(
+        editor.get_mark_layer("lint").unwrap(),
+        vec![(2, 4, bg(0, 255, 0))]
+    );
+    assert!(editor.has_marks());
+
+    editor.clear_mark_layers();
+    assert!(editor.mark_layers().is_empty());
+    assert!(!editor.has_marks());
+}
+
+#[test]
+fn higher_priority_layers_paint_last() {
+    let mut editor = Editor::new("text", "0123456789", vec![]).unwrap();
+    editor.set_mark_layer("search", vec![(0, 4)], bg(255, 0, 0));
+    editor.set_mark_layer("lint", vec![(2, 6)], bg(0, 255, 0));
+    editor.set_mark_layer_priority("search", 10);
+    assert_eq!(editor.mark_layers(), vec!["lint", "search"]);
+    assert_eq!(
+        editor.mark_styles_at(3),
+        vec![(2, 6, bg(0, 255, 0)), (0, 4, bg(255, 0, 0))]
+    );
+
+    let spans = editor.render_line_spans(0, 10);
+    let at = |col: usize| {
+        let mut x = 0;
+        spans
+            .iter()
+            .find(|span| {
+                x += span.content.chars().count();
+                x > col
+            })
+            .unwrap()
+            .style
+    };
+    assert_eq!(at(3).bg, Some(Color::Rgb(255, 0, 0)));
+    assert_eq!(at(5).bg, Some(Color::Rgb(0, 255, 0)));
+}
+
+#[test]
+fn underline_marks_keep_syntax_colors() {
+    let keyword = Color::Rgb(1, 2, 3);
+    let theme = vec![("keyword", "#010203")];
+    let mut editor = Editor::new("rust", "let x = 1;", theme).unwrap();
+    let underline = Style::default().add_modifier(Modifier::UNDERLINED);
+    editor.set_mark_layer("spell", vec![(0, 3)], underline);
+    editor.set_cursor(10);
+    assert_eq!(editor.mark_styles_at(1), vec![(0, 3, underline)]);
+    assert_eq!(editor.marks_at(1), vec![(0, 3, Color::Reset)]);
+
+    let spans = editor.render_line_spans(0, 20);
+    assert_eq!(spans[0].content, "let");
+    assert_eq!(spans[0].style.fg, Some(keyword));
+    assert_eq!(spans[0].style.bg, spans[1].style.bg);
+    assert!(spans[0].style.add_modifier.contains(Modifier::UNDERLINED));
+    assert!(!spans[1].style.add_modifier.contains(Modifier::UNDERLINED));
+}
@@ -0,0 +1,58 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn paragraph_movement_stops_at_blank_lines() {
+    let text = "one\ntwo\n\nthree\n\n\nfour";
+    let mut editor = Editor::new("text", text, vec![]).unwrap();
+    let mut stops = vec![];
+    for _ in 0..4 {
+        editor.execute(Command::MoveParagraphDown { shift: false });
+        stops.push(editor.get_cursor());
+    }
+    assert_eq!(stops, vec![8, 15, 21, 21]);
+
+    let mut stops = vec![];
+    for _ in 0..4 {
+        editor.execute(Command::MoveParagraphUp { shift: false });
+        stops.push(editor.get_cursor());
+    }
+    assert_eq!(stops, vec![16, 8, 0, 0]);
+
+    editor.execute(Command::MoveParagraphDown { shift: true });
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 8)));
+}
+
+#[test]
+fn block_movement_follows_the_syntax_tree() {
+    let text = "fn main() {\n    if x {\n        a();\n        b();\n    }\n}\n";
+    let mut editor = Editor::new("rust", text, vec![]).unwrap();
+    let a = text.find("a()").unwrap();
+    editor.set_cursor(a + 1);
+
+    editor.execute(Command::MoveBlockStart { shift: false });
+    assert_eq!(editor.get_cursor(), a);
+    editor.execute(Command::MoveBlockEnd { shift: false });
+    assert_eq!(editor.get_cursor(), text.find("b();").unwrap() + 4);
+
+    // at the end already, the enclosing block is next
+    editor.execute(Command::MoveBlockEnd { shift: false });
+    assert_eq!(editor.get_cursor(), text.rfind("}\n}").unwrap() + 1);
+    editor.execute(Command::MoveBlockStart { shift: true });
+    assert_eq!(editor.get_cursor(), text.find("if").unwrap());
+    assert!(editor.get_selection().is_some());
+}
+
+#[test]
+fn block_movement_uses_indentation_without_a_syntax_tree() {
+    let text = "root\n  child\n    leaf\n    leaf2\n  child2\nend";
+    let mut editor = Editor::new("text", text, vec![]).unwrap();
+    editor.set_cursor(text.find("leaf2").unwrap() + 2);
+    editor.execute(Command::MoveBlockStart { shift: false });
+    assert_eq!(editor.get_cursor(), text.find("leaf").unwrap());
+    editor.execute(Command::MoveBlockStart { shift: false });
+    assert_eq!(editor.get_cursor(), text.find("child").unwrap());
+    editor.execute(Command::MoveBlockEnd { shift: false });
+    assert_eq!(editor.get_cursor(), text.find("\nend").unwrap());
+}
@@ -0,0 +1,112 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn select_next_occurrence_adds_selections() {
+    let mut editor = Editor::new("text", "foo bar foo\nfoo", vec![]).unwrap();
+    editor.set_cursor(1);
+
+    // First press selects the word under the cursor
+    editor.execute(Command::SelectNextOccurrence);
+    assert_eq!(editor.selections(), [Selection::new(0, 3)]);
+
+    editor.execute(Command::SelectNextOccurrence);
+    editor.execute(Command::SelectNextOccurrence);
+    assert_eq!(
+        editor.selections(),
+        [
+            Selection::new(12, 15),
+            Selection::new(0, 3),
+            Selection::new(8, 11)
+        ]
+    );
+
+    // Nothing left to add
+    editor.execute(Command::SelectNextOccurrence);
+    assert_eq!(editor.selections().len(), 3);
+
+    editor.execute(Command::InsertText { text: "x".into() });
+    assert_eq!(editor.get_content(), "x bar x\nx");
+    editor.execute(Command::InsertText { text: "y".into() });
+    editor.execute(Command::Delete);
+    editor.execute(Command::Delete);
+    assert_eq!(editor.get_content(), " bar \n");
+    assert_eq!(editor.get_cursor(), 6);
+
+    editor.execute(Command::Undo);
+    assert!(!editor.has_secondary_selections());
+    assert_eq!(editor.get_content(), "x bar x\nx");
+}
+
+#[test]
+fn select_all_occurrences() {
+    let mut editor = Editor::new("text", "a1 a1 b a1", vec![]).unwrap();
+    editor.set_selection(Some(Selection::new(3, 5)));
+    editor.set_cursor(5);
+    editor.execute(Command::SelectAllOccurrences);
+    assert_eq!(
+        editor.selections(),
+        [
+            Selection::new(3, 5),
+            Selection::new(0, 2),
+            Selection::new(8, 10)
+        ]
+    );
+
+    editor.execute(Command::InsertNewline);
+    assert_eq!(editor.get_content(), "\n \n b \n");
+
+    // Moving drops the secondary selections
+    editor.execute(Command::MoveLeft { shift: false });
+    assert_eq!(editor.selections().len(), 1);
+}
+
+#[test]
+fn transform_selection_replaces_selections_and_words_in_one_step() {
+    let mut editor = Editor::new("text", "foo bar foo\nbaz", vec![]).unwrap();
+    editor.set_cursor(13);
+    editor.transform_selection(|text| text.to_uppercase());
+    assert_eq!(editor.get_content(), "foo bar foo\nBAZ");
+
+    editor.set_cursor(1);
+    editor.execute(Command::SelectNextOccurrence);
+    editor.execute(Command::SelectNextOccurrence);
+    let mut calls = 0;
+    editor.transform_selection(|text| {
+        calls += 1;
+        format!("<{text}>")
+    });
+    assert_eq!(calls, 2);
+    assert_eq!(editor.get_content(), "<foo> bar <foo>\nBAZ");
+
+    editor.execute(Command::Undo);
+    assert_eq!(editor.get_content(), "foo bar foo\nBAZ");
+
+    // a cursor off any word is left alone
+    let mut editor = Editor::new("text", "a  b", vec![]).unwrap();
+    editor.set_cursor(2);
+    editor.transform_selection(|_| unreachable!());
+    assert_eq!(editor.get_content(), "a  b");
+}
+
+#[test]
+fn newline_indents_each_cursor_line_and_click_collapses_cursors() {
+    let source = "def f():\n        a = 1\n    b = 2\n";
+    let mut editor = Editor::new("python", source, vec![]).unwrap();
+    let a = source.find("1").unwrap() + 1;
+    let b = source.find("2").unwrap() + 1;
+    editor.set_cursor(a);
+    editor.set_secondary_selections(vec![Selection::new(b, b)]);
+    editor.execute(Command::InsertNewline);
+    assert_eq!(
+        editor.get_content(),
+        "def f():\n        a = 1\n        \n    b = 2\n    \n"
+    );
+
+    editor.set_secondary_selections(vec![Selection::new(0, 0)]);
+    editor.handle_mouse_down(4);
+    assert!(!editor.has_secondary_selections());
+    editor.execute(Command::InsertText { text: "x".into() });
+    assert!(editor.get_content().starts_with("def xf():\n"));
+}
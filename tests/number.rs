@@ -0,0 +1,39 @@
+use ratatui_code_editor::actions::{DecrementNumber, IncrementNumber, Undo};
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+
+#[test]
+fn increment_number_after_cursor() {
+    let source = "let port = 8080;\nlet x = 1;";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.apply(IncrementNumber { count: 1 });
+    assert_eq!(editor.get_content(), "let port = 8081;\nlet x = 1;");
+    // The cursor ends on the last digit
+    assert_eq!(editor.get_cursor(), 14);
+
+    editor.apply(DecrementNumber { count: 100 });
+    assert_eq!(editor.get_content(), "let port = 7981;\nlet x = 1;");
+
+    editor.apply(Undo);
+    assert_eq!(editor.get_content(), "let port = 8081;\nlet x = 1;");
+}
+
+#[test]
+fn number_step_and_commands() {
+    let source = "color = 0x00ff00 # 1.25";
+    let mut editor = Editor::new("python", source, vec![]).unwrap();
+    editor.set_number_step(16);
+    editor.set_cursor(8);
+    editor.execute(Command::IncrementNumber { count: 2 });
+    assert_eq!(editor.get_content(), "color = 0x00ff20 # 1.25");
+
+    editor.set_number_step(1);
+    editor.set_cursor(17);
+    editor.execute(Command::DecrementNumber { count: 3 });
+    assert_eq!(editor.get_content(), "color = 0x00ff20 # -1.75");
+
+    // No number on the rest of the line
+    editor.set_cursor(editor.get_content().chars().count());
+    editor.execute(Command::IncrementNumber { count: 1 });
+    assert_eq!(editor.get_content(), "color = 0x00ff20 # -1.75");
+}
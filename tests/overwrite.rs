@@ -0,0 +1,35 @@
+use ratatui_code_editor::actions::Undo;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::testing::TestHarness;
+use ratatui_code_editor::types::CursorStyle;
+
+#[test]
+fn insert_key_toggles_overwriting_typed_characters() {
+    let editor = Editor::new("text", "12345\nab", vec![]).unwrap();
+    let mut harness = TestHarness::new(editor, 20, 3);
+    harness.keys("<Ins>xy");
+    assert!(harness.editor().is_overwrite());
+    assert_eq!(harness.editor().cursor_style(), CursorStyle::Underline);
+    harness.assert_lines(&["xy345", "ab"]);
+
+    // the line break is never overwritten
+    harness.keys("<Right><Right>zzz");
+    harness.assert_lines(&["xy34zzz", "ab"]);
+    harness.assert_cursor(0, 7);
+
+    harness.keys("<Ins><Down>c");
+    assert!(!harness.editor().is_overwrite());
+    harness.assert_lines(&["xy34zzz", "abc"]);
+}
+
+#[test]
+fn each_overwritten_character_is_one_undo_step() {
+    let mut editor = Editor::new("text", "abc", vec![]).unwrap();
+    editor.set_overwrite(true);
+    let mut harness = TestHarness::new(editor, 20, 3);
+    harness.keys("XY");
+    harness.assert_lines(&["XYc"]);
+    harness.editor_mut().apply(Undo);
+    harness.assert_lines(&["Xbc"]);
+    harness.assert_cursor(0, 1);
+}
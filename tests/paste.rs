@@ -0,0 +1,68 @@
+use ratatui_code_editor::actions::{Paste, PasteLiteral};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::types::PasteMode;
+
+const SNIPPET: &str = "if a:\n        b()\n";
+
+fn python_editor() -> Editor {
+    let mut editor = Editor::new("python", "", vec![]).unwrap();
+    editor.set_clipboard(SNIPPET).unwrap();
+    editor
+}
+
+#[test]
+fn paste_mode_controls_reindenting() {
+    let mut editor = python_editor();
+    assert_eq!(editor.paste_mode(), PasteMode::Smart);
+    editor.apply(Paste {});
+    assert_ne!(editor.get_content(), SNIPPET);
+
+    let mut editor = python_editor();
+    editor.set_paste_mode(PasteMode::Literal);
+    editor.apply(Paste {});
+    assert_eq!(editor.get_content(), SNIPPET);
+    assert_eq!(editor.get_cursor(), SNIPPET.len());
+}
+
+#[test]
+fn paste_literal_ignores_smart_mode() {
+    let mut editor = python_editor();
+    editor.apply(PasteLiteral {});
+    assert_eq!(editor.get_content(), SNIPPET);
+}
+
+#[test]
+fn line_wise_copy_cut_and_paste() {
+    use ratatui_code_editor::actions::{Copy, Cut};
+
+    let mut editor = Editor::new("text", "one\ntwo\nthree", vec![]).unwrap();
+    editor.set_cursor(1);
+
+    // Without the flag, copy does nothing and paste is not line-wise
+    let mut plain = Editor::new("text", "x", vec![]).unwrap();
+    plain.apply(Copy {});
+    plain.apply(Cut {});
+    assert_eq!(plain.get_content(), "x");
+
+    editor.set_line_wise_copy(true);
+    editor.apply(Copy {});
+    assert_eq!(editor.get_clipboard().unwrap(), "one\n");
+
+    // Pasted above the cursor line, the cursor stays on its text
+    editor.set_cursor(5);
+    editor.apply(Paste {});
+    assert_eq!(editor.get_content(), "one\none\ntwo\nthree");
+    assert_eq!(editor.get_cursor(), 9);
+
+    // Cutting the last line takes the line break before it
+    editor.set_cursor(editor.get_content().len());
+    editor.apply(Cut {});
+    assert_eq!(editor.get_content(), "one\none\ntwo");
+    assert_eq!(editor.get_clipboard().unwrap(), "three\n");
+    assert_eq!(editor.get_cursor(), 8);
+
+    editor.set_cursor(0);
+    editor.apply(Cut {});
+    assert_eq!(editor.get_content(), "one\ntwo");
+    assert_eq!(editor.get_cursor(), 0);
+}
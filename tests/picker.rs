@@ -0,0 +1,67 @@
+mod common;
+
+use common::row;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::picker::PickerSource;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+const SOURCE: &str = "struct Config {\n    path: String,\n}\n\nfn load_config() {}\n\nfn main() {\n    load_config();\n}\n";
+
+fn key(code: KeyCode) -> KeyEvent {
+    KeyEvent::new(code, KeyModifiers::NONE)
+}
+
+#[test]
+fn symbol_picker_filters_and_jumps() {
+    let mut editor = Editor::new("rust", SOURCE, vec![]).unwrap();
+    editor.open_picker(PickerSource::Symbols);
+
+    let names: Vec<_> = editor
+        .picker()
+        .unwrap()
+        .matches()
+        .map(|item| item.label.clone())
+        .collect();
+    assert_eq!(names, ["Config", "load_config", "main"]);
+
+    let area = Rect::new(0, 0, 40, 12);
+    for c in "ldcf".chars() {
+        editor.input(key(KeyCode::Char(c)), &area).unwrap();
+    }
+    assert_eq!(editor.get_content(), SOURCE);
+    let picker = editor.picker().unwrap();
+    assert_eq!(picker.query(), "ldcf");
+    assert_eq!(picker.selected_item().unwrap().label, "load_config");
+
+    let mut buf = Buffer::empty(area);
+    editor.render(area, &mut buf);
+    let row = row(&buf, 2);
+    assert!(row.contains("> ldcf"), "{row:?}");
+
+    editor.input(key(KeyCode::Enter), &area).unwrap();
+    assert!(editor.picker().is_none());
+    assert_eq!(editor.get_cursor(), SOURCE.find("load_config").unwrap());
+}
+
+#[test]
+fn line_picker_esc_closes_without_moving() {
+    let mut editor = Editor::new("text", "alpha\n\n  beta\ngamma", vec![]).unwrap();
+    editor.open_picker(PickerSource::Lines);
+    let picker = editor.picker_mut().unwrap();
+    assert_eq!(picker.matches().count(), 3);
+    picker.set_query("bt");
+    assert_eq!(picker.selected_item().unwrap().label, "3: beta");
+
+    let area = Rect::new(0, 0, 40, 10);
+    editor.input(key(KeyCode::Esc), &area).unwrap();
+    assert!(editor.picker().is_none());
+    assert_eq!(editor.get_cursor(), 0);
+
+    editor.open_picker(PickerSource::Lines);
+    editor.picker_mut().unwrap().set_query("bt");
+    assert!(editor.accept_picker());
+    assert_eq!(editor.get_cursor(), 9);
+}
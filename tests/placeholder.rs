@@ -0,0 +1,38 @@
+mod common;
+
+use common::bare_editor;
+use ratatui_code_editor::testing::TestHarness;
+use ratatui_core::style::Color;
+
+fn harness() -> TestHarness {
+    let mut editor = bare_editor("text", "");
+    editor.set_placeholder("Type your query…");
+    TestHarness::new(editor, 20, 2)
+}
+
+#[test]
+fn placeholder_is_shown_while_the_buffer_is_empty() {
+    let mut harness = harness();
+    harness.assert_screen(&["Type your query…", ""]);
+    assert_eq!(harness.render()[(0, 0)].fg, Color::DarkGray);
+    assert_eq!(harness.screen_cursor(), Some((0, 0)));
+
+    harness.keys("ab");
+    harness.assert_screen(&["ab", ""]);
+
+    harness.keys("<BS><BS>");
+    harness.assert_screen(&["Type your query…", ""]);
+
+    harness.editor_mut().set_placeholder("");
+    assert_eq!(harness.editor().placeholder(), None);
+    harness.assert_screen(&["", ""]);
+}
+
+#[test]
+fn placeholder_is_cut_to_the_text_width() {
+    let mut harness = harness();
+    harness
+        .editor_mut()
+        .set_placeholder("a placeholder longer than the editor");
+    harness.assert_screen(&["a placeholder longer", ""]);
+}
@@ -0,0 +1,62 @@
+use ratatui_code_editor::actions::{InsertNewline, InsertText, ToggleComment, Undo};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::language::LanguageConfig;
+use ratatui_code_editor::workspace::Workspace;
+use std::collections::HashMap;
+
+#[test]
+fn unknown_languages_open_as_plain_text() {
+    let mut editor = Editor::new("klingon", "qapla'", vec![]).unwrap();
+    editor.set_cursor(6);
+    editor.apply(InsertNewline);
+    editor.apply(InsertText {
+        text: "batlh".to_string(),
+    });
+    assert_eq!(editor.get_content(), "qapla'\nbatlh");
+    // No grammar means no comment prefix either
+    editor.apply(ToggleComment);
+    assert_eq!(editor.get_content(), "qapla'\nbatlh");
+    assert_eq!(
+        LanguageConfig::builtin("klingon"),
+        LanguageConfig::plain_text()
+    );
+    assert_eq!(LanguageConfig::builtin("lua").comment, "--");
+
+    let mut editor = Editor::new("text", "  notes", vec![]).unwrap();
+    editor.set_cursor(7);
+    editor.apply(InsertNewline);
+    assert_eq!(editor.get_content(), "  notes\n  ");
+
+    // There is no comment prefix to toggle
+    editor.apply(ToggleComment);
+    assert_eq!(editor.get_content(), "  notes\n  ");
+    editor.apply(Undo);
+    assert_eq!(editor.get_content(), "  notes");
+}
+
+#[test]
+fn invalid_custom_queries_are_errors() {
+    let highlights = HashMap::from([("rust".to_string(), "(no_such_node) @x".to_string())]);
+    let err = Editor::new_with_highlights("rust", "fn main() {}", vec![], Some(highlights))
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .contains("invalid highlights query for rust")
+    );
+    assert!(err.to_string().contains("no_such_node"));
+
+    let queries = HashMap::from([("rust.locals".to_string(), "(".to_string())]);
+    let err = Editor::new_with_highlights("rust", "", vec![], Some(queries.clone()))
+        .err()
+        .unwrap();
+    assert!(
+        err.to_string()
+            .starts_with("invalid custom locals query for rust")
+    );
+
+    let mut workspace = Workspace::new(vec![]);
+    workspace.set_custom_highlights(Some(queries));
+    assert!(workspace.open("rust", "").is_err());
+    assert!(workspace.open("text", "plain").is_ok());
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use common::row;
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::style::Modifier;
+use ratatui_core::widgets::Widget;
+
+#[test]
+fn preedit_is_drawn_inline_and_committed_as_one_edit() {
+    let mut editor = Editor::new("text", "ab", vec![]).unwrap();
+    editor.show_line_numbers(false);
+    editor.set_cursor(1);
+    editor.set_preedit("にほ", 1);
+
+    let area = Rect::new(0, 0, 20, 2);
+    let mut buf = Buffer::empty(area);
+    editor.render(area, &mut buf);
+    assert_eq!(row(&buf, 0).replace(' ', ""), "aにほb");
+    let x = (0..area.width)
+        .find(|&x| buf[(x, 0)].symbol() == "a")
+        .unwrap();
+    assert!(buf[(x + 1, 0)].modifier.contains(Modifier::UNDERLINED));
+    assert!(!buf[(x + 5, 0)].modifier.contains(Modifier::UNDERLINED));
+    // the terminal cursor sits after the first wide char of the composition
+    assert_eq!(editor.get_visible_cursor(&area), Some((x + 3, 0)));
+    assert_eq!(editor.get_content(), "ab");
+
+    editor.set_preedit("日本", 2);
+    assert!(editor.commit_preedit());
+    assert!(editor.preedit().is_none());
+    assert_eq!(editor.get_content(), "a日本b");
+    assert_eq!(editor.get_cursor(), 3);
+
+    editor.execute(Command::Undo);
+    assert_eq!(editor.get_content(), "ab");
+}
+
+#[test]
+fn preedit_follows_edits_and_cancels_cleanly() {
+    let mut editor = Editor::new("text", "ab", vec![]).unwrap();
+    editor.set_cursor(1);
+    editor.set_preedit("x", 1);
+
+    // text inserted before the composition shifts it
+    let mut other = Editor::with_shared_code(editor.shared_code(), vec![]);
+    other.set_cursor(0);
+    other.apply(InsertText {
+        text: "12".to_string(),
+    });
+    assert_eq!(editor.preedit().unwrap().position(), 3);
+
+    editor.cancel_preedit();
+    assert!(editor.preedit().is_none());
+    assert!(!editor.commit_preedit());
+    assert_eq!(editor.get_content(), "12ab");
+
+    // an empty composition cancels too
+    editor.set_preedit("y", 0);
+    editor.set_preedit("", 0);
+    assert!(editor.preedit().is_none());
+}
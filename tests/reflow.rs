@@ -0,0 +1,30 @@
+use ratatui_code_editor::actions::Reflow;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn reflow_comment_block_keeps_prefix() {
+    let source = "fn f() {}\n    /// one two three four five six\n    /// seven\n    ///\n    /// eight nine\nfn g() {}";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_cursor(source.find("four").unwrap());
+    // 12 columns remain after the prefix; the empty comment line ends the block
+    editor.apply(Reflow { width: 20 });
+    assert_eq!(
+        editor.get_content(),
+        "fn f() {}\n    /// one two\n    /// three four\n    /// five six\n    /// seven\n    ///\n    /// eight nine\nfn g() {}"
+    );
+}
+
+#[test]
+fn reflow_selected_lines() {
+    let source = "# a b c\n# d e f g h\nx = 1";
+    let mut editor = Editor::new("python", source, vec![]).unwrap();
+    editor.set_selection(Some(Selection::new(0, 20)));
+    editor.apply(Reflow { width: 9 });
+    assert_eq!(editor.get_content(), "# a b c d\n# e f g h\nx = 1");
+
+    // Plain paragraphs keep their indentation
+    let mut editor = Editor::new("text", "  aaa bbb\n  ccc\n\nddd", vec![]).unwrap();
+    editor.apply(Reflow { width: 80 });
+    assert_eq!(editor.get_content(), "  aaa bbb ccc\n\nddd");
+}
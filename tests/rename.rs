@@ -0,0 +1,42 @@
+use ratatui_code_editor::actions::RenameInBuffer;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+const SOURCE: &str = "fn f(count: u32) -> u32 {\n    // count things\n    let counter = count + 1;\n    count * counter\n}";
+
+fn cursor_on_count() -> Editor {
+    let mut editor = Editor::new("rust", SOURCE, vec![]).unwrap();
+    editor.set_cursor(SOURCE.find("count +").unwrap() + 2);
+    editor
+}
+
+#[test]
+fn rename_replaces_only_matching_tokens() {
+    let mut editor = cursor_on_count();
+    editor.apply(RenameInBuffer {
+        replacement: Some("n".into()),
+    });
+    assert_eq!(
+        editor.get_content(),
+        "fn f(n: u32) -> u32 {\n    // count things\n    let counter = n + 1;\n    n * counter\n}"
+    );
+    let cursor = editor.get_cursor();
+    assert_eq!(&editor.get_content()[cursor - 1..cursor + 3], "n + ");
+}
+
+#[test]
+fn rename_without_replacement_selects_occurrences() {
+    let mut editor = cursor_on_count();
+    editor.apply(RenameInBuffer { replacement: None });
+    let selections = editor.selections();
+    let start = SOURCE.find("count +").unwrap();
+    assert_eq!(selections[0], Selection::new(start, start + 5));
+    assert_eq!(selections.len(), 3);
+
+    let mut plain = Editor::new("text", "ab abc ab", vec![]).unwrap();
+    plain.apply(RenameInBuffer { replacement: None });
+    assert_eq!(
+        plain.selections(),
+        [Selection::new(0, 2), Selection::new(7, 9)]
+    );
+}
@@ -0,0 +1,38 @@
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::flash::FLASH_MARK_LAYER;
+use ratatui_core::layout::Rect;
+use ratatui_core::style::Color;
+use std::time::{Duration, Instant};
+
+#[test]
+fn reveal_scrolls_and_flashes_until_expired() {
+    let area = Rect::new(0, 0, 40, 10);
+    let content = (0..100)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut editor = Editor::new("text", &content, vec![]).unwrap();
+    editor.set_flash_duration(Duration::from_millis(400));
+
+    let start = content.find("line 50").unwrap();
+    let now = Instant::now();
+    editor.reveal_at(start..start + 7, &area, now);
+    assert_eq!(editor.get_offset_y(), 45);
+    assert_eq!(editor.get_cursor(), 0);
+    let marks = editor.get_mark_layer(FLASH_MARK_LAYER).unwrap();
+    assert_eq!((marks[0].0, marks[0].1), (start, start + 7));
+    assert_eq!(marks[0].2.bg, Some(Color::Rgb(229, 192, 123)));
+
+    // Already visible ranges do not scroll
+    editor.reveal_at(start + 8..start + 14, &area, now);
+    assert_eq!(editor.get_offset_y(), 45);
+
+    editor.tick(now + Duration::from_millis(300));
+    assert!(editor.needs_redraw());
+    let faded = editor.get_mark_layer(FLASH_MARK_LAYER).unwrap()[0].2.bg;
+    assert_ne!(faded, Some(Color::Rgb(229, 192, 123)));
+
+    assert!(editor.next_deadline().unwrap() <= now + Duration::from_millis(400));
+    editor.tick(now + Duration::from_millis(400));
+    assert!(editor.get_mark_layer(FLASH_MARK_LAYER).is_none());
+}
@@ -0,0 +1,33 @@
+use ratatui_code_editor::editor::Editor;
+
+#[test]
+fn scope_at_lists_nodes_and_captures_outermost_first() {
+    let editor = Editor::new("rust", "fn f() {\n    let s = \"a\\n\";\n}\n", vec![]).unwrap();
+
+    assert_eq!(
+        editor.scope_at(3),
+        [
+            "source_file",
+            "function_item",
+            "identifier",
+            "@variable",
+            "@function"
+        ]
+    );
+
+    let escape = editor.get_content().find('\\').unwrap();
+    let scope = editor.scope_at(escape);
+    assert_eq!(
+        scope[scope.len() - 4..],
+        [
+            "string_literal",
+            "@string",
+            "escape_sequence",
+            "@constant.character.escape"
+        ]
+    );
+    assert!(scope.contains(&"let_declaration".to_string()));
+
+    let plain = Editor::new("text", "abc", vec![]).unwrap();
+    assert!(plain.scope_at(1).is_empty());
+}
@@ -0,0 +1,48 @@
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+#[test]
+fn doc_to_screen_and_back() {
+    let content = (0..30)
+        .map(|i| format!("line {i} ❤️ end"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut editor = Editor::new("text", &content, vec![]).unwrap();
+    let area = Rect::new(5, 2, 40, 10);
+    editor.set_offset_y(10);
+    let mut buf = Buffer::empty(area);
+    editor.render(area, &mut buf);
+
+    let offset = content.find("line 12").unwrap();
+    let offset = content[..offset].chars().count();
+    let (x, y) = editor.doc_to_screen(offset, &area).unwrap();
+    assert_eq!(y, 4);
+    assert_eq!(buf[(x, y)].symbol(), "l");
+    assert_eq!(editor.screen_to_doc(x, y, &area), Some(offset));
+
+    // The heart is two cells wide, so `end` starts three cells after it
+    let heart = offset + "line 12 ".chars().count();
+    let (heart_x, _) = editor.doc_to_screen(heart, &area).unwrap();
+    let (end_x, _) = editor.doc_to_screen(heart + 3, &area).unwrap();
+    assert_eq!(end_x, heart_x + 3);
+    assert_eq!(buf[(end_x, y)].symbol(), "e");
+    assert_eq!(editor.screen_to_doc(end_x, y, &area), Some(heart + 3));
+
+    // Past the end of a line maps to its end
+    let line_end = offset + "line 12 ❤️ end".chars().count();
+    assert_eq!(
+        editor.screen_to_doc(area.right() - 1, y, &area),
+        Some(line_end)
+    );
+
+    // Lines scrolled out of view and the gutter have no position
+    assert_eq!(editor.doc_to_screen(0, &area), None);
+    assert_eq!(editor.screen_to_doc(area.left(), y, &area), None);
+
+    // Nor do columns scrolled off to the left
+    editor.set_offset_x(3);
+    assert_eq!(editor.doc_to_screen(offset, &area), None);
+    assert_eq!(editor.doc_to_screen(offset + 3, &area), Some((x, y)));
+}
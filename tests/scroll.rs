@@ -0,0 +1,376 @@
+use ratatui_code_editor::actions::{MoveDown, MoveUp, ScrollHalfPageDown, ScrollViewDown};
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::types::ScrollFollow;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+use std::time::{Duration, Instant};
+
+fn editor_with_lines(count: usize) -> Editor {
+    let content = (0..count)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Editor::new("text", &content, vec![]).unwrap()
+}
+
+fn cursor_line(editor: &Editor) -> usize {
+    editor.borrow_code().char_to_line(editor.get_cursor())
+}
+
+#[test]
+fn scrolloff_keeps_context_around_cursor() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = editor_with_lines(50);
+    editor.set_scrolloff(3);
+
+    for _ in 0..6 {
+        editor.apply(MoveDown { shift: false });
+        editor.focus(&area);
+    }
+    assert_eq!(editor.get_offset_y(), 0);
+
+    editor.apply(MoveDown { shift: false });
+    editor.focus(&area);
+    assert_eq!(cursor_line(&editor), 7);
+    assert_eq!(editor.get_offset_y(), 1);
+
+    editor.set_offset_y(5);
+    editor.apply(MoveUp { shift: false });
+    editor.focus(&area);
+    assert_eq!(cursor_line(&editor), 6);
+    assert_eq!(editor.get_offset_y(), 3);
+
+    // Near the end of the file the margin does not scroll past the last line
+    editor.set_cursor(editor.get_content().len());
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 40);
+}
+
+#[test]
+fn scroll_to_line_and_center_cursor() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = editor_with_lines(50);
+
+    editor.scroll_to_line(20);
+    assert_eq!(editor.get_offset_y(), 20);
+    assert_eq!(editor.get_cursor(), 0);
+    editor.scroll_to_line(500);
+    assert_eq!(editor.get_offset_y(), 49);
+
+    editor.set_cursor(editor.get_content().find("line 30").unwrap());
+    editor.center_cursor(&area);
+    assert_eq!(editor.get_offset_y(), 25);
+}
+
+#[test]
+fn mouse_wheel_scrolls_by_configured_amount() {
+    use crossterm::event::{KeyModifiers, MouseEvent, MouseEventKind};
+
+    let wheel = |kind, modifiers| MouseEvent {
+        kind,
+        column: 10,
+        row: 2,
+        modifiers,
+    };
+    let area = Rect::new(0, 0, 20, 10);
+    let long = "x".repeat(40);
+    let content = (0..50)
+        .map(|_| long.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut editor = Editor::new("text", &content, vec![]).unwrap();
+    editor.show_line_numbers(false);
+    editor.set_code_folding_enabled(false);
+
+    editor
+        .mouse(wheel(MouseEventKind::ScrollDown, KeyModifiers::NONE), &area)
+        .unwrap();
+    assert_eq!(editor.get_offset_y(), 3);
+
+    editor.set_scroll_lines_per_tick(5);
+    editor
+        .mouse(wheel(MouseEventKind::ScrollDown, KeyModifiers::NONE), &area)
+        .unwrap();
+    editor
+        .mouse(wheel(MouseEventKind::ScrollUp, KeyModifiers::NONE), &area)
+        .unwrap();
+    editor
+        .mouse(wheel(MouseEventKind::ScrollDown, KeyModifiers::NONE), &area)
+        .unwrap();
+    assert_eq!(editor.get_offset_y(), 8);
+
+    editor
+        .mouse(
+            wheel(MouseEventKind::ScrollRight, KeyModifiers::NONE),
+            &area,
+        )
+        .unwrap();
+    assert_eq!(editor.get_offset_x(), 5);
+    editor
+        .mouse(
+            wheel(MouseEventKind::ScrollDown, KeyModifiers::SHIFT),
+            &area,
+        )
+        .unwrap();
+    assert_eq!(editor.get_offset_x(), 10);
+    editor
+        .mouse(wheel(MouseEventKind::ScrollUp, KeyModifiers::SHIFT), &area)
+        .unwrap();
+    assert_eq!(editor.get_offset_x(), 5);
+
+    // 40 columns of text in an 18 column wide text area
+    for _ in 0..10 {
+        editor
+            .mouse(
+                wheel(MouseEventKind::ScrollRight, KeyModifiers::NONE),
+                &area,
+            )
+            .unwrap();
+    }
+    assert_eq!(editor.get_offset_x(), 22);
+    assert_eq!(editor.get_offset_y(), 8);
+}
+
+#[test]
+fn drag_past_edge_autoscrolls_over_time() {
+    use std::time::{Duration, Instant};
+
+    let area = Rect::new(0, 5, 40, 10);
+    let mut editor = editor_with_lines(200);
+    editor.show_line_numbers(false);
+    editor.set_code_folding_enabled(false);
+    editor.handle_mouse_down(0);
+
+    // Dragging onto the last row scrolls one line right away
+    let start = Instant::now();
+    editor.handle_mouse_drag_at(4, 14, &area, start);
+    assert_eq!(editor.get_offset_y(), 1);
+    assert_eq!(cursor_line(&editor), 10);
+    let deadline = editor.next_deadline().unwrap();
+    assert!(deadline > start && deadline <= start + Duration::from_millis(100));
+
+    // Without further mouse events, ticks keep scrolling at 15 lines per second
+    editor.tick(start + Duration::from_millis(200));
+    assert_eq!(editor.get_offset_y(), 4);
+    assert_eq!(cursor_line(&editor), 13);
+
+    // Three rows below the last row scrolls four times as fast
+    let t = start + Duration::from_millis(200);
+    editor.handle_mouse_drag_at(4, 17, &area, t);
+    editor.tick(t + Duration::from_millis(200));
+    assert_eq!(editor.get_offset_y(), 16);
+
+    // Back inside the viewport stops scrolling
+    editor.handle_mouse_drag_at(4, 10, &area, t + Duration::from_millis(300));
+    let offset = editor.get_offset_y();
+    editor.tick(t + Duration::from_secs(1));
+    assert_eq!(editor.get_offset_y(), offset);
+    // once the double click window has passed too, nothing asks for ticks
+    assert_eq!(editor.next_deadline(), None);
+    assert!(editor.get_selection().is_some());
+}
+
+#[test]
+fn highlight_prefetch_asks_for_ticks_until_done() {
+    let content = "fn f() {}\n".repeat(300);
+    let mut editor = Editor::new("rust", &content, vec![]).unwrap();
+    let area = Rect::new(0, 0, 40, 10);
+    (&editor).render(area, &mut Buffer::empty(area));
+    assert_eq!(editor.next_deadline(), None);
+
+    editor.set_highlight_prefetch(100);
+    let before = Instant::now();
+    assert!(
+        editor
+            .next_deadline()
+            .is_some_and(|deadline| deadline >= before)
+    );
+    let mut ticks = 0;
+    while editor.next_deadline().is_some() {
+        editor.tick(Instant::now());
+        ticks += 1;
+        assert!(ticks < 10, "prefetch never finishes");
+    }
+}
+
+#[test]
+fn smooth_scroll_animates_jumps_on_tick() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = editor_with_lines(100);
+    let duration = Duration::from_millis(150);
+    editor.set_smooth_scroll(Some(duration));
+
+    editor.set_cursor(editor.get_content().find("line 60").unwrap());
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 0);
+    assert_eq!(editor.scroll_target(), 51);
+    assert!(editor.next_deadline().is_some());
+
+    editor.tick(Instant::now() + duration / 2);
+    let halfway = editor.get_offset_y();
+    assert!(halfway > 0 && halfway < 51, "offset {halfway}");
+    assert!(editor.needs_redraw());
+
+    // Focusing again keeps heading for the same target
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), halfway);
+    assert_eq!(editor.scroll_target(), 51);
+
+    editor.tick(Instant::now() + duration * 2);
+    assert_eq!(editor.get_offset_y(), 51);
+    assert!(editor.next_deadline().is_none());
+
+    // Moving a single line scrolls at once
+    editor.apply(MoveDown { shift: false });
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 52);
+
+    // Scrolling by hand stops the animation
+    editor.scroll_to_line(10);
+    editor.scroll_up();
+    assert_eq!(editor.get_offset_y(), 51);
+    editor.tick(Instant::now() + duration * 2);
+    assert_eq!(editor.get_offset_y(), 51);
+}
+
+#[test]
+fn typewriter_mode_keeps_cursor_centered() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = editor_with_lines(50);
+    editor.set_scroll_follow(ScrollFollow::Typewriter);
+
+    editor.apply(MoveDown { shift: false });
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 0);
+
+    for _ in 0..9 {
+        editor.apply(MoveDown { shift: false });
+        editor.focus(&area);
+    }
+    assert_eq!(cursor_line(&editor), 10);
+    assert_eq!(editor.get_offset_y(), 5);
+
+    editor.apply(MoveUp { shift: false });
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 4);
+
+    // Near the end the view stops at the last line instead of centering
+    for _ in 0..45 {
+        editor.apply(MoveDown { shift: false });
+        editor.focus(&area);
+    }
+    assert_eq!(editor.get_offset_y(), 40);
+}
+
+#[test]
+fn tail_mode_sticks_to_last_line_until_scrolled_up() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = editor_with_lines(20);
+    editor.set_scroll_follow(ScrollFollow::Tail);
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 10);
+    assert!(editor.is_following_tail());
+
+    // Output appended at the end scrolls into view although the cursor stays
+    let end = editor.get_content().len();
+    editor
+        .replace_range(end..end, "\nline 20\nline 21")
+        .unwrap();
+    editor.focus(&area);
+    assert_eq!(editor.get_cursor(), 0);
+    assert_eq!(editor.get_offset_y(), 12);
+    editor.set_cursor(editor.get_content().find("line 15").unwrap());
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 12);
+
+    editor.scroll_up();
+    assert!(!editor.is_following_tail());
+    let end = editor.get_content().len();
+    editor.replace_range(end..end, "\nline 22").unwrap();
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 11);
+
+    editor.scroll_down(area.height as usize);
+    assert!(!editor.is_following_tail());
+    editor.scroll_down(area.height as usize);
+    assert!(editor.is_following_tail());
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 13);
+
+    // Moving the cursor out of view follows the cursor instead
+    editor.set_cursor(1);
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 0);
+    assert!(!editor.is_following_tail());
+}
+
+#[test]
+fn scroll_view_keeps_cursor_in_view() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = editor_with_lines(50);
+    editor.render(area, &mut Buffer::empty(area));
+
+    // The view moves, the cursor stays until it would leave the view
+    editor.apply(ScrollViewDown {
+        lines: 3,
+        keep_cursor: false,
+    });
+    assert_eq!(editor.get_offset_y(), 3);
+    assert_eq!(editor.get_cursor(), 0);
+
+    editor.set_cursor(editor.get_content().find("line 4").unwrap() + 2);
+    editor.apply(ScrollViewDown {
+        lines: 2,
+        keep_cursor: true,
+    });
+    assert_eq!(editor.get_offset_y(), 5);
+    assert_eq!(cursor_line(&editor), 5);
+    assert_eq!(
+        editor.get_cursor(),
+        editor.get_content().find("line 5").unwrap() + 2
+    );
+
+    // With a margin the cursor keeps its distance to the edges
+    editor.set_scrolloff(2);
+    editor.execute(Command::ScrollViewUp {
+        lines: 4,
+        keep_cursor: true,
+    });
+    assert_eq!(editor.get_offset_y(), 1);
+    assert_eq!(cursor_line(&editor), 5);
+    editor.focus(&area);
+    assert_eq!(editor.get_offset_y(), 1);
+    editor.execute(Command::ScrollViewUp {
+        lines: 4,
+        keep_cursor: true,
+    });
+    assert_eq!(editor.get_offset_y(), 0);
+    assert_eq!(cursor_line(&editor), 5);
+
+    // Huge counts stop at the end
+    editor.apply(ScrollViewDown {
+        lines: usize::MAX,
+        keep_cursor: true,
+    });
+    assert_eq!(editor.get_offset_y(), 40);
+    assert_eq!(cursor_line(&editor), 42);
+}
+
+#[test]
+fn half_page_scrolls_move_cursor_along() {
+    let area = Rect::new(0, 0, 40, 10);
+    let mut editor = editor_with_lines(50);
+    editor.render(area, &mut Buffer::empty(area));
+    editor.set_cursor(editor.get_content().find("line 2").unwrap());
+
+    editor.apply(ScrollHalfPageDown);
+    assert_eq!(editor.get_offset_y(), 5);
+    assert_eq!(cursor_line(&editor), 7);
+
+    editor.execute(Command::ScrollHalfPageUp);
+    editor.execute(Command::ScrollHalfPageUp);
+    assert_eq!(editor.get_offset_y(), 0);
+    assert_eq!(cursor_line(&editor), 0);
+}
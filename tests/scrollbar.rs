@@ -0,0 +1,81 @@
+use crossterm::event::{KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+fn editor_with_lines(count: usize) -> Editor {
+    let content = (0..count)
+        .map(|i| format!("line {i}"))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let mut editor = Editor::new("text", &content, vec![]).unwrap();
+    editor.show_line_numbers(false);
+    editor.set_code_folding_enabled(false);
+    editor.show_scrollbar(true);
+    editor
+}
+
+fn scrollbar_column(editor: &Editor, area: Rect) -> Vec<String> {
+    let mut buf = Buffer::empty(area);
+    editor.render(area, &mut buf);
+    (0..area.height)
+        .map(|y| buf[(area.right() - 1, y)].symbol().to_string())
+        .collect()
+}
+
+fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+    MouseEvent {
+        kind,
+        column,
+        row,
+        modifiers: KeyModifiers::NONE,
+    }
+}
+
+#[test]
+fn scrollbar_thumb_follows_offset() {
+    let area = Rect::new(0, 0, 20, 10);
+    let mut editor = editor_with_lines(100);
+
+    let column = scrollbar_column(&editor, area);
+    assert_eq!(column[0], "█");
+    assert_ne!(column[9], "█");
+
+    editor.set_offset_y(90);
+    let column = scrollbar_column(&editor, area);
+    assert_ne!(column[0], "█");
+    assert_eq!(column[9], "█");
+
+    let state = editor.scroll_state();
+    assert_eq!(state, state.content_length(100).position(90));
+}
+
+#[test]
+fn scrollbar_drag_scrolls() {
+    let area = Rect::new(0, 0, 20, 11);
+    let mut editor = editor_with_lines(110);
+
+    editor
+        .mouse(
+            mouse(MouseEventKind::Down(MouseButton::Left), 19, 10),
+            &area,
+        )
+        .unwrap();
+    assert_eq!(editor.get_offset_y(), 99);
+    editor
+        .mouse(mouse(MouseEventKind::Drag(MouseButton::Left), 5, 5), &area)
+        .unwrap();
+    assert_eq!(editor.get_offset_y(), 50);
+    assert_eq!(editor.get_selection(), None);
+    editor
+        .mouse(mouse(MouseEventKind::Up(MouseButton::Left), 5, 5), &area)
+        .unwrap();
+    editor
+        .mouse(mouse(MouseEventKind::Down(MouseButton::Left), 5, 0), &area)
+        .unwrap();
+    assert_eq!(
+        editor.get_cursor(),
+        editor.get_content().find("line 50").unwrap() + 3
+    );
+}
@@ -0,0 +1,105 @@
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::search::{SEARCH_MARK_LAYER, SearchOptions, SearchState};
+use ratatui_code_editor::selection::Selection;
+use ratatui_code_editor::workspace::Workspace;
+
+fn state(query: &str, current: Option<usize>, total: usize) -> Option<SearchState> {
+    Some(SearchState {
+        query: query.to_string(),
+        current,
+        total,
+    })
+}
+
+#[test]
+fn search_counts_matches_and_cycles_through_them() {
+    let mut editor = Editor::new("text", "foo bar\nfoo baz\nfoo", vec![]).unwrap();
+    editor.set_cursor(4);
+    assert_eq!(editor.search("foo", SearchOptions::default()), 3);
+    assert_eq!(editor.get_selection(), Some(Selection::new(8, 11)));
+    assert_eq!(editor.search_state(), state("foo", Some(1), 3));
+
+    assert!(editor.search_next());
+    assert_eq!(editor.search_state(), state("foo", Some(2), 3));
+    assert!(editor.search_next());
+    assert_eq!(editor.get_selection(), Some(Selection::new(0, 3)));
+    assert!(editor.search_prev());
+    assert_eq!(editor.search_state(), state("foo", Some(2), 3));
+
+    editor.execute(Command::MoveLeft { shift: false });
+    assert_eq!(editor.search_state(), state("foo", None, 3));
+
+    editor.execute(Command::ClearSearch);
+    assert_eq!(editor.search_state(), None);
+    assert_eq!(editor.get_mark_layer(SEARCH_MARK_LAYER), None);
+}
+
+#[test]
+fn matches_follow_edits() {
+    let mut editor = Editor::new("text", "ab ab", vec![]).unwrap();
+    editor.execute(Command::Search {
+        query: "ab".into(),
+        options: SearchOptions::default(),
+    });
+    assert_eq!(editor.search_state().unwrap().total, 2);
+
+    editor.set_selection(None);
+    editor.set_cursor(5);
+    editor.apply(InsertText {
+        text: " ab".to_string(),
+    });
+    assert_eq!(editor.search_state(), state("ab", None, 3));
+    assert_eq!(editor.search("missing", SearchOptions::default()), 0);
+    assert!(!editor.search_next());
+}
+
+#[test]
+fn search_state_is_kept_per_document() {
+    let mut workspace = Workspace::new(vec![]);
+    let first = workspace.open("text", "one two one").unwrap();
+    let second = workspace.open("text", "two").unwrap();
+    let mut editor = workspace.editor(first).unwrap();
+    editor.search("one", SearchOptions::default());
+
+    workspace.attach(&mut editor, second).unwrap();
+    assert_eq!(editor.search_state(), None);
+    editor.search("two", SearchOptions::default());
+
+    workspace.attach(&mut editor, first).unwrap();
+    assert_eq!(editor.search_state(), state("one", Some(0), 2));
+}
+
+#[test]
+fn smart_case_and_whole_word() {
+    let mut editor = Editor::new("text", "Foo foo food FOO_BAR", vec![]).unwrap();
+    let smart_case = SearchOptions {
+        smart_case: true,
+        whole_word: false,
+    };
+    assert_eq!(editor.search("foo", SearchOptions::default()), 2);
+    assert_eq!(editor.search("foo", smart_case), 4);
+    // an uppercase letter makes the search case sensitive
+    assert_eq!(editor.search("Foo", smart_case), 1);
+
+    let whole_word = SearchOptions {
+        smart_case: true,
+        whole_word: true,
+    };
+    assert_eq!(editor.search("foo", whole_word), 2);
+    assert_eq!(editor.search("_bar", whole_word), 0);
+    assert_eq!(editor.search("foo ", whole_word), 2);
+}
+
+#[test]
+fn whole_word_uses_the_language_word_chars() {
+    let mut editor = Editor::new("javascript", "$el = el + $el$", vec![]).unwrap();
+    let whole_word = SearchOptions {
+        smart_case: false,
+        whole_word: true,
+    };
+    // `$` is part of JavaScript identifiers
+    assert_eq!(editor.search("el", whole_word), 1);
+    assert_eq!(editor.search("$el", whole_word), 1);
+}
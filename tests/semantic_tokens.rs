@@ -0,0 +1,74 @@
+mod common;
+
+use common::bare_builder;
+use ratatui_code_editor::semantic_tokens::SEMANTIC_TOKENS_MARK_LAYER;
+use ratatui_code_editor::testing::TestHarness;
+use ratatui_core::style::{Color, Modifier, Style};
+
+fn harness() -> TestHarness {
+    let theme = vec![
+        ("variable", "#ffffff"),
+        ("variable.parameter", "#00ff00"),
+        ("lsp.type.function", "#0000ff"),
+        ("lsp.mod.mutable", "#ff0000"),
+    ];
+    let editor = bare_builder("rust", "fn f(a: u8) { a; f(a); }")
+        .theme(theme)
+        .build()
+        .unwrap();
+    TestHarness::new(editor, 30, 1)
+}
+
+#[test]
+fn semantic_tokens_are_drawn_over_syntax_and_follow_edits() {
+    let mut harness = harness();
+    assert_eq!(harness.render()[(17, 0)].fg, Color::Rgb(255, 255, 255));
+
+    harness.editor_mut().set_semantic_tokens(vec![
+        (17..18, "function", vec![]),
+        (14..15, "parameter", vec!["mutable"]),
+        (3..4, "unknown", vec![]),
+    ]);
+    let buffer = harness.render();
+    assert_eq!(buffer[(17, 0)].fg, Color::Rgb(0, 0, 255));
+    assert_eq!(buffer[(14, 0)].fg, Color::Rgb(255, 0, 0));
+    assert_eq!(buffer[(19, 0)].fg, Color::Rgb(0, 255, 0));
+    assert_eq!(
+        harness
+            .editor()
+            .get_mark_layer(SEMANTIC_TOKENS_MARK_LAYER)
+            .unwrap()
+            .len(),
+        2
+    );
+
+    // The first line scrolls out of view once the cursor is on the second
+    harness.editor_mut().set_cursor(0);
+    harness.keys("// x<Enter>");
+    let tokens = harness
+        .editor()
+        .get_mark_layer(SEMANTIC_TOKENS_MARK_LAYER)
+        .unwrap();
+    assert_eq!(tokens[0].0..tokens[0].1, 22..23);
+    assert_eq!(harness.render()[(17, 0)].fg, Color::Rgb(0, 0, 255));
+
+    harness.editor_mut().clear_semantic_tokens();
+    assert_eq!(harness.render()[(17, 0)].fg, Color::Rgb(255, 255, 255));
+}
+
+#[test]
+fn mark_layers_are_drawn_over_semantic_tokens() {
+    let mut harness = harness();
+    harness
+        .editor_mut()
+        .set_semantic_tokens(vec![(17..18, "function", vec![])]);
+    let style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    harness
+        .editor_mut()
+        .set_mark_layer("diagnostics", vec![(17, 18)], style);
+    let cell = harness.render()[(17, 0)].clone();
+    assert_eq!(cell.fg, Color::Yellow);
+    assert!(cell.modifier.contains(Modifier::BOLD));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use common::row;
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+
+#[test]
+fn shared_editors_edit_one_document_with_own_cursors() {
+    let mut left = Editor::new("text", "one\ntwo\nthree", vec![]).unwrap();
+    let mut right = Editor::with_shared_code(left.shared_code(), vec![]);
+    left.show_line_numbers(false);
+    right.show_line_numbers(false);
+    right.set_cursor(8);
+
+    let area = Rect::new(0, 0, 20, 4);
+    let mut buf = Buffer::empty(area);
+    right.render(area, &mut buf);
+    assert_eq!(row(&buf, 0).trim_start(), "one");
+
+    // an edit in one view shows up in the other, whose cursor follows the text
+    left.apply(InsertText {
+        text: "zero\n".into(),
+    });
+    assert_eq!(right.get_content(), "zero\none\ntwo\nthree");
+    let mut buf = Buffer::empty(area);
+    right.render(area, &mut buf);
+    assert_eq!(row(&buf, 0).trim_start(), "zero");
+    assert_eq!(row(&buf, 3).trim_start(), "three");
+
+    right.execute(Command::InsertText { text: "3".into() });
+    assert_eq!(left.get_content(), "zero\none\ntwo\n3three");
+    assert_eq!(left.get_cursor(), 5);
+    assert_eq!(right.get_cursor(), 14);
+}
+
+#[test]
+fn shared_editor_cursor_is_clamped_when_text_is_removed() {
+    let mut left = Editor::new("text", "hello world", vec![]).unwrap();
+    let mut right = Editor::with_shared_code(left.shared_code(), vec![]);
+    right.set_cursor(11);
+
+    left.execute(Command::SelectAll);
+    left.execute(Command::Delete);
+    right.sync_shared_code();
+    assert_eq!(right.get_cursor(), 0);
+    right.execute(Command::InsertText { text: "x".into() });
+    assert_eq!(left.get_content(), "x");
+}
+
+#[test]
+#[allow(deprecated)]
+fn borrow_code_reaches_a_shared_document() {
+    let mut left = Editor::new("text", "hello", vec![]).unwrap();
+    assert_eq!(left.code_ref().len_chars(), 5);
+    left.code_mut().insert(5, "!");
+
+    let right = Editor::with_shared_code(left.shared_code(), vec![]);
+    left.borrow_code_mut().insert(0, "> ");
+    assert_eq!(right.borrow_code().get_content(), "> hello!");
+}
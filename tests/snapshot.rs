@@ -0,0 +1,81 @@
+use ratatui_code_editor::actions::{InsertText, Redo, Undo};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+use ratatui_code_editor::types::LineEnding;
+
+#[test]
+fn restores_content_positions_and_history() {
+    let mut editor = Editor::new("text", "one\ntwo\nthree", vec![]).unwrap();
+    editor.set_cursor(3);
+    editor.apply(InsertText {
+        text: "!".to_string(),
+    });
+    editor.apply(InsertText {
+        text: "?".to_string(),
+    });
+    editor.apply(Undo {});
+    editor.set_selection(Some(Selection::new(5, 8)));
+    editor.set_offset_y(1);
+    let snapshot = editor.snapshot_with_history();
+
+    let mut restored = Editor::new("text", "", vec![]).unwrap();
+    restored.restore(snapshot.clone()).unwrap();
+    assert_eq!(restored.get_content(), "one!\ntwo\nthree");
+    assert_eq!(restored.get_cursor(), editor.get_cursor());
+    assert_eq!(restored.get_selection(), Some(Selection::new(5, 8)));
+    assert_eq!(restored.get_offset_y(), 1);
+    assert!(restored.borrow_code().is_modified());
+    assert_eq!(restored.snapshot_with_history(), snapshot);
+
+    restored.apply(Redo {});
+    assert_eq!(restored.get_content(), "one!?\ntwo\nthree");
+    restored.apply(Undo {});
+    restored.apply(Undo {});
+    assert_eq!(restored.get_content(), "one\ntwo\nthree");
+    restored.apply(Undo {});
+    assert_eq!(restored.get_content(), "one\ntwo\nthree");
+}
+
+#[test]
+fn restore_without_history_clears_undo_and_clamps_positions() {
+    let mut editor = Editor::new("text", "a long line of text", vec![]).unwrap();
+    editor.set_cursor(19);
+    let mut snapshot = editor.snapshot();
+    assert!(snapshot.history.is_none());
+    snapshot.content = "short".to_string();
+    snapshot.selection = Some(Selection::new(2, 40));
+
+    let mut restored = Editor::new("text", "previous", vec![]).unwrap();
+    restored.restore(snapshot).unwrap();
+    assert_eq!(restored.get_content(), "short");
+    assert_eq!(restored.get_cursor(), 5);
+    assert_eq!(restored.get_selection(), Some(Selection::new(2, 5)));
+
+    restored.apply(Undo {});
+    assert_eq!(restored.get_content(), "short");
+}
+
+#[test]
+fn restore_rejects_history_that_does_not_fit_the_content() {
+    let mut editor = Editor::new("text", "one\r\ntwo", vec![]).unwrap();
+    editor.set_cursor(3);
+    editor.apply(InsertText {
+        text: "!".to_string(),
+    });
+    let snapshot = editor.snapshot_with_history();
+
+    let mut restored = Editor::new("text", "previous", vec![]).unwrap();
+    restored.restore(snapshot.clone()).unwrap();
+    assert_eq!(restored.line_ending(), LineEnding::CrLf);
+    assert_eq!(restored.get_content(), "one!\r\ntwo");
+
+    // the insertion can't be undone from a shorter text
+    let mut shortened = snapshot.clone();
+    shortened.content = "on".to_string();
+    assert!(restored.restore(shortened).is_err());
+    assert_eq!(restored.get_content(), "one!\r\ntwo");
+
+    let mut past_end = snapshot;
+    past_end.history.as_mut().unwrap().index = 2;
+    assert!(restored.restore(past_end).is_err());
+}
@@ -0,0 +1,69 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn snippet_tabstops_are_visited_in_order() {
+    let mut editor = Editor::new("python", "    x", vec![]).unwrap();
+    editor.set_cursor(5);
+    editor.insert_snippet("for ${1:item} in ${2:iter}:\n    $0");
+    assert_eq!(editor.get_content(), "    xfor item in iter:\n        ");
+    assert!(editor.has_snippet());
+    assert_eq!(editor.get_selection(), Some(Selection::new(9, 13)));
+
+    // Typing replaces the placeholder, the next stops follow the edit
+    editor.execute(Command::InsertText { text: "n".into() });
+    editor.execute(Command::NextTabstop);
+    assert_eq!(editor.get_selection(), Some(Selection::new(14, 18)));
+    editor.execute(Command::PrevTabstop);
+    assert_eq!(editor.get_selection(), Some(Selection::new(9, 10)));
+    editor.execute(Command::NextTabstop);
+    editor.execute(Command::InsertText { text: "xs".into() });
+
+    editor.execute(Command::NextTabstop);
+    assert!(!editor.has_snippet());
+    assert_eq!(editor.get_content(), "    xfor n in xs:\n        ");
+    assert_eq!(editor.get_cursor(), editor.get_content().len());
+}
+
+#[test]
+fn mirrored_tabstops_are_edited_together() {
+    let mut editor = Editor::new("text", "", vec![]).unwrap();
+    editor.insert_snippet("<${1:div}></$1>");
+    assert_eq!(editor.selections().len(), 2);
+    editor.execute(Command::InsertText { text: "p".into() });
+    assert_eq!(editor.get_content(), "<p></p>");
+    editor.execute(Command::NextTabstop);
+    assert_eq!(editor.get_cursor(), 7);
+}
+
+#[test]
+fn abbreviations_expand_on_space_or_tab() {
+    let mut editor = Editor::new("rust", "", vec![]).unwrap();
+    editor.add_abbreviation("fnm", "fn main() {\n    $0\n}");
+    editor.add_language_abbreviation("python", "fnm", "def main():\n    $0");
+    editor.add_abbreviation("todo", "// TODO: ${1:what}");
+
+    for c in "fnm ".chars() {
+        editor.execute(Command::InsertText { text: c.into() });
+    }
+    assert_eq!(editor.get_content(), "fn main() {\n    \n}");
+    assert_eq!(editor.get_cursor(), 16);
+
+    editor.execute(Command::InsertText {
+        text: "todo".into(),
+    });
+    editor.execute(Command::Indent);
+    assert_eq!(editor.get_content(), "fn main() {\n    // TODO: what\n}");
+    assert_eq!(editor.get_selection(), Some(Selection::new(25, 29)));
+
+    // Only whole words expand
+    let mut editor = Editor::new("python", "xfnm", vec![]).unwrap();
+    editor.add_language_abbreviation("python", "fnm", "def main():\n    $0");
+    editor.set_cursor(4);
+    editor.execute(Command::InsertText { text: " ".into() });
+    assert_eq!(editor.get_content(), "xfnm ");
+    editor.execute(Command::InsertText { text: "fnm".into() });
+    editor.execute(Command::InsertText { text: " ".into() });
+    assert_eq!(editor.get_content(), "xfnm def main():\n    ");
+}
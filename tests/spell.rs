@@ -0,0 +1,41 @@
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::spell::{Dictionary, SPELL_MARK_LAYER};
+use ratatui_core::style::Modifier;
+
+struct Words;
+
+impl Dictionary for Words {
+    fn check(&self, word: &str) -> bool {
+        ["the", "answer", "is", "hello", "world"].contains(&word.to_lowercase().as_str())
+    }
+
+    fn suggest(&self, word: &str) -> Vec<String> {
+        vec![word.replace("wrold", "world")]
+    }
+}
+
+#[test]
+fn misspelled_words_in_comments_and_strings_are_underlined() {
+    let source = "// The answr is\nfn wrold() { println!(\"hello wrold\"); }";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.check_spelling();
+    assert!(editor.get_mark_layer(SPELL_MARK_LAYER).is_none());
+
+    editor.set_dictionary(Some(Box::new(Words)));
+    editor.check_spelling();
+    let marks = editor.get_mark_layer(SPELL_MARK_LAYER).unwrap();
+    let words: Vec<_> = marks
+        .iter()
+        .map(|&(start, end, _)| &source[start..end])
+        .collect();
+    // The function name is code, not prose
+    assert_eq!(words, ["answr", "wrold"]);
+    assert!(marks[0].2.add_modifier.contains(Modifier::UNDERLINED));
+
+    let offset = source.rfind("wrold").unwrap() + 1;
+    assert_eq!(editor.suggestions(offset), ["world"]);
+    assert!(editor.suggestions(0).is_empty());
+
+    editor.set_dictionary(None);
+    assert!(editor.get_mark_layer(SPELL_MARK_LAYER).is_none());
+}
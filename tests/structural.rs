@@ -0,0 +1,88 @@
+use ratatui_code_editor::actions::{Barf, RaiseNode, Slurp, SwapNode, Undo};
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn swap_node_with_siblings() {
+    let source = "fn f() { g(a, bb, c); }";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_cursor(source.find("a,").unwrap());
+    editor.apply(SwapNode { forward: true });
+    assert_eq!(editor.get_content(), "fn f() { g(bb, a, c); }");
+    assert_eq!(editor.get_selection(), Some(Selection::new(15, 16)));
+
+    // The selection moves with the node
+    editor.apply(SwapNode { forward: true });
+    assert_eq!(editor.get_content(), "fn f() { g(bb, c, a); }");
+    editor.apply(SwapNode { forward: false });
+    editor.apply(SwapNode { forward: false });
+    assert_eq!(editor.get_content(), source);
+
+    // A selected node is swapped as a whole
+    let source = "fn a() {}\nfn b() {}\n";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_selection(Some(Selection::new(0, 9)));
+    editor.apply(SwapNode { forward: true });
+    assert_eq!(editor.get_content(), "fn b() {}\nfn a() {}\n");
+
+    // Nothing to swap with
+    editor.apply(SwapNode { forward: true });
+    assert_eq!(editor.get_content(), "fn b() {}\nfn a() {}\n");
+}
+
+#[test]
+fn raise_node_replaces_call() {
+    let source = "fn f() { g(h(x, y)); }";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_cursor(source.find('x').unwrap());
+    editor.apply(RaiseNode);
+    assert_eq!(editor.get_content(), "fn f() { g(x); }");
+    assert_eq!(editor.get_selection(), Some(Selection::new(11, 12)));
+
+    // A single undo step
+    editor.apply(Undo);
+    assert_eq!(editor.get_content(), source);
+}
+
+#[test]
+fn slurp_and_barf_move_brackets() {
+    let source = "[[1], 2, 3]";
+    let mut editor = Editor::new("json", source, vec![]).unwrap();
+    editor.set_cursor(2);
+    editor.apply(Slurp { forward: true });
+    assert_eq!(editor.get_content(), "[[1, 2], 3]");
+    assert_eq!(editor.get_cursor(), 2);
+    editor.apply(Slurp { forward: true });
+    assert_eq!(editor.get_content(), "[[1, 2, 3]]");
+
+    // Without anything left to pull in, the outer brackets are used
+    editor.apply(Barf { forward: false });
+    assert_eq!(editor.get_content(), "[1, [2, 3]]");
+    assert_eq!(editor.get_cursor(), 5);
+    editor.apply(Barf { forward: true });
+    assert_eq!(editor.get_content(), "[1, [2], 3]");
+    editor.apply(Slurp { forward: false });
+    assert_eq!(editor.get_content(), "[[1, 2], 3]");
+
+    editor.apply(Undo);
+    assert_eq!(editor.get_content(), "[1, [2], 3]");
+}
+
+#[test]
+fn slurp_into_block_keeps_line_breaks() {
+    let source = "fn f() {\n    a();\n}\nfn g() {}\n";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_cursor(source.find("a()").unwrap());
+    editor.apply(Slurp { forward: true });
+    assert_eq!(editor.get_content(), "fn f() {\n    a();\nfn g() {}\n}\n");
+    editor.apply(Barf { forward: true });
+    assert_eq!(editor.get_content(), source);
+
+    // The `;` after the call keeps its brackets in place, so the block's
+    // closing brace moves instead.
+    let source = "fn f() { g(a); b(); }";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_cursor(source.find('a').unwrap());
+    editor.apply(Barf { forward: true });
+    assert_eq!(editor.get_content(), "fn f() { g(a); } b();");
+}
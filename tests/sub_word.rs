@@ -0,0 +1,67 @@
+use ratatui_code_editor::command::Command;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+use ratatui_code_editor::testing::TestHarness;
+
+#[test]
+fn sub_word_boundaries_split_identifiers() {
+    let editor = Editor::new("text", "parseHTTPRequest snake_case2 a->b", vec![]).unwrap();
+    let code = editor.borrow_code();
+    let mut stops = vec![0];
+    while *stops.last().unwrap() < code.len_chars() {
+        stops.push(code.next_sub_word_boundary(*stops.last().unwrap()));
+    }
+    assert_eq!(stops, vec![0, 5, 9, 16, 22, 28, 30, 32, 33]);
+
+    let mut stops = vec![code.len_chars()];
+    while *stops.last().unwrap() > 0 {
+        stops.push(code.prev_sub_word_boundary(*stops.last().unwrap()));
+    }
+    assert_eq!(stops, vec![33, 32, 30, 29, 23, 17, 9, 5, 0]);
+
+    assert_eq!(code.sub_word_boundaries(11), (9, 16));
+    assert_eq!(code.sub_word_boundaries(22), (17, 28));
+}
+
+#[test]
+fn next_sub_word_boundary_is_the_end_of_the_part() {
+    let editor = Editor::new("text", "snake_case  next", vec![]).unwrap();
+    let code = editor.borrow_code();
+    // from inside a part and from its start
+    assert_eq!(code.next_sub_word_boundary(2), 5);
+    assert_eq!(code.next_sub_word_boundary(0), 5);
+    // separators before the next part are skipped
+    assert_eq!(code.next_sub_word_boundary(5), 10);
+    assert_eq!(code.next_sub_word_boundary(10), 16);
+    assert_eq!(code.prev_sub_word_boundary(8), 6);
+    assert_eq!(code.prev_sub_word_boundary(6), 0);
+}
+
+#[test]
+fn alt_arrows_move_and_select_by_sub_words() {
+    let editor = Editor::new("text", "fooBarBaz", vec![]).unwrap();
+    let mut harness = TestHarness::new(editor, 30, 2);
+    harness.keys("<A-Right><A-S-Right>");
+    harness.assert_cursor(0, 6);
+    assert_eq!(
+        harness.editor_mut().get_selection(),
+        Some(Selection::new(3, 6))
+    );
+    harness.keys("<A-Left>");
+    harness.assert_cursor(0, 3);
+    assert_eq!(harness.editor_mut().get_selection(), None);
+
+    let mut editor = Editor::new("text", "fooBarBaz", vec![]).unwrap();
+    editor.execute(Command::MoveSubWordRight { shift: false });
+    assert_eq!(editor.get_cursor(), 3);
+}
+
+#[test]
+fn sub_word_double_click_selects_a_part() {
+    let mut editor = Editor::new("text", "get_user_name()", vec![]).unwrap();
+    editor.handle_sub_word_mouse_down(5);
+    editor.handle_sub_word_mouse_down(5);
+    assert_eq!(editor.get_selection(), Some(Selection::new(4, 8)));
+    editor.handle_mouse_drag(10);
+    assert_eq!(editor.get_selection(), Some(Selection::new(4, 13)));
+}
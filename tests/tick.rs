@@ -0,0 +1,67 @@
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::editor::Editor;
+use ratatui_core::buffer::Buffer;
+use ratatui_core::layout::Rect;
+use ratatui_core::widgets::Widget;
+use std::time::{Duration, Instant};
+
+#[test]
+fn cursor_blinks_on_tick() {
+    let area = Rect::new(0, 0, 20, 5);
+    let mut editor = Editor::new("text", "hello", vec![]).unwrap();
+    assert_eq!(editor.next_deadline(), None);
+
+    let interval = Duration::from_millis(500);
+    editor.set_cursor_blink(Some(interval));
+    let deadline = editor.next_deadline().unwrap();
+    assert!(editor.get_visible_cursor(&area).is_some());
+
+    editor.tick(deadline - Duration::from_millis(1));
+    assert!(editor.cursor_visible());
+    assert!(!editor.needs_redraw());
+
+    editor.tick(deadline);
+    assert!(!editor.cursor_visible());
+    assert!(editor.get_visible_cursor(&area).is_none());
+    assert!(editor.needs_redraw());
+
+    let mut buf = Buffer::empty(area);
+    (&editor).render(area, &mut buf);
+    assert!(!editor.needs_redraw());
+
+    editor.tick(deadline + interval);
+    assert!(editor.cursor_visible());
+
+    editor.tick(deadline + interval * 2);
+    assert!(!editor.cursor_visible());
+    editor.apply(InsertText { text: "!".into() });
+    assert!(editor.cursor_visible());
+}
+
+#[test]
+fn messages_expire_on_tick() {
+    let mut editor = Editor::new("text", "hello", vec![]).unwrap();
+    editor.set_message("saved", Duration::from_secs(2));
+    assert_eq!(editor.message(), Some("saved"));
+
+    let expires = editor.next_deadline().unwrap();
+    editor.tick(expires - Duration::from_millis(10));
+    assert_eq!(editor.message(), Some("saved"));
+    editor.tick(expires);
+    assert_eq!(editor.message(), None);
+    assert!(editor.needs_redraw());
+    assert_eq!(editor.next_deadline(), None);
+}
+
+#[test]
+fn click_sequence_expires_on_tick() {
+    let mut editor = Editor::new("text", "hello world", vec![]).unwrap();
+    editor.handle_mouse_down(2);
+    let timeout = editor.next_deadline().unwrap();
+    assert!(timeout > Instant::now());
+
+    editor.tick(timeout);
+    assert_eq!(editor.next_deadline(), None);
+    editor.handle_mouse_down(2);
+    assert_eq!(editor.get_selection_text(), None);
+}
@@ -0,0 +1,97 @@
+use ratatui_code_editor::actions::Undo;
+use ratatui_code_editor::editor::Editor;
+use ratatui_code_editor::selection::Selection;
+
+#[test]
+fn edit_uses_original_offsets_and_is_one_undo_step() {
+    let mut editor = Editor::new("text", "foo(bar, baz)", vec![]).unwrap();
+    editor.set_cursor(9);
+
+    editor
+        .edit(|tx| {
+            tx.replace(0..3, "call");
+            tx.insert(8, " qux,");
+            tx.remove(12..13);
+        })
+        .unwrap();
+
+    assert_eq!(editor.get_content(), "call(bar, qux, baz");
+    // cursor was before "baz", which moved right by 1 + 5
+    assert_eq!(editor.get_cursor(), 15);
+
+    editor.apply(Undo {});
+    assert_eq!(editor.get_content(), "foo(bar, baz)");
+    assert_eq!(editor.get_cursor(), 9);
+}
+
+#[test]
+fn inserts_at_the_same_offset_keep_queue_order() {
+    let mut editor = Editor::new("text", "ac", vec![]).unwrap();
+    editor
+        .apply_edits(vec![(1..1, "b".to_string()), (1..1, "B".to_string())])
+        .unwrap();
+    assert_eq!(editor.get_content(), "abBc");
+}
+
+#[test]
+fn overlapping_edits_are_rejected() {
+    let mut editor = Editor::new("text", "abcdef", vec![]).unwrap();
+    let result = editor.edit(|tx| {
+        tx.replace(1..4, "x");
+        tx.replace(3..5, "y");
+    });
+    assert!(result.is_err());
+    assert!(editor.replace_range(2..10, "z").is_err());
+    assert_eq!(editor.get_content(), "abcdef");
+}
+
+#[test]
+fn replace_range_remaps_selection() {
+    let mut editor = Editor::new("text", "hello world", vec![]).unwrap();
+    editor.set_selection(Some(Selection::new(6, 11)));
+    editor.replace_range(0..5, "hi").unwrap();
+    assert_eq!(editor.get_content(), "hi world");
+    assert_eq!(editor.get_selection(), Some(Selection::new(3, 8)));
+}
+
+#[test]
+fn apply_text_edits_formats_without_losing_cursor_or_undo() {
+    use ratatui_code_editor::types::TextEdit;
+
+    let source = "fn main(){\nlet a=1;\n}\n";
+    let mut editor = Editor::new("rust", source, vec![]).unwrap();
+    editor.set_cursor(source.find("a=").unwrap());
+
+    editor
+        .apply_text_edits(vec![
+            TextEdit {
+                start_row: 0,
+                start_col: 9,
+                end_row: 0,
+                end_col: 9,
+                text: " ".into(),
+            },
+            TextEdit {
+                start_row: 1,
+                start_col: 0,
+                end_row: 1,
+                end_col: 0,
+                text: "    ".into(),
+            },
+            TextEdit {
+                start_row: 1,
+                start_col: 5,
+                end_row: 1,
+                end_col: 6,
+                text: " = ".into(),
+            },
+        ])
+        .unwrap();
+
+    let formatted = "fn main() {\n    let a = 1;\n}\n";
+    assert_eq!(editor.get_content(), formatted);
+    assert_eq!(editor.get_cursor(), formatted.find("a =").unwrap());
+
+    editor.apply(Undo {});
+    assert_eq!(editor.get_content(), source);
+}
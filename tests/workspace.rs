@@ -0,0 +1,55 @@
+use ratatui_code_editor::actions::InsertText;
+use ratatui_code_editor::selection::Selection;
+use ratatui_code_editor::workspace::Workspace;
+
+#[test]
+fn attach_switches_documents_and_keeps_their_state() {
+    let mut workspace = Workspace::new(vec![]);
+    let notes = workspace.open("text", "one\ntwo").unwrap();
+    let main = workspace.open("rust", "fn main() {}\r\n").unwrap();
+    assert_eq!(workspace.documents().collect::<Vec<_>>(), vec![notes, main]);
+
+    let mut editor = workspace.editor(notes).unwrap();
+    editor.set_cursor(7);
+    editor.set_selection(Some(Selection::new(4, 7)));
+
+    workspace.attach(&mut editor, main).unwrap();
+    assert_eq!(workspace.document_of(&editor), Some(main));
+    assert_eq!(editor.get_content(), "fn main() {}\r\n");
+    assert_eq!(editor.get_cursor(), 0);
+    assert_eq!(editor.get_selection(), None);
+    editor.apply(InsertText {
+        text: "// ".to_string(),
+    });
+
+    workspace.attach(&mut editor, notes).unwrap();
+    assert_eq!(editor.get_content(), "one\ntwo");
+    assert_eq!(editor.get_cursor(), 7);
+    assert_eq!(editor.get_selection(), Some(Selection::new(4, 7)));
+    assert_eq!(
+        workspace.code(main).unwrap().borrow().get_content(),
+        "// fn main() {}\n"
+    );
+}
+
+#[test]
+fn parked_positions_follow_edits_from_other_views() {
+    let mut workspace = Workspace::new(vec![]);
+    let doc = workspace.open("text", "hello world").unwrap();
+    let mut left = workspace.editor(doc).unwrap();
+    let mut right = workspace.editor(doc).unwrap();
+    left.set_cursor(6);
+    assert_eq!(workspace.detach(&mut left), Some(doc));
+    assert_eq!(left.get_content(), "");
+    assert_eq!(workspace.document_of(&left), None);
+
+    right.apply(InsertText {
+        text: ">> ".to_string(),
+    });
+    workspace.attach(&mut left, doc).unwrap();
+    assert_eq!(left.get_content(), ">> hello world");
+    assert_eq!(left.get_cursor(), 9);
+
+    assert!(workspace.close(doc).is_some());
+    assert!(workspace.attach(&mut right, doc).is_err());
+}